@@ -37,5 +37,15 @@ try 42 "intlit.go"
 try 9 "four_arith.go"
 try 9 "unary_minus.go"
 try 30 "declare_autovar.go"
+try 30 "main_return.go"
+try 1 "argc.go"
+try 21 "arguments.go"
+try 20 "interface.go"
+try 0 "hello_world.go"
+try 27 "division.go"
+try 1 "if_expression.go"
+try 45 "simple_struct.go"
+try 156 "slices.go"
+try 2 "out_of_range.go"
 
 echo -e "\n\nOK"
@@ -1,12 +1,16 @@
 #!/bin/bash
 build_and_test_peachili_executable() {
   input="$1"
-  ../target/debug/peachili "$input" -L
+  expected="$2"
+  output=$(../target/debug/peachili check "$input" 2>&1)
   rustc_actual="$?"
   if [ $rustc_actual -ne 1 ]; then
 
     echo -e "$input => \e[31mcompiler must detect with one or more errors, but not.\e[m"
     exit 1
+  elif ! echo "$output" | grep -qF -- "$expected"; then
+    echo -e "$input => \e[31mexpected \"$expected\", but got \"$output\"\e[m"
+    exit 1
   else
     echo -e "$input => \e[32mpassed\e[m"
   fi
@@ -16,23 +20,30 @@ echo -e "start to test invalid program...\n\n"
 
 cd failures
 
-build_and_test_peachili_executable "invalid_integer_literal.go"
-build_and_test_peachili_executable "if_expr1.go"
-build_and_test_peachili_executable "if_expr2.go"
-build_and_test_peachili_executable "if_expr_stmt1.go"
-build_and_test_peachili_executable "if_expr_stmt2.go"
-build_and_test_peachili_executable "invalid_assignment.go"
-build_and_test_peachili_executable "add_int64_and_boolean.go"
-build_and_test_peachili_executable "uint_and_sint.go"
-build_and_test_peachili_executable "assignment_to_constant.go"
-build_and_test_peachili_executable "invalid_arg_types.go"
-build_and_test_peachili_executable "invalid_arg_number.go"
-build_and_test_peachili_executable "minus_to_unsigned.go"
-build_and_test_peachili_executable "return_in_noreturn_func.go"
-build_and_test_peachili_executable "not_found_main.go"
-build_and_test_peachili_executable "invalid_type_main.go"
-build_and_test_peachili_executable "return_local_pointer.go"
-build_and_test_peachili_executable "member_with_not_struct.go"
-build_and_test_peachili_executable "invalid_member.go"
+build_and_test_peachili_executable "invalid_integer_literal.go" 'out of range Int64'
+build_and_test_peachili_executable "if_expr1.go" 'condition must be `Boolean`, got `Int64`'
+build_and_test_peachili_executable "if_expr2.go" 'condition must be `Boolean`, got `Int64`'
+build_and_test_peachili_executable "if_expr_stmt1.go" 'condition must be `Boolean`, got `Int64`'
+build_and_test_peachili_executable "if_expr_stmt2.go" 'condition must be `Boolean`, got `Int64`'
+build_and_test_peachili_executable "invalid_assignment.go" 'mismatched type in assignment: expected `Boolean`, got `Int64`'
+build_and_test_peachili_executable "add_int64_and_boolean.go" 'operand of arithmetic must be an integer, got `Boolean`'
+build_and_test_peachili_executable "uint_and_sint.go" 'mismatched type in assignment: expected `Int64`, got `Uint64`'
+build_and_test_peachili_executable "assignment_to_constant.go" 'cannot assign to constant `y`'
+build_and_test_peachili_executable "undefined_function_call.go" 'undefined such a function -> `x64::exit`'
+build_and_test_peachili_executable "invalid_arg_types.go" 'mismatched argument type in calling `callee`: expected `Int64`, got `Uint64`'
+build_and_test_peachili_executable "invalid_arg_number.go" '`callee` expects 3 argument(s), but got 0'
+build_and_test_peachili_executable "minus_to_unsigned.go" 'cannot negate a value of unsigned type `Uint64`'
+build_and_test_peachili_executable "negative_constant_to_unsigned.go" 'constant `-1` overflows `Uint64`'
+build_and_test_peachili_executable "return_in_noreturn_func.go" 'mismatched type in return: expected `Noreturn`, got `Int64`'
+build_and_test_peachili_executable "not_found_main.go" 'entry point `main` not found'
+build_and_test_peachili_executable "invalid_type_main.go" 'entry point `main` must take'
+build_and_test_peachili_executable "return_local_pointer.go" 'cannot return the address of local variable `x`'
+build_and_test_peachili_executable "member_with_not_struct.go" 'its not a struct'
+build_and_test_peachili_executable "invalid_member.go" 'undefined such a member -> `foo`'
+build_and_test_peachili_executable "use_before_assignment.go" 'variable `x` may be used before being assigned in `main`'
+build_and_test_peachili_executable "undefined_receiver_type.go" 'receiver type `::Nope` of method `norm` is not a struct defined in this module'
+build_and_test_peachili_executable "variable_leaked_from_if.go" 'undefined such a variable -> `inner`'
+build_and_test_peachili_executable "variable_leaked_from_ifret.go" 'undefined such a variable -> `inner`'
+build_and_test_peachili_executable "variable_leaked_from_match_arm.go" 'undefined such a variable -> `inner`'
 
 echo -e "\n\nOK"
\ No newline at end of file
@@ -0,0 +1,5 @@
+import aarch64;
+
+func main(argc Int64, argv *ConstStr) Noreturn {
+    aarch64::exit_with(argc);
+}
@@ -0,0 +1,7 @@
+func pick(a Int64, b Int64, c Int64) Int64 {
+    return b;
+}
+
+func main() Int64 {
+    return pick(10, 21, 30);
+}
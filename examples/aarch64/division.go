@@ -0,0 +1,8 @@
+func main() Int64 {
+    varinit x Int64 = 90;
+    varinit y Int64 = 3;
+    varinit z Int64 = -y;
+
+    // 90 / 3 - 3 = 27
+    return x / y + z;
+}
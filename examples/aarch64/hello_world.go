@@ -0,0 +1,6 @@
+import aarch64;
+
+func main() Noreturn {
+    aarch64::write(aarch64::STDOUT, "Hello, world!\n");
+    aarch64::exit_with(0);
+}
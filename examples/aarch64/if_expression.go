@@ -0,0 +1,11 @@
+import aarch64;
+
+func main() Noreturn {
+	declare x Int64;
+	x = if(true) {
+		ifret 1;
+	} else {
+		ifret 2;
+	};
+	aarch64::exit_with(x);
+}
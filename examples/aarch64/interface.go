@@ -0,0 +1,35 @@
+import aarch64;
+
+pubinterface Answer {
+    get() Int64
+}
+
+struct Seven {
+    unused Int64
+}
+
+struct Twenty {
+    unused Int64
+}
+
+func (s *Seven) get() Int64 {
+    return 7;
+}
+
+func (t *Twenty) get() Int64 {
+    return 20;
+}
+
+func ask(a Answer) Int64 {
+    return a.get();
+}
+
+func main() Noreturn {
+    declare s Seven;
+    declare t Twenty;
+
+    varinit a Answer = &s;
+    varinit seven Int64 = a.get();
+
+    aarch64::exit_with(ask(&t));
+}
@@ -0,0 +1,8 @@
+func thirty() Int64 {
+    varinit x Int64 = 30;
+    return x;
+}
+
+func main() Int64 {
+    return thirty();
+}
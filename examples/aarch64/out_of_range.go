@@ -0,0 +1,10 @@
+func main() Int64 {
+    declare a [3]Int64;
+    a[0] = 1;
+    a[1] = 2;
+    a[2] = 3;
+
+    // 範囲外の添字は終了コード2で中断する
+    varinit i Int64 = 3;
+    return a[i];
+}
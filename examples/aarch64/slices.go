@@ -0,0 +1,38 @@
+import aarch64;
+
+struct Point {
+    x Int64
+    y Int64
+}
+
+func sum3(xs []Int64) Int64 {
+    return xs[0] + xs[1] + xs[2];
+}
+
+func main() Int64 {
+    declare a [5]Int64;
+    a[0] = 1;
+    a[1] = 2;
+    a[2] = 3;
+    a[3] = 4;
+    a[4] = 5;
+
+    // a[2:5] は {3, 4, 5}
+    declare s []Int64;
+    s = a[2:];
+    s[0] = 10;
+
+    declare bytes [4]Uint8;
+    bytes[1] = 255;
+    bytes[2] = 7;
+
+    declare ps [2]Point;
+    ps[1].y = 6;
+
+    varinit msg []Uint8 = "peachili\n";
+    aarch64::write(aarch64::STDOUT, msg[0:5]);
+    aarch64::write(aarch64::STDOUT, msg[8:]);
+
+    // 10 + 4 + 5 + 255 + 7 + 6 + 3 + 9 + 5 + 108('l') = 412 -> 156
+    return sum3(s) + bytes[1] + bytes[2] + ps[1].y + len(s) + len(msg) + len(a) + "peachili"[6];
+}
@@ -0,0 +1,5 @@
+import x64;
+
+func main(argc Int64, argv *ConstStr) Noreturn {
+    x64::exit_with(argc + 20);
+}
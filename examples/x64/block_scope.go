@@ -0,0 +1,12 @@
+func main() Int64 {
+    varinit x = 1;
+    varinit y = if (true) {
+        varinit x = 10;
+        varinit z = x + 5;
+        ifret z;
+    } else {
+        varinit z = 3;
+        ifret z;
+    };
+    return x + y + 5;
+}
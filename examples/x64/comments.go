@@ -0,0 +1,14 @@
+/*
+ * ブロックコメントは /* 入れ子にできる */
+ */
+
+/// 2つの値の和
+/// ドキュメントコメントは宣言に付与される
+func add(a Int64, b Int64) Int64 {
+    /// 関数本体の中では普通のコメントとして扱う
+    return a /* 式の途中 */ + b;
+}
+
+func main() Int64 {
+    return add(20, 1);
+}
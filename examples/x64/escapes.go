@@ -0,0 +1,13 @@
+import x64;
+
+func banner() Noreturn {
+    x64::write(x64::STDOUT, "\x41\tB\n");
+}
+
+func main() Int64 {
+    banner();
+    x64::write(x64::STDOUT, "\x41\tB\n");
+
+    // 4 + 34('"') + 92('\') + 0 = 130
+    return len("\x41\tB\n") + "\"\\\0"[0] + "\"\\\0"[1] + "\"\\\0"[2];
+}
@@ -0,0 +1,29 @@
+import x64;
+
+struct Calculator {
+    op *func(Int64, Int64) Int64
+}
+
+func add(a Int64, b Int64) Int64 {
+    return a + b;
+}
+
+func mul(a Int64, b Int64) Int64 {
+    return a * b;
+}
+
+func apply(f *func(Int64, Int64) Int64, a Int64, b Int64) Int64 {
+    return f(a, b);
+}
+
+func main() Noreturn {
+    varinit f *func(Int64, Int64) Int64 = &add;
+    varinit sum Int64 = f(3, 4);
+
+    declare calc Calculator;
+    calc.op = &mul;
+    varinit product Int64 = calc.op(2, 5);
+
+    varinit applied Int64 = apply(&add, 1, 3);
+    x64::exit_with(sum + product + applied);
+}
@@ -0,0 +1,29 @@
+import x64;
+
+struct Pair[T] {
+    first T
+    second T
+}
+
+func sum[T](p *Pair[T]) T {
+    return p.first + p.second;
+}
+
+func twice_sum[T](p *Pair[T]) T {
+    return sum[T](p) * 2;
+}
+
+func main() Noreturn {
+    declare ip Pair[Int64];
+    ip.first = 3;
+    ip.second = 4;
+
+    declare up Pair[Uint64];
+    up.first = 1u;
+    up.second = 2u;
+
+    varinit twice Int64 = twice_sum[Int64](&ip);
+    varinit once Int64 = sum[Int64](&ip);
+    sum[Uint64](&up);
+    x64::exit_with(twice + once);
+}
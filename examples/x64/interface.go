@@ -0,0 +1,53 @@
+import x64;
+
+pubinterface Shape {
+    area() Int64
+    scale(k Int64) Int64
+}
+
+struct Rect {
+    w Int64
+    h Int64
+}
+
+struct Square {
+    side Int64
+}
+
+func (r *Rect) area() Int64 {
+    return r.w * r.h;
+}
+
+func (r *Rect) scale(k Int64) Int64 {
+    return r.area() * k;
+}
+
+func (s *Square) area() Int64 {
+    return s.side * s.side;
+}
+
+func (s *Square) scale(k Int64) Int64 {
+    return s.side * k;
+}
+
+func twice(s Shape) Int64 {
+    return s.scale(2);
+}
+
+func main() Noreturn {
+    declare r Rect;
+    r.w = 2;
+    r.h = 3;
+
+    declare sq Square;
+    sq.side = 4;
+
+    varinit s Shape = &r;
+    varinit rect_area Int64 = s.area();
+
+    s = sq;
+    varinit square_area Int64 = s.area();
+    varinit doubled Int64 = twice(&r);
+
+    x64::exit_with(rect_area + square_area + doubled);
+}
@@ -0,0 +1,13 @@
+func main() Int64 {
+    declare bs [3]Uint8;
+    bs[0] = 'A';
+    bs[1] = '\n';
+    bs[2] = 0x7f;
+
+    varinit mask Int64 = 0b1111_0000;
+    varinit perm Int64 = 0o17;
+    varinit big Int64 = 1_000;
+
+    // 65 + 10 + 127 + 240 + 15 + 1000 = 1457 -> 177
+    return bs[0] + bs[1] + bs[2] + mask + perm + big;
+}
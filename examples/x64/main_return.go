@@ -0,0 +1,4 @@
+func main() Int64 {
+    varinit x Int64 = 21;
+    return x + 9;
+}
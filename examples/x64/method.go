@@ -0,0 +1,24 @@
+import x64;
+
+struct Point {
+    x Int64
+    y Int64
+}
+
+func (p *Point) sum() Int64 {
+    return p.x + p.y;
+}
+
+func (p *Point) scaled_sum(k Int64) Int64 {
+    return p.sum() * k;
+}
+
+func main() Noreturn {
+    declare pt Point;
+    pt.x = 3;
+    pt.y = 4;
+
+    varinit ptr *Point = &pt;
+    varinit scaled Int64 = pt.scaled_sum(2);
+    x64::exit_with(scaled + ptr.sum());
+}
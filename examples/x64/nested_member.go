@@ -0,0 +1,29 @@
+struct Inner {
+    x Int64
+    y Int64
+}
+
+struct Outer {
+    tag Int64
+    inner Inner
+    ptr *Inner
+}
+
+func main() Int64 {
+    declare o Outer;
+    declare i Inner;
+    o.tag = 1;
+    o.inner.x = 2;
+    o.inner.y = 3;
+
+    varinit p = &i;
+    p.x = 4;
+    (*p).y = 5;
+
+    o.ptr = p;
+    o.ptr.x = 10;
+    varinit a = o.tag + o.inner.x;
+    varinit b = o.inner.y + o.ptr.x;
+    varinit c = p.y;
+    return a + b + c;
+}
@@ -0,0 +1,16 @@
+import x64;
+
+struct A {
+    foo Int64
+    bar Int64
+}
+
+func main() Noreturn {
+  declare a A;
+  declare b Int64;
+  b = 40;
+  a.foo = 1;
+  a.bar = 2;
+
+  x64::exit_with(a.foo + a.bar + b);
+}
@@ -0,0 +1,28 @@
+struct Point {
+    x Int64
+    y Int64
+}
+
+struct Segment {
+    from Point
+    to Point
+}
+
+func main() Int64 {
+    varinit p = Point { x: 1, y: 2 };
+    varinit q Point = p;
+    q.x = 10;
+
+    declare s Segment;
+    s = Segment {
+        from: p,
+        to: Point { x: 3, y: 4 },
+    };
+    s.from = q;
+
+    varinit e = s.to;
+    varinit w = Point { y: 6, x: 5 }.x;
+    varinit a = p.x + q.x;
+    varinit b = s.from.x + e.y;
+    return a + b + w;
+}
@@ -0,0 +1,18 @@
+func add(a Int64, b Int64) Int64 {
+    return a + b;
+}
+
+#[test]
+func add_test() Int64 {
+    return add(10, 20) - 30;
+}
+
+// 返り値が0でないので，このテストは失敗する
+#[test]
+func broken_add_test() Int64 {
+    return add(10, 20) - 31;
+}
+
+func main() Int64 {
+    return add(10, 20);
+}
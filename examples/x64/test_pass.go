@@ -0,0 +1,17 @@
+func add(a Int64, b Int64) Int64 {
+    return a + b;
+}
+
+#[test]
+func add_test() Int64 {
+    return add(10, 20) - 30;
+}
+
+#[test]
+func add_zero_test() Int64 {
+    return add(21, 0) - 21;
+}
+
+func main() Int64 {
+    return add(10, 20);
+}
@@ -0,0 +1,15 @@
+import x64;
+
+func double(x Int64) Int64 {
+    return x * 2;
+}
+
+func main() Int64 {
+    varinit fd x64::FileDescriptor = 1;
+    varinit u Uint64 = 30;
+    varinit size = 5;
+    varinit twice = double(size);
+    varinit sum = 1 + twice;
+    const base = 10;
+    return sum + base;
+}
@@ -0,0 +1,20 @@
+struct Pair {
+    x Int64
+    y Int64
+}
+
+func main() Int64 {
+    declare p Pair;
+    p.x = 3;
+
+    declare q Pair;
+    q = p;
+
+    declare a Int64;
+    if (true) {
+        a = 4;
+    } else {
+        a = 100;
+    };
+    return p.x + p.y + q.x + a;
+}
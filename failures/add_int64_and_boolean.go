@@ -1,9 +1,7 @@
-require (
-    "std"
-)
+import x64;
 
 func main() Noreturn {
     declare x Int64;
     x = 30 + true;
-    std::os::exit_with(2);
+    x64::exit_with(2);
 }
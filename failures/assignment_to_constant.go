@@ -1,11 +1,9 @@
-require (
-    "std"
-)
+import x64;
 
 func main() Noreturn {
     varinit x Int64 = 30;
     const y Int64 = x;
     y = y + 2;
 
-    std::os::exit_with(0);
+    x64::exit_with(0);
 }
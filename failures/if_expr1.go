@@ -1,6 +1,4 @@
-require (
-    "std"
-)
+import x64;
 func main() Noreturn {
 	declare x Int64;
 
@@ -11,5 +9,5 @@ func main() Noreturn {
 		ifret 0;
 	};
 
-	std::os::exit_with(x);
+	x64::exit_with(x);
 }
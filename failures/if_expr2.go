@@ -1,6 +1,4 @@
-require (
-    "std"
-)
+import x64;
 func main() Noreturn {
 	declare x Int64;
 
@@ -10,5 +8,5 @@ func main() Noreturn {
 	} else { 
 		ifret 0; 
 	}; 
-	std::os::exit_with(x);
+	x64::exit_with(x);
 }
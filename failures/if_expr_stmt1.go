@@ -1,11 +1,9 @@
-require (
-    "std"
-)
+import x64;
 func main() Noreturn {
 
     // if式内の条件式はBoolean型のみ
 	if (1) {
-		std::os::exit_with(1);
+		x64::exit_with(1);
 	};
-	std::os::exit_with(0);
+	x64::exit_with(0);
 }
@@ -1,8 +1,6 @@
-require (
-    "std"
-)
+import x64;
 func main() Noreturn {
     declare x Boolean;
 	x = 24;
-	std::os::exit_with(0);
+	x64::exit_with(0);
 }
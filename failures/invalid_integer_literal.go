@@ -1,9 +1,7 @@
-require (
-    "std"
-)
+import x64;
 
 func main() Noreturn {
-    declare status int64;
+    declare status Int64;
     status = 12343278421789312748923173892147983217938762189473129807329107329180743920817490237189203798321074890312;
-    std::os::exit_with(status);
+    x64::exit_with(status);
 }
@@ -1,6 +1,4 @@
-require (
-    "std"
-)
+import x64;
 
 struct X {
     exist Boolean
@@ -9,5 +7,5 @@ struct X {
 func main() Noreturn {
     declare x X;
 	x.foo = true;
-	std::os::exit_with(x.foo);
+	x64::exit_with(x.foo);
 }
@@ -1,6 +1,4 @@
-require (
-    "std"
-)
+import x64;
 
 func main() Noreturn {
   declare x Boolean;
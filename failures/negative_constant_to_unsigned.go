@@ -0,0 +1,3 @@
+func main() Noreturn {
+    varinit x Uint64 = -1;
+}
@@ -1,2 +1,2 @@
-func not_main() noreturn {
+func not_main() Noreturn {
 }
\ No newline at end of file
@@ -1,3 +1,3 @@
-func main() noreturn {
+func main() Noreturn {
     return 30;
 }
\ No newline at end of file
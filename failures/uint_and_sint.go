@@ -1,6 +1,4 @@
-require (
-    "std"
-)
+import x64;
 
 func main() Noreturn {
     declare x Uint64;
@@ -10,5 +8,5 @@ func main() Noreturn {
 
     declare z Int64;
     z = x + y;
-    std::os::exit_with(z);
+    x64::exit_with(z);
 }
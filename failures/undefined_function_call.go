@@ -1,7 +1,5 @@
-require (
-    "std"
-)
-func main() noreturn {
+import x64;
+func main() Noreturn {
   // exit_with はあるけど exit はない
-	std::os::exit(0);
+	x64::exit(0);
 }
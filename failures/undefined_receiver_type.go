@@ -0,0 +1,7 @@
+func (p *Nope) norm() Int64 {
+    return 0;
+}
+
+func main() Int64 {
+    return 0;
+}
@@ -0,0 +1,4 @@
+func main() Int64 {
+    declare x Int64;
+    return x;
+}
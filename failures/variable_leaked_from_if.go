@@ -0,0 +1,6 @@
+func main() Int64 {
+    if (true) {
+        varinit inner Int64 = 7;
+    };
+    return inner;
+}
@@ -0,0 +1,9 @@
+func main() Int64 {
+    varinit y Int64 = if (true) {
+        varinit inner Int64 = 10;
+        ifret inner;
+    } else {
+        ifret 3;
+    };
+    return y + inner;
+}
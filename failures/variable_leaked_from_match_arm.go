@@ -0,0 +1,17 @@
+pubenum Color {
+    Red,
+    Blue,
+}
+
+func main() Int64 {
+    varinit c Color = Color::Red;
+    match c {
+        Color::Red -> {
+            varinit inner Int64 = 1;
+        },
+        Color::Blue -> {
+            varinit other Int64 = 2;
+        },
+    };
+    return inner;
+}
@@ -1,15 +1,23 @@
+/// statusを終了ステータスとしてプロセスを終了する
 func exit_with(status Int64) Noreturn {
-     asm {
-			 "mov x8, #93"; // 64bit linuxにおけるexitシステムコール
-			 "svc #0";
-     };
- }
+    asm {
+        "mov x8, #93"; // 64bit linuxにおけるexitシステムコール
+        "svc #0";
+    };
+}
 
-func write(fd FileDescriptor, buf ConstStr, count Int64) Noreturn {
+/// fdにbufの内容を書き込む
+// bufの先頭アドレスと長さが，それぞれx1/x2に渡される
+func write(fd FileDescriptor, buf []Uint8) Noreturn {
     asm {
-			 "mov x8, #64"; // 64bit linuxにおけるwriteシステムコール
-			 "svc #0";
+        "mov x8, #64"; // 64bit linuxにおけるwriteシステムコール
+        "svc #0";
     };
 }
 
+/// ファイルディスクリプタ
 pubtype FileDescriptor = Uint64;
+
+pubconst STDIN : FileDescriptor = 0;
+pubconst STDOUT : FileDescriptor = 1;
+pubconst STDERR : FileDescriptor = 2;
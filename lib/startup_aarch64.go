@@ -1,8 +1,31 @@
-func initialize() noreturn {
+func initialize() Noreturn {
     asm {
-        "bl main",
-        "mov x0, x1", // main関数の返り値(通常は0)をプロセス全体の返り値に
-        "mov x8, #93", // 64bit linuxにおけるexitシステムコール
-        "svc #0"
+        "ldr x0, [x29, #16]"; // argc(エントリ時のスタックトップ，プロローグで退避したfp/lrの直上)
+        "add x1, x29, #24"; // argv
+        "add x2, x1, x0, lsl #3";
+        "add x2, x2, #8"; // envp(argvの終端NULLの次)
+        "bl main";
+        // main関数の返り値(通常は0)はx0に入っているので，そのままプロセス全体の返り値にする
+        "mov x8, #93"; // 64bit linuxにおけるexitシステムコール
+        "svc #0";
+    };
+}
+
+// 添字/スライスの範囲外アクセスで飛んでくる
+func index_out_of_range() Noreturn {
+    startup::abort("index out of range\n");
+}
+
+// 標準エラー出力にメッセージを書き出し，終了コード2で終了する
+func abort(msg []Uint8) Noreturn {
+    asm {
+        "mov x2, x1"; // 長さ
+        "mov x1, x0"; // 先頭アドレス
+        "mov x0, #2"; // 標準エラー出力
+        "mov x8, #64"; // 64bit linuxにおけるwriteシステムコール
+        "svc #0";
+        "mov x0, #2";
+        "mov x8, #93"; // 64bit linuxにおけるexitシステムコール
+        "svc #0";
     };
 }
@@ -1,8 +1,77 @@
 func initialize() Noreturn {
     asm {
+        "movq 8(%rbp), %rdi"; // argc(エントリ時のスタックトップ)
+        "leaq 16(%rbp), %rsi"; // argv
+        "leaq 8(%rsi,%rdi,8), %rdx"; // envp(argvの終端NULLの次)
         "call main";
         "movq %rax, %rdi"; // main関数の返り値(通常は0)をプロセス全体の返り値に
         "movq $60, %rax"; // 64bit linuxにおけるexitシステムコール
         "syscall";
     };
 }
+
+// 添字/スライスの範囲外アクセスで飛んでくる
+func index_out_of_range() Noreturn {
+    startup::abort("index out of range\n");
+}
+
+// 標準エラー出力にメッセージを書き出し，終了コード2で終了する
+func abort(msg []Uint8) Noreturn {
+    asm {
+        "movq %rsi, %rdx"; // 長さ
+        "movq %rdi, %rsi"; // 先頭アドレス
+        "movq $2, %rdi"; // 標準エラー出力
+        "movq $1, %rax"; // 64bit linuxにおけるwriteシステムコール
+        "syscall";
+        "movq $2, %rdi";
+        "movq $60, %rax"; // 64bit linuxにおけるexitシステムコール
+        "syscall";
+    };
+}
+
+// 以下は `peachili test` が生成するハーネス `startup::run_tests` から呼び出す
+
+// forkし，親プロセスではin_parentにtrue(子のpid)，子プロセスではfalse(0)を書き込む
+func fork(in_parent *Boolean) Noreturn {
+    asm {
+        "movq $57, %rax"; // 64bit linuxにおけるforkシステムコール
+        "syscall";
+        "movq %rax, (%rdi)";
+    };
+}
+
+// 子プロセスの終了を待ち，異常終了していればfailedにtrue(0以外の終了ステータス)を書き込む
+func wait_child(failed *Boolean) Noreturn {
+    asm {
+        "pushq %rdi";
+        "pushq $0"; // 終了ステータスの格納先
+        "movq $-1, %rdi"; // 任意の子プロセス
+        "movq %rsp, %rsi";
+        "movq $0, %rdx";
+        "movq $0, %r10";
+        "movq $61, %rax"; // 64bit linuxにおけるwait4システムコール
+        "syscall";
+        "popq %rax";
+        "popq %rdi";
+        "movq %rax, (%rdi)";
+    };
+}
+
+// 標準出力にメッセージを書き出す
+func print(msg []Uint8) Noreturn {
+    asm {
+        "movq %rsi, %rdx"; // 長さ
+        "movq %rdi, %rsi"; // 先頭アドレス
+        "movq $1, %rdi"; // 標準出力
+        "movq $1, %rax"; // 64bit linuxにおけるwriteシステムコール
+        "syscall";
+    };
+}
+
+// 指定した終了コードでプロセスを終了する
+func exit(status Int64) Noreturn {
+    asm {
+        "movq $60, %rax"; // 64bit linuxにおけるexitシステムコール
+        "syscall";
+    };
+}
@@ -1,3 +1,4 @@
+/// statusを終了ステータスとしてプロセスを終了する
 func exit_with(status Int64) Noreturn {
     asm {
         "movq $60, %rax";
@@ -5,15 +6,18 @@ func exit_with(status Int64) Noreturn {
     };
 }
 
-func write(fd FileDescriptor, buf ConstStr, count Int64) Noreturn {
+/// fdにbufの内容を書き込む
+// bufの先頭アドレスと長さが，それぞれrsi/rdxに渡される
+func write(fd FileDescriptor, buf []Uint8) Noreturn {
     asm {
         "movq $1, %rax";
         "syscall";
     };
 }
 
+/// ファイルディスクリプタ
 pubtype FileDescriptor = Uint64;
 
-pubconst STDIN : FileDescriptor = 0u;
-pubconst STDOUT : FileDescriptor = 1u;
-pubconst STDERR : FileDescriptor = 2u;
+pubconst STDIN : FileDescriptor = 0;
+pubconst STDOUT : FileDescriptor = 1;
+pubconst STDERR : FileDescriptor = 2;
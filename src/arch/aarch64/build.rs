@@ -1,5 +1,6 @@
 use crate::arch::aarch64;
 use crate::common;
+use crate::interpreter;
 use crate::setup;
 
 /// aarch64アーキテクチャ向けのビルドルーチン
@@ -20,6 +21,14 @@ pub fn main(
 
             common::file_util::write_program_into("asm.s", aarch64_module.to_assembly());
         }
+        ("run", Some(_run_m)) => {
+            eprintln!("running native code is only supported on x86_64. use --interp.");
+            std::process::exit(interpreter::FAILED_TO_RUN);
+        }
+        ("test", Some(_test_m)) => {
+            eprintln!("running tests is only supported on x86_64.");
+            std::process::exit(interpreter::FAILED_TO_RUN);
+        }
         _ => eprintln!("please specify a subcommand. see --help."),
     }
     Ok(())
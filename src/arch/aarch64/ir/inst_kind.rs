@@ -23,6 +23,13 @@ pub enum InstKind {
         lop: ir::Operand,
         rop: ir::Operand,
     },
+    /// Signed Divide
+    SDIV {
+        operand_size: ir::OperandSize,
+        dst: ir::Operand,
+        lop: ir::Operand,
+        rop: ir::Operand,
+    },
     /// NEG
     NEG {
         operand_size: ir::OperandSize,
@@ -54,6 +61,10 @@ pub enum InstKind {
         dst: ir::Operand,
         src: ir::Operand,
     },
+    /// Load Register Byte (ゼロ拡張)
+    LDRB { dst: ir::Operand, src: ir::Operand },
+    /// Store Register Byte
+    STRB { dst: ir::Operand, src: ir::Operand },
     /// Load Register Pair
     LDP {
         operand_size: ir::OperandSize,
@@ -62,8 +73,27 @@ pub enum InstKind {
         src: ir::Operand,
     },
 
+    /// Compare
+    CMP {
+        operand_size: ir::OperandSize,
+        lop: ir::Operand,
+        rop: ir::Operand,
+    },
+
+    /// Branch (関数内のラベルへ)
+    B { label: String },
+    /// Compare and Branch on Zero
+    CBZ { value: ir::Operand, label: String },
+    /// Branch if unsigned higher
+    BHI { name: String },
+    /// Branch if unsigned higher or same
+    BHS { name: String },
     /// Branch with Link.
     BL { name: String },
+    /// Branch with Link to Register.
+    BLR { reg: ir::Register },
+    /// Form PC-relative address
+    ADR { dst: ir::Operand, label: String },
 
     /// Inline Assembly
     INLINEASM { contents: String },
@@ -46,6 +46,19 @@ impl Instruction {
                     rop.to_dword()
                 ),
             },
+            ir::InstKind::SDIV {
+                operand_size,
+                dst,
+                lop,
+                rop,
+            } => match operand_size {
+                ir::OperandSize::DWORD => format!(
+                    "sdiv {}, {}, {}",
+                    dst.to_dword(),
+                    lop.to_dword(),
+                    rop.to_dword()
+                ),
+            },
             ir::InstKind::NEG {
                 operand_size,
                 dst,
@@ -87,6 +100,12 @@ impl Instruction {
             } => match operand_size {
                 ir::OperandSize::DWORD => format!("ldr {}, {}", dst.to_dword(), src.to_dword()),
             },
+            ir::InstKind::LDRB { dst, src } => {
+                format!("ldrb {}, {}", dst.to_word(), src.to_dword())
+            }
+            ir::InstKind::STRB { dst, src } => {
+                format!("strb {}, {}", src.to_word(), dst.to_dword())
+            }
             ir::InstKind::LDP {
                 operand_size,
                 reg1,
@@ -100,7 +119,22 @@ impl Instruction {
                     src.to_dword()
                 ),
             },
+            ir::InstKind::CMP {
+                operand_size,
+                lop,
+                rop,
+            } => match operand_size {
+                ir::OperandSize::DWORD => format!("cmp {}, {}", lop.to_dword(), rop.to_dword()),
+            },
+            ir::InstKind::B { label } => format!("b \"{}\"", label),
+            ir::InstKind::CBZ { value, label } => {
+                format!("cbz {}, \"{}\"", value.to_dword(), label)
+            }
+            ir::InstKind::BHI { name } => format!("b.hi \"{}\"", name),
+            ir::InstKind::BHS { name } => format!("b.hs \"{}\"", name),
             ir::InstKind::BL { name } => format!("bl \"{}\"", name),
+            ir::InstKind::BLR { reg } => format!("blr {}", reg.to_dword()),
+            ir::InstKind::ADR { dst, label } => format!("adr {}, \"{}\"", dst.to_dword(), label),
             ir::InstKind::INLINEASM { contents } => contents.to_string(),
             ir::InstKind::RET => "ret".to_string(),
         }
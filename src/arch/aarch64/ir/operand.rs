@@ -24,6 +24,14 @@ impl Operand {
         }
     }
 
+    /// バイト単位のロード/ストアで用いる，32bitレジスタとしての表記
+    pub fn to_word(&self) -> String {
+        match &self.kind {
+            OperandKind::REGISTER { reg } => reg.to_word(),
+            _ => unreachable!(),
+        }
+    }
+
     pub fn new(kind: OperandKind) -> Self {
         Self { kind }
     }
@@ -41,6 +49,12 @@ impl Operand {
     pub fn get_kind(&self) -> &OperandKind {
         &self.kind
     }
+    pub fn get_reg(&self) -> Register {
+        match self.kind {
+            OperandKind::REGISTER { reg } => reg,
+            _ => unreachable!(),
+        }
+    }
     pub fn get_base_reg(&self) -> Register {
         match self.kind {
             OperandKind::MEMORY { base, offset: _ } => base,
@@ -76,8 +90,8 @@ pub enum Register {
 }
 
 impl Register {
-    /// X8 ~ X18
-    pub const AVAILABLES: usize = 10;
+    /// 一時変数に割り当てる X9 ~ X15
+    pub const AVAILABLES: usize = 7;
 
     pub fn to_dword(&self) -> String {
         match self {
@@ -87,4 +101,11 @@ impl Register {
             Register::GPR { number } => format!("x{}", number),
         }
     }
+
+    pub fn to_word(&self) -> String {
+        match self {
+            Register::GPR { number } => format!("w{}", number),
+            _ => unreachable!(),
+        }
+    }
 }
@@ -1,37 +1,61 @@
 use crate::arch::aarch64::ir as lir;
 use crate::common::analyze_resource::frame_object::StackFrame;
+use crate::common::analyze_resource::peachili_type::{Type, TypeKind};
+use crate::common::analyze_resource::string_pool::StringPool;
+use crate::common::ast;
 use crate::common::three_address_code as tac;
+use std::collections::BTreeMap;
 
 pub fn codegen_main(ir_module: tac::IRModule, stack_frame: StackFrame) -> lir::Module {
     let mut aarch64_module: lir::Module = Default::default();
+    let mut strings: StringPool = Default::default();
 
     for tac_fn_id in ir_module.funcs.iter() {
         let tac_fn = ir_module.get_fn(tac_fn_id);
-        let aarch64_fn = gen_aarch64_fn(tac_fn, &stack_frame);
+        let aarch64_fn = gen_aarch64_fn(tac_fn, &stack_frame, &mut strings);
         aarch64_module.push_function(aarch64_fn);
     }
+    aarch64_module.set_strings(strings);
+
+    for (label, symbols) in ir_module.vtables.iter() {
+        aarch64_module.push_vtable(label.to_string(), symbols.clone());
+    }
 
     aarch64_module
 }
 
-fn gen_aarch64_fn(tac_fn: &tac::IRFunction, stack_frame: &StackFrame) -> lir::Function {
+fn gen_aarch64_fn(
+    tac_fn: &tac::IRFunction,
+    stack_frame: &StackFrame,
+    strings: &mut StringPool,
+) -> lir::Function {
     let mut aarch64_fn = lir::Function::new(&tac_fn.name);
     aarch64_fn.push_block("entry");
 
-    let mut generator = FunctionGenerator::new(aarch64_fn, stack_frame);
+    let mut generator = FunctionGenerator::new(aarch64_fn, stack_frame, strings);
 
     // prologue
     generator.gen_function_prologue();
 
     // 引数定義があったらその分storeする
-    // generator.gen_arguments_to_stack(tac_fn);
+    generator.gen_arguments_to_stack(tac_fn);
 
     for code_id in tac_fn.codes.iter() {
         let code = tac_fn.get_code(*code_id);
         generator.gen_aarch64_inst(tac_fn, code);
     }
 
-    generator.gen_function_epilogue();
+    if let TypeKind::FUNCTION {
+        return_type,
+        args: _,
+    } = &tac_fn.fn_ty.kind
+    {
+        if return_type.kind == TypeKind::NORETURN {
+            generator.gen_function_epilogue();
+
+            generator.gen_inst_to_last_bb(lir::InstKind::RET);
+        }
+    }
 
     generator.f
 }
@@ -39,18 +63,91 @@ fn gen_aarch64_fn(tac_fn: &tac::IRFunction, stack_frame: &StackFrame) -> lir::Fu
 struct FunctionGenerator<'a> {
     f: lir::Function,
     param_count: usize,
-    virtual_number: usize,
+    /// 三番地コードの一時変数番号 => 物理レジスタ
+    virt_to_phys: BTreeMap<usize, lir::Register>,
     frame: &'a StackFrame,
+    /// モジュール全体の文字列リテラルプール
+    strings: &'a mut StringPool,
 }
 
 impl<'a> FunctionGenerator<'a> {
     /// IRタイプごとに命令を生成する
     fn gen_aarch64_inst(&mut self, tac_fn: &tac::IRFunction, code: tac::Code) {
         match code.kind {
+            tac::CodeKind::RETURN { value } => self.gen_return_inst(tac_fn, value),
+            tac::CodeKind::ADD { lop, rop, result } => {
+                self.gen_binop_inst(tac_fn, lop, rop, result, |dst, lop, rop| {
+                    lir::InstKind::ADD {
+                        operand_size: lir::OperandSize::DWORD,
+                        dst,
+                        lop,
+                        rop,
+                    }
+                })
+            }
+            tac::CodeKind::SUB { lop, rop, result } => {
+                self.gen_binop_inst(tac_fn, lop, rop, result, |dst, lop, rop| {
+                    lir::InstKind::SUB {
+                        operand_size: lir::OperandSize::DWORD,
+                        dst,
+                        lop,
+                        rop,
+                    }
+                })
+            }
+            tac::CodeKind::MUL { lop, rop, result } => {
+                self.gen_binop_inst(tac_fn, lop, rop, result, |dst, lop, rop| {
+                    lir::InstKind::MUL {
+                        operand_size: lir::OperandSize::DWORD,
+                        dst,
+                        lop,
+                        rop,
+                    }
+                })
+            }
+            tac::CodeKind::DIV { lop, rop, result } => {
+                self.gen_binop_inst(tac_fn, lop, rop, result, |dst, lop, rop| {
+                    lir::InstKind::SDIV {
+                        operand_size: lir::OperandSize::DWORD,
+                        dst,
+                        lop,
+                        rop,
+                    }
+                })
+            }
+            tac::CodeKind::NEG { value, result } => self.gen_neg_inst(tac_fn, value, result),
             tac::CodeKind::ADDRESSOF { value, result } => {
                 self.gen_address_inst(tac_fn, value, result)
             }
+            tac::CodeKind::MEMBER { id, member, result } => {
+                self.gen_member_inst(tac_fn, id, &member, result)
+            }
+            tac::CodeKind::ASSIGN { value, result } => self.gen_assign_inst(tac_fn, value, result),
+            tac::CodeKind::DEREFERENCE { value, result } => {
+                self.gen_deref_inst(tac_fn, value, result)
+            }
             tac::CodeKind::STORE { value, result } => self.gen_store_inst(tac_fn, value, result),
+            tac::CodeKind::COPY { src, dst, size } => self.gen_copy_inst(tac_fn, src, dst, size),
+            tac::CodeKind::ZEROINIT { value } => self.gen_zeroinit_inst(tac_fn, value),
+            tac::CodeKind::LOADBYTE { addr, result } => {
+                self.gen_loadbyte_inst(tac_fn, addr, result)
+            }
+            tac::CodeKind::STOREBYTE { value, addr } => {
+                self.gen_storebyte_inst(tac_fn, value, addr)
+            }
+            tac::CodeKind::BOUNDSCHECK {
+                index,
+                len,
+                inclusive,
+            } => self.gen_bounds_check_inst(tac_fn, index, len, inclusive),
+            tac::CodeKind::LABEL { name } => self.f.push_block(&name),
+            tac::CodeKind::JUMP { label } => {
+                let label = format!("{}_{}", self.f.get_name(), label);
+                self.gen_inst_to_last_bb(lir::InstKind::B { label });
+            }
+            tac::CodeKind::JUMPIFFALSE { label, cond_result } => {
+                self.gen_jump_if_false_inst(tac_fn, label, cond_result)
+            }
             tac::CodeKind::PARAM { value } => self.gen_param_inst(tac_fn, value),
             tac::CodeKind::CALL { name, result } => self.gen_call_inst(tac_fn, name, result),
             tac::CodeKind::ASM { value } => {
@@ -59,10 +156,172 @@ impl<'a> FunctionGenerator<'a> {
                     contents: asm_literal.copy_contents(),
                 });
             }
-            _ => eprintln!("unimplemented {:?} inst", code.kind),
+            tac::CodeKind::VTABLE { label, result } => {
+                let result_value = tac_fn.get_value(result);
+                let result_op = self.operand_from_value(result_value);
+                self.gen_inst_to_last_bb(lir::InstKind::ADR {
+                    dst: result_op,
+                    label,
+                });
+            }
+            tac::CodeKind::FUNCADDR { name, result } => {
+                let result_value = tac_fn.get_value(result);
+                let result_op = self.operand_from_value(result_value);
+                self.gen_inst_to_last_bb(lir::InstKind::ADR {
+                    dst: result_op,
+                    label: name,
+                });
+            }
+            tac::CodeKind::ICALL { callee, result } => self.gen_icall_inst(tac_fn, callee, result),
+            tac::CodeKind::VCALL {
+                iface,
+                index,
+                result,
+            } => self.gen_vcall_inst(tac_fn, iface, index, result),
+            tac::CodeKind::ALLOC { temp: _ } => {}
+        }
+    }
+
+    /// 符号反転の結果を一時変数のレジスタに置く
+    fn gen_neg_inst(
+        &mut self,
+        tac_fn: &tac::IRFunction,
+        value: tac::ValueId,
+        result: tac::ValueId,
+    ) {
+        let value = tac_fn.get_value(value);
+        let value_op = self.operand_from_value(value);
+        let value_op = self.load_to_register(value_op);
+        let result = tac_fn.get_value(result);
+        let result_op = self.operand_from_value(result);
+
+        self.gen_inst_to_last_bb(lir::InstKind::NEG {
+            operand_size: lir::OperandSize::DWORD,
+            dst: result_op,
+            value: value_op,
+        });
+    }
+
+    /// 変数のメンバを8バイトロードする
+    fn gen_member_inst(
+        &mut self,
+        tac_fn: &tac::IRFunction,
+        id: tac::ValueId,
+        member: &str,
+        result: tac::ValueId,
+    ) {
+        let id_value = tac_fn.get_value(id);
+        let member_offset = id_value.ty.member_offset(member).unwrap();
+        let id_op = self.operand_from_value(id_value);
+        let result = tac_fn.get_value(result);
+        let result_op = self.operand_from_value(result);
+
+        match id_op.get_kind() {
+            // 構造体の先頭(最下位アドレス)からメンバオフセット分上位にある
+            lir::OperandKind::MEMORY { base, offset } => {
+                self.gen_inst_to_last_bb(lir::InstKind::LDR {
+                    operand_size: lir::OperandSize::DWORD,
+                    dst: result_op,
+                    src: lir::Operand::new_memory(*base, offset + member_offset as isize),
+                });
+            }
+            _ => {
+                self.gen_inst_to_last_bb(lir::InstKind::MOV {
+                    operand_size: lir::OperandSize::DWORD,
+                    dst: result_op,
+                    src: id_op,
+                });
+            }
+        }
+    }
+
+    /// addrの1バイトをゼロ拡張してロードする
+    fn gen_loadbyte_inst(
+        &mut self,
+        tac_fn: &tac::IRFunction,
+        addr: tac::ValueId,
+        result: tac::ValueId,
+    ) {
+        let addr = tac_fn.get_value(addr);
+        let addr_op = self.operand_from_value(addr);
+        let addr_op = self.load_to_register(addr_op);
+        let result = tac_fn.get_value(result);
+        let result_op = self.operand_from_value(result);
+
+        self.gen_inst_to_last_bb(lir::InstKind::LDRB {
+            dst: result_op,
+            src: lir::Operand::new_memory(addr_op.get_reg(), 0),
+        });
+    }
+
+    /// valueの下位1バイトをaddrに書き込む
+    /// 値はIP1(x17)に置かれうるので，アドレスはIP0(x16)にロードする
+    fn gen_storebyte_inst(
+        &mut self,
+        tac_fn: &tac::IRFunction,
+        value: tac::ValueId,
+        addr: tac::ValueId,
+    ) {
+        let value = tac_fn.get_value(value);
+        let value_op = self.operand_from_value(value);
+        let value_op = self.load_to_register(value_op);
+        let addr = tac_fn.get_value(addr);
+        let addr_op = self.operand_from_value(addr);
+        let addr_op = self.load_to_ip0(addr_op);
+
+        self.gen_inst_to_last_bb(lir::InstKind::STRB {
+            dst: lir::Operand::new_memory(addr_op.get_reg(), 0),
+            src: value_op,
+        });
+    }
+
+    /// 符号なし比較で範囲外であれば，中断ルーチンへ飛ぶ
+    /// 添字はIP0(x16)に，要素数はIP1(x17)に置いて比較する
+    fn gen_bounds_check_inst(
+        &mut self,
+        tac_fn: &tac::IRFunction,
+        index: tac::ValueId,
+        len: tac::ValueId,
+        inclusive: bool,
+    ) {
+        let index = tac_fn.get_value(index);
+        let index_op = self.operand_from_value(index);
+        let index_op = self.load_to_ip0(index_op);
+        let len = tac_fn.get_value(len);
+        let len_op = self.operand_from_value(len);
+        let len_op = self.load_to_register(len_op);
+
+        self.gen_inst_to_last_bb(lir::InstKind::CMP {
+            operand_size: lir::OperandSize::DWORD,
+            lop: index_op,
+            rop: len_op,
+        });
+
+        let name = ast::INDEX_OUT_OF_RANGE_ROUTINE.to_string();
+        if inclusive {
+            self.gen_inst_to_last_bb(lir::InstKind::BHI { name });
+        } else {
+            self.gen_inst_to_last_bb(lir::InstKind::BHS { name });
         }
     }
 
+    /// 条件が偽(0)であれば関数内のラベルへ分岐する
+    fn gen_jump_if_false_inst(
+        &mut self,
+        tac_fn: &tac::IRFunction,
+        label: String,
+        cond_result: tac::ValueId,
+    ) {
+        let cond = tac_fn.get_value(cond_result);
+        let cond_op = self.operand_from_value(cond);
+        let cond_op = self.load_to_register(cond_op);
+
+        self.gen_inst_to_last_bb(lir::InstKind::CBZ {
+            value: cond_op,
+            label: format!("{}_{}", self.f.get_name(), label),
+        });
+    }
+
     fn gen_store_inst(
         &mut self,
         tac_fn: &tac::IRFunction,
@@ -71,8 +330,8 @@ impl<'a> FunctionGenerator<'a> {
     ) {
         let src_value = tac_fn.get_value(src);
         let src_op = self.operand_from_value(src_value);
+        let src_op = self.load_to_register(src_op);
 
-        self.virtual_number -= 2;
         let result_value = tac_fn.get_value(result);
         let result_op = self.operand_from_value(result_value);
 
@@ -94,6 +353,137 @@ impl<'a> FunctionGenerator<'a> {
         }
     }
 
+    /// 左オペランドを結果のレジスタに置いてから演算する
+    /// 即値は両オペランドともIP1(x17)にロードされるので，先に左を退避しておく
+    fn gen_binop_inst(
+        &mut self,
+        tac_fn: &tac::IRFunction,
+        lop: tac::ValueId,
+        rop: tac::ValueId,
+        result: tac::ValueId,
+        inst: fn(lir::Operand, lir::Operand, lir::Operand) -> lir::InstKind,
+    ) {
+        let result = tac_fn.get_value(result);
+        let result_op = self.operand_from_value(result);
+
+        let lop = tac_fn.get_value(lop);
+        let lop_op = self.operand_from_value(lop);
+        let lop_op = self.load_to_register(lop_op);
+        self.gen_inst_to_last_bb(lir::InstKind::MOV {
+            operand_size: lir::OperandSize::DWORD,
+            dst: result_op,
+            src: lop_op,
+        });
+
+        let rop = tac_fn.get_value(rop);
+        let rop_op = self.operand_from_value(rop);
+        let rop_op = self.load_to_register(rop_op);
+        self.gen_inst_to_last_bb(inst(result_op, result_op, rop_op));
+    }
+
+    /// 変数への代入はstr，一時変数への代入はmovとする
+    fn gen_assign_inst(
+        &mut self,
+        tac_fn: &tac::IRFunction,
+        value: tac::ValueId,
+        result: tac::ValueId,
+    ) {
+        let value = tac_fn.get_value(value);
+        let value_op = self.operand_from_value(value);
+        let value_op = self.load_to_register(value_op);
+        let result = tac_fn.get_value(result);
+        let result_op = self.operand_from_value(result);
+
+        match result_op.get_kind() {
+            lir::OperandKind::MEMORY { base: _, offset: _ } => {
+                self.gen_inst_to_last_bb(lir::InstKind::STR {
+                    operand_size: lir::OperandSize::DWORD,
+                    dst: result_op,
+                    src: value_op,
+                });
+            }
+            _ => {
+                self.gen_inst_to_last_bb(lir::InstKind::MOV {
+                    operand_size: lir::OperandSize::DWORD,
+                    dst: result_op,
+                    src: value_op,
+                });
+            }
+        }
+    }
+
+    /// アドレスを保持する値から，その指す先の8バイトをロードする
+    fn gen_deref_inst(
+        &mut self,
+        tac_fn: &tac::IRFunction,
+        value: tac::ValueId,
+        result: tac::ValueId,
+    ) {
+        let value = tac_fn.get_value(value);
+        let value_op = self.operand_from_value(value);
+        let addr_op = self.load_to_register(value_op);
+        let result = tac_fn.get_value(result);
+        let result_op = self.operand_from_value(result);
+
+        self.gen_inst_to_last_bb(lir::InstKind::LDR {
+            operand_size: lir::OperandSize::DWORD,
+            dst: result_op,
+            src: lir::Operand::new_memory(addr_op.get_reg(), 0),
+        });
+    }
+
+    /// 変数の領域を，先頭から8バイトずつ0で埋める
+    fn gen_zeroinit_inst(&mut self, tac_fn: &tac::IRFunction, value: tac::ValueId) {
+        let ip0 = lir::Operand::new_register(lir::Register::GPR { number: 16 });
+        let var_value = tac_fn.get_value(value);
+        let size = var_value.ty.size;
+        let var_op = self.operand_from_value(var_value);
+
+        self.gen_inst_to_last_bb(lir::InstKind::MOV {
+            operand_size: lir::OperandSize::DWORD,
+            dst: ip0,
+            src: lir::Operand::new_immediate(0),
+        });
+        for word_offset in (0..size).step_by(8) {
+            self.gen_inst_to_last_bb(lir::InstKind::STR {
+                operand_size: lir::OperandSize::DWORD,
+                dst: lir::Operand::new_memory(
+                    var_op.get_base_reg(),
+                    var_op.get_offset() + word_offset as isize,
+                ),
+                src: ip0,
+            });
+        }
+    }
+
+    /// IP0(x16)を経由して，8バイトずつブロック転送する
+    fn gen_copy_inst(
+        &mut self,
+        tac_fn: &tac::IRFunction,
+        src: tac::ValueId,
+        dst: tac::ValueId,
+        size: usize,
+    ) {
+        let ip0 = lir::Operand::new_register(lir::Register::GPR { number: 16 });
+        let src_value = tac_fn.get_value(src);
+        let src_reg = self.operand_from_value(src_value).get_reg();
+        let dst_value = tac_fn.get_value(dst);
+        let dst_reg = self.operand_from_value(dst_value).get_reg();
+
+        for word_offset in (0..size).step_by(8) {
+            self.gen_inst_to_last_bb(lir::InstKind::LDR {
+                operand_size: lir::OperandSize::DWORD,
+                dst: ip0,
+                src: lir::Operand::new_memory(src_reg, word_offset as isize),
+            });
+            self.gen_inst_to_last_bb(lir::InstKind::STR {
+                operand_size: lir::OperandSize::DWORD,
+                dst: lir::Operand::new_memory(dst_reg, word_offset as isize),
+                src: ip0,
+            });
+        }
+    }
+
     fn gen_address_inst(
         &mut self,
         tac_fn: &tac::IRFunction,
@@ -105,14 +495,42 @@ impl<'a> FunctionGenerator<'a> {
         let result_value = tac_fn.get_value(result);
         let result_op = self.operand_from_value(result_value);
 
-        self.gen_inst_to_last_bb(lir::InstKind::ADD {
+        // ローカル変数はフレームポインタより下位にある
+        self.gen_inst_to_last_bb(lir::InstKind::SUB {
             operand_size: lir::OperandSize::DWORD,
             dst: result_op,
             lop: lir::Operand::new_register(src_op.get_base_reg()),
-            rop: lir::Operand::new_immediate(src_op.get_offset() as i64),
+            rop: lir::Operand::new_immediate(-src_op.get_offset() as i64),
         });
     }
 
+    /// 返り値をx0に置き，エピローグを生成する
+    fn gen_return_inst(&mut self, tac_fn: &tac::IRFunction, value: tac::ValueId) {
+        let value = tac_fn.get_value(value);
+        let value_op = self.operand_from_value(value);
+        let x0 = lir::Operand::new_register(lir::Register::GPR { number: 0 });
+
+        match value_op.get_kind() {
+            lir::OperandKind::MEMORY { base: _, offset: _ } => {
+                self.gen_inst_to_last_bb(lir::InstKind::LDR {
+                    operand_size: lir::OperandSize::DWORD,
+                    dst: x0,
+                    src: value_op,
+                });
+            }
+            _ => {
+                self.gen_inst_to_last_bb(lir::InstKind::MOV {
+                    operand_size: lir::OperandSize::DWORD,
+                    dst: x0,
+                    src: value_op,
+                });
+            }
+        }
+
+        self.gen_function_epilogue();
+        self.gen_inst_to_last_bb(lir::InstKind::RET);
+    }
+
     fn gen_call_inst(
         &mut self,
         tac_fn: &tac::IRFunction,
@@ -134,6 +552,81 @@ impl<'a> FunctionGenerator<'a> {
         self.param_count = 0;
     }
 
+    /// 関数ポインタを介して間接呼び出しする
+    /// 呼び出し先アドレスの保持にはIP0(x16)を用いる
+    fn gen_icall_inst(
+        &mut self,
+        tac_fn: &tac::IRFunction,
+        callee: tac::ValueId,
+        result: tac::ValueId,
+    ) {
+        let ip0 = lir::Operand::new_register(lir::Register::GPR { number: 16 });
+        let callee_value = tac_fn.get_value(callee);
+        let callee_op = self.operand_from_value(callee_value);
+
+        self.gen_inst_to_last_bb(lir::InstKind::MOV {
+            operand_size: lir::OperandSize::DWORD,
+            dst: ip0,
+            src: callee_op,
+        });
+        self.gen_inst_to_last_bb(lir::InstKind::BLR {
+            reg: lir::Register::GPR { number: 16 },
+        });
+
+        let result_value = tac_fn.get_value(result);
+        let result_op = self.operand_from_value(result_value);
+        self.gen_inst_to_last_bb(lir::InstKind::MOV {
+            operand_size: lir::OperandSize::DWORD,
+            dst: result_op,
+            src: lir::Operand::new_register(lir::Register::GPR { number: 0 }),
+        });
+
+        self.param_count = 0;
+    }
+
+    /// インタフェース値の2ワード目からvtableを引き，間接呼び出しする
+    /// 呼び出し先アドレスの保持にはIP0(x16)を用いる
+    fn gen_vcall_inst(
+        &mut self,
+        tac_fn: &tac::IRFunction,
+        iface: tac::ValueId,
+        index: usize,
+        result: tac::ValueId,
+    ) {
+        let ip0 = lir::Operand::new_register(lir::Register::GPR { number: 16 });
+        let iface_value = tac_fn.get_value(iface);
+        let iface_op = self.operand_from_value(iface_value);
+
+        self.gen_inst_to_last_bb(lir::InstKind::MOV {
+            operand_size: lir::OperandSize::DWORD,
+            dst: ip0,
+            src: iface_op,
+        });
+        self.gen_inst_to_last_bb(lir::InstKind::LDR {
+            operand_size: lir::OperandSize::DWORD,
+            dst: ip0,
+            src: lir::Operand::new_memory(lir::Register::GPR { number: 16 }, 8),
+        });
+        self.gen_inst_to_last_bb(lir::InstKind::LDR {
+            operand_size: lir::OperandSize::DWORD,
+            dst: ip0,
+            src: lir::Operand::new_memory(lir::Register::GPR { number: 16 }, index as isize * 8),
+        });
+        self.gen_inst_to_last_bb(lir::InstKind::BLR {
+            reg: lir::Register::GPR { number: 16 },
+        });
+
+        let result_value = tac_fn.get_value(result);
+        let result_op = self.operand_from_value(result_value);
+        self.gen_inst_to_last_bb(lir::InstKind::MOV {
+            operand_size: lir::OperandSize::DWORD,
+            dst: result_op,
+            src: lir::Operand::new_register(lir::Register::GPR { number: 0 }),
+        });
+
+        self.param_count = 0;
+    }
+
     /// 引数のpushを実装する
     fn gen_param_inst(&mut self, tac_fn: &tac::IRFunction, value_id: tac::ValueId) {
         let value = tac_fn.get_value(value_id);
@@ -160,73 +653,116 @@ impl<'a> FunctionGenerator<'a> {
         self.param_count += 1;
     }
 
+    /// x0から順に渡された引数を，スタック上の領域に格納する
+    fn gen_arguments_to_stack(&mut self, tac_fn: &tac::IRFunction) {
+        for (arg_name, arg_type) in tac_fn.args.iter() {
+            let param_reg = self.get_param_register();
+            let offset = self.get_local_var_offset(arg_name);
+
+            self.gen_inst_to_last_bb(lir::InstKind::STR {
+                operand_size: lir::OperandSize::DWORD,
+                dst: lir::Operand::new_memory(lir::Register::FP, -(offset as isize)),
+                src: param_reg,
+            });
+            self.param_count += 1;
+
+            // インタフェース型の引数はvtableポインタも，スライス型の引数は長さも受け取る
+            if arg_type.is_interface() || arg_type.is_slice() {
+                let param_reg = self.get_param_register();
+                self.gen_inst_to_last_bb(lir::InstKind::STR {
+                    operand_size: lir::OperandSize::DWORD,
+                    dst: lir::Operand::new_memory(lir::Register::FP, -(offset as isize) + 8),
+                    src: param_reg,
+                });
+                self.param_count += 1;
+            }
+        }
+
+        self.param_count = 0;
+    }
+
     /// 関数プロローグを生成する．
+    /// fp/lrを退避した直下をフレームポインタとし，ローカル変数はその下位に置く
     fn gen_function_prologue(&mut self) {
         let stack_size = self.get_stack_size_from_current_function();
 
-        // 関数フレームの割付
+        // fp, lr の保存
         self.gen_inst_to_last_bb(lir::InstKind::SUB {
             operand_size: lir::OperandSize::DWORD,
             dst: lir::Operand::new_register(lir::Register::SP),
             lop: lir::Operand::new_register(lir::Register::SP),
-            rop: lir::Operand::new_immediate(stack_size as i64),
+            rop: lir::Operand::new_immediate(16),
         });
-
-        // fp, lr の保存
         self.gen_inst_to_last_bb(lir::InstKind::STP {
             operand_size: lir::OperandSize::DWORD,
             reg1: lir::Register::FP,
             reg2: lir::Register::LINK,
-            dst: lir::Operand::new_memory(lir::Register::SP, stack_size as isize - 16),
+            dst: lir::Operand::new_memory(lir::Register::SP, 0),
         });
 
         // フレームポインタの更新
-        // これは，fp/lrの保存に用いた領域を無視する為に挿入される
-        self.gen_inst_to_last_bb(lir::InstKind::ADD {
+        self.gen_inst_to_last_bb(lir::InstKind::MOV {
             operand_size: lir::OperandSize::DWORD,
             dst: lir::Operand::new_register(lir::Register::FP),
-            lop: lir::Operand::new_register(lir::Register::FP),
-            rop: lir::Operand::new_immediate(16),
+            src: lir::Operand::new_register(lir::Register::SP),
+        });
+
+        // 関数フレームの割付
+        self.gen_inst_to_last_bb(lir::InstKind::SUB {
+            operand_size: lir::OperandSize::DWORD,
+            dst: lir::Operand::new_register(lir::Register::SP),
+            lop: lir::Operand::new_register(lir::Register::SP),
+            rop: lir::Operand::new_immediate(stack_size as i64),
         });
     }
 
     /// 関数エピローグの生成
     fn gen_function_epilogue(&mut self) {
-        let stack_size = self.get_stack_size_from_current_function();
-        // フレーム/リンクレジスタの復帰，スタックポインタの復帰
+        // スタックポインタ，フレーム/リンクレジスタの復帰
+        self.gen_inst_to_last_bb(lir::InstKind::MOV {
+            operand_size: lir::OperandSize::DWORD,
+            dst: lir::Operand::new_register(lir::Register::SP),
+            src: lir::Operand::new_register(lir::Register::FP),
+        });
         self.gen_inst_to_last_bb(lir::InstKind::LDP {
             operand_size: lir::OperandSize::DWORD,
             reg1: lir::Register::FP,
             reg2: lir::Register::LINK,
-            src: lir::Operand::new_memory(lir::Register::SP, stack_size as isize - 16),
+            src: lir::Operand::new_memory(lir::Register::SP, 0),
         });
-
         self.gen_inst_to_last_bb(lir::InstKind::ADD {
             operand_size: lir::OperandSize::DWORD,
             dst: lir::Operand::new_register(lir::Register::SP),
             lop: lir::Operand::new_register(lir::Register::SP),
-            rop: lir::Operand::new_immediate(stack_size as i64),
+            rop: lir::Operand::new_immediate(16),
         });
     }
 
     /// 三番地コードをaarch64の命令オペランドに変換する
     fn operand_from_value(&mut self, v: tac::Value) -> lir::Operand {
         match v.kind {
-            tac::ValueKind::TEMP { number: _ } => self.gen_physical_reg(),
+            tac::ValueKind::TEMP { number } => self.gen_physical_reg(number),
 
             tac::ValueKind::ID { name } => {
-                let id_offset = self
-                    .frame
-                    .get(self.f.get_name())
-                    .unwrap()
-                    .get(&name)
-                    .unwrap()
-                    .offset;
-                lir::Operand::new_memory(lir::Register::FP, id_offset as isize)
+                if v.ty.is_constant() {
+                    let value = self.constant_value(v.ty);
+                    let dst_reg = self.scratch_reg();
+
+                    self.gen_inst_to_last_bb(lir::InstKind::MOV {
+                        operand_size: lir::OperandSize::DWORD,
+                        dst: dst_reg,
+                        src: lir::Operand::new_immediate(value),
+                    });
+
+                    return dst_reg;
+                }
+
+                let id_offset = self.get_local_var_offset(&name);
+                lir::Operand::new_memory(lir::Register::FP, -(id_offset as isize))
             }
             // 多少冗長だけど，レジスタにロードしておく
             tac::ValueKind::INTLITERAL { value: int_value } => {
-                let dst_reg = self.gen_physical_reg();
+                let dst_reg = self.scratch_reg();
 
                 self.gen_inst_to_last_bb(lir::InstKind::MOV {
                     operand_size: lir::OperandSize::DWORD,
@@ -236,6 +772,17 @@ impl<'a> FunctionGenerator<'a> {
 
                 dst_reg
             }
+            tac::ValueKind::UINTLITERAL { value: uint_value } => {
+                let dst_reg = self.scratch_reg();
+
+                self.gen_inst_to_last_bb(lir::InstKind::MOV {
+                    operand_size: lir::OperandSize::DWORD,
+                    dst: dst_reg,
+                    src: lir::Operand::new_immediate(uint_value as i64),
+                });
+
+                dst_reg
+            }
             tac::ValueKind::BOOLEANLITERAL { truth } => {
                 let int_value = if truth {
                     lir::Operand::new_immediate(1)
@@ -243,7 +790,7 @@ impl<'a> FunctionGenerator<'a> {
                     lir::Operand::new_immediate(0)
                 };
 
-                let dst_reg = self.gen_physical_reg();
+                let dst_reg = self.scratch_reg();
 
                 self.gen_inst_to_last_bb(lir::InstKind::MOV {
                     operand_size: lir::OperandSize::DWORD,
@@ -253,7 +800,34 @@ impl<'a> FunctionGenerator<'a> {
 
                 dst_reg
             }
-            _ => panic!("cannot generate from {:?}", v.kind),
+            tac::ValueKind::STRINGLITERAL { contents } => {
+                let label = self.strings.intern(&contents);
+                let dst_reg = self.scratch_reg();
+
+                self.gen_inst_to_last_bb(lir::InstKind::ADR {
+                    dst: dst_reg,
+                    label,
+                });
+
+                dst_reg
+            }
+        }
+    }
+
+    /// 定数の値を即値にする
+    fn constant_value(&self, const_type: Type) -> i64 {
+        let expr_string = const_type.get_const_value();
+        match const_type.get_const_type().kind {
+            TypeKind::INT64 => expr_string.parse().unwrap(),
+            TypeKind::UINT64 => expr_string.parse::<u64>().unwrap() as i64,
+            TypeKind::BOOLEAN => {
+                if expr_string == "true" {
+                    1
+                } else {
+                    0
+                }
+            }
+            _ => unreachable!(),
         }
     }
 
@@ -269,40 +843,100 @@ impl<'a> FunctionGenerator<'a> {
         })
     }
 
-    /// aarch64のために re-numbering しつつレジスタを生成する
-    fn gen_physical_reg(&mut self) -> lir::Operand {
-        let reg = match self.virtual_number % lir::Register::AVAILABLES {
-            reg_number @ 0..=lir::Register::AVAILABLES => lir::Register::GPR {
-                number: reg_number + 10,
-            },
-            _ => panic!("phys register exhausted"),
-        };
+    /// 一時変数に物理レジスタ(x9~x15)を対応付ける
+    fn gen_physical_reg(&mut self, virt_num: usize) -> lir::Operand {
+        let reg = *self
+            .virt_to_phys
+            .entry(virt_num)
+            .or_insert(lir::Register::GPR {
+                number: virt_num % lir::Register::AVAILABLES + 9,
+            });
 
-        self.virtual_number += 1;
         lir::Operand::new_register(reg)
     }
 
+    /// 即値やアドレスの一時的な置き場としてIP1(x17)を用いる
+    fn scratch_reg(&self) -> lir::Operand {
+        lir::Operand::new_register(lir::Register::GPR { number: 17 })
+    }
+
+    /// メモリオペランドであれば，IP1(x17)にロードして返す
+    fn load_to_register(&mut self, op: lir::Operand) -> lir::Operand {
+        match op.get_kind() {
+            lir::OperandKind::MEMORY { base: _, offset: _ } => {
+                let scratch = self.scratch_reg();
+                self.gen_inst_to_last_bb(lir::InstKind::LDR {
+                    operand_size: lir::OperandSize::DWORD,
+                    dst: scratch,
+                    src: op,
+                });
+                scratch
+            }
+            _ => op,
+        }
+    }
+
+    /// オペランドをIP0(x16)に置いて返す
+    /// IP1(x17)に置かれた即値も，次のオペランドで上書きされないよう移しておく
+    fn load_to_ip0(&mut self, op: lir::Operand) -> lir::Operand {
+        let ip0 = lir::Operand::new_register(lir::Register::GPR { number: 16 });
+
+        match op.get_kind() {
+            lir::OperandKind::MEMORY { base: _, offset: _ } => {
+                self.gen_inst_to_last_bb(lir::InstKind::LDR {
+                    operand_size: lir::OperandSize::DWORD,
+                    dst: ip0,
+                    src: op,
+                });
+            }
+            _ => {
+                self.gen_inst_to_last_bb(lir::InstKind::MOV {
+                    operand_size: lir::OperandSize::DWORD,
+                    dst: ip0,
+                    src: op,
+                });
+            }
+        }
+
+        ip0
+    }
+
+    fn get_local_var_offset(&self, var_name: &str) -> usize {
+        self.frame
+            .get(self.f.get_name())
+            .unwrap()
+            .get(var_name)
+            .unwrap()
+            .offset
+    }
+
     /// 関数フレームのサイズ取得
-    /// スタックサイズは，fp/lrの保存のために増しておく
+    /// スタックポインタを16バイト境界に保つため切り上げる
     fn get_stack_size_from_current_function(&self) -> usize {
         let fn_name = self.f.get_name();
-        self.frame
+        let frame_size = self
+            .frame
             .get(fn_name)
             .unwrap()
             .get(fn_name)
             .unwrap()
-            .offset
-            + 16
+            .offset;
+        frame_size.div_ceil(16) * 16
     }
     fn gen_inst_to_last_bb(&mut self, ik: lir::InstKind) {
         self.f.add_inst_to_last_bb(lir::Instruction::new(ik));
     }
-    fn new(aarch64_fn: lir::Function, stack_frame: &'a StackFrame) -> Self {
+    fn new(
+        aarch64_fn: lir::Function,
+        stack_frame: &'a StackFrame,
+        strings: &'a mut StringPool,
+    ) -> Self {
         Self {
             f: aarch64_fn,
             param_count: 0,
-            virtual_number: 0,
+            virt_to_phys: BTreeMap::new(),
             frame: stack_frame,
+            strings,
         }
     }
 }
@@ -1,6 +1,8 @@
 use crate::arch::x64;
 use crate::common;
+use crate::interpreter;
 use crate::setup;
+use std::os::unix::process::ExitStatusExt;
 
 /// x64アーキテクチャ向けのビルドルーチン
 pub fn main(
@@ -27,6 +29,26 @@ pub fn main(
             let exec_file_dumper = pld::static_link_with(obj_file_dumper.file, link_option);
             exec_file_dumper.generate_elf_file("a.out", 0o755)?;
         }
+        ("run", Some(run_m)) => {
+            let exit_code = match build_and_run(module_arena, main_module_id, run_m) {
+                Ok(exit_code) => exit_code,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    interpreter::FAILED_TO_RUN
+                }
+            };
+            std::process::exit(exit_code);
+        }
+        ("test", Some(test_m)) => {
+            let exit_code = match build_and_test(module_arena, main_module_id, test_m) {
+                Ok(exit_code) => exit_code,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    interpreter::FAILED_TO_RUN
+                }
+            };
+            std::process::exit(exit_code);
+        }
         ("compile", Some(compile_m)) => {
             let x64_module = compile_main(
                 module_arena,
@@ -43,6 +65,87 @@ pub fn main(
     Ok(())
 }
 
+/// `startup::initialize` をエントリポイントとしてビルドし，実行する
+fn build_and_run(
+    module_arena: common::module::ModuleArena,
+    main_module_id: common::module::ModuleId,
+    run_m: &clap::ArgMatches,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let link_option = pld::LinkOption {
+        entry_point: "startup::initialize".to_string(),
+    };
+    let x64_module = compile_main(
+        module_arena,
+        main_module_id,
+        false,
+        run_m.is_present("debug"),
+        link_option.entry_point.to_string(),
+    );
+
+    link_and_run(
+        x64_module,
+        link_option,
+        run_m.values_of("args").into_iter().flatten().collect(),
+    )
+}
+
+/// `#[test]` 関数を実行するハーネスをエントリポイントとしてビルドし，実行する
+/// ハーネスの終了コード(すべて成功なら0)を返す
+fn build_and_test(
+    module_arena: common::module::ModuleArena,
+    main_module_id: common::module::ModuleId,
+    test_m: &clap::ArgMatches,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let (fn_arena, ast_root, type_env, stack_frame) =
+        common::pass::test_frontend(module_arena, main_module_id);
+    if ast_root.tests.is_empty() {
+        eprintln!("no tests found");
+        return Ok(0);
+    }
+
+    let link_option = pld::LinkOption {
+        entry_point: common::ast::TEST_HARNESS_ROUTINE.to_string(),
+    };
+    let (ir_module, _local_cfg) = common::pass::backend(
+        fn_arena,
+        ast_root,
+        &type_env,
+        setup::BUILD_OPTION.target,
+        test_m.is_present("verbose-hir"),
+        link_option.entry_point.to_string(),
+    );
+    let x64_module = x64::pass::codegen_main(ir_module, stack_frame);
+
+    link_and_run(x64_module, link_option, Vec::new())
+}
+
+/// 一時ファイルに実行ファイルを生成して実行し，その終了コードを返す
+fn link_and_run(
+    x64_module: x64::ir::Module,
+    link_option: pld::LinkOption,
+    args: Vec<&str>,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let obj_file_dumper =
+        asmpeach::assemble_code(x64_module.to_atandt(), asmpeach::Syntax::ATANDT)?;
+
+    let exec_path = std::env::temp_dir()
+        .join(format!("peachili-run-{}", std::process::id()))
+        .to_string_lossy()
+        .to_string();
+    let exec_file_dumper = pld::static_link_with(obj_file_dumper.file, link_option);
+    exec_file_dumper.generate_elf_file(&exec_path, 0o755)?;
+
+    let status = std::process::Command::new(&exec_path).args(args).status();
+    let _ = std::fs::remove_file(&exec_path);
+
+    // シグナルで終了した場合は，シェルと同じく128+シグナル番号とする
+    let status = status?;
+    Ok(status
+        .code()
+        .or_else(|| status.signal().map(|sig| 128 + sig))
+        .unwrap_or(1))
+}
+
 /// x64用コンパイラのメインルーチン
 /// 機械独立なパスを呼び出した後x64依存のパスを処理する．
 pub fn compile_main(
@@ -1,13 +1,8 @@
-use std::collections::HashMap;
-
 use crate::arch::x64::ir;
 
-type StrHash = u64;
-
 pub struct Function {
     name: String,
     blocks: Vec<ir::BasicBlock>,
-    strings: HashMap<String, StrHash>,
 }
 
 impl Function {
@@ -15,7 +10,6 @@ impl Function {
         Self {
             name: name.to_string(),
             blocks: Vec::new(),
-            strings: HashMap::new(),
         }
     }
 
@@ -33,9 +27,6 @@ impl Function {
 
         self.blocks[last_bb].push_inst(inst);
     }
-    pub fn push_string(&mut self, contents: String, hash: StrHash) {
-        self.strings.entry(contents).or_insert(hash);
-    }
 
     pub fn to_atandt(&self) -> String {
         let mut func_code = format!(".global \"{}\"\n", self.name);
@@ -45,14 +36,6 @@ impl Function {
             func_code += &format!("  {}\n", bb.to_atandt());
         }
 
-        func_code += "  .section .rodata\n";
-
-        for (contents, hash) in self.strings.iter() {
-            func_code += &format!(".LS{}:\n", hash);
-            func_code += &format!("  .string \"{}\"\n", contents);
-        }
-        func_code += "  .text\n";
-
         func_code
     }
 }
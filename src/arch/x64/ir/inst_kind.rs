@@ -30,6 +30,18 @@ pub enum InstKind {
         src: ir::Operand,
         dst: ir::Operand,
     },
+    /// movzbq src, dst
+    /// 1バイトをゼロ拡張してロードする
+    MOVZBQ {
+        src: ir::Operand,
+        dst: ir::Operand,
+    },
+    /// movb src, dst
+    /// srcレジスタの下位1バイトをストアする
+    MOVB {
+        src: ir::Register,
+        dst: ir::Operand,
+    },
     /// cmp[d/l/q] src, dst
     CMP {
         operand_size: ir::OperandSize,
@@ -49,6 +61,10 @@ pub enum InstKind {
     CALL {
         name: String,
     },
+    /// call *value
+    CALLINDIRECT {
+        value: ir::Operand,
+    },
     NEG {
         operand_size: ir::OperandSize,
         value: ir::Operand,
@@ -69,4 +85,12 @@ pub enum InstKind {
     JE {
         label: String,
     },
+    /// 符号なしで大きければ関数シンボルへ飛ぶ
+    JA {
+        name: String,
+    },
+    /// 符号なしで大きいか等しければ関数シンボルへ飛ぶ
+    JAE {
+        name: String,
+    },
 }
@@ -41,6 +41,12 @@ impl Instruction {
             } => match operand_size {
                 ir::OperandSize::QWORD => format!("movq {}, {}", src.to_atandt(), dst.to_atandt()),
             },
+            ir::InstKind::MOVZBQ { src, dst } => {
+                format!("movzbq {}, {}", src.to_atandt(), dst.to_atandt())
+            }
+            ir::InstKind::MOVB { src, dst } => {
+                format!("movb {}, {}", src.to_byte_atandt(), dst.to_atandt())
+            }
             ir::InstKind::CMP {
                 operand_size,
                 src,
@@ -57,6 +63,7 @@ impl Instruction {
             },
             ir::InstKind::INLINEASM { contents } => contents.to_string(),
             ir::InstKind::CALL { name } => format!("call \"{}\"", name),
+            ir::InstKind::CALLINDIRECT { value } => format!("call *{}", value.to_atandt()),
             ir::InstKind::PUSH {
                 operand_size,
                 value,
@@ -79,6 +86,8 @@ impl Instruction {
             ir::InstKind::RET => "ret".to_string(),
             ir::InstKind::JMP { label } => format!("jmp .L{}", label),
             ir::InstKind::JE { label } => format!("je .L{}", label),
+            ir::InstKind::JA { name } => format!("ja \"{}\"", name),
+            ir::InstKind::JAE { name } => format!("jae \"{}\"", name),
         }
     }
 
@@ -1,18 +1,34 @@
 use crate::arch::x64::ir;
+use crate::common::analyze_resource::string_pool::StringPool;
+use std::collections::BTreeMap;
 
 pub struct Module {
     funcs: Vec<ir::Function>,
+    /// vtableのラベル => メソッドシンボル列
+    vtables: BTreeMap<String, Vec<String>>,
+    /// 全関数で共有する文字列リテラル
+    strings: StringPool,
 }
 
 impl Default for Module {
     fn default() -> Self {
-        Self { funcs: Vec::new() }
+        Self {
+            funcs: Vec::new(),
+            vtables: BTreeMap::new(),
+            strings: Default::default(),
+        }
     }
 }
 impl Module {
     pub fn push_function(&mut self, f: ir::Function) {
         self.funcs.push(f);
     }
+    pub fn push_vtable(&mut self, label: String, symbols: Vec<String>) {
+        self.vtables.insert(label, symbols);
+    }
+    pub fn set_strings(&mut self, strings: StringPool) {
+        self.strings = strings;
+    }
 
     pub fn to_atandt(&self) -> String {
         let mut module_code = String::new();
@@ -21,6 +37,28 @@ impl Module {
             module_code += &ir_fn.to_atandt();
         }
 
+        // vtableは読み込み専用領域に置く
+        if !self.vtables.is_empty() {
+            module_code += "  .section .rodata\n";
+            for (label, symbols) in self.vtables.iter() {
+                module_code += &format!("\"{}\":\n", label);
+                for symbol in symbols.iter() {
+                    module_code += &format!("  .quad \"{}\"\n", symbol);
+                }
+            }
+            module_code += "  .text\n";
+        }
+
+        // 文字列リテラルは重複を除いてまとめて置く
+        if !self.strings.is_empty() {
+            module_code += "  .section .rodata\n";
+            for (label, contents) in self.strings.entries() {
+                module_code += &format!("{}:\n", label);
+                module_code += &format!("  .string \"{}\"\n", contents);
+            }
+            module_code += "  .text\n";
+        }
+
         module_code
     }
 }
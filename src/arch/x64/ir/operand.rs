@@ -13,18 +13,20 @@ impl Operand {
             OperandKind::REGISTER { reg } => reg.to_atandt(),
             OperandKind::IMMEDIATE { value } => format!("${}", value),
             OperandKind::MEMORY { base, offset } => {
+                // オフセットはベースレジスタからの下位方向の距離
                 if *offset == 0 {
                     format!("({})", base.to_atandt())
                 } else {
-                    format!("-{}({})", offset, base.to_atandt())
+                    format!("{}({})", -offset, base.to_atandt())
                 }
             }
             OperandKind::LABEL { name } => name.to_string(),
         }
     }
-    pub fn add_offset(&mut self, appendix: usize) {
+    /// メモリオペランドのアドレスを上位方向にずらす
+    pub fn sub_offset(&mut self, appendix: usize) {
         if let OperandKind::MEMORY { base: _, offset } = &mut self.kind {
-            *offset += appendix;
+            *offset -= appendix as isize;
         }
     }
     pub fn get_reg(&self) -> Register {
@@ -47,7 +49,7 @@ impl Operand {
 pub enum OperandKind {
     IMMEDIATE { value: i64 },
     REGISTER { reg: Register },
-    MEMORY { base: Register, offset: usize },
+    MEMORY { base: Register, offset: isize },
     LABEL { name: String },
 }
 
@@ -113,4 +115,52 @@ impl Register {
     pub fn to_atandt(&self) -> String {
         format!("%{}", self.to_str())
     }
+
+    /// 下位1バイトを指すレジスタ名
+    pub fn to_byte_atandt(self) -> String {
+        let name = match self {
+            Register::RAX => "al",
+            Register::RCX => "cl",
+            Register::RDX => "dl",
+            Register::RBX => "bl",
+            Register::RSP => "spl",
+            Register::RBP => "bpl",
+            Register::RSI => "sil",
+            Register::RDI => "dil",
+            Register::R8 => "r8b",
+            Register::R9 => "r9b",
+            Register::R10 => "r10b",
+            Register::R11 => "r11b",
+            Register::R12 => "r12b",
+            Register::R13 => "r13b",
+            Register::R14 => "r14b",
+            Register::R15 => "r15b",
+        };
+        format!("%{}", name)
+    }
+}
+
+#[cfg(test)]
+mod operand_tests {
+    use super::*;
+
+    #[test]
+    fn sub_offset_test() {
+        let mut op = Operand::new(OperandKind::MEMORY {
+            base: Register::RBP,
+            offset: 16,
+        });
+        op.sub_offset(8);
+        assert_eq!("-8(%rbp)", op.to_atandt());
+
+        op.sub_offset(8);
+        assert_eq!("(%rbp)", op.to_atandt());
+    }
+
+    #[test]
+    fn sub_offset_to_not_memory_test() {
+        let mut op = Operand::new(OperandKind::REGISTER { reg: Register::RAX });
+        op.sub_offset(8);
+        assert_eq!("%rax", op.to_atandt());
+    }
 }
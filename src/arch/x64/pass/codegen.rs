@@ -1,29 +1,39 @@
-use std::collections::hash_map::DefaultHasher;
 use std::collections::BTreeMap;
-use std::hash::{Hash, Hasher};
 
 use crate::arch::x64::ir as lir;
 use crate::common::analyze_resource::frame_object::StackFrame;
 use crate::common::analyze_resource::peachili_type::{Type, TypeKind};
+use crate::common::analyze_resource::string_pool::StringPool;
+use crate::common::ast;
 use crate::common::three_address_code as tac;
 
 pub fn codegen_main(ir_module: tac::IRModule, stack_frame: StackFrame) -> lir::Module {
     let mut x64_module: lir::Module = Default::default();
+    let mut strings: StringPool = Default::default();
 
     for tac_fn_id in ir_module.funcs.iter() {
         let tac_fn = ir_module.get_fn(tac_fn_id);
-        let x64_fn = gen_x64_fn(tac_fn, &stack_frame);
+        let x64_fn = gen_x64_fn(tac_fn, &stack_frame, &mut strings);
         x64_module.push_function(x64_fn);
     }
+    x64_module.set_strings(strings);
+
+    for (label, symbols) in ir_module.vtables.iter() {
+        x64_module.push_vtable(label.to_string(), symbols.clone());
+    }
 
     x64_module
 }
 
-fn gen_x64_fn(tac_fn: &tac::IRFunction, stack_frame: &StackFrame) -> lir::Function {
+fn gen_x64_fn(
+    tac_fn: &tac::IRFunction,
+    stack_frame: &StackFrame,
+    strings: &mut StringPool,
+) -> lir::Function {
     let mut x64_fn = lir::Function::new(&tac_fn.name);
     x64_fn.push_block("entry");
 
-    let mut generator = FunctionGenerator::new(x64_fn, stack_frame);
+    let mut generator = FunctionGenerator::new(x64_fn, stack_frame, strings);
 
     // prologue
     generator.gen_function_prologue();
@@ -36,7 +46,11 @@ fn gen_x64_fn(tac_fn: &tac::IRFunction, stack_frame: &StackFrame) -> lir::Functi
         generator.gen_x64_inst(tac_fn, code);
     }
 
-    if let TypeKind::FUNCTION { return_type } = &tac_fn.fn_ty.kind {
+    if let TypeKind::FUNCTION {
+        return_type,
+        args: _,
+    } = &tac_fn.fn_ty.kind
+    {
         if return_type.kind == TypeKind::NORETURN {
             generator.gen_function_epilogue();
 
@@ -52,6 +66,8 @@ struct FunctionGenerator<'a> {
     param_count: usize,
     virt_to_phys: BTreeMap<tac::Value, lir::Register>,
     frame: &'a StackFrame,
+    /// モジュール全体の文字列リテラルプール
+    strings: &'a mut StringPool,
 }
 
 impl<'a> FunctionGenerator<'a> {
@@ -139,6 +155,51 @@ impl<'a> FunctionGenerator<'a> {
                 self.param_count = 0;
             }
 
+            tac::CodeKind::VTABLE { label, result } => {
+                let result = tac_fn.get_value(result);
+                let result_reg = self.gen_phys_reg_from(result);
+
+                // leaq "label"(%rip), result
+                self.leaq_memory_to_reg(
+                    lir::Operand::new(lir::OperandKind::LABEL {
+                        name: format!("\"{}\"(%rip)", label),
+                    }),
+                    result_reg,
+                );
+            }
+            tac::CodeKind::FUNCADDR { name, result } => {
+                let result = tac_fn.get_value(result);
+                let result_reg = self.gen_phys_reg_from(result);
+
+                // leaq "name"(%rip), result
+                self.leaq_memory_to_reg(
+                    lir::Operand::new(lir::OperandKind::LABEL {
+                        name: format!("\"{}\"(%rip)", name),
+                    }),
+                    result_reg,
+                );
+            }
+            tac::CodeKind::ICALL { callee, result } => {
+                let callee = tac_fn.get_value(callee);
+                let result_value = tac_fn.get_value(result);
+
+                self.gen_icall_inst(callee, result_value);
+
+                self.param_count = 0;
+            }
+            tac::CodeKind::VCALL {
+                iface,
+                index,
+                result,
+            } => {
+                let iface = tac_fn.get_value(iface);
+                let result_value = tac_fn.get_value(result);
+
+                self.gen_vcall_inst(iface, index, result_value);
+
+                self.param_count = 0;
+            }
+
             tac::CodeKind::JUMP { label } => {
                 self.add_inst_to_last_bb(lir::InstKind::JMP {
                     label: format!("{}_{}", self.f.get_name(), label),
@@ -146,10 +207,17 @@ impl<'a> FunctionGenerator<'a> {
             }
             tac::CodeKind::STORE { value, result } => {
                 let value = tac_fn.get_value(value);
-                let value_op = self.operand_from_value(value);
+                let mut value_op = self.operand_from_value(value);
                 let result = tac_fn.get_value(result);
                 let result_op = self.operand_from_value(result);
 
+                // メモリ間のmovはできないので，一度raxにロードする
+                if let lir::OperandKind::MEMORY { base: _, offset: _ } = value_op.get_kind() {
+                    let rax = self.new_reg_operand(lir::Register::RAX);
+                    self.storeq(value_op, rax.clone());
+                    value_op = rax;
+                }
+
                 self.storeq(value_op, self.new_memory_operand(result_op.get_reg(), 0));
             }
             tac::CodeKind::JUMPIFFALSE { label, cond_result } => {
@@ -167,9 +235,10 @@ impl<'a> FunctionGenerator<'a> {
             }
             tac::CodeKind::MEMBER { id, member, result } => {
                 let ident_op = tac_fn.get_value(id);
-                let member_offset = ident_op.ty.get_members().get(&member).unwrap().1;
+                let member_offset = ident_op.ty.member_offset(&member).unwrap();
+                // 構造体の先頭(最下位アドレス)からメンバオフセット分上位にある
                 let mut ident_op = self.operand_from_value(ident_op);
-                ident_op.add_offset(member_offset);
+                ident_op.sub_offset(member_offset);
                 let result = tac_fn.get_value(result);
                 let result_op = self.operand_from_value(result);
 
@@ -179,10 +248,109 @@ impl<'a> FunctionGenerator<'a> {
                     dst: result_op,
                 });
             }
+            tac::CodeKind::ZEROINIT { value } => {
+                let value = tac_fn.get_value(value);
+                let size = value.ty.size;
+                let value_op = self.operand_from_value(value);
+
+                self.gen_zeroinit_inst(value_op, size);
+            }
+            tac::CodeKind::COPY { src, dst, size } => {
+                let src = tac_fn.get_value(src);
+                let src_op = self.operand_from_value(src);
+                let dst = tac_fn.get_value(dst);
+                let dst_op = self.operand_from_value(dst);
+
+                self.gen_copy_inst(src_op.get_reg(), dst_op.get_reg(), size);
+            }
+            tac::CodeKind::LOADBYTE { addr, result } => {
+                let addr = tac_fn.get_value(addr);
+                let addr_op = self.operand_from_value(addr);
+                let result = tac_fn.get_value(result);
+                let result_reg = self.gen_phys_reg_from(result);
+
+                // movq addr, result; movzbq (result), result
+                self.storeq(addr_op, result_reg.clone());
+                self.add_inst_to_last_bb(lir::InstKind::MOVZBQ {
+                    src: self.new_memory_operand(result_reg.get_reg(), 0),
+                    dst: result_reg,
+                });
+            }
+            tac::CodeKind::STOREBYTE { value, addr } => {
+                let value = tac_fn.get_value(value);
+                let value_op = self.operand_from_value(value);
+                let addr = tac_fn.get_value(addr);
+                let addr_op = self.operand_from_value(addr);
+
+                // 下位1バイトを取り出すため，一度raxにロードする
+                self.storeq(value_op, self.new_reg_operand(lir::Register::RAX));
+                self.add_inst_to_last_bb(lir::InstKind::MOVB {
+                    src: lir::Register::RAX,
+                    dst: self.new_memory_operand(addr_op.get_reg(), 0),
+                });
+            }
+            tac::CodeKind::BOUNDSCHECK {
+                index,
+                len,
+                inclusive,
+            } => {
+                let index = tac_fn.get_value(index);
+                let index_op = self.operand_from_value(index);
+                let len = tac_fn.get_value(len);
+                let len_op = self.operand_from_value(len);
+                self.gen_bounds_check_inst(index_op, len_op, inclusive);
+            }
             tac::CodeKind::ALLOC { temp: _ } => {}
         }
     }
 
+    /// 符号なし比較で範囲外であれば，中断ルーチンへ飛ぶ
+    fn gen_bounds_check_inst(&mut self, index: lir::Operand, len: lir::Operand, inclusive: bool) {
+        let rax = self.new_reg_operand(lir::Register::RAX);
+        self.storeq(index, rax.clone());
+        self.add_inst_to_last_bb(lir::InstKind::CMP {
+            operand_size: lir::OperandSize::QWORD,
+            src: len,
+            dst: rax,
+        });
+
+        let name = ast::INDEX_OUT_OF_RANGE_ROUTINE.to_string();
+        if inclusive {
+            self.add_inst_to_last_bb(lir::InstKind::JA { name });
+        } else {
+            self.add_inst_to_last_bb(lir::InstKind::JAE { name });
+        }
+    }
+
+    /// 変数の領域を，先頭から8バイトずつ0で埋める
+    fn gen_zeroinit_inst(&mut self, var_op: lir::Operand, size: usize) {
+        for word_offset in (0..size).step_by(8) {
+            let mut word_mem = var_op.clone();
+            word_mem.sub_offset(word_offset);
+
+            self.storeq(
+                lir::Operand::new(lir::OperandKind::IMMEDIATE { value: 0 }),
+                word_mem,
+            );
+        }
+    }
+
+    /// raxを経由して，8バイトずつブロック転送する
+    fn gen_copy_inst(&mut self, src: lir::Register, dst: lir::Register, size: usize) {
+        let rax = self.new_reg_operand(lir::Register::RAX);
+
+        for word_offset in (0..size).step_by(8) {
+            // 構造体の先頭(最下位アドレス)から上位に向かって転送する
+            let mut src_mem = self.new_memory_operand(src, 0);
+            src_mem.sub_offset(word_offset);
+            let mut dst_mem = self.new_memory_operand(dst, 0);
+            dst_mem.sub_offset(word_offset);
+
+            self.storeq(src_mem, rax.clone());
+            self.storeq(rax.clone(), dst_mem);
+        }
+    }
+
     fn gen_return_inst(&mut self, value: tac::Value) {
         let value = self.operand_from_value(value);
         self.moveq_reg_to_reg_inst(value, self.new_reg_operand(lir::Register::RAX));
@@ -201,6 +369,40 @@ impl<'a> FunctionGenerator<'a> {
         self.moveq_reg_to_reg_inst(returned_reg, result);
     }
 
+    /// 関数ポインタを介して間接呼び出しする
+    fn gen_icall_inst(&mut self, callee: tac::Value, result_value: tac::Value) {
+        let rax = self.new_reg_operand(lir::Register::RAX);
+        let callee_op = self.operand_from_value(callee);
+        self.moveq_reg_to_reg_inst(callee_op, rax.clone());
+
+        self.add_inst_to_last_bb(lir::InstKind::CALLINDIRECT { value: rax.clone() });
+
+        let result = self.operand_from_value(result_value);
+        self.moveq_reg_to_reg_inst(rax, result);
+    }
+
+    /// インタフェース値の2ワード目からvtableを引き，間接呼び出しする
+    fn gen_vcall_inst(&mut self, iface: tac::Value, index: usize, result_value: tac::Value) {
+        let rax = self.new_reg_operand(lir::Register::RAX);
+        let iface_op = self.operand_from_value(iface);
+        self.moveq_reg_to_reg_inst(iface_op, rax.clone());
+
+        // movq 8(%rax), %rax
+        let mut vtable_ptr = self.new_memory_operand(lir::Register::RAX, 0);
+        vtable_ptr.sub_offset(8);
+        self.moveq_reg_to_reg_inst(vtable_ptr, rax.clone());
+
+        // movq index*8(%rax), %rax
+        let mut method_ptr = self.new_memory_operand(lir::Register::RAX, 0);
+        method_ptr.sub_offset(index * 8);
+        self.moveq_reg_to_reg_inst(method_ptr, rax.clone());
+
+        self.add_inst_to_last_bb(lir::InstKind::CALLINDIRECT { value: rax.clone() });
+
+        let result = self.operand_from_value(result_value);
+        self.moveq_reg_to_reg_inst(rax, result);
+    }
+
     fn gen_add_inst(&mut self, lop: tac::Value, rop: tac::Value, result: tac::Value) {
         let result_reg = self.gen_phys_reg_from(result);
         let lop = self.operand_from_value(lop);
@@ -387,17 +589,12 @@ impl<'a> FunctionGenerator<'a> {
                 })
             }
             tac::ValueKind::STRINGLITERAL { contents } => {
-                let mut s = DefaultHasher::new();
-                contents.hash(&mut s);
-                let str_id = s.finish();
-                self.f.push_string(contents, str_id);
+                let label = self.strings.intern(&contents);
 
                 // leaq .LS, %rax
                 self.add_inst_to_last_bb(lir::InstKind::LEA {
                     operand_size: lir::OperandSize::QWORD,
-                    src: lir::Operand::new(lir::OperandKind::LABEL {
-                        name: format!(".LS{}", str_id),
-                    }),
+                    src: lir::Operand::new(lir::OperandKind::LABEL { name: label }),
                     dst: lir::Operand::new(lir::OperandKind::REGISTER {
                         reg: lir::Register::RAX,
                     }),
@@ -480,7 +677,10 @@ impl<'a> FunctionGenerator<'a> {
         lir::Operand::new(lir::OperandKind::REGISTER { reg })
     }
     fn new_memory_operand(&self, base: lir::Register, offset: usize) -> lir::Operand {
-        lir::Operand::new(lir::OperandKind::MEMORY { base, offset })
+        lir::Operand::new(lir::OperandKind::MEMORY {
+            base,
+            offset: offset as isize,
+        })
     }
 
     fn gen_function_prologue(&mut self) {
@@ -583,12 +783,25 @@ impl<'a> FunctionGenerator<'a> {
     }
 
     fn gen_arguments_to_stack(&mut self, tac_fn: &tac::IRFunction) {
-        for (arg_idx, arg_name) in tac_fn.args.iter().enumerate() {
-            let param_reg = self.get_param_register(arg_idx);
+        let mut param_idx = 0;
+
+        for (arg_name, arg_type) in tac_fn.args.iter() {
+            let param_reg = self.get_param_register(param_idx);
             let memory_op =
                 self.new_memory_operand(lir::Register::RBP, self.get_local_var_offset(arg_name));
 
-            self.storeq(param_reg, memory_op);
+            self.storeq(param_reg, memory_op.clone());
+            param_idx += 1;
+
+            // インタフェース型の引数はvtableポインタも，スライス型の引数は長さも受け取る
+            if arg_type.is_interface() || arg_type.is_slice() {
+                let mut second_word_op = memory_op;
+                second_word_op.sub_offset(8);
+
+                let param_reg = self.get_param_register(param_idx);
+                self.storeq(param_reg, second_word_op);
+                param_idx += 1;
+            }
         }
     }
 
@@ -613,12 +826,17 @@ impl<'a> FunctionGenerator<'a> {
         })
     }
 
-    fn new(x64_fn: lir::Function, stack_frame: &'a StackFrame) -> Self {
+    fn new(
+        x64_fn: lir::Function,
+        stack_frame: &'a StackFrame,
+        strings: &'a mut StringPool,
+    ) -> Self {
         Self {
             f: x64_fn,
             param_count: 0,
             virt_to_phys: Default::default(),
             frame: stack_frame,
+            strings,
         }
     }
 }
@@ -2,5 +2,6 @@ pub mod ast;
 pub mod cfg;
 pub mod frame_object;
 pub mod peachili_type;
+pub mod string_pool;
 pub mod tld;
 pub mod token;
@@ -1,10 +1,13 @@
-use crate::common::ast::function;
+use crate::common::ast::{function, FunctionTypeDef};
 use std::collections::{BTreeMap, HashSet};
 
 /// Root
 #[derive(Debug, Clone)]
 pub struct ASTRoot {
     pub funcs: Vec<function::FnId>,
+    /// ジェネリック関数のテンプレート
+    /// 実体化されたものだけが `funcs` に追加される
+    pub generic_funcs: Vec<function::FnId>,
     pub typedefs: BTreeMap<String, StructDef>,
     pub alias: BTreeMap<String, String>,
     pub called_functions: HashSet<String>,
@@ -12,17 +15,29 @@ pub struct ASTRoot {
     /// 定数名 => (型名, 代入されている式)
     pub constants: BTreeMap<String, (String, String)>,
     pub enum_decls: BTreeMap<String, EnumDef>,
+    pub interfaces: BTreeMap<String, InterfaceDef>,
+
+    /// 宣言の完全修飾名 => ドキュメントコメント
+    /// 関数/構造体/列挙型/インタフェース/定数/型エイリアスが対象
+    pub docs: BTreeMap<String, String>,
+
+    /// `#[test]` を付与された関数の完全修飾名(パースした順)
+    pub tests: Vec<String>,
 }
 
 impl Default for ASTRoot {
     fn default() -> Self {
         Self {
             funcs: Vec::new(),
+            generic_funcs: Vec::new(),
             alias: BTreeMap::new(),
             typedefs: BTreeMap::new(),
             called_functions: HashSet::new(),
             constants: BTreeMap::new(),
             enum_decls: BTreeMap::new(),
+            interfaces: BTreeMap::new(),
+            docs: BTreeMap::new(),
+            tests: Vec::new(),
         }
     }
 }
@@ -34,28 +49,42 @@ impl ASTRoot {
         let src_func_number = target.funcs.len();
         self.funcs.append(&mut target.funcs);
         assert_eq!(dst_func_number + src_func_number, self.funcs.len());
+        self.generic_funcs.append(&mut target.generic_funcs);
 
         self.typedefs.append(&mut target.typedefs);
         self.constants.append(&mut target.constants);
         self.enum_decls.append(&mut target.enum_decls);
+        self.interfaces.append(&mut target.interfaces);
         self.alias.append(&mut target.alias);
+        self.docs.append(&mut target.docs);
+        self.tests.append(&mut target.tests);
         self.called_functions = &self.called_functions | &target.called_functions;
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct StructDef {
-    pub members: BTreeMap<String, String>,
+    /// (メンバ名, 型名) を宣言順に並べる
+    pub members: Vec<(String, String)>,
+    /// 型パラメータ(モジュール名で修飾済み)
+    pub type_params: Vec<String>,
 }
 
 impl Default for StructDef {
     fn default() -> Self {
         Self {
-            members: BTreeMap::new(),
+            members: Vec::new(),
+            type_params: Vec::new(),
         }
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct InterfaceDef {
+    /// メソッド名 => シグネチャ(レシーバは含まない)
+    pub methods: BTreeMap<String, FunctionTypeDef>,
+}
+
 #[derive(Debug, Clone)]
 pub struct EnumDef {
     pub variants: BTreeMap<String, VariantDef>,
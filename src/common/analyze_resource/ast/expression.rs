@@ -1,8 +1,9 @@
-use crate::common::ast::{ExpressionNodeKind, StNodeId};
+use crate::common::ast::{substitute_type_string, ExpressionNodeKind, StNodeId};
 use crate::common::position;
 use crate::common::token::TokenKind;
 
 use id_arena::Id;
+use std::collections::BTreeMap;
 
 pub type ExNodeId = Id<ExpressionNode>;
 
@@ -21,6 +22,49 @@ impl ExpressionNode {
     pub fn get_pos(&self) -> position::Position {
         self.p
     }
+    /// 呼び出しの型引数/構造体リテラルの型名中の型パラメータを置換する
+    pub fn substitute_type_params(&mut self, subst: &BTreeMap<String, String>) {
+        match &mut self.k {
+            ExpressionNodeKind::CALL {
+                names: _,
+                type_args,
+                args: _,
+            } => {
+                for type_arg in type_args.iter_mut() {
+                    *type_arg = substitute_type_string(type_arg, subst);
+                }
+            }
+            ExpressionNodeKind::STRUCTLITERAL {
+                type_name,
+                fields: _,
+                storage: _,
+            } => *type_name = substitute_type_string(type_name, subst),
+            _ => {}
+        }
+    }
+    /// 構造体リテラルに一時領域の変数名を割り当てる
+    pub fn set_literal_storage(&mut self, name: String) {
+        match &mut self.k {
+            ExpressionNodeKind::STRUCTLITERAL {
+                type_name: _,
+                fields: _,
+                storage,
+            } => *storage = name,
+            _ => panic!("cannot set storage to {:?}", self),
+        }
+    }
+    /// 変数を指す識別子(及び関数ポインタ変数の呼び出し)の名前を付け替える
+    pub fn rename_variable(&mut self, new_name: String) {
+        match &mut self.k {
+            ExpressionNodeKind::IDENTIFIER { names }
+            | ExpressionNodeKind::CALL {
+                names,
+                type_args: _,
+                args: _,
+            } => *names = vec![new_name],
+            _ => panic!("cannot rename a variable in {:?}", self),
+        }
+    }
     pub fn copy_names(&self) -> Vec<String> {
         match self.get_kind() {
             ExpressionNodeKind::IDENTIFIER { names } => names.clone(),
@@ -36,14 +80,65 @@ impl ExpressionNode {
     pub fn new_uinteger(uint_value: u64, pos: position::Position) -> Self {
         Self::new(ExpressionNodeKind::UINTEGER { value: uint_value }, pos)
     }
+    pub fn new_character(value: u8, pos: position::Position) -> Self {
+        Self::new(ExpressionNodeKind::CHARACTER { value }, pos)
+    }
     pub fn new_string_literal(contents: String, pos: position::Position) -> Self {
         Self::new(ExpressionNodeKind::STRING { contents }, pos)
     }
     pub fn new_boolean(truth: bool, pos: position::Position) -> Self {
         Self::new(ExpressionNodeKind::BOOLEAN { truth }, pos)
     }
-    pub fn new_call(names: Vec<String>, args: Vec<ExNodeId>, pos: position::Position) -> Self {
-        Self::new(ExpressionNodeKind::CALL { names, args }, pos)
+    pub fn new_call(
+        names: Vec<String>,
+        type_args: Vec<String>,
+        args: Vec<ExNodeId>,
+        pos: position::Position,
+    ) -> Self {
+        Self::new(
+            ExpressionNodeKind::CALL {
+                names,
+                type_args,
+                args,
+            },
+            pos,
+        )
+    }
+    pub fn new_method_call(
+        recv: ExNodeId,
+        method: String,
+        args: Vec<ExNodeId>,
+        pos: position::Position,
+    ) -> Self {
+        Self::new(ExpressionNodeKind::METHODCALL { recv, method, args }, pos)
+    }
+    pub fn new_struct_literal(
+        type_name: String,
+        fields: Vec<(String, ExNodeId)>,
+        pos: position::Position,
+    ) -> Self {
+        Self::new(
+            ExpressionNodeKind::STRUCTLITERAL {
+                type_name,
+                fields,
+                storage: String::new(),
+            },
+            pos,
+        )
+    }
+    pub fn new_index(value: ExNodeId, index: ExNodeId, pos: position::Position) -> Self {
+        Self::new(ExpressionNodeKind::INDEX { value, index }, pos)
+    }
+    pub fn new_slice(
+        value: ExNodeId,
+        low: Option<ExNodeId>,
+        high: Option<ExNodeId>,
+        pos: position::Position,
+    ) -> Self {
+        Self::new(ExpressionNodeKind::SLICE { value, low, high }, pos)
+    }
+    pub fn new_len(value: ExNodeId, pos: position::Position) -> Self {
+        Self::new(ExpressionNodeKind::LEN { value }, pos)
     }
     pub fn new_if(
         cond_id: ExNodeId,
@@ -29,11 +29,24 @@ pub enum ExpressionNodeKind {
     DEREFERENCE { value: ExNodeId },
     /// メンバアクセス
     MEMBER { id: ExNodeId, member: String },
+    /// 添字アクセス `a[i]`
+    INDEX { value: ExNodeId, index: ExNodeId },
+    /// スライス式 `a[low:high]`
+    /// 省略された端はNone
+    SLICE {
+        value: ExNodeId,
+        low: Option<ExNodeId>,
+        high: Option<ExNodeId>,
+    },
+    /// 組み込み関数 `len(a)`
+    LEN { value: ExNodeId },
 
     /// 整数ノード
     INTEGER { value: i64 },
     /// 非符号付き整数ノード
     UINTEGER { value: u64 },
+    /// 文字リテラル(Uint8として扱う)
+    CHARACTER { value: u8 },
     /// 真偽値リテラル
     BOOLEAN { truth: bool },
     /// 文字列リテラル
@@ -44,6 +57,22 @@ pub enum ExpressionNodeKind {
     /// 呼び出し式ノード
     CALL {
         names: Vec<String>,
+        /// 明示された型引数(ジェネリック関数の呼び出しでなければ空)
+        type_args: Vec<String>,
+        args: Vec<ExNodeId>,
+    },
+    /// メソッド呼び出し式ノード
+    METHODCALL {
+        recv: ExNodeId,
+        method: String,
         args: Vec<ExNodeId>,
     },
+    /// 構造体リテラル
+    /// `Point { x: 1, y: 2 }` のフィールドを記述順に保持
+    STRUCTLITERAL {
+        type_name: String,
+        fields: Vec<(String, ExNodeId)>,
+        /// リテラルを組み立てる一時領域の変数名(型解決時に割り当てる)
+        storage: String,
+    },
 }
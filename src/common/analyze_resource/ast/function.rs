@@ -3,6 +3,7 @@ use crate::common::{
     position,
 };
 use id_arena::{Arena, Id};
+use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
 
 pub type FnId = Id<Function>;
@@ -20,6 +21,14 @@ pub struct Function {
 
     pub module_name: String,
 
+    /// メソッドであれば，レシーバの構造体型名を持つ
+    /// レシーバ自体は第一引数として `fn_type.args` に含まれる
+    pub method_of: Option<String>,
+
+    /// 型パラメータ(モジュール名で修飾済み)
+    /// 空でなければジェネリック関数のテンプレート
+    pub type_params: Vec<String>,
+
     pub fn_type: FunctionTypeDef,
 
     // アロケータ
@@ -29,6 +38,10 @@ pub struct Function {
 
 impl Function {
     pub fn full_path(&self) -> String {
+        if let Some(struct_name) = &self.method_of {
+            return method_symbol(struct_name, &self.name);
+        }
+
         if self.module_name.is_empty() {
             return self.name.to_string();
         }
@@ -43,6 +56,180 @@ impl Function {
     pub fn get_parameters(&self) -> &Vec<(String, String)> {
         &self.fn_type.args
     }
+
+    pub fn is_generic(&self) -> bool {
+        !self.type_params.is_empty()
+    }
+
+    /// 型引数を与えてテンプレートを実体化する
+    /// 文/式のアロケータは複製し，テンプレートとは共有しない
+    pub fn instantiate(&self, type_args: &[String]) -> Self {
+        let subst: BTreeMap<String, String> = self
+            .type_params
+            .iter()
+            .cloned()
+            .zip(type_args.iter().cloned())
+            .collect();
+
+        let mut stmt_arena = self.stmt_arena.lock().unwrap().clone();
+        for (_, stmt) in stmt_arena.iter_mut() {
+            stmt.substitute_type_params(&subst);
+        }
+        let mut expr_arena = self.expr_arena.lock().unwrap().clone();
+        for (_, expr) in expr_arena.iter_mut() {
+            expr.substitute_type_params(&subst);
+        }
+
+        let args = self
+            .fn_type
+            .args
+            .iter()
+            .map(|(name, ty)| (name.clone(), substitute_type_string(ty, &subst)))
+            .collect();
+        let return_type = substitute_type_string(&self.fn_type.return_type, &subst);
+
+        Self {
+            name: generic_instance_name(&self.name, type_args),
+            stmts: self.stmts.clone(),
+            pos: self.pos,
+            module_name: self.module_name.clone(),
+            method_of: self.method_of.clone(),
+            type_params: Vec::new(),
+            fn_type: FunctionTypeDef::new(return_type, args),
+            stmt_arena: Arc::new(Mutex::new(stmt_arena)),
+            expr_arena: Arc::new(Mutex::new(expr_arena)),
+        }
+    }
+}
+
+/// ジェネリックな定義の実体名を生成する
+/// `max[Int64]` や `::Pair[Int64,Uint64]` のように型引数を並べる
+pub fn generic_instance_name(name: &str, type_args: &[String]) -> String {
+    format!("{}[{}]", name, type_args.join(","))
+}
+
+/// `::Pair[Int64,*::Pair[Uint64]]` のような型名を，基底名と型引数に分解する
+/// 型引数を持たなければNone
+pub fn split_generic_type_name(type_name: &str) -> Option<(String, Vec<String>)> {
+    let open = type_name.find('[')?;
+    let type_args = split_top_level_commas(&type_name[open + 1..type_name.len() - 1]);
+
+    Some((type_name[..open].to_string(), type_args))
+}
+
+/// `[]Int64` や `[4]::Point` のような型名を，要素数と要素型に分解する
+/// スライス型であれば要素数はNone，配列/スライス型でなければNone
+pub fn split_array_type_name(type_name: &str) -> Option<(Option<usize>, String)> {
+    let inner = type_name.strip_prefix('[')?;
+    let close = inner.find(']')?;
+    let elem_type = inner[close + 1..].to_string();
+
+    if close == 0 {
+        return Some((None, elem_type));
+    }
+
+    let length = inner[..close].parse().ok()?;
+    Some((Some(length), elem_type))
+}
+
+/// 関数型の型名を生成する
+/// `func(Int64,*::Point)Int64` のように空白を含めず並べる
+pub fn function_type_name(param_types: &[String], return_type: &str) -> String {
+    format!("func({}){}", param_types.join(","), return_type)
+}
+
+/// `func(Int64,Int64)Int64` のような関数型名を，引数型の列と返り値型に分解する
+/// 関数型でなければNone
+pub fn split_function_type_name(type_name: &str) -> Option<(Vec<String>, String)> {
+    let inner = type_name.strip_prefix("func(")?;
+
+    let mut depth = 0;
+    for (idx, c) in inner.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ']' => depth -= 1,
+            ')' if depth == 0 => {
+                let param_types = if idx == 0 {
+                    Vec::new()
+                } else {
+                    split_top_level_commas(&inner[..idx])
+                };
+                return Some((param_types, inner[idx + 1..].to_string()));
+            }
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// 括弧の内側にあるものを除いて，カンマで区切る
+fn split_top_level_commas(s: &str) -> Vec<String> {
+    let mut elements = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in s.chars() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                elements.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    elements.push(current);
+
+    elements
+}
+
+/// 型名中の型パラメータを置換する
+pub fn substitute_type_string(type_name: &str, subst: &BTreeMap<String, String>) -> String {
+    if let Some(pointer_to) = type_name.strip_prefix('*') {
+        return format!("*{}", substitute_type_string(pointer_to, subst));
+    }
+
+    if let Some((length, elem_type)) = split_array_type_name(type_name) {
+        let length = length.map_or(String::new(), |length| length.to_string());
+        return format!("[{}]{}", length, substitute_type_string(&elem_type, subst));
+    }
+
+    if let Some((param_types, return_type)) = split_function_type_name(type_name) {
+        let param_types: Vec<String> = param_types
+            .iter()
+            .map(|param| substitute_type_string(param, subst))
+            .collect();
+        return function_type_name(&param_types, &substitute_type_string(&return_type, subst));
+    }
+
+    if let Some((base, type_args)) = split_generic_type_name(type_name) {
+        let type_args: Vec<String> = type_args
+            .iter()
+            .map(|arg| substitute_type_string(arg, subst))
+            .collect();
+        return generic_instance_name(&base, &type_args);
+    }
+
+    match subst.get(type_name) {
+        Some(concrete) => concrete.to_string(),
+        None => type_name.to_string(),
+    }
+}
+
+/// 範囲外の添字でアクセスした際に呼び出すランタイムルーチン
+/// 添字/スライス式をパースした時点で呼び出し済みとして登録する
+pub const INDEX_OUT_OF_RANGE_ROUTINE: &str = "startup::index_out_of_range";
+
+/// `peachili test` で `startup::initialize` の代わりにエントリポイントとする，生成されたハーネス
+pub const TEST_HARNESS_ROUTINE: &str = "startup::run_tests";
+
+/// メソッドのシンボル名を生成する
+/// 構造体名がモジュールパスを含むので，モジュール/構造体ごとに一意になる
+pub fn method_symbol(struct_name: &str, method_name: &str) -> String {
+    format!("{}.{}", struct_name, method_name)
 }
 
 #[derive(Debug, Clone)]
@@ -59,3 +246,71 @@ impl FunctionTypeDef {
         Self { return_type, args }
     }
 }
+
+#[cfg(test)]
+mod function_tests {
+    use super::*;
+
+    #[test]
+    fn split_generic_type_name_test() {
+        assert_eq!(None, split_generic_type_name("::Pair"));
+        assert_eq!(
+            Some((
+                "::Pair".to_string(),
+                vec!["Int64".to_string(), "*::Pair[Int64,Uint64]".to_string()]
+            )),
+            split_generic_type_name("::Pair[Int64,*::Pair[Int64,Uint64]]")
+        );
+    }
+
+    #[test]
+    fn split_array_type_name_test() {
+        assert_eq!(None, split_array_type_name("::Pair[Int64]"));
+        assert_eq!(
+            Some((None, "Uint8".to_string())),
+            split_array_type_name("[]Uint8")
+        );
+        assert_eq!(
+            Some((Some(4), "[]::Pair[Int64]".to_string())),
+            split_array_type_name("[4][]::Pair[Int64]")
+        );
+    }
+
+    #[test]
+    fn split_function_type_name_test() {
+        assert_eq!(None, split_function_type_name("::func"));
+        assert_eq!(
+            Some((Vec::new(), "Noreturn".to_string())),
+            split_function_type_name("func()Noreturn")
+        );
+        assert_eq!(
+            Some((
+                vec![
+                    "::Pair[Int64,Int64]".to_string(),
+                    "*func(Int64)Int64".to_string()
+                ],
+                "Int64".to_string()
+            )),
+            split_function_type_name("func(::Pair[Int64,Int64],*func(Int64)Int64)Int64")
+        );
+    }
+
+    #[test]
+    fn substitute_type_string_test() {
+        let mut subst = BTreeMap::new();
+        subst.insert("::T".to_string(), "Int64".to_string());
+
+        assert_eq!("Int64", substitute_type_string("::T", &subst));
+        assert_eq!("**Int64", substitute_type_string("**::T", &subst));
+        assert_eq!(
+            "::Pair[Int64,*::Box[Int64]]",
+            substitute_type_string("::Pair[::T,*::Box[::T]]", &subst)
+        );
+        assert_eq!(
+            "*func(Int64,::U)Int64",
+            substitute_type_string("*func(::T,::U)::T", &subst)
+        );
+        assert_eq!("[3][]Int64", substitute_type_string("[3][]::T", &subst));
+        assert_eq!("::U", substitute_type_string("::U", &subst));
+    }
+}
@@ -1,4 +1,8 @@
-use crate::common::{ast::StatementNodeKind, position};
+use crate::common::{
+    ast::{substitute_type_string, StatementNodeKind},
+    position,
+};
+use std::collections::BTreeMap;
 
 use id_arena::Id;
 
@@ -22,6 +26,52 @@ impl StatementNode {
     pub fn get_kind(&self) -> &StatementNodeKind {
         &self.k
     }
+    /// 宣言中の型パラメータを置換する
+    pub fn substitute_type_params(&mut self, subst: &BTreeMap<String, String>) {
+        match &mut self.k {
+            StatementNodeKind::DECLARE {
+                ident_name: _,
+                type_name,
+            }
+            | StatementNodeKind::VARINIT {
+                ident_name: _,
+                type_name,
+                expr: _,
+            }
+            | StatementNodeKind::CONST {
+                ident_name: _,
+                type_name,
+                expr: _,
+            } => *type_name = substitute_type_string(type_name, subst),
+            _ => {}
+        }
+    }
+    /// 宣言する変数の名前を付け替える
+    pub fn rename_declared_variable(&mut self, new_name: String) {
+        match &mut self.k {
+            StatementNodeKind::DECLARE {
+                ident_name,
+                type_name: _,
+            }
+            | StatementNodeKind::VARINIT {
+                ident_name,
+                type_name: _,
+                expr: _,
+            }
+            | StatementNodeKind::CONST {
+                ident_name,
+                type_name: _,
+                expr: _,
+            }
+            | StatementNodeKind::COUNTUP {
+                ident_name,
+                begin_ex: _,
+                endpoint_ex: _,
+                body: _,
+            } => *ident_name = new_name,
+            _ => panic!("cannot rename a variable in {:?}", self),
+        }
+    }
     pub fn is_ifret(&self) -> bool {
         match self.k {
             StatementNodeKind::IFRET { expr: _ } => true,
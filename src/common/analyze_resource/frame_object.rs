@@ -9,3 +9,16 @@ pub struct FrameObject {
     /// 関数の場合は関数が割り当てるスタックフレームのサイズが入っている
     pub offset: usize,
 }
+
+/// 関数名 => 関数本体のスコープ
+pub type ScopeEnv = BTreeMap<String, LexicalScope>;
+
+/// 関数内のレキシカルスコープ
+/// 兄弟関係にあるスコープの変数は同時に生存しないので，スタック上の領域を共有できる
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Default)]
+pub struct LexicalScope {
+    /// このスコープで宣言された変数(一意な名前に付け替え済み)
+    pub variables: Vec<String>,
+    /// 内側のスコープ
+    pub children: Vec<LexicalScope>,
+}
@@ -1,5 +1,4 @@
 use crate::common::option::Target;
-use std::collections::BTreeMap;
 
 /// 型
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
@@ -17,10 +16,20 @@ impl Type {
             TypeKind::CONSTSTR => "ConstStr".to_string(),
             TypeKind::INT64 => "Int64".to_string(),
             TypeKind::UINT64 => "Uint64".to_string(),
+            TypeKind::UINT8 => "Uint8".to_string(),
             TypeKind::NORETURN => "Noreturn".to_string(),
-            TypeKind::FUNCTION { return_type } => format!("func() {}", return_type.dump()),
+            TypeKind::FUNCTION { return_type, args } => format!(
+                "func({}) {}",
+                args.iter()
+                    .map(|arg| arg.dump())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                return_type.dump()
+            ),
             TypeKind::POINTER { to } => format!("*{}", to.dump()),
-            TypeKind::STRUCT { members } => {
+            TypeKind::ARRAY { elem, length } => format!("[{}]{}", length, elem.dump()),
+            TypeKind::SLICE { elem } => format!("[]{}", elem.dump()),
+            TypeKind::STRUCT { name: _, members } => {
                 let mut type_strs = Vec::new();
 
                 for (member_name, (member_type, _offset)) in members.iter() {
@@ -34,13 +43,16 @@ impl Type {
                 value: _,
             } => const_type.dump(),
             TypeKind::ENUM => "enum".to_string(),
+            TypeKind::INTERFACE { name, methods: _ } => name.to_string(),
         }
     }
     /// 関数型サイズ
-    pub fn new_function(ret_ty: Type) -> Self {
+    /// 関数そのものは値として扱わず，関数ポインタはこの型へのポインタで表す
+    pub fn new_function(ret_ty: Type, args: Vec<Type>) -> Self {
         Self {
             kind: TypeKind::FUNCTION {
                 return_type: Box::new(ret_ty),
+                args,
             },
             size: 0,
         }
@@ -59,11 +71,37 @@ impl Type {
             TypeKind::CONSTSTR => Self::conststr_size(target),
             TypeKind::INT64 => Self::int64_size(target),
             TypeKind::UINT64 => Self::uint64_size(target),
+            TypeKind::UINT8 => Self::uint8_size(target),
             TypeKind::ENUM => 8,
             _ => unreachable!(),
         }
     }
 
+    /// アラインメント
+    /// スカラ型は自身のサイズに，構造体はメンバ中で最大のアラインメントに揃える
+    pub fn alignment(&self) -> usize {
+        match &self.kind {
+            TypeKind::STRUCT { name: _, members } => members
+                .iter()
+                .map(|(_, (member_type, _))| member_type.alignment())
+                .max()
+                .unwrap_or(1),
+            // データポインタとvtableポインタの組
+            TypeKind::INTERFACE {
+                name: _,
+                methods: _,
+            } => self.size / 2,
+            TypeKind::ARRAY { elem, length: _ } => elem.alignment(),
+            // 先頭要素へのポインタと要素数の組
+            TypeKind::SLICE { elem: _ } => self.size / 2,
+            TypeKind::CONST {
+                const_type,
+                value: _,
+            } => const_type.alignment(),
+            _ => self.size.max(1),
+        }
+    }
+
     /// Int64型サイズ
     pub fn int64_size(target: Target) -> usize {
         match target {
@@ -80,6 +118,15 @@ impl Type {
         }
     }
 
+    /// Uint8型サイズ
+    /// 変数やメンバとしては他の整数型と同様に1ワードを占める
+    pub fn uint8_size(target: Target) -> usize {
+        match target {
+            Target::X86_64 => 8,
+            Target::AARCH64 => 8,
+        }
+    }
+
     /// ポインタ型サイズ
     pub fn pointer_size(target: Target) -> usize {
         match target {
@@ -87,6 +134,16 @@ impl Type {
             Target::AARCH64 => 8,
         }
     }
+    /// インタフェース型サイズ
+    /// データポインタとvtableポインタの組で表現する
+    pub fn interface_size(target: Target) -> usize {
+        Self::pointer_size(target) * 2
+    }
+    /// スライス型サイズ
+    /// 先頭要素へのポインタと要素数の組で表現する
+    pub fn slice_size(target: Target) -> usize {
+        Self::pointer_size(target) * 2
+    }
     /// Boolean型サイズ
     pub fn boolean_size(target: Target) -> usize {
         match target {
@@ -117,6 +174,13 @@ impl Type {
             size: Self::uint64_size(target),
         }
     }
+    /// Uint8型を新たに割り当てる
+    pub fn new_uint8(target: Target) -> Self {
+        Self {
+            kind: TypeKind::UINT8,
+            size: Self::uint8_size(target),
+        }
+    }
     /// Boolean型を新たに割り当てる
     pub fn new_boolean(target: Target) -> Self {
         Self {
@@ -157,13 +221,49 @@ impl Type {
         }
     }
 
+    /// 配列型を新たに割り当てる
+    /// 要素を詰めて並べ，全体のサイズはワード境界に揃える
+    pub fn new_array(elem: Self, length: usize, target: Target) -> Self {
+        let word_size = Self::pointer_size(target);
+        let size = (elem.element_stride() * length).div_ceil(word_size) * word_size;
+
+        Self {
+            kind: TypeKind::ARRAY {
+                elem: Box::new(elem),
+                length,
+            },
+            size: size.max(word_size),
+        }
+    }
+    /// スライス型を新たに割り当てる
+    pub fn new_slice(elem: Self, target: Target) -> Self {
+        Self {
+            kind: TypeKind::SLICE {
+                elem: Box::new(elem),
+            },
+            size: Self::slice_size(target),
+        }
+    }
+
     /// 構造体型型を新たに割り当てる
-    pub fn new_struct(members: BTreeMap<String, (Box<Type>, usize)>, total_size: usize) -> Self {
+    pub fn new_struct(
+        name: String,
+        members: Vec<(String, (Box<Type>, usize))>,
+        total_size: usize,
+    ) -> Self {
         Self {
-            kind: TypeKind::STRUCT { members },
+            kind: TypeKind::STRUCT { name, members },
             size: total_size,
         }
     }
+    /// インタフェース型を新たに割り当てる
+    /// メソッドは名前順に並べ，その添字がvtable内のスロットになる
+    pub fn new_interface(name: String, methods: Vec<(String, Box<Type>)>, target: Target) -> Self {
+        Self {
+            kind: TypeKind::INTERFACE { name, methods },
+            size: Self::interface_size(target),
+        }
+    }
     pub fn new_enum(size: usize) -> Self {
         Self {
             kind: TypeKind::ENUM,
@@ -171,10 +271,57 @@ impl Type {
         }
     }
 
+    /// 整数型であるか
+    pub fn is_integer(&self) -> bool {
+        match self.kind {
+            TypeKind::INT64 | TypeKind::UINT64 | TypeKind::UINT8 => true,
+            _ => false,
+        }
+    }
+    /// 符号なし整数型であるか
+    pub fn is_unsigned(&self) -> bool {
+        matches!(self.kind, TypeKind::UINT64 | TypeKind::UINT8)
+    }
+    /// Boolean型であるか
+    pub fn is_boolean(&self) -> bool {
+        self.kind == TypeKind::BOOLEAN
+    }
+    /// 文字列リテラルの型であるか
+    pub fn is_const_str(&self) -> bool {
+        self.kind == TypeKind::CONSTSTR
+    }
+    /// 配列型であるか
+    pub fn is_array(&self) -> bool {
+        matches!(self.kind, TypeKind::ARRAY { elem: _, length: _ })
+    }
+    /// スライス型であるか
+    pub fn is_slice(&self) -> bool {
+        matches!(self.kind, TypeKind::SLICE { elem: _ })
+    }
+    /// 文字列リテラルを代入できる `[]Uint8` であるか
+    pub fn is_byte_slice(&self) -> bool {
+        match &self.kind {
+            TypeKind::SLICE { elem } => elem.kind == TypeKind::UINT8,
+            _ => false,
+        }
+    }
     /// 構造体型であるか
     pub fn is_struct(&self) -> bool {
         match self.kind {
-            TypeKind::STRUCT { members: _ } => true,
+            TypeKind::STRUCT {
+                name: _,
+                members: _,
+            } => true,
+            _ => false,
+        }
+    }
+    /// インタフェース型であるか
+    pub fn is_interface(&self) -> bool {
+        match self.kind {
+            TypeKind::INTERFACE {
+                name: _,
+                methods: _,
+            } => true,
             _ => false,
         }
     }
@@ -191,10 +338,40 @@ impl Type {
     /// 関数型であるか
     pub fn is_function(&self) -> bool {
         match &self.kind {
-            TypeKind::FUNCTION { return_type: _ } => true,
+            TypeKind::FUNCTION {
+                return_type: _,
+                args: _,
+            } => true,
+            _ => false,
+        }
+    }
+    /// 関数ポインタ型であるか
+    pub fn is_function_pointer(&self) -> bool {
+        match &self.kind {
+            TypeKind::POINTER { to } => to.is_function(),
             _ => false,
         }
     }
+    /// 関数型であると解釈し, 返り値型を取り出す
+    pub fn get_return_type(&self) -> &Type {
+        match &self.kind {
+            TypeKind::FUNCTION {
+                return_type,
+                args: _,
+            } => return_type,
+            _ => panic!("cannot call get_return_type() with not a function"),
+        }
+    }
+    /// 関数型であると解釈し, 引数型の列を取り出す
+    pub fn get_function_args(&self) -> &Vec<Type> {
+        match &self.kind {
+            TypeKind::FUNCTION {
+                return_type: _,
+                args,
+            } => args,
+            _ => panic!("cannot call get_function_args() with not a function"),
+        }
+    }
     /// ポインタ型であると解釈し, 指す型を取り出す
     pub fn pointer_to(&self) -> &Type {
         match &self.kind {
@@ -203,6 +380,29 @@ impl Type {
         }
     }
 
+    /// 配列/スライス型であると解釈し, 要素型を取り出す
+    pub fn element_type(&self) -> &Type {
+        match &self.kind {
+            TypeKind::ARRAY { elem, length: _ } | TypeKind::SLICE { elem } => elem,
+            _ => panic!("cannot call element_type() with not an array or a slice"),
+        }
+    }
+    /// 配列型であると解釈し, 要素数を取り出す
+    pub fn array_length(&self) -> usize {
+        match &self.kind {
+            TypeKind::ARRAY { elem: _, length } => *length,
+            _ => panic!("cannot call array_length() with not an array"),
+        }
+    }
+    /// 配列/スライスの要素として並べたときの間隔
+    /// Uint8のみ1バイトに詰める
+    pub fn element_stride(&self) -> usize {
+        match self.kind {
+            TypeKind::UINT8 => 1,
+            _ => self.size,
+        }
+    }
+
     /// 定数であると解釈し,式文字列を取得する
     pub fn get_const_value(&self) -> String {
         match &self.kind {
@@ -224,12 +424,69 @@ impl Type {
     }
 
     /// 構造体型であると解釈し, メンバを取り出す
-    pub fn get_members(&self) -> &BTreeMap<String, (Box<Type>, usize)> {
+    pub fn get_members(&self) -> &Vec<(String, (Box<Type>, usize))> {
         match &self.kind {
-            TypeKind::STRUCT { members } => members,
+            TypeKind::STRUCT { name: _, members } => members,
             _ => panic!("cannot call get_members() with not a struct"),
         }
     }
+    /// 構造体型であると解釈し, 名前からメンバの(型, オフセット)を探す
+    pub fn get_member(&self, member: &str) -> Option<&(Box<Type>, usize)> {
+        self.get_members()
+            .iter()
+            .find(|(member_name, _)| member_name == member)
+            .map(|(_, member_entry)| member_entry)
+    }
+    /// 構造体型のメンバ，もしくはスライス型の2ワードのオフセットを求める
+    /// スライス型は (`ptr`, `len`) を並べた構造体とみなす
+    pub fn member_offset(&self, member: &str) -> Option<usize> {
+        match (&self.kind, member) {
+            (TypeKind::SLICE { elem: _ }, "ptr") => Some(0),
+            (TypeKind::SLICE { elem: _ }, "len") => Some(self.size / 2),
+            (TypeKind::SLICE { elem: _ }, _) => None,
+            _ => self.get_member(member).map(|(_, offset)| *offset),
+        }
+    }
+
+    /// 構造体型であると解釈し, 型名を取り出す
+    pub fn get_struct_name(&self) -> &str {
+        match &self.kind {
+            TypeKind::STRUCT { name, members: _ } => name,
+            _ => panic!("cannot call get_struct_name() with not a struct"),
+        }
+    }
+
+    /// インタフェース型であると解釈し, 型名を取り出す
+    pub fn get_interface_name(&self) -> &str {
+        match &self.kind {
+            TypeKind::INTERFACE { name, methods: _ } => name,
+            _ => panic!("cannot call get_interface_name() with not an interface"),
+        }
+    }
+
+    /// インタフェース型であると解釈し, メソッドのvtableスロットと返り値型を取り出す
+    pub fn get_interface_method(&self, method: &str) -> Option<(usize, &Type)> {
+        match &self.kind {
+            TypeKind::INTERFACE { name: _, methods } => methods
+                .iter()
+                .position(|(method_name, _)| method_name == method)
+                .map(|idx| (idx, methods[idx].1.as_ref())),
+            _ => panic!("cannot call get_interface_method() with not an interface"),
+        }
+    }
+
+    /// 構造体型か，構造体へのポインタ型であればその構造体型を取り出す
+    /// メソッド呼び出しのレシーバで用いる
+    pub fn receiver_struct(&self) -> Option<&Type> {
+        match &self.kind {
+            TypeKind::STRUCT {
+                name: _,
+                members: _,
+            } => Some(self),
+            TypeKind::POINTER { to } if to.is_struct() => Some(to),
+            _ => None,
+        }
+    }
 }
 
 /// 型の種類
@@ -239,10 +496,22 @@ pub enum TypeKind {
     INT64,
     /// 64bit非符号付き整数
     UINT64,
+    /// 8bit非符号付き整数
+    /// 配列/スライスの要素としてのみ1バイトに詰める
+    UINT8,
     /// ポインタ
     POINTER {
         to: Box<Type>,
     },
+    /// 配列型
+    ARRAY {
+        elem: Box<Type>,
+        length: usize,
+    },
+    /// スライス型
+    SLICE {
+        elem: Box<Type>,
+    },
     /// ConstStr
     CONSTSTR,
     /// Boolean
@@ -251,13 +520,15 @@ pub enum TypeKind {
     NORETURN,
     /// 構造体型
     STRUCT {
-        /// member_name -> (member_type, member_offset)
-        members: BTreeMap<String, (Box<Type>, usize)>,
+        /// 型名(モジュールパスを含む)
+        name: String,
+        /// (member_name, (member_type, member_offset)) を宣言順に並べる
+        members: Vec<(String, (Box<Type>, usize))>,
     },
     /// 関数型
     FUNCTION {
         return_type: Box<Type>,
-        // args
+        args: Vec<Type>,
     },
     /// 定数
     CONST {
@@ -265,4 +536,11 @@ pub enum TypeKind {
         value: String,
     },
     ENUM,
+    /// インタフェース型
+    INTERFACE {
+        /// 型名(モジュールパスを含む)
+        name: String,
+        /// (method_name, return_type) をメソッド名順に並べたもの
+        methods: Vec<(String, Box<Type>)>,
+    },
 }
@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use crate::common::token::literal_bytes;
+
+/// 出力モジュール全体で共有する文字列リテラルのプール
+/// 同じ内容のリテラルには同じラベルを割り当てる
+#[derive(Debug, Default, Clone)]
+pub struct StringPool {
+    /// リテラルの内容 => 登録順の番号
+    numbers: BTreeMap<String, usize>,
+}
+
+impl StringPool {
+    /// リテラルを登録し，そのラベルを返す
+    pub fn intern(&mut self, contents: &str) -> String {
+        let next_number = self.numbers.len();
+        let number = *self
+            .numbers
+            .entry(contents.to_string())
+            .or_insert(next_number);
+
+        Self::label(number)
+    }
+
+    /// (ラベル, アセンブラ向けにエスケープした内容) を登録順に並べる
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut entries: Vec<(&usize, &String)> = self
+            .numbers
+            .iter()
+            .map(|(contents, number)| (number, contents))
+            .collect();
+        entries.sort();
+
+        entries
+            .into_iter()
+            .map(|(number, contents)| (Self::label(*number), escape_for_assembler(contents)))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.numbers.is_empty()
+    }
+
+    fn label(number: usize) -> String {
+        format!(".LS{}", number)
+    }
+}
+
+/// `.string` ディレクティブに埋め込めるようにエスケープする
+/// 表示可能なASCII以外は，1バイトずつ3桁の8進エスケープにする
+fn escape_for_assembler(contents: &str) -> String {
+    let mut escaped = String::new();
+
+    for byte in literal_bytes(contents) {
+        match byte {
+            b'"' => escaped += "\\\"",
+            b'\\' => escaped += "\\\\",
+            b' '..=b'~' => escaped.push(byte as char),
+            _ => escaped += &format!("\\{:03o}", byte),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod string_pool_tests {
+    use super::*;
+
+    #[test]
+    fn intern_test() {
+        let mut pool: StringPool = Default::default();
+        assert_eq!(".LS0", pool.intern("Hello\n"));
+        assert_eq!(".LS1", pool.intern("World"));
+        assert_eq!(".LS0", pool.intern("Hello\n"));
+
+        assert_eq!(
+            vec![
+                (".LS0".to_string(), "Hello\\012".to_string()),
+                (".LS1".to_string(), "World".to_string()),
+            ],
+            pool.entries()
+        );
+    }
+
+    #[test]
+    fn escape_for_assembler_test() {
+        assert_eq!("a\\\"b\\\\c", escape_for_assembler("a\"b\\c"));
+        assert_eq!("\\011\\000", escape_for_assembler("\t\0"));
+        assert_eq!("\\303\\251", escape_for_assembler("é"));
+        assert_eq!("\\377", escape_for_assembler("\u{F7FF}"));
+    }
+}
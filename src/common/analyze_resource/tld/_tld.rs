@@ -1,5 +1,6 @@
 use crate::common::ast;
 use crate::common::tld::tld_kind;
+use std::collections::BTreeMap;
 
 /// 宣言
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
@@ -30,9 +31,46 @@ impl TopLevelDecl {
         })
     }
 
+    pub fn new_generic_function_from_ast(func: &ast::Function) -> Self {
+        Self::new(tld_kind::TLDKind::GENERICFN {
+            type_params: func.type_params.clone(),
+            return_type: func.copy_return_type(),
+            args: func.get_parameters().clone(),
+        })
+    }
+
     pub fn new_struct_from_ast(st_ty: ast::StructDef) -> Self {
+        if !st_ty.type_params.is_empty() {
+            return Self::new(tld_kind::TLDKind::GENERICSTRUCT {
+                type_params: st_ty.type_params,
+                members: st_ty.members,
+            });
+        }
+
         Self::new(tld_kind::TLDKind::STRUCT {
             members: st_ty.members,
+            methods: BTreeMap::new(),
+        })
+    }
+
+    /// 構造体型であると解釈し，メソッドを登録する
+    pub fn add_method(&mut self, method_name: &str, symbol: String) {
+        if let tld_kind::TLDKind::STRUCT {
+            members: _,
+            methods,
+        } = &mut self.kind
+        {
+            methods.insert(method_name.to_string(), symbol);
+        }
+    }
+
+    pub fn new_interface_from_ast(if_ty: ast::InterfaceDef) -> Self {
+        Self::new(tld_kind::TLDKind::INTERFACE {
+            methods: if_ty
+                .methods
+                .into_iter()
+                .map(|(name, fn_ty)| (name, (fn_ty.args, fn_ty.return_type)))
+                .collect(),
         })
     }
 
@@ -4,6 +4,9 @@ type ArgType = String;
 type ArgName = String;
 type MemberType = String;
 type MemberName = String;
+type MethodName = String;
+type MethodSymbol = String;
+type ReturnType = String;
 
 #[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Clone)]
 pub enum TLDKind {
@@ -19,9 +22,29 @@ pub enum TLDKind {
         src_type: String,
     },
     STRUCT {
-        members: BTreeMap<MemberName, MemberType>,
+        /// 宣言順に並べたメンバ
+        members: Vec<(MemberName, MemberType)>,
+        /// レシーバ付きで定義された関数群
+        methods: BTreeMap<MethodName, MethodSymbol>,
     },
     ENUM {
         variants: BTreeMap<String, usize>,
     },
+    /// 型パラメータを持つ関数のテンプレート
+    /// 呼び出し側の型引数ごとに実体化され，実体は `FN` として登録される
+    GENERICFN {
+        type_params: Vec<String>,
+        return_type: String,
+        args: Vec<(ArgName, ArgType)>,
+    },
+    /// 型パラメータを持つ構造体
+    /// `Pair[Int64]` のように型引数を与えたときに型解決される
+    GENERICSTRUCT {
+        type_params: Vec<String>,
+        members: Vec<(MemberName, MemberType)>,
+    },
+    INTERFACE {
+        /// レシーバを除いたメソッドシグネチャ
+        methods: BTreeMap<MethodName, (Vec<(ArgName, ArgType)>, ReturnType)>,
+    },
 }
@@ -1,5 +1,7 @@
 mod _token;
+mod literal_bytes;
 mod tokenkind;
 
 pub use _token::*;
+pub use literal_bytes::*;
 pub use tokenkind::*;
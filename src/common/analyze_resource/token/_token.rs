@@ -50,6 +50,11 @@ impl Token {
         Self::new(TokenKind::UNSIGNEDINTEGER { value: v }, position)
     }
 
+    /// 文字リテラルトークンの定義
+    pub fn new_char_literal(v: u8, position: Position) -> Self {
+        Self::new(TokenKind::CHARLIT { value: v }, position)
+    }
+
     /// 空白の定義
     pub fn new_blank(position: Position) -> Self {
         Self::new(TokenKind::BLANK, position)
@@ -0,0 +1,66 @@
+/// 文字列リテラルの内容はStringで保持する
+/// UTF-8にならない `\x80` ~ `\xff` のバイトは，私用領域の文字 U+F780 ~ U+F7FF に対応付けて格納する
+const RAW_BYTE_BASE: u32 = 0xF700;
+
+/// 1バイトを，文字列リテラルの内容に格納する文字に変換する
+pub fn byte_to_literal_char(byte: u8) -> char {
+    if byte.is_ascii() {
+        byte as char
+    } else {
+        char::from_u32(RAW_BYTE_BASE + byte as u32).unwrap()
+    }
+}
+
+/// ソース中の文字を，文字列リテラルの内容に格納する
+/// 私用領域の文字そのものはUTF-8の各バイトに分けて格納し，`\xNN` 由来のバイトと区別する
+pub fn push_literal_char(contents: &mut String, c: char) {
+    if is_raw_byte_char(c) {
+        let mut buf = [0; 4];
+        for byte in c.encode_utf8(&mut buf).bytes() {
+            contents.push(byte_to_literal_char(byte));
+        }
+    } else {
+        contents.push(c);
+    }
+}
+
+/// 文字列リテラルの内容を，実際に出力するバイト列に戻す
+pub fn literal_bytes(contents: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(contents.len());
+    let mut buf = [0; 4];
+
+    for c in contents.chars() {
+        if is_raw_byte_char(c) {
+            bytes.push((c as u32 - RAW_BYTE_BASE) as u8);
+        } else {
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+        }
+    }
+
+    bytes
+}
+
+/// `\x80` ~ `\xff` のバイトを表す文字か
+pub fn is_raw_byte_char(c: char) -> bool {
+    (RAW_BYTE_BASE + 0x80..=RAW_BYTE_BASE + 0xff).contains(&(c as u32))
+}
+
+#[cfg(test)]
+mod literal_bytes_tests {
+    use super::*;
+
+    #[test]
+    fn literal_bytes_test() {
+        let mut contents = String::new();
+        for c in "aé".chars() {
+            push_literal_char(&mut contents, c);
+        }
+        contents.push(byte_to_literal_char(0xff));
+        push_literal_char(&mut contents, '\u{F7FF}');
+
+        assert_eq!(
+            vec![b'a', 0xc3, 0xa9, 0xff, 0xef, 0x9f, 0xbf],
+            literal_bytes(&contents)
+        );
+    }
+}
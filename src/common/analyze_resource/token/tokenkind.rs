@@ -10,6 +10,9 @@ pub enum TokenKind {
     /// 非符号付き整数リテラル( `100u` )
     UNSIGNEDINTEGER { value: u64 },
 
+    /// 文字リテラル( `'a'` )
+    CHARLIT { value: u8 },
+
     // TODO: あとでIDにするかも
     /// 文字列リテラル( `"Drumato"` )
     STRLIT { contents: String },
@@ -38,6 +41,10 @@ pub enum TokenKind {
     LBRACE,
     /// `}`
     RBRACE,
+    /// `[`
+    LBRACKET,
+    /// `]`
+    RBRACKET,
     /// `:`
     COLON,
     /// `::`
@@ -48,6 +55,8 @@ pub enum TokenKind {
     SEMICOLON,
     /// `=`
     ASSIGN,
+    /// `#`(`#[test]` のような属性に用いる)
+    SHARP,
     /// ` `
     BLANK,
     /// `\n`
@@ -60,6 +69,9 @@ pub enum TokenKind {
     EOF,
     /// `(COMMENT)`
     COMMENT { contents: String },
+    /// `/// ...`
+    /// 直後の宣言に付与するため，パーサまで残す
+    DOCCOMMENT { contents: String },
 
     // 予約語
     /// `asm`
@@ -98,6 +110,8 @@ pub enum TokenKind {
     NORETURN,
     /// `pubenum`
     PUBENUM,
+    /// `pubinterface`
+    PUBINTERFACE,
     /// `pubtype`
     PUBTYPE,
     /// `pubconst`
@@ -110,6 +124,8 @@ pub enum TokenKind {
     TRUE,
     /// `Uint64`
     UINT64,
+    /// `Uint8`
+    UINT8,
     /// `varinit`
     VARINIT,
 }
@@ -121,6 +137,8 @@ impl Display for TokenKind {
 
             TokenKind::UNSIGNEDINTEGER { value } => value.to_string(),
 
+            TokenKind::CHARLIT { value } => format!("'{}'", (*value as char).escape_default()),
+
             TokenKind::STRLIT { contents } => format!("\"{}\"", contents),
 
             TokenKind::IDENTIFIER { name } => name.to_string(),
@@ -136,17 +154,21 @@ impl Display for TokenKind {
             TokenKind::RPAREN => ")".to_string(),
             TokenKind::LBRACE => "{".to_string(),
             TokenKind::RBRACE => "}".to_string(),
+            TokenKind::LBRACKET => "[".to_string(),
+            TokenKind::RBRACKET => "]".to_string(),
             TokenKind::COLON => ":".to_string(),
             TokenKind::DOUBLECOLON => "::".to_string(),
             TokenKind::ARROW => "->".to_string(),
             TokenKind::SEMICOLON => ";".to_string(),
             TokenKind::ASSIGN => "=".to_string(),
+            TokenKind::SHARP => "#".to_string(),
             TokenKind::BLANK => "(BLANK)".to_string(),
             TokenKind::NEWLINE => "(NEWLINE)".to_string(),
             TokenKind::COMMA => ",".to_string(),
             TokenKind::DOT => ".".to_string(),
             TokenKind::EOF => "(EOF)".to_string(),
             TokenKind::COMMENT { contents: _ } => "(COMMENT)".to_string(),
+            TokenKind::DOCCOMMENT { contents: _ } => "(DOCCOMMENT)".to_string(),
 
             // 予約語
             TokenKind::ASM => "asm".to_string(),
@@ -167,12 +189,14 @@ impl Display for TokenKind {
             TokenKind::MATCH => "match".to_string(),
             TokenKind::NORETURN => "Noreturn".to_string(),
             TokenKind::PUBENUM => "pubenum".to_string(),
+            TokenKind::PUBINTERFACE => "pubinterface".to_string(),
             TokenKind::PUBTYPE => "pubtype".to_string(),
             TokenKind::PUBCONST => "pubconst".to_string(),
             TokenKind::RETURN => "return".to_string(),
             TokenKind::STRUCT => "struct".to_string(),
             TokenKind::TRUE => "true".to_string(),
             TokenKind::UINT64 => "Uint64".to_string(),
+            TokenKind::UINT8 => "Uint8".to_string(),
             TokenKind::VARINIT => "varinit".to_string(),
         };
 
@@ -192,10 +216,13 @@ impl TokenKind {
             ")" => TokenKind::RPAREN,
             "{" => TokenKind::LBRACE,
             "}" => TokenKind::RBRACE,
+            "[" => TokenKind::LBRACKET,
+            "]" => TokenKind::RBRACKET,
             ":" => TokenKind::COLON,
             "::" => TokenKind::DOUBLECOLON,
             "->" => TokenKind::ARROW,
             "=" => TokenKind::ASSIGN,
+            "#" => TokenKind::SHARP,
             "," => TokenKind::COMMA,
             ";" => TokenKind::SEMICOLON,
             "&" => TokenKind::AMPERSAND,
@@ -223,11 +250,13 @@ impl TokenKind {
             "match" => Some(TokenKind::MATCH),
             "Noreturn" => Some(TokenKind::NORETURN),
             "pubenum" => Some(TokenKind::PUBENUM),
+            "pubinterface" => Some(TokenKind::PUBINTERFACE),
             "pubtype" => Some(TokenKind::PUBTYPE),
             "return" => Some(TokenKind::RETURN),
             "struct" => Some(TokenKind::STRUCT),
             "true" => Some(TokenKind::TRUE),
             "Uint64" => Some(TokenKind::UINT64),
+            "Uint8" => Some(TokenKind::UINT8),
             "varinit" => Some(TokenKind::VARINIT),
             "pubconst" => Some(TokenKind::PUBCONST),
             _ => None,
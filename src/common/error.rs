@@ -1,7 +1,11 @@
 mod bundle_error;
 mod compile_error;
+mod dataflow_error;
+mod runtime_error;
 mod type_error;
 
 pub use bundle_error::*;
 pub use compile_error::*;
+pub use dataflow_error::*;
+pub use runtime_error::*;
 pub use type_error::*;
@@ -1,5 +1,7 @@
+mod parse_error;
 mod tokenize_error;
 
+pub use parse_error::*;
 pub use tokenize_error::*;
 
 use colored::*;
@@ -0,0 +1,40 @@
+use fmt::Formatter;
+use std::fmt;
+
+use crate::common::error::CompileErrorKind;
+
+/// Parserが発行するエラーの種類を列挙
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum ParseErrorKind {
+    /// メソッドのレシーバがちょうど1つでない
+    INVALIDRECEIVERCOUNT(usize),
+
+    /// `#[test]` の直後が関数定義でない
+    TESTATTRIBUTEWITHOUTFUNCTION,
+
+    /// `#[test]` 以外の属性
+    UNKNOWNATTRIBUTE(String),
+}
+
+impl CompileErrorKind for ParseErrorKind {
+    fn category(&self) -> &'static str {
+        "ParseError"
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ParseErrorKind::INVALIDRECEIVERCOUNT(count) => format!(
+                "a method must have exactly one receiver, but {} receivers are declared",
+                count
+            ),
+            ParseErrorKind::TESTATTRIBUTEWITHOUTFUNCTION => {
+                "`#[test]` must be followed by a function definition".to_string()
+            }
+            ParseErrorKind::UNKNOWNATTRIBUTE(name) => format!("unknown attribute `#[{}]`", name),
+        };
+
+        write!(f, "{}", s)
+    }
+}
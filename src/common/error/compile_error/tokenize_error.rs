@@ -7,7 +7,29 @@ use crate::common::error::CompileErrorKind;
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub enum TokenizeErrorKind {
     /// 整数トークンが許容範囲外であった
-    INTEGERLITERALOUTOFRANGE(String),
+    INTEGERLITERALOUTOFRANGE {
+        literal: String,
+        radix: u32,
+        unsigned: bool,
+    },
+
+    /// 基数に合わない桁や，不正な位置の `_` を含む整数リテラル
+    INVALIDINTEGERLITERAL(String),
+
+    /// ちょうど1つのASCII文字を含まない文字リテラル
+    INVALIDCHARACTERLITERAL,
+
+    /// 文字列/文字リテラル中の不正なエスケープシーケンス
+    INVALIDESCAPESEQUENCE(String),
+
+    /// 閉じられていない文字列リテラル
+    UNTERMINATEDSTRINGLITERAL,
+
+    /// 閉じられていないブロックコメント
+    UNTERMINATEDBLOCKCOMMENT,
+
+    /// 字句規則に当てはまらない文字
+    UNDEFINEDSYMBOL(char),
 
     /// これ以上トークナイズできない
     SOURCEISEMPTY,
@@ -22,12 +44,61 @@ impl CompileErrorKind for TokenizeErrorKind {
 impl fmt::Display for TokenizeErrorKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let s = match self {
-            TokenizeErrorKind::INTEGERLITERALOUTOFRANGE(number) => {
-                format!("an int-literal `{}` out of range 64bit", number)
+            TokenizeErrorKind::INTEGERLITERALOUTOFRANGE {
+                literal,
+                radix,
+                unsigned,
+            } => {
+                let (kind, type_name, max) = if *unsigned {
+                    ("uint-literal", "Uint64", u64::MAX)
+                } else {
+                    ("int-literal", "Int64", i64::MAX as u64)
+                };
+                format!(
+                    "{} {} `{}` out of range {} (max {})",
+                    radix_name(*radix),
+                    kind,
+                    literal,
+                    type_name,
+                    format_in_radix(max, *radix)
+                )
+            }
+            TokenizeErrorKind::INVALIDINTEGERLITERAL(literal) => {
+                format!("invalid int-literal `{}`", literal)
+            }
+            TokenizeErrorKind::INVALIDCHARACTERLITERAL => {
+                "a char-literal must contain exactly one ASCII character".to_string()
             }
+            TokenizeErrorKind::INVALIDESCAPESEQUENCE(sequence) => {
+                format!("invalid escape sequence `{}`", sequence)
+            }
+            TokenizeErrorKind::UNTERMINATEDSTRINGLITERAL => {
+                "unterminated string-literal".to_string()
+            }
+            TokenizeErrorKind::UNTERMINATEDBLOCKCOMMENT => "unterminated block comment".to_string(),
+            TokenizeErrorKind::UNDEFINEDSYMBOL(c) => format!("undefined symbol `{}`", c),
             TokenizeErrorKind::SOURCEISEMPTY => "source is empty".to_string(),
         };
 
         write!(f, "{}", s)
     }
 }
+
+fn radix_name(radix: u32) -> &'static str {
+    match radix {
+        16 => "a hexadecimal",
+        8 => "an octal",
+        2 => "a binary",
+        _ => "a decimal",
+    }
+}
+
+/// 上限値を，リテラルと同じ基数で表記する
+fn format_in_radix(value: u64, radix: u32) -> String {
+    match radix {
+        16 => format!("0x{:x}", value),
+        8 => format!("0o{:o}", value),
+        2 => format!("0b{:b}", value),
+        _ => value.to_string(),
+    }
+}
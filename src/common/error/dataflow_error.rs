@@ -0,0 +1,32 @@
+use crate::common::error::CompileErrorKind;
+use fmt::Formatter;
+use std::fmt;
+
+/// データフロー解析が発行するエラーの種類を列挙
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum DataflowErrorKind {
+    /// 代入されていない可能性のある変数を読み出した
+    UseBeforeAssignment { func_name: String, var_name: String },
+}
+
+impl CompileErrorKind for DataflowErrorKind {
+    fn category(&self) -> &'static str {
+        "DataflowError"
+    }
+}
+
+impl fmt::Display for DataflowErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            DataflowErrorKind::UseBeforeAssignment {
+                func_name,
+                var_name,
+            } => format!(
+                "variable `{}` may be used before being assigned in `{}`",
+                var_name, func_name
+            ),
+        };
+
+        write!(f, "{}", s)
+    }
+}
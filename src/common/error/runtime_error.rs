@@ -0,0 +1,53 @@
+use crate::common::error::CompileErrorKind;
+use fmt::Formatter;
+use std::fmt;
+
+/// 三番地コードのインタプリタが発行するエラーの種類を列挙
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum RuntimeErrorKind {
+    /// 定義されていない関数を呼び出した
+    UndefinedFunction { name: String },
+    /// 割り当てられていないアドレスを読み書きした
+    InvalidMemoryAccess { address: u64 },
+    /// 0除算，もしくは結果が表現できない除算
+    DivisionError { func_name: String },
+    /// 呼び出しが深すぎてスタックを使い果たした
+    StackOverflow { func_name: String },
+    /// インタプリタが解釈できないインラインアセンブリ
+    UnsupportedAssembly { func_name: String, asm: String },
+    /// インタプリタが解釈できないシステムコール
+    UnsupportedSyscall { number: u64 },
+}
+
+impl CompileErrorKind for RuntimeErrorKind {
+    fn category(&self) -> &'static str {
+        "RuntimeError"
+    }
+}
+
+impl fmt::Display for RuntimeErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            RuntimeErrorKind::UndefinedFunction { name } => {
+                format!("called undefined function `{}`", name)
+            }
+            RuntimeErrorKind::InvalidMemoryAccess { address } => {
+                format!("invalid memory access at {:#x}", address)
+            }
+            RuntimeErrorKind::DivisionError { func_name } => {
+                format!("division by zero or overflow in `{}`", func_name)
+            }
+            RuntimeErrorKind::StackOverflow { func_name } => {
+                format!("stack overflow while calling `{}`", func_name)
+            }
+            RuntimeErrorKind::UnsupportedAssembly { func_name, asm } => {
+                format!("cannot interpret `{}` in `{}`", asm, func_name)
+            }
+            RuntimeErrorKind::UnsupportedSyscall { number } => {
+                format!("cannot interpret system call {}", number)
+            }
+        };
+
+        write!(f, "{}", s)
+    }
+}
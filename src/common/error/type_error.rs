@@ -20,25 +20,145 @@ pub enum TypeErrorKind {
     /// メイン関数が見つからなかった
     NotFoundMainFunction,
 
-    /// メイン関数に何らかの引数が定義されてしまっている
-    MAINFUNCMUSTNOTHAVEANYARGUMENTS,
+    /// メイン関数の引数が `()` でも `(argc, argv[, envp])` でもない
+    InvalidMainFunctionArguments,
 
-    /// メイン関数はいかなる値も返さない
-    MainFunctionMustNotReturnAnyValues,
+    /// メイン関数の返り値の型がNoreturnでもInt64でもない
+    InvalidMainFunctionReturnType,
+
+    /// テスト関数が `()` を受け取りInt64を返す関数になっていない
+    InvalidTestFunction { func_name: String },
 
     /// 型名の場所で関数名が使用された
     GotFunctionNameAsType { func_name: String },
     /// 型名の場所で定数名が使用された
     GotConstantNameAsType { const_name: String },
 
-    /// 変数以外へメンバアクセスしようとした．
-    CannotAccessMemberWithNotAnIdentifier { struct_node: ast::ExpressionNode },
-
-    /// 構造体型以外にメンバアクセスした
+    /// 構造体(へのポインタ)型以外にメンバアクセスした
     CannotAccessMemberWIthNotAStruct { struct_node: ast::ExpressionNode },
 
     /// 該当するメンバが存在しなかった
     UndefinedSuchAMember { member: String },
+
+    /// 構造体型でない型名で構造体リテラルを書いた
+    NotAStructType { type_name: String },
+
+    /// 識別子がどのスコープの変数でも，トップレベルの宣言でもない
+    UndefinedSuchAVariable { name: String },
+
+    /// 配列/スライス/文字列リテラル以外に添字やlenを適用した
+    NotASequence { type_name: String },
+
+    /// 添字やスライスの境界が整数でない
+    NonIntegerIndex { actual: String },
+
+    /// 構造体リテラルで同じメンバを複数回初期化した
+    DuplicatedStructField { struct_name: String, member: String },
+
+    /// 構造体リテラルで初期化されていないメンバがある
+    MissingStructFields {
+        struct_name: String,
+        members: Vec<String>,
+    },
+
+    /// 構造体リテラルのメンバの型と初期化式の型が一致しない
+    MismatchedFieldType {
+        member: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// 代入先と代入する値の型が一致しない
+    MismatchedAssignmentType { expected: String, actual: String },
+
+    /// 該当するメソッドが存在しなかった
+    UndefinedSuchAMethod { struct_name: String, method: String },
+
+    /// 構造体型以外に対してメソッドを呼び出した
+    CannotCallMethodWithNotAStruct { recv_node: ast::ExpressionNode },
+
+    /// レシーバが構造体へのポインタ型でない
+    InvalidReceiverType {
+        method: String,
+        receiver_type: String,
+    },
+
+    /// レシーバの型が，メソッドと同じモジュールの構造体として定義されていない
+    UndefinedReceiverType { method: String, type_name: String },
+
+    /// 構造体(へのポインタ)型以外をインタフェース型に変換しようとした
+    CannotConvertToInterface {
+        type_name: String,
+        interface_name: String,
+    },
+
+    /// 構造体がインタフェースのメソッドを満たしていない
+    DoesNotImplementInterface {
+        type_name: String,
+        interface_name: String,
+        method: String,
+    },
+
+    /// ジェネリックな定義に与えた型引数の数が型パラメータと一致しない
+    WrongNumberOfTypeArguments {
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// ジェネリックでない関数に型引数を与えた
+    NotAGenericFunction { func_name: String },
+
+    /// 関数でも関数ポインタでもないものを呼び出した
+    NotAFunction { name: String },
+
+    /// 変数の型と初期化式の型が一致しない
+    MismatchedVariableType {
+        ident_name: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// 引数の数が関数型と一致しない
+    MismatchedArgumentCount {
+        func_name: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// 引数の型が関数型と一致しない
+    MismatchedArgumentType {
+        func_name: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// 型なし整数定数が変換先の整数型に収まらない
+    UntypedConstantOutOfRange { value: String, type_name: String },
+
+    /// 算術演算の被演算子が整数でない
+    NonIntegerOperand { actual: String },
+
+    /// 算術演算の両辺の型が一致しない
+    MismatchedOperandTypes { lhs: String, rhs: String },
+
+    /// 符号なし整数型の値を符号反転した
+    NegationOfUnsigned { actual: String },
+
+    /// if式の条件式がBooleanでない
+    NonBooleanCondition { actual: String },
+
+    /// return文の式の型が関数の返り値型と一致しない
+    MismatchedReturnType { expected: String, actual: String },
+
+    /// 局所変数のアドレスを返そうとした
+    ReturnLocalAddress { name: String },
+
+    /// 定数に代入しようとした
+    AssignmentToConstant { name: String },
+
+    /// モジュールで修飾された関数が，そのモジュールに定義されていない
+    UndefinedSuchAFunction { name: String },
 }
 
 impl CompileErrorKind for TypeErrorKind {
@@ -59,10 +179,6 @@ impl fmt::Display for TypeErrorKind {
             TypeErrorKind::GotConstantNameAsType { const_name } => {
                 format!("a constant `{}` used as a type-name", const_name)
             }
-            TypeErrorKind::CannotAccessMemberWithNotAnIdentifier { struct_node } => format!(
-                "cannot access member of `{:?}`, its not an identifier",
-                struct_node
-            ),
             TypeErrorKind::CannotAccessMemberWIthNotAStruct { struct_node } => format!(
                 "cannot access member of `{:?}`, its not a struct",
                 struct_node
@@ -70,13 +186,156 @@ impl fmt::Display for TypeErrorKind {
             TypeErrorKind::UndefinedSuchAMember { member } => {
                 format!("undefined such a member -> `{}`", member)
             }
+            TypeErrorKind::NotAStructType { type_name } => {
+                format!("`{}` is not a struct type", type_name)
+            }
+            TypeErrorKind::UndefinedSuchAVariable { name } => {
+                format!("undefined such a variable -> `{}`", name)
+            }
+            TypeErrorKind::NotASequence { type_name } => format!(
+                "`{}` is neither an array, a slice nor a string literal",
+                type_name
+            ),
+            TypeErrorKind::NonIntegerIndex { actual } => {
+                format!("index must be an integer, got `{}`", actual)
+            }
+            TypeErrorKind::DuplicatedStructField {
+                struct_name,
+                member,
+            } => format!(
+                "member `{}` of `{}` is initialized more than once",
+                member, struct_name
+            ),
+            TypeErrorKind::MissingStructFields {
+                struct_name,
+                members,
+            } => format!(
+                "missing member(s) in literal of `{}` -> `{}`",
+                struct_name,
+                members.join("`, `")
+            ),
+            TypeErrorKind::MismatchedFieldType {
+                member,
+                expected,
+                actual,
+            } => format!(
+                "mismatched type in initializing member `{}`: expected `{}`, got `{}`",
+                member, expected, actual
+            ),
+            TypeErrorKind::MismatchedAssignmentType { expected, actual } => format!(
+                "mismatched type in assignment: expected `{}`, got `{}`",
+                expected, actual
+            ),
+            TypeErrorKind::UndefinedSuchAMethod {
+                struct_name,
+                method,
+            } => format!(
+                "undefined such a method -> `{}` in `{}`",
+                method, struct_name
+            ),
+            TypeErrorKind::CannotCallMethodWithNotAStruct { recv_node } => {
+                format!("cannot call method of `{:?}`, its not a struct", recv_node)
+            }
+            TypeErrorKind::InvalidReceiverType {
+                method,
+                receiver_type,
+            } => format!(
+                "receiver of method `{}` must be a pointer to struct, got `{}`",
+                method, receiver_type
+            ),
+            TypeErrorKind::UndefinedReceiverType { method, type_name } => format!(
+                "receiver type `{}` of method `{}` is not a struct defined in this module",
+                type_name, method
+            ),
+            TypeErrorKind::CannotConvertToInterface {
+                type_name,
+                interface_name,
+            } => format!("cannot use `{}` as `{}`", type_name, interface_name),
+            TypeErrorKind::DoesNotImplementInterface {
+                type_name,
+                interface_name,
+                method,
+            } => format!(
+                "`{}` does not implement `{}` (missing or mismatched method `{}`)",
+                type_name, interface_name, method
+            ),
+            TypeErrorKind::WrongNumberOfTypeArguments {
+                name,
+                expected,
+                actual,
+            } => format!(
+                "`{}` expects {} type argument(s), but got {}",
+                name, expected, actual
+            ),
+            TypeErrorKind::NotAGenericFunction { func_name } => {
+                format!("`{}` is not a generic function", func_name)
+            }
+            TypeErrorKind::NotAFunction { name } => {
+                format!("`{}` is neither a function nor a function pointer", name)
+            }
+            TypeErrorKind::MismatchedArgumentCount {
+                func_name,
+                expected,
+                actual,
+            } => format!(
+                "`{}` expects {} argument(s), but got {}",
+                func_name, expected, actual
+            ),
+            TypeErrorKind::MismatchedArgumentType {
+                func_name,
+                expected,
+                actual,
+            } => format!(
+                "mismatched argument type in calling `{}`: expected `{}`, got `{}`",
+                func_name, expected, actual
+            ),
+            TypeErrorKind::UntypedConstantOutOfRange { value, type_name } => {
+                format!("constant `{}` overflows `{}`", value, type_name)
+            }
+            TypeErrorKind::NonIntegerOperand { actual } => {
+                format!("operand of arithmetic must be an integer, got `{}`", actual)
+            }
+            TypeErrorKind::MismatchedOperandTypes { lhs, rhs } => {
+                format!("mismatched operand types: `{}` and `{}`", lhs, rhs)
+            }
+            TypeErrorKind::NegationOfUnsigned { actual } => {
+                format!("cannot negate a value of unsigned type `{}`", actual)
+            }
+            TypeErrorKind::NonBooleanCondition { actual } => {
+                format!("condition must be `Boolean`, got `{}`", actual)
+            }
+            TypeErrorKind::MismatchedReturnType { expected, actual } => format!(
+                "mismatched type in return: expected `{}`, got `{}`",
+                expected, actual
+            ),
+            TypeErrorKind::ReturnLocalAddress { name } => {
+                format!("cannot return the address of local variable `{}`", name)
+            }
+            TypeErrorKind::AssignmentToConstant { name } => {
+                format!("cannot assign to constant `{}`", name)
+            }
+            TypeErrorKind::UndefinedSuchAFunction { name } => {
+                format!("undefined such a function -> `{}`", name)
+            }
+            TypeErrorKind::MismatchedVariableType {
+                ident_name,
+                expected,
+                actual,
+            } => format!(
+                "mismatched type in initializing `{}`: expected `{}`, got `{}`",
+                ident_name, expected, actual
+            ),
             TypeErrorKind::NotFoundMainFunction => "entry point `main` not found".to_string(),
-            TypeErrorKind::MAINFUNCMUSTNOTHAVEANYARGUMENTS => {
-                "entry point `main` mustn't have any arguments".to_string()
+            TypeErrorKind::InvalidMainFunctionArguments => {
+                "entry point `main` must take `()`, `(argc Int64, argv *ConstStr)` or `(argc Int64, argv *ConstStr, envp *ConstStr)`".to_string()
             }
-            TypeErrorKind::MainFunctionMustNotReturnAnyValues => {
-                "entry point `main` mustn't return any values".to_string()
+            TypeErrorKind::InvalidMainFunctionReturnType => {
+                "entry point `main` must return `Int64` or `Noreturn`".to_string()
             }
+            TypeErrorKind::InvalidTestFunction { func_name } => format!(
+                "test `{}` must be a non-generic function taking `()` and returning `Int64`",
+                func_name
+            ),
         };
 
         write!(f, "{}", s)
@@ -24,6 +24,22 @@ impl BuildOption {
                 Some(s) => s.to_string(),
                 None => panic!("source file must be specified"),
             },
+            ("doc", Some(doc_m)) => match doc_m.value_of("source") {
+                Some(s) => s.to_string(),
+                None => panic!("source file must be specified"),
+            },
+            ("check", Some(check_m)) => match check_m.value_of("source") {
+                Some(s) => s.to_string(),
+                None => panic!("source file must be specified"),
+            },
+            ("run", Some(run_m)) => match run_m.value_of("source") {
+                Some(s) => s.to_string(),
+                None => panic!("source file must be specified"),
+            },
+            ("test", Some(test_m)) => match test_m.value_of("source") {
+                Some(s) => s.to_string(),
+                None => panic!("source file must be specified"),
+            },
             _ => panic!("source file must be specified"),
         }
     }
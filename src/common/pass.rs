@@ -1,13 +1,17 @@
 mod analyzer;
 mod backend;
 mod build_cfg;
+mod dataflow;
 mod frontend;
 mod parser;
+mod test_harness;
 mod tld_collector;
 mod tokenizer;
 mod translator;
 
 pub use backend::*;
 pub use build_cfg::*;
+pub use dataflow::*;
 pub use frontend::*;
+pub use tokenizer::tokenize_with_trivia;
 pub use translator::*;
@@ -6,6 +6,9 @@ mod type_check;
 
 pub use type_check::*;
 
+mod definite_assignment;
+pub use definite_assignment::*;
+
 mod alloc_frame;
 pub use alloc_frame::*;
 
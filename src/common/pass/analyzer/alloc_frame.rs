@@ -4,17 +4,22 @@ use std::collections::BTreeMap;
 pub fn allocate_stack_frame(
     _tld_map: &BTreeMap<String, tld::TopLevelDecl>,
     type_env: &BTreeMap<String, BTreeMap<String, peachili_type::Type>>,
+    scope_env: &frame_object::ScopeEnv,
 ) -> frame_object::StackFrame {
     let mut stack_frame = BTreeMap::new();
 
     for (scope_name, func_env) in type_env {
         let mut frame_in_func = BTreeMap::new();
 
-        let mut total_offset_in_func = 0;
-        // 先に関数以外をすべて回したあと，関数を回す
-        // すべてのローカル変数のサイズを合計しないといけないため
+        // 関数本体のスコープ木がわかっていれば，兄弟スコープ間で領域を共有する
+        let mut total_offset_in_func = match scope_env.get(scope_name) {
+            Some(fn_scope) => allocate_scope(func_env, fn_scope, 0, &mut frame_in_func),
+            None => 0,
+        };
+
+        // スコープ木に含まれない変数は，すべて別々の領域に置く
         for (entry_name, entry) in func_env {
-            if entry.is_function() {
+            if entry.is_function() || frame_in_func.contains_key(entry_name) {
                 continue;
             }
             total_offset_in_func += entry.size;
@@ -46,3 +51,76 @@ pub fn allocate_stack_frame(
 
     stack_frame
 }
+
+/// スコープ内の変数を base の直後から並べ，内側のスコープはその後ろに置く
+/// 兄弟スコープは同じ位置から割り付けるので，スタック上の領域が再利用される
+/// 使用した領域の末尾を返す
+fn allocate_scope(
+    func_env: &BTreeMap<String, peachili_type::Type>,
+    scope: &frame_object::LexicalScope,
+    base: usize,
+    frame_in_func: &mut BTreeMap<String, frame_object::FrameObject>,
+) -> usize {
+    let mut offset = base;
+    for var_name in scope.variables.iter() {
+        offset += func_env.get(var_name).unwrap().size;
+        frame_in_func.insert(var_name.to_string(), frame_object::FrameObject { offset });
+    }
+
+    scope.children.iter().fold(offset, |end, child| {
+        end.max(allocate_scope(func_env, child, offset, frame_in_func))
+    })
+}
+
+#[cfg(test)]
+mod alloc_frame_tests {
+    use super::*;
+    use crate::common::option::Target;
+    use crate::common::peachili_type::Type;
+
+    #[test]
+    fn reuse_slots_in_disjoint_scopes_test() {
+        let mut func_env = BTreeMap::new();
+        for name in ["a", "b", "c", "c#1"].iter() {
+            func_env.insert(name.to_string(), Type::new_int64(Target::X86_64));
+        }
+        func_env.insert(
+            "f".to_string(),
+            Type::new_function(Type::new_noreturn(), Vec::new()),
+        );
+
+        // f() { a; if { b; } else { c; if { c#1; } } }
+        let fn_scope = frame_object::LexicalScope {
+            variables: vec!["a".to_string()],
+            children: vec![
+                frame_object::LexicalScope {
+                    variables: vec!["b".to_string()],
+                    children: Vec::new(),
+                },
+                frame_object::LexicalScope {
+                    variables: vec!["c".to_string()],
+                    children: vec![frame_object::LexicalScope {
+                        variables: vec!["c#1".to_string()],
+                        children: Vec::new(),
+                    }],
+                },
+            ],
+        };
+
+        let mut type_env = BTreeMap::new();
+        type_env.insert("f".to_string(), func_env);
+        let mut scope_env = BTreeMap::new();
+        scope_env.insert("f".to_string(), fn_scope);
+
+        let frame = allocate_stack_frame(&BTreeMap::new(), &type_env, &scope_env);
+        let f_frame = frame.get("f").unwrap();
+
+        assert_eq!(8, f_frame.get("a").unwrap().offset);
+        // bとcは同じ領域を共有する
+        assert_eq!(16, f_frame.get("b").unwrap().offset);
+        assert_eq!(16, f_frame.get("c").unwrap().offset);
+        assert_eq!(24, f_frame.get("c#1").unwrap().offset);
+        // フレームサイズは最も深い位置まで
+        assert_eq!(24, f_frame.get("f").unwrap().offset);
+    }
+}
@@ -141,7 +141,11 @@ fn folding_expr(expr_arena: ast::ExprArena, ast_expr_id: ast::ExNodeId) -> ast::
 
             ast_expr
         }
-        ast::ExpressionNodeKind::CALL { names, args } => {
+        ast::ExpressionNodeKind::CALL {
+            names,
+            type_args,
+            args,
+        } => {
             let mut optimized_args: Vec<ast::ExNodeId> = Vec::new();
 
             for arg_id in args.iter() {
@@ -149,7 +153,27 @@ fn folding_expr(expr_arena: ast::ExprArena, ast_expr_id: ast::ExNodeId) -> ast::
                 optimized_args.push(expr_arena.lock().unwrap().alloc(optimized_arg));
             }
 
-            ast::ExpressionNode::new_call(names.clone(), optimized_args, ast_expr.get_pos())
+            ast::ExpressionNode::new_call(
+                names.clone(),
+                type_args.clone(),
+                optimized_args,
+                ast_expr.get_pos(),
+            )
+        }
+        ast::ExpressionNodeKind::METHODCALL { recv, method, args } => {
+            let mut optimized_args: Vec<ast::ExNodeId> = Vec::new();
+
+            for arg_id in args.iter() {
+                let optimized_arg = folding_expr(expr_arena.clone(), *arg_id);
+                optimized_args.push(expr_arena.lock().unwrap().alloc(optimized_arg));
+            }
+
+            ast::ExpressionNode::new_method_call(
+                *recv,
+                method.clone(),
+                optimized_args,
+                ast_expr.get_pos(),
+            )
         }
         _ => ast_expr,
     }
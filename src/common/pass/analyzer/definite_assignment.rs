@@ -0,0 +1,457 @@
+use crate::common::error::{CompileError, DataflowErrorKind};
+use crate::common::position::Position;
+use crate::common::{ast, peachili_type::Type};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 変数名の集合
+/// Noneは到達しない(すべての変数が代入済みとみなす)状態を表す
+type AssignedSet = Option<BTreeSet<String>>;
+
+/// 定義代入検査
+/// `declare` した変数が，代入される前に読まれうる箇所があればエラーとする
+/// 型検査の有無に関わらず，型解決済みのASTに対して行う
+pub fn definite_assignment_check(
+    fn_arena: ast::FnArena,
+    type_env: &BTreeMap<String, BTreeMap<String, Type>>,
+    ast_root: &ast::ASTRoot,
+) -> Result<(), CompileError<DataflowErrorKind>> {
+    let fn_arena = fn_arena.lock().unwrap();
+
+    for fn_id in ast_root.funcs.iter() {
+        let function = fn_arena.get(*fn_id).unwrap();
+        let locals = match type_env.get(&function.full_path()) {
+            Some(locals) => locals,
+            None => continue,
+        };
+
+        let mut checker = AssignmentChecker {
+            function,
+            locals,
+            declared: BTreeSet::new(),
+        };
+        let mut state = Some(BTreeSet::new());
+        checker.check_stmts(&function.stmts, &mut state)?;
+    }
+
+    Ok(())
+}
+
+/// 合流点では，どちらの経路でも代入済みの変数だけが残る
+fn meet(lhs: AssignedSet, rhs: AssignedSet) -> AssignedSet {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => Some(lhs.intersection(&rhs).cloned().collect()),
+        (lhs, None) => lhs,
+        (None, rhs) => rhs,
+    }
+}
+
+struct AssignmentChecker<'a> {
+    function: &'a ast::Function,
+    /// 一意な変数名 => 型
+    locals: &'a BTreeMap<String, Type>,
+    /// 検査対象(declareされた変数)の変数名
+    declared: BTreeSet<String>,
+}
+
+impl<'a> AssignmentChecker<'a> {
+    fn check_stmts(
+        &mut self,
+        stmts: &[ast::StNodeId],
+        state: &mut AssignedSet,
+    ) -> Result<(), CompileError<DataflowErrorKind>> {
+        for stmt_id in stmts.iter() {
+            self.check_stmt(*stmt_id, state)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_stmt(
+        &mut self,
+        stmt_id: ast::StNodeId,
+        state: &mut AssignedSet,
+    ) -> Result<(), CompileError<DataflowErrorKind>> {
+        let stmt = self
+            .function
+            .stmt_arena
+            .lock()
+            .unwrap()
+            .get(stmt_id)
+            .unwrap()
+            .clone();
+
+        match stmt.get_kind() {
+            ast::StatementNodeKind::DECLARE {
+                ident_name,
+                type_name: _,
+            } => {
+                self.declared.insert(ident_name.to_string());
+                if let Some(assigned) = state {
+                    assigned.remove(ident_name);
+                }
+            }
+            ast::StatementNodeKind::VARINIT {
+                ident_name: _,
+                type_name: _,
+                expr,
+            }
+            | ast::StatementNodeKind::CONST {
+                ident_name: _,
+                type_name: _,
+                expr,
+            }
+            | ast::StatementNodeKind::EXPR { expr }
+            | ast::StatementNodeKind::IFRET { expr } => self.read(*expr, state)?,
+            ast::StatementNodeKind::RETURN { expr } => {
+                self.read(*expr, state)?;
+                *state = None;
+            }
+            ast::StatementNodeKind::COUNTUP {
+                ident_name: _,
+                begin_ex,
+                endpoint_ex,
+                body,
+            } => {
+                self.read(*begin_ex, state)?;
+                self.read(*endpoint_ex, state)?;
+
+                // 一度も実行されない場合があるので，ループ後は本体の代入を考慮しない
+                let mut body_state = state.clone();
+                self.check_stmts(body, &mut body_state)?;
+            }
+            ast::StatementNodeKind::MATCH { expr, arms } => {
+                self.read(*expr, state)?;
+
+                // どのアームにも一致しない場合は，何も実行されない
+                let mut merged = state.clone();
+                for arm in arms.values() {
+                    let mut arm_state = state.clone();
+                    self.check_stmts(arm, &mut arm_state)?;
+                    merged = meet(merged, arm_state);
+                }
+                *state = merged;
+            }
+            ast::StatementNodeKind::ASM { stmts: _ } => {}
+        }
+
+        Ok(())
+    }
+
+    /// 値としての評価
+    fn read(
+        &mut self,
+        expr_id: ast::ExNodeId,
+        state: &mut AssignedSet,
+    ) -> Result<(), CompileError<DataflowErrorKind>> {
+        let expr = self.get_expr(expr_id);
+
+        match expr.get_kind() {
+            ast::ExpressionNodeKind::IDENTIFIER { names } => {
+                self.check_assigned(names, expr.get_pos(), state)?
+            }
+            ast::ExpressionNodeKind::CALL {
+                names,
+                type_args: _,
+                args,
+            } => {
+                for arg in args.iter() {
+                    self.read(*arg, state)?;
+                }
+
+                // 関数ポインタ変数の呼び出しもありうる
+                self.check_assigned(names, expr.get_pos(), state)?;
+            }
+            ast::ExpressionNodeKind::METHODCALL {
+                recv,
+                method: _,
+                args,
+            } => {
+                // 構造体そのものがレシーバであれば，そのアドレスを渡す
+                if self.is_struct_place(*recv) {
+                    self.write(*recv, state)?;
+                } else {
+                    self.read(*recv, state)?;
+                }
+
+                for arg in args.iter() {
+                    self.read(*arg, state)?;
+                }
+            }
+            ast::ExpressionNodeKind::IF {
+                cond_ex,
+                body,
+                alter,
+            } => {
+                self.read(*cond_ex, state)?;
+
+                let mut then_state = state.clone();
+                self.check_stmts(body, &mut then_state)?;
+
+                let mut else_state = state.clone();
+                if let Some(alter) = alter {
+                    self.check_stmts(alter, &mut else_state)?;
+                }
+
+                *state = meet(then_state, else_state);
+            }
+            ast::ExpressionNodeKind::ADD { lhs, rhs }
+            | ast::ExpressionNodeKind::SUB { lhs, rhs }
+            | ast::ExpressionNodeKind::MUL { lhs, rhs }
+            | ast::ExpressionNodeKind::DIV { lhs, rhs } => {
+                self.read(*lhs, state)?;
+                self.read(*rhs, state)?;
+            }
+            ast::ExpressionNodeKind::ASSIGN { lhs, rhs } => {
+                self.read(*rhs, state)?;
+                self.write(*lhs, state)?;
+            }
+            ast::ExpressionNodeKind::NEG { value }
+            | ast::ExpressionNodeKind::DEREFERENCE { value } => self.read(*value, state)?,
+            // アドレスが漏れた後はどこで読み書きされるか追えないので，代入済みとみなす
+            ast::ExpressionNodeKind::ADDRESSOF { value } => self.write(*value, state)?,
+            ast::ExpressionNodeKind::MEMBER { id, member: _ } => self.read(*id, state)?,
+            ast::ExpressionNodeKind::INDEX { value, index } => {
+                self.read(*value, state)?;
+                self.read(*index, state)?;
+            }
+            ast::ExpressionNodeKind::SLICE { value, low, high } => {
+                // 配列のスライスは配列のアドレスを持つ
+                if self.is_array_place(*value) {
+                    self.write(*value, state)?;
+                } else {
+                    self.read(*value, state)?;
+                }
+
+                for bound in low.iter().chain(high.iter()) {
+                    self.read(*bound, state)?;
+                }
+            }
+            ast::ExpressionNodeKind::LEN { value } => {
+                // 配列の長さは型から決まるので，読み出さない
+                if !self.is_array_place(*value) {
+                    self.read(*value, state)?;
+                }
+            }
+            ast::ExpressionNodeKind::STRUCTLITERAL {
+                type_name: _,
+                fields,
+                storage: _,
+            } => {
+                for (_member, value) in fields.iter() {
+                    self.read(*value, state)?;
+                }
+            }
+            ast::ExpressionNodeKind::INTEGER { value: _ }
+            | ast::ExpressionNodeKind::UINTEGER { value: _ }
+            | ast::ExpressionNodeKind::CHARACTER { value: _ }
+            | ast::ExpressionNodeKind::BOOLEAN { truth: _ }
+            | ast::ExpressionNodeKind::STRING { contents: _ } => {}
+        }
+
+        Ok(())
+    }
+
+    /// 代入先としての評価
+    /// メンバや要素への書き込みも，変数全体に代入したものとみなす
+    fn write(
+        &mut self,
+        expr_id: ast::ExNodeId,
+        state: &mut AssignedSet,
+    ) -> Result<(), CompileError<DataflowErrorKind>> {
+        self.read_place_operands(expr_id, state)?;
+
+        if let (Some(name), Some(assigned)) = (self.base_variable(expr_id), state) {
+            assigned.insert(name);
+        }
+
+        Ok(())
+    }
+
+    /// 代入先の式のうち，アドレス計算のために読み出す部分を評価する
+    fn read_place_operands(
+        &mut self,
+        expr_id: ast::ExNodeId,
+        state: &mut AssignedSet,
+    ) -> Result<(), CompileError<DataflowErrorKind>> {
+        let expr = self.get_expr(expr_id);
+
+        match expr.get_kind() {
+            ast::ExpressionNodeKind::IDENTIFIER { names: _ } => Ok(()),
+            ast::ExpressionNodeKind::MEMBER { id, member: _ } => {
+                if self.is_struct_place(*id) {
+                    self.read_place_operands(*id, state)
+                } else {
+                    self.read(*id, state)
+                }
+            }
+            ast::ExpressionNodeKind::INDEX { value, index } => {
+                if self.is_array_place(*value) {
+                    self.read_place_operands(*value, state)?;
+                } else {
+                    self.read(*value, state)?;
+                }
+                self.read(*index, state)
+            }
+            _ => self.read(expr_id, state),
+        }
+    }
+
+    /// ポインタを介さずに辿れる，代入先の変数
+    fn base_variable(&self, expr_id: ast::ExNodeId) -> Option<String> {
+        match self.get_expr(expr_id).get_kind() {
+            ast::ExpressionNodeKind::IDENTIFIER { names } if names.len() == 1 => {
+                Some(names[0].clone())
+            }
+            ast::ExpressionNodeKind::MEMBER { id, member: _ } if self.is_struct_place(*id) => {
+                self.base_variable(*id)
+            }
+            ast::ExpressionNodeKind::INDEX { value, index: _ } if self.is_array_place(*value) => {
+                self.base_variable(*value)
+            }
+            _ => None,
+        }
+    }
+
+    fn is_struct_place(&self, expr_id: ast::ExNodeId) -> bool {
+        self.place_type(expr_id).is_some_and(|ty| ty.is_struct())
+    }
+
+    fn is_array_place(&self, expr_id: ast::ExNodeId) -> bool {
+        self.place_type(expr_id).is_some_and(|ty| ty.is_array())
+    }
+
+    /// 変数や，そのメンバ/要素を指す式の型
+    fn place_type(&self, expr_id: ast::ExNodeId) -> Option<Type> {
+        match self.get_expr(expr_id).get_kind() {
+            ast::ExpressionNodeKind::IDENTIFIER { names } if names.len() == 1 => {
+                self.locals.get(&names[0]).cloned()
+            }
+            ast::ExpressionNodeKind::MEMBER { id, member } => {
+                let base_type = self.place_type(*id)?;
+                let (member_type, _offset) = base_type.receiver_struct()?.get_member(member)?;
+                Some(*member_type.clone())
+            }
+            ast::ExpressionNodeKind::INDEX { value, index: _ } => {
+                let base_type = self.place_type(*value)?;
+                if base_type.is_array() || base_type.is_slice() {
+                    Some(base_type.element_type().clone())
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn check_assigned(
+        &self,
+        names: &[String],
+        pos: Position,
+        state: &AssignedSet,
+    ) -> Result<(), CompileError<DataflowErrorKind>> {
+        if names.len() != 1 || !self.declared.contains(&names[0]) {
+            return Ok(());
+        }
+
+        match state {
+            Some(assigned) if !assigned.contains(&names[0]) => {
+                // スコープ解決で付与された接尾辞は取り除いて報告する
+                let var_name = names[0].split('#').next().unwrap().to_string();
+                Err(CompileError::new(
+                    DataflowErrorKind::UseBeforeAssignment {
+                        func_name: self.function.full_path(),
+                        var_name,
+                    },
+                    pos,
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn get_expr(&self, expr_id: ast::ExNodeId) -> ast::ExpressionNode {
+        self.function
+            .expr_arena
+            .lock()
+            .unwrap()
+            .get(expr_id)
+            .unwrap()
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod definite_assignment_tests {
+    use super::*;
+    use crate::common::option::Target;
+    use crate::common::pass::{analyzer, parser, tld_collector, tokenizer};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn use_before_assignment_test() {
+        // 読み出した識別子の位置を報告する
+        let actual = check_source("func main() Int64 {\n    declare x Int64;\n    return x;\n}");
+        assert_eq!(
+            Err(CompileError::new(
+                DataflowErrorKind::UseBeforeAssignment {
+                    func_name: "main".to_string(),
+                    var_name: "x".to_string(),
+                },
+                Position::new(3, 12),
+            )),
+            actual
+        );
+    }
+
+    #[test]
+    fn assigned_in_only_one_branch_test() {
+        assert!(check_source(
+            "func main() Int64 { declare x Int64; if (true) { x = 1; }; return x; }"
+        )
+        .is_err());
+        assert!(check_source(
+            "func main() Int64 { declare x Int64; if (true) { x = 1; } else { x = 2; }; return x; }"
+        )
+        .is_ok());
+        // returnした経路は合流しない
+        assert!(check_source(
+            "func main() Int64 { declare x Int64; if (true) { return 0; } else { x = 2; }; return x; }"
+        )
+        .is_ok());
+        // ループ本体は実行されない場合がある
+        assert!(check_source(
+            "func main() Int64 { declare x Int64; countup i begin 0 exclude 3 { x = i; }; return x; }"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn write_through_member_and_address_test() {
+        // メンバへの書き込みやアドレスの流出は，代入とみなす
+        assert!(check_source(
+            "struct P { x Int64 y Int64 } func main() Int64 { declare p P; p.x = 1; return p.x; }"
+        )
+        .is_ok());
+        assert!(check_source(
+            "func set(p *Int64) Noreturn { *p = 1; } func main() Int64 { declare x Int64; set(&x); return x; }"
+        )
+        .is_ok());
+        // ポインタ変数を介した書き込みはポインタの読み出し
+        assert!(check_source(
+            "struct P { x Int64 y Int64 } func main() Int64 { declare p *P; p.x = 1; return 0; }"
+        )
+        .is_err());
+    }
+
+    fn check_source(source: &str) -> Result<(), CompileError<DataflowErrorKind>> {
+        let fn_arena: ast::FnArena = Arc::new(Mutex::new(Default::default()));
+        let tokens = tokenizer::main(source.to_string());
+        let ast_root = parser::parse(fn_arena.clone(), tokens, String::new()).unwrap();
+        let tld_map = tld_collector::main(fn_arena.clone(), &ast_root).unwrap();
+        let (type_env, _scope_env) =
+            analyzer::type_resolve_main(fn_arena.clone(), &tld_map, &ast_root, Target::X86_64)
+                .unwrap();
+
+        definite_assignment_check(fn_arena, &type_env, &ast_root)
+    }
+}
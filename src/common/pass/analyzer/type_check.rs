@@ -1,8 +1,11 @@
 use crate::common::{ast, error::CompileError, option, peachili_type::Type, tld};
 
 use crate::common::error::TypeErrorKind;
+use crate::common::pass::analyzer::{resolve_function_type, resolve_type_string};
 use crate::common::peachili_type::TypeKind;
-use std::collections::BTreeMap;
+use crate::common::position::Position;
+use id_arena::Arena;
+use std::collections::{BTreeMap, BTreeSet};
 
 /// 型検査
 pub fn type_check_main(
@@ -11,7 +14,7 @@ pub fn type_check_main(
     type_env: &BTreeMap<String, BTreeMap<String, Type>>,
     ast_root: &ast::ASTRoot,
     target: option::Target,
-) {
+) -> Result<(), CompileError<TypeErrorKind>> {
     // メイン関数が存在しなければエラー
     let mut main_func_exists = false;
 
@@ -23,336 +26,2364 @@ pub fn type_check_main(
             // メイン関数の場合，特別なチェックが必要
             if func_name == "main" {
                 main_func_exists = true;
-                if let Err(e) = type_check_main_fn(tld_env, type_env, function, target) {
-                    e.output();
-                    std::process::exit(1);
-                }
-            } else if let Err(e) = type_check_fn(tld_env, type_env, function, target) {
-                e.output();
-                std::process::exit(1);
+                type_check_main_fn(tld_env, type_env, function, target)?;
+            } else {
+                type_check_fn(tld_env, type_env, function, target)?;
             }
         }
     }
 
     // エントリポイントがなければエラー
     if !main_func_exists {
-        CompileError::new(TypeErrorKind::NotFoundMainFunction, Default::default()).output();
-        std::process::exit(1);
+        return Err(CompileError::new(
+            TypeErrorKind::NotFoundMainFunction,
+            Default::default(),
+        ));
     }
+
+    Ok(())
 }
 
-/// メイン関数特有のチェック
-fn type_check_main_fn(
+/// `#[test]` を付与された関数を検査する
+/// ハーネスは各テストを引数なしで呼び出し，返り値を子プロセスの終了コードにする
+pub fn test_function_check(
+    fn_arena: ast::FnArena,
+    ast_root: &ast::ASTRoot,
+) -> Result<(), CompileError<TypeErrorKind>> {
+    let fn_arena = fn_arena.lock().unwrap();
+
+    for fn_id in ast_root.funcs.iter().chain(ast_root.generic_funcs.iter()) {
+        let function = fn_arena.get(*fn_id).unwrap();
+        if !ast_root.tests.contains(&function.full_path()) {
+            continue;
+        }
+
+        let valid = !function.is_generic()
+            && function.method_of.is_none()
+            && function.get_parameters().is_empty()
+            && function.fn_type.return_type == "Int64";
+        if !valid {
+            return Err(CompileError::new(
+                TypeErrorKind::InvalidTestFunction {
+                    func_name: function.full_path(),
+                },
+                function.pos,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// インタフェース型への変換箇所を検査する
+/// 変換は暗黙的に行われ，バックエンドがvtableを生成するため常に実行する
+pub fn interface_check_main(
+    fn_arena: ast::FnArena,
     tld_env: &BTreeMap<String, tld::TopLevelDecl>,
     type_env: &BTreeMap<String, BTreeMap<String, Type>>,
+    ast_root: &ast::ASTRoot,
+    target: option::Target,
+) {
+    for fn_id in ast_root.funcs.iter() {
+        if let Ok(fn_arena) = fn_arena.lock() {
+            let function = fn_arena.get(*fn_id).unwrap();
+
+            if let Err(e) = check_interfaces_in_stmts(
+                tld_env,
+                type_env.get(&function.full_path()).unwrap(),
+                function,
+                &function.stmts,
+                target,
+            ) {
+                e.output();
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// 文列中のインタフェース変換をチェックする
+fn check_interfaces_in_stmts(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
     function: &ast::Function,
+    stmts: &[ast::StNodeId],
     target: option::Target,
 ) -> Result<(), CompileError<TypeErrorKind>> {
-    // メイン関数では，以下のチェックが必要
-    // - 引数が空になっているか
-    // - 返り値の方がNoreturnになっているか
+    for stmt_id in stmts.iter() {
+        let stmt = function
+            .stmt_arena
+            .lock()
+            .unwrap()
+            .get(*stmt_id)
+            .unwrap()
+            .clone();
 
-    if !function.get_parameters().is_empty() {
-        return Err(CompileError::new(
-            TypeErrorKind::MAINFUNCMUSTNOTHAVEANYARGUMENTS,
-            function.pos,
-        ));
-    }
+        match stmt.get_kind() {
+            ast::StatementNodeKind::VARINIT {
+                ident_name,
+                type_name: _,
+                expr,
+            }
+            | ast::StatementNodeKind::CONST {
+                ident_name,
+                type_name: _,
+                expr,
+            } => {
+                check_interfaces_in_expr(tld_env, type_env, function, *expr, target)?;
 
-    if type_env.get("main").unwrap().get("main").unwrap().kind != TypeKind::NORETURN {
-        return Err(CompileError::new(
-            TypeErrorKind::MainFunctionMustNotReturnAnyValues,
-            function.pos,
-        ));
+                let var_type = type_env.get(ident_name).unwrap();
+                check_interface_conversion(tld_env, type_env, function, var_type, *expr, target)?;
+            }
+            ast::StatementNodeKind::RETURN { expr }
+            | ast::StatementNodeKind::IFRET { expr }
+            | ast::StatementNodeKind::EXPR { expr } => {
+                check_interfaces_in_expr(tld_env, type_env, function, *expr, target)?
+            }
+            ast::StatementNodeKind::COUNTUP {
+                ident_name: _,
+                begin_ex: _,
+                endpoint_ex: _,
+                body,
+            } => check_interfaces_in_stmts(tld_env, type_env, function, body, target)?,
+            _ => {}
+        }
     }
 
-    // 通常のチェックも同様に行う
-    type_check_fn(tld_env, type_env, function, target)?;
-
     Ok(())
 }
 
-/// 関数に対するチェック
-fn type_check_fn(
+/// 式中のインタフェース変換(代入，引数渡し)をチェックする
+fn check_interfaces_in_expr(
     tld_env: &BTreeMap<String, tld::TopLevelDecl>,
-    type_env: &BTreeMap<String, BTreeMap<String, Type>>,
+    type_env: &BTreeMap<String, Type>,
     function: &ast::Function,
+    expr_id: ast::ExNodeId,
     target: option::Target,
 ) -> Result<(), CompileError<TypeErrorKind>> {
-    if let Ok(stmt_arena) = function.stmt_arena.lock() {
-        for stmt_id in function.stmts.iter() {
-            let stmt = stmt_arena.get(*stmt_id).unwrap();
-            type_check_stmt(
+    let expr = function
+        .expr_arena
+        .lock()
+        .unwrap()
+        .get(expr_id)
+        .unwrap()
+        .clone();
+
+    match expr.get_kind() {
+        ast::ExpressionNodeKind::ASSIGN { lhs, rhs } => {
+            check_interfaces_in_expr(tld_env, type_env, function, *rhs, target)?;
+
+            let lhs_node = function
+                .expr_arena
+                .lock()
+                .unwrap()
+                .get(*lhs)
+                .unwrap()
+                .clone();
+            let lhs_type = type_check_expr(
                 tld_env,
-                type_env.get(&function.name).unwrap(),
-                stmt,
+                type_env,
                 function.expr_arena.clone(),
+                &lhs_node,
                 target,
             )?;
+            check_interface_conversion(tld_env, type_env, function, &lhs_type, *rhs, target)
         }
-    }
+        ast::ExpressionNodeKind::CALL {
+            names,
+            type_args,
+            args,
+        } => {
+            let params = match callee_type(tld_env, type_env, names, type_args, target)? {
+                Some(fn_type) => fn_type.get_function_args().clone(),
+                None => return Ok(()),
+            };
+            check_interfaces_in_args(tld_env, type_env, function, &params, args, target)
+        }
+        ast::ExpressionNodeKind::METHODCALL { recv, method, args } => {
+            check_interfaces_in_expr(tld_env, type_env, function, *recv, target)?;
 
-    Ok(())
+            // メソッドの存在はここで確かめておく
+            type_check_expr(
+                tld_env,
+                type_env,
+                function.expr_arena.clone(),
+                &expr,
+                target,
+            )?;
+
+            let recv_node = function
+                .expr_arena
+                .lock()
+                .unwrap()
+                .get(*recv)
+                .unwrap()
+                .clone();
+            let recv_type = type_check_expr(
+                tld_env,
+                type_env,
+                function.expr_arena.clone(),
+                &recv_node,
+                target,
+            )?;
+            let params = method_parameters(tld_env, &recv_type, method, target)?;
+            check_interfaces_in_args(tld_env, type_env, function, &params, args, target)
+        }
+        ast::ExpressionNodeKind::STRUCTLITERAL {
+            type_name: _,
+            fields,
+            storage,
+        } => {
+            let st_type = type_env.get(storage).unwrap();
+            for (member, value_id) in fields.iter() {
+                check_interfaces_in_expr(tld_env, type_env, function, *value_id, target)?;
+
+                if let Some((member_type, _member_offset)) = st_type.get_member(member) {
+                    check_interface_conversion(
+                        tld_env,
+                        type_env,
+                        function,
+                        member_type,
+                        *value_id,
+                        target,
+                    )?;
+                }
+            }
+            Ok(())
+        }
+        ast::ExpressionNodeKind::NEG { value }
+        | ast::ExpressionNodeKind::ADDRESSOF { value }
+        | ast::ExpressionNodeKind::DEREFERENCE { value }
+        | ast::ExpressionNodeKind::LEN { value } => {
+            check_interfaces_in_expr(tld_env, type_env, function, *value, target)
+        }
+        ast::ExpressionNodeKind::INDEX {
+            value: lhs,
+            index: rhs,
+        }
+        | ast::ExpressionNodeKind::ADD { lhs, rhs }
+        | ast::ExpressionNodeKind::SUB { lhs, rhs }
+        | ast::ExpressionNodeKind::MUL { lhs, rhs }
+        | ast::ExpressionNodeKind::DIV { lhs, rhs } => {
+            check_interfaces_in_expr(tld_env, type_env, function, *lhs, target)?;
+            check_interfaces_in_expr(tld_env, type_env, function, *rhs, target)
+        }
+        ast::ExpressionNodeKind::SLICE { value, low, high } => {
+            check_interfaces_in_expr(tld_env, type_env, function, *value, target)?;
+            for bound in low.iter().chain(high.iter()) {
+                check_interfaces_in_expr(tld_env, type_env, function, *bound, target)?;
+            }
+            Ok(())
+        }
+        ast::ExpressionNodeKind::IF {
+            cond_ex,
+            body,
+            alter,
+        } => {
+            check_interfaces_in_expr(tld_env, type_env, function, *cond_ex, target)?;
+            check_interfaces_in_stmts(tld_env, type_env, function, body, target)?;
+
+            if let Some(alter) = alter {
+                check_interfaces_in_stmts(tld_env, type_env, function, alter, target)?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
 }
 
-/// 文に対するチェック
-fn type_check_stmt(
+/// 実引数列中のインタフェース変換をチェックする
+fn check_interfaces_in_args(
     tld_env: &BTreeMap<String, tld::TopLevelDecl>,
     type_env: &BTreeMap<String, Type>,
-    stmt: &ast::StatementNode,
-    expr_arena: ast::ExprArena,
+    function: &ast::Function,
+    params: &[Type],
+    args: &[ast::ExNodeId],
     target: option::Target,
 ) -> Result<(), CompileError<TypeErrorKind>> {
-    match stmt.get_kind() {
-        ast::StatementNodeKind::VARINIT {
-            ident_name,
-            type_name: _,
-            expr,
-        } => type_check_vardecl_stmt(tld_env, type_env, ident_name, *expr, expr_arena, target),
-        ast::StatementNodeKind::RETURN { expr } => {
-            type_check_return_stmt(tld_env, type_env, *expr, expr_arena, target)
-        }
-        _ => panic!("unimplemented type check with `{:?}`", stmt),
+    for (arg_id, param_type) in args.iter().zip(params.iter()) {
+        check_interfaces_in_expr(tld_env, type_env, function, *arg_id, target)?;
+        check_interface_conversion(tld_env, type_env, function, param_type, *arg_id, target)?;
     }
+
+    Ok(())
 }
 
-// varinit文に関するチェック
-fn type_check_vardecl_stmt(
+/// 変換先がインタフェース型であれば，式の型がそれを満たすかチェックする
+fn check_interface_conversion(
     tld_env: &BTreeMap<String, tld::TopLevelDecl>,
     type_env: &BTreeMap<String, Type>,
-    ident_name: &str,
-    expr_id: ast::ExNodeId,
-    expr_arena: ast::ExprArena,
+    function: &ast::Function,
+    dst_type: &Type,
+    src_id: ast::ExNodeId,
     target: option::Target,
 ) -> Result<(), CompileError<TypeErrorKind>> {
-    // varinit文に必要なチェック
-    // - もちろんexpressionの型が検査できる
-    // - 代入する識別子の型と式の型が一致している
-    let _var_type = type_env.get(ident_name).unwrap();
-    let _expr_type = type_check_expr(
+    if !dst_type.is_interface() {
+        return Ok(());
+    }
+
+    let src_node = function
+        .expr_arena
+        .lock()
+        .unwrap()
+        .get(src_id)
+        .unwrap()
+        .clone();
+    let src_type = type_check_expr(
         tld_env,
         type_env,
-        expr_arena.clone(),
-        expr_arena.lock().unwrap().get(expr_id).unwrap(),
+        function.expr_arena.clone(),
+        &src_node,
         target,
     )?;
 
-    Ok(())
+    check_interface_satisfaction(tld_env, &src_type, dst_type, target)
+        .map_err(|e| CompileError::new(e.get_kind().clone(), src_node.get_pos()))
 }
 
-// return文に関するチェック
-fn type_check_return_stmt(
+/// 型がインタフェースを満たすかチェックする
+/// 構造体(へのポインタ)は，全メソッドを同じシグネチャで持っていれば満たす
+pub fn check_interface_satisfaction(
     tld_env: &BTreeMap<String, tld::TopLevelDecl>,
-    type_env: &BTreeMap<String, Type>,
-    expr_id: ast::ExNodeId,
-    expr_arena: ast::ExprArena,
+    src_type: &Type,
+    iface_type: &Type,
     target: option::Target,
 ) -> Result<(), CompileError<TypeErrorKind>> {
-    // return文に必要なチェック
-    // - もちろんexpressionの型が検査できる
-    type_check_expr(
-        tld_env,
-        type_env,
-        expr_arena.clone(),
-        expr_arena.lock().unwrap().get(expr_id).unwrap(),
-        target,
-    )?;
+    let iface_name = iface_type.get_interface_name();
+
+    // インタフェース同士はvtableの形が異なるため，同じ型の間でのみ代入できる
+    if src_type.is_interface() && src_type.get_interface_name() == iface_name {
+        return Ok(());
+    }
+
+    let struct_name = match src_type.receiver_struct() {
+        Some(st_type) => st_type.get_struct_name().to_string(),
+        None => {
+            return Err(CompileError::new(
+                TypeErrorKind::CannotConvertToInterface {
+                    type_name: src_type.dump(),
+                    interface_name: iface_name.to_string(),
+                },
+                Default::default(),
+            ))
+        }
+    };
+
+    let iface_methods = match &tld_env.get(iface_name).unwrap().kind {
+        tld::TLDKind::INTERFACE { methods } => methods,
+        _ => unreachable!(),
+    };
+    let struct_methods = match &tld_env.get(&struct_name).unwrap().kind {
+        tld::TLDKind::STRUCT {
+            members: _,
+            methods,
+        } => methods,
+        _ => unreachable!(),
+    };
+
+    for (method_name, (iface_args, iface_return_type)) in iface_methods.iter() {
+        let not_implemented = Err(CompileError::new(
+            TypeErrorKind::DoesNotImplementInterface {
+                type_name: struct_name.to_string(),
+                interface_name: iface_name.to_string(),
+                method: method_name.to_string(),
+            },
+            Default::default(),
+        ));
+
+        let (args, return_type) = match struct_methods
+            .get(method_name)
+            .map(|symbol| &tld_env.get(symbol).unwrap().kind)
+        {
+            Some(tld::TLDKind::FN { return_type, args }) => (args, return_type),
+            _ => return not_implemented,
+        };
+
+        // レシーバを除いて，引数と返り値の型が一致しているか
+        let method_args = &args[1..];
+        if method_args.len() != iface_args.len()
+            || resolve_type_string(tld_env, return_type.to_string(), target)?
+                != resolve_type_string(tld_env, iface_return_type.to_string(), target)?
+        {
+            return not_implemented;
+        }
+
+        for ((_, arg_type), (_, iface_arg_type)) in method_args.iter().zip(iface_args.iter()) {
+            if resolve_type_string(tld_env, arg_type.to_string(), target)?
+                != resolve_type_string(tld_env, iface_arg_type.to_string(), target)?
+            {
+                return not_implemented;
+            }
+        }
+    }
 
     Ok(())
 }
 
-/// 式に対するチェック
-fn type_check_expr(
+/// 呼び出される関数の引数定義と返り値型を取り出す
+fn called_function<'a>(
+    tld_env: &'a BTreeMap<String, tld::TopLevelDecl>,
+    names: &[String],
+    type_args: &[String],
+) -> Option<(&'a Vec<(String, String)>, &'a String)> {
+    // 通常の関数は修飾なしの名前でTLDに登録されている
+    // ジェネリック関数の場合は，実体化された名前で探す
+    let func_name = if type_args.is_empty() {
+        names.last().unwrap().to_string()
+    } else {
+        ast::generic_instance_name(names.last().unwrap(), type_args)
+    };
+
+    match tld_env.get(&func_name).map(|entry| &entry.kind) {
+        Some(tld::TLDKind::FN { return_type, args }) => Some((args, return_type)),
+        _ => None,
+    }
+}
+
+/// 識別子がトップレベルの定数か列挙子を指していれば，その型を返す
+/// メインモジュールの宣言は `::X` のように登録されている
+fn toplevel_value_type(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    names: &[String],
+    target: option::Target,
+) -> Option<Result<Type, CompileError<TypeErrorKind>>> {
+    let lookup = |name: String| {
+        tld_env
+            .get(&name)
+            .or_else(|| tld_env.get(&format!("::{}", name)))
+            .map(|entry| &entry.kind)
+    };
+
+    if let Some(tld::TLDKind::CONST { type_name, expr: _ }) = lookup(names.join("::")) {
+        return Some(resolve_type_string(tld_env, type_name.to_string(), target));
+    }
+
+    let (variant, enum_path) = names.split_last()?;
+    match lookup(enum_path.join("::")) {
+        Some(tld::TLDKind::ENUM { variants }) if variants.contains_key(variant) => {
+            Some(Ok(Type::new_enum(8)))
+        }
+        _ => None,
+    }
+}
+
+/// 呼び出し先の関数型を求める
+/// 関数ポインタ型の変数であればそれを優先し，そうでなければ関数定義を探す
+fn callee_type(
     tld_env: &BTreeMap<String, tld::TopLevelDecl>,
     type_env: &BTreeMap<String, Type>,
-    expr_arena: ast::ExprArena,
-    expr: &ast::ExpressionNode,
+    names: &[String],
+    type_args: &[String],
     target: option::Target,
-) -> Result<Type, CompileError<TypeErrorKind>> {
-    match expr.get_kind() {
-        ast::ExpressionNodeKind::INTEGER { value: _ } => Ok(Type::new_int64(target)),
-        ast::ExpressionNodeKind::UINTEGER { value: _ } => Ok(Type::new_uint64(target)),
-        ast::ExpressionNodeKind::IDENTIFIER { names } => {
-            let full_path = names.join("::");
-            Ok(type_env.get(&full_path).unwrap().clone())
+) -> Result<Option<Type>, CompileError<TypeErrorKind>> {
+    if let Some(var_type) = type_env.get(&names.join("::")) {
+        if var_type.is_function_pointer() {
+            return Ok(Some(var_type.pointer_to().clone()));
         }
-        ast::ExpressionNodeKind::BOOLEAN { truth: _ } => Ok(Type::new_boolean(target)),
-        ast::ExpressionNodeKind::STRING { contents: _ } => Ok(Type::new_const_str(target)),
-        ast::ExpressionNodeKind::MEMBER { id: st_id, member } => {
-            let struct_node = expr_arena.lock().unwrap().get(*st_id).unwrap().clone();
-            type_check_member_expression(tld_env, type_env, struct_node, member, target)
+    }
+
+    match called_function(tld_env, names, type_args) {
+        Some((params, return_type)) => Ok(Some(resolve_function_type(
+            tld_env,
+            params,
+            return_type,
+            target,
+        )?)),
+        None => Ok(None),
+    }
+}
+
+/// 関数ポインタ型のメンバを探す
+/// 構造体にメソッドがなく，同名のメンバが関数ポインタであれば，その呼び出しとみなす
+fn function_pointer_member<'a>(recv_type: &'a Type, member: &str) -> Option<&'a Type> {
+    let st_type = recv_type.receiver_struct()?;
+    match st_type.get_member(member) {
+        Some((member_type, _offset)) if member_type.is_function_pointer() => {
+            Some(member_type.pointer_to())
         }
-        _ => panic!("unimplemented type check with `{:?}`", expr),
+        _ => None,
     }
 }
 
-fn type_check_member_expression(
-    _tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+/// メソッドの引数型(レシーバを除く)を取り出す
+fn method_parameters(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    recv_type: &Type,
+    method: &str,
+    target: option::Target,
+) -> Result<Vec<Type>, CompileError<TypeErrorKind>> {
+    let params = if recv_type.is_interface() {
+        match &tld_env.get(recv_type.get_interface_name()).unwrap().kind {
+            tld::TLDKind::INTERFACE { methods } => methods.get(method).unwrap().0.clone(),
+            _ => unreachable!(),
+        }
+    } else {
+        let struct_name = recv_type.receiver_struct().unwrap().get_struct_name();
+        let symbol = ast::method_symbol(struct_name, method);
+        match tld_env.get(&symbol).map(|entry| &entry.kind) {
+            Some(tld::TLDKind::FN {
+                return_type: _,
+                args,
+            }) => args[1..].to_vec(),
+            _ => {
+                // 関数ポインタ型メンバの呼び出し
+                let fn_type = function_pointer_member(recv_type, method).unwrap();
+                return Ok(fn_type.get_function_args().clone());
+            }
+        }
+    };
+
+    params
+        .iter()
+        .map(|(_name, param_type)| resolve_type_string(tld_env, param_type.to_string(), target))
+        .collect()
+}
+
+/// 呼び出しの実引数を，関数型の引数と突き合わせる
+#[allow(clippy::too_many_arguments)]
+fn type_check_call_arguments(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
     type_env: &BTreeMap<String, Type>,
-    struct_node: ast::ExpressionNode,
-    member: &str,
-    _target: option::Target,
-) -> Result<Type, CompileError<TypeErrorKind>> {
-    // メンバ式でチェックすること
-    // - DOTの前後ノードが変数であるか
-    // - DOT前のノードが構造体型であるか
-    // - メンバ名が構造体に存在するか
-    if !struct_node.is_identifier() {
-        let err_pos = struct_node.get_pos();
+    expr_arena: ast::ExprArena,
+    func_name: &str,
+    fn_type: &Type,
+    args: &[ast::ExNodeId],
+    target: option::Target,
+    err_pos: Position,
+) -> Result<(), CompileError<TypeErrorKind>> {
+    let params = fn_type.get_function_args();
+    if params.len() != args.len() {
         return Err(CompileError::new(
-            TypeErrorKind::CannotAccessMemberWithNotAnIdentifier { struct_node },
+            TypeErrorKind::MismatchedArgumentCount {
+                func_name: func_name.to_string(),
+                expected: params.len(),
+                actual: args.len(),
+            },
             err_pos,
         ));
     }
 
-    // 型環境から，ドット前のノードの型を持ってくる
-    match type_env.get(&struct_node.copy_names().join("::")) {
-        Some(node_type) => {
-            // 構造体型でなければエラー
-            if !node_type.is_struct() {
-                let err_pos = struct_node.get_pos();
-                return Err(CompileError::new(
-                    TypeErrorKind::CannotAccessMemberWIthNotAStruct { struct_node },
-                    err_pos,
-                ));
-            }
-
-            let members = node_type.get_members();
+    for (arg_id, param_type) in args.iter().zip(params.iter()) {
+        let arg = expr_arena.lock().unwrap().get(*arg_id).unwrap().clone();
+        let arg_type = type_check_expr(tld_env, type_env, expr_arena.clone(), &arg, target)?;
 
-            // メンバが存在するかチェック
-            match members.get(member) {
-                Some((member_type, _member_offset)) => Ok(*member_type.clone()),
-                None => {
-                    let err_pos = struct_node.get_pos();
-                    Err(CompileError::new(
-                        TypeErrorKind::UndefinedSuchAMember {
-                            member: member.to_string(),
-                        },
-                        err_pos,
-                    ))
-                }
-            }
+        // インタフェース型への変換は別途検査している
+        check_untyped_constant_range(&expr_arena, &arg, param_type)?;
+        let untyped = is_untyped_constant(&expr_arena, &arg);
+        if !param_type.is_interface() && !is_assignable(param_type, &arg_type, untyped) {
+            return Err(CompileError::new(
+                TypeErrorKind::MismatchedArgumentType {
+                    func_name: func_name.to_string(),
+                    expected: param_type.dump(),
+                    actual: arg_type.dump(),
+                },
+                arg.get_pos(),
+            ));
         }
-        None => unimplemented!(),
     }
-}
-
-#[cfg(test)]
-mod type_check_tests {
-    use super::*;
-    use crate::common::analyze_resource::ast::FunctionTypeDef;
-    use crate::common::option::Target;
-    use crate::common::token::TokenKind;
-    use id_arena::Arena;
-    use std::sync::{Arc, Mutex};
 
-    #[test]
-    fn type_check_main_fn_with_invalid_return_type_test() {
-        let invalid_return_func = new_func("main".to_string(), Default::default());
-        let tld_env = new_tld();
+    Ok(())
+}
 
-        let env = type_env();
+/// 初期化式から変数の型を推論する
+/// 型なし整数定数はInt64になる
+pub fn infer_expr_type(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
+    expr_arena: ast::ExprArena,
+    expr_id: ast::ExNodeId,
+    target: option::Target,
+) -> Result<Type, CompileError<TypeErrorKind>> {
+    let expr = expr_arena.lock().unwrap().get(expr_id).unwrap().clone();
+    type_check_expr(tld_env, type_env, expr_arena, &expr, target)
+}
 
-        let actual =
-            type_check_main_fn(&tld_env, &env, &invalid_return_func, option::Target::X86_64);
-        assert!(actual.is_err());
+/// 添字/スライス/lenの対象となる式の型を検査する
+/// 文字列リテラルは `[]Uint8` として扱う
+fn type_check_sequence(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
+    expr_arena: ast::ExprArena,
+    expr_id: ast::ExNodeId,
+    target: option::Target,
+) -> Result<Type, CompileError<TypeErrorKind>> {
+    let expr = expr_arena.lock().unwrap().get(expr_id).unwrap().clone();
+    let seq_type = type_check_expr(tld_env, type_env, expr_arena, &expr, target)?;
 
-        if let Err(e) = actual {
-            assert_eq!(
-                &TypeErrorKind::MainFunctionMustNotReturnAnyValues,
-                e.get_kind()
-            );
+    if seq_type.is_const_str() {
+        if let ast::ExpressionNodeKind::STRING { contents: _ } = expr.get_kind() {
+            return Ok(Type::new_slice(Type::new_uint8(target), target));
         }
     }
+    if !seq_type.is_array() && !seq_type.is_slice() {
+        return Err(CompileError::new(
+            TypeErrorKind::NotASequence {
+                type_name: seq_type.dump(),
+            },
+            expr.get_pos(),
+        ));
+    }
 
-    #[test]
-    fn type_check_main_fn_with_invalid_args_test() {
-        let invalid_args_func = new_func(
-            "main".to_string(),
-            vec![("foo".to_string(), "Int64".to_string())],
-        );
-        let tld_env = new_tld();
+    Ok(seq_type)
+}
+
+/// 添字/スライスの境界が整数であるか検査する
+fn type_check_index(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
+    expr_arena: ast::ExprArena,
+    expr_id: ast::ExNodeId,
+    target: option::Target,
+) -> Result<(), CompileError<TypeErrorKind>> {
+    let expr = expr_arena.lock().unwrap().get(expr_id).unwrap().clone();
+    let index_type = type_check_expr(tld_env, type_env, expr_arena, &expr, target)?;
+
+    if !index_type.is_integer() {
+        return Err(CompileError::new(
+            TypeErrorKind::NonIntegerIndex {
+                actual: index_type.dump(),
+            },
+            expr.get_pos(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// 型なし定数であるか
+/// 接尾辞のない整数リテラルと，それらのみからなる算術式，文字列リテラルが該当する
+fn is_untyped_constant(expr_arena: &ast::ExprArena, expr: &ast::ExpressionNode) -> bool {
+    let child = |id: &ast::ExNodeId| expr_arena.lock().unwrap().get(*id).unwrap().clone();
+
+    match expr.get_kind() {
+        ast::ExpressionNodeKind::INTEGER { value: _ } => true,
+        ast::ExpressionNodeKind::STRING { contents: _ } => true,
+        ast::ExpressionNodeKind::NEG { value } => is_untyped_constant(expr_arena, &child(value)),
+        ast::ExpressionNodeKind::ADD { lhs, rhs }
+        | ast::ExpressionNodeKind::SUB { lhs, rhs }
+        | ast::ExpressionNodeKind::MUL { lhs, rhs }
+        | ast::ExpressionNodeKind::DIV { lhs, rhs } => {
+            is_untyped_constant(expr_arena, &child(lhs))
+                && is_untyped_constant(expr_arena, &child(rhs))
+        }
+        _ => false,
+    }
+}
+
+/// 型なし整数定数の値を求める
+/// 0除算などで値が定まらなければNone
+fn untyped_integer_value(expr_arena: &ast::ExprArena, expr: &ast::ExpressionNode) -> Option<i128> {
+    let child = |id: &ast::ExNodeId| expr_arena.lock().unwrap().get(*id).unwrap().clone();
+    let operands = |lhs: &ast::ExNodeId, rhs: &ast::ExNodeId| {
+        Some((
+            untyped_integer_value(expr_arena, &child(lhs))?,
+            untyped_integer_value(expr_arena, &child(rhs))?,
+        ))
+    };
+
+    match expr.get_kind() {
+        ast::ExpressionNodeKind::INTEGER { value } => Some(*value as i128),
+        ast::ExpressionNodeKind::NEG { value } => {
+            untyped_integer_value(expr_arena, &child(value))?.checked_neg()
+        }
+        ast::ExpressionNodeKind::ADD { lhs, rhs } => {
+            operands(lhs, rhs).and_then(|(l, r)| l.checked_add(r))
+        }
+        ast::ExpressionNodeKind::SUB { lhs, rhs } => {
+            operands(lhs, rhs).and_then(|(l, r)| l.checked_sub(r))
+        }
+        ast::ExpressionNodeKind::MUL { lhs, rhs } => {
+            operands(lhs, rhs).and_then(|(l, r)| l.checked_mul(r))
+        }
+        ast::ExpressionNodeKind::DIV { lhs, rhs } => {
+            operands(lhs, rhs).and_then(|(l, r)| l.checked_div(r))
+        }
+        _ => None,
+    }
+}
+
+/// 型なし整数定数が，変換先の整数型の範囲に収まっているか検査する
+/// 負の値は符号なし整数型に変換できない
+fn check_untyped_constant_range(
+    expr_arena: &ast::ExprArena,
+    expr: &ast::ExpressionNode,
+    dst: &Type,
+) -> Result<(), CompileError<TypeErrorKind>> {
+    if !dst.is_integer() || !is_untyped_constant(expr_arena, expr) {
+        return Ok(());
+    }
+
+    let value = match untyped_integer_value(expr_arena, expr) {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    let in_range = match dst.kind {
+        TypeKind::UINT64 => (0..=u64::MAX as i128).contains(&value),
+        TypeKind::UINT8 => (0..=u8::MAX as i128).contains(&value),
+        _ => (i64::MIN as i128..=i64::MAX as i128).contains(&value),
+    };
+
+    if in_range {
+        Ok(())
+    } else {
+        Err(CompileError::new(
+            TypeErrorKind::UntypedConstantOutOfRange {
+                value: value.to_string(),
+                type_name: dst.dump(),
+            },
+            expr.get_pos(),
+        ))
+    }
+}
+
+/// src型の値をdst型へ代入できるか
+/// 型なし整数定数は任意の整数型(及びその別名)に，文字列リテラルは[]Uint8に適合する
+fn is_assignable(dst: &Type, src: &Type, src_is_untyped: bool) -> bool {
+    dst == src
+        || (src_is_untyped && src.is_integer() && dst.is_integer())
+        || (src_is_untyped && src.is_const_str() && dst.is_byte_slice())
+}
+
+/// メイン関数特有のチェック
+fn type_check_main_fn(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, BTreeMap<String, Type>>,
+    function: &ast::Function,
+    target: option::Target,
+) -> Result<(), CompileError<TypeErrorKind>> {
+    // メイン関数では，以下のチェックが必要
+    // - 引数が空，もしくは (argc, argv[, envp]) になっているか
+    // - 返り値の型がNoreturnかInt64になっているか
+
+    if !is_valid_main_parameters(function.get_parameters()) {
+        return Err(CompileError::new(
+            TypeErrorKind::InvalidMainFunctionArguments,
+            function.pos,
+        ));
+    }
+
+    if !is_valid_main_return_type(type_env.get("main").unwrap().get("main").unwrap()) {
+        return Err(CompileError::new(
+            TypeErrorKind::InvalidMainFunctionReturnType,
+            function.pos,
+        ));
+    }
+
+    // 通常のチェックも同様に行う
+    type_check_fn(tld_env, type_env, function, target)?;
+
+    Ok(())
+}
+
+/// メイン関数が受け取れる仮引数列か
+/// argc/argvはスタートアップルーチンがプロセスのスタックから読み出して渡す
+fn is_valid_main_parameters(params: &[(String, String)]) -> bool {
+    let types: Vec<&str> = params.iter().map(|(_, ty)| ty.as_str()).collect();
+    matches!(
+        types.as_slice(),
+        [] | ["Int64", "*ConstStr"] | ["Int64", "*ConstStr", "*ConstStr"]
+    )
+}
+
+/// メイン関数が返せる型か
+/// Int64の返り値はスタートアップルーチンがプロセスの終了コードにする
+fn is_valid_main_return_type(main_type: &Type) -> bool {
+    main_type.is_function()
+        && matches!(
+            main_type.get_return_type().kind,
+            TypeKind::NORETURN | TypeKind::INT64
+        )
+}
+
+/// 関数に対するチェック
+fn type_check_fn(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, BTreeMap<String, Type>>,
+    function: &ast::Function,
+    target: option::Target,
+) -> Result<(), CompileError<TypeErrorKind>> {
+    let func_env = type_env.get(&function.full_path()).unwrap();
+    let return_type = func_env
+        .get(&function.full_path())
+        .unwrap()
+        .get_return_type();
+
+    if let Ok(stmt_arena) = function.stmt_arena.lock() {
+        type_check_stmts(
+            tld_env,
+            func_env,
+            &stmt_arena,
+            &function.stmts,
+            function.expr_arena.clone(),
+            return_type,
+            target,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// 文列に対するチェック
+fn type_check_stmts(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
+    stmt_arena: &Arena<ast::StatementNode>,
+    stmts: &[ast::StNodeId],
+    expr_arena: ast::ExprArena,
+    return_type: &Type,
+    target: option::Target,
+) -> Result<(), CompileError<TypeErrorKind>> {
+    for stmt_id in stmts.iter() {
+        let stmt = stmt_arena.get(*stmt_id).unwrap();
+        type_check_stmt(
+            tld_env,
+            type_env,
+            stmt_arena,
+            stmt,
+            expr_arena.clone(),
+            return_type,
+            target,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// 文に対するチェック
+fn type_check_stmt(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
+    stmt_arena: &Arena<ast::StatementNode>,
+    stmt: &ast::StatementNode,
+    expr_arena: ast::ExprArena,
+    return_type: &Type,
+    target: option::Target,
+) -> Result<(), CompileError<TypeErrorKind>> {
+    match stmt.get_kind() {
+        ast::StatementNodeKind::VARINIT {
+            ident_name,
+            type_name: _,
+            expr,
+        }
+        | ast::StatementNodeKind::CONST {
+            ident_name,
+            type_name: _,
+            expr,
+        } => type_check_vardecl_stmt(tld_env, type_env, ident_name, *expr, expr_arena, target),
+        ast::StatementNodeKind::RETURN { expr } => {
+            type_check_return_stmt(tld_env, type_env, *expr, expr_arena, return_type, target)
+        }
+        ast::StatementNodeKind::EXPR { expr } | ast::StatementNodeKind::IFRET { expr } => {
+            let expr = expr_arena.lock().unwrap().get(*expr).unwrap().clone();
+            type_check_expr(tld_env, type_env, expr_arena, &expr, target)?;
+            Ok(())
+        }
+        ast::StatementNodeKind::COUNTUP {
+            ident_name: _,
+            begin_ex,
+            endpoint_ex,
+            body,
+        } => {
+            for ex_id in [begin_ex, endpoint_ex] {
+                let ex = expr_arena.lock().unwrap().get(*ex_id).unwrap().clone();
+                type_check_expr(tld_env, type_env, expr_arena.clone(), &ex, target)?;
+            }
+            type_check_stmts(
+                tld_env,
+                type_env,
+                stmt_arena,
+                body,
+                expr_arena,
+                return_type,
+                target,
+            )
+        }
+        ast::StatementNodeKind::MATCH { expr, arms } => {
+            let ex = expr_arena.lock().unwrap().get(*expr).unwrap().clone();
+            type_check_expr(tld_env, type_env, expr_arena.clone(), &ex, target)?;
+            for arm in arms.values() {
+                type_check_stmts(
+                    tld_env,
+                    type_env,
+                    stmt_arena,
+                    arm,
+                    expr_arena.clone(),
+                    return_type,
+                    target,
+                )?;
+            }
+            Ok(())
+        }
+        // インラインアセンブリ/宣言のみの文には検査する式がない
+        ast::StatementNodeKind::ASM { stmts: _ } | ast::StatementNodeKind::DECLARE { .. } => Ok(()),
+    }
+}
+
+// varinit文に関するチェック
+fn type_check_vardecl_stmt(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
+    ident_name: &str,
+    expr_id: ast::ExNodeId,
+    expr_arena: ast::ExprArena,
+    target: option::Target,
+) -> Result<(), CompileError<TypeErrorKind>> {
+    // varinit文に必要なチェック
+    // - もちろんexpressionの型が検査できる
+    // - 代入する識別子の型と式の型が一致している
+    // 式の検査中にもアロケータをロックするので，ノードを複製しておく
+    let var_type = type_env.get(ident_name).unwrap();
+    let expr = expr_arena.lock().unwrap().get(expr_id).unwrap().clone();
+    let expr_type = type_check_expr(tld_env, type_env, expr_arena.clone(), &expr, target)?;
+
+    // インタフェース型への変換は別途検査している
+    check_untyped_constant_range(&expr_arena, &expr, var_type)?;
+    let untyped = is_untyped_constant(&expr_arena, &expr);
+    if !var_type.is_interface() && !is_assignable(var_type, &expr_type, untyped) {
+        return Err(CompileError::new(
+            TypeErrorKind::MismatchedVariableType {
+                ident_name: ident_name.to_string(),
+                expected: var_type.dump(),
+                actual: expr_type.dump(),
+            },
+            expr.get_pos(),
+        ));
+    }
+
+    Ok(())
+}
+
+// return文に関するチェック
+fn type_check_return_stmt(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
+    expr_id: ast::ExNodeId,
+    expr_arena: ast::ExprArena,
+    return_type: &Type,
+    target: option::Target,
+) -> Result<(), CompileError<TypeErrorKind>> {
+    // return文に必要なチェック
+    // - もちろんexpressionの型が検査できる
+    // - 式の型が関数の返り値型と一致している(Noreturnな関数は値を返せない)
+    // - 局所変数のアドレスを返していない
+    let expr = expr_arena.lock().unwrap().get(expr_id).unwrap().clone();
+    let expr_type = type_check_expr(tld_env, type_env, expr_arena.clone(), &expr, target)?;
+
+    // インタフェース型への変換は別途検査している
+    check_untyped_constant_range(&expr_arena, &expr, return_type)?;
+    let untyped = is_untyped_constant(&expr_arena, &expr);
+    if !return_type.is_interface() && !is_assignable(return_type, &expr_type, untyped) {
+        return Err(CompileError::new(
+            TypeErrorKind::MismatchedReturnType {
+                expected: return_type.dump(),
+                actual: expr_type.dump(),
+            },
+            expr.get_pos(),
+        ));
+    }
+
+    if let ast::ExpressionNodeKind::ADDRESSOF { value } = expr.get_kind() {
+        let value = expr_arena.lock().unwrap().get(*value).unwrap().clone();
+        if let ast::ExpressionNodeKind::IDENTIFIER { names } = value.get_kind() {
+            // 関数名のアドレスは関数ポインタになる
+            let is_local = names.len() == 1
+                && type_env
+                    .get(&names[0])
+                    .is_some_and(|var_type| !var_type.is_function());
+            if is_local {
+                return Err(CompileError::new(
+                    TypeErrorKind::ReturnLocalAddress {
+                        name: names[0].split('#').next().unwrap().to_string(),
+                    },
+                    expr.get_pos(),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 式に対するチェック
+fn type_check_expr(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
+    expr_arena: ast::ExprArena,
+    expr: &ast::ExpressionNode,
+    target: option::Target,
+) -> Result<Type, CompileError<TypeErrorKind>> {
+    match expr.get_kind() {
+        ast::ExpressionNodeKind::INTEGER { value: _ } => Ok(Type::new_int64(target)),
+        ast::ExpressionNodeKind::UINTEGER { value: _ } => Ok(Type::new_uint64(target)),
+        ast::ExpressionNodeKind::CHARACTER { value: _ } => Ok(Type::new_uint8(target)),
+        ast::ExpressionNodeKind::IDENTIFIER { names } => {
+            let full_path = names.join("::");
+            if let Some(var_type) = type_env.get(&full_path) {
+                return Ok(var_type.clone());
+            }
+
+            // 定数/列挙子であれば，その型
+            if let Some(value_type) = toplevel_value_type(tld_env, names, target) {
+                return value_type;
+            }
+
+            // 変数でなければ関数名(`&f` でアドレスを取る)
+            match called_function(tld_env, names, &[]) {
+                Some((params, return_type)) => {
+                    resolve_function_type(tld_env, params, return_type, target)
+                }
+                None => Err(CompileError::new(
+                    TypeErrorKind::CannotResolve {
+                        type_name: full_path,
+                    },
+                    expr.get_pos(),
+                )),
+            }
+        }
+        ast::ExpressionNodeKind::BOOLEAN { truth: _ } => Ok(Type::new_boolean(target)),
+        ast::ExpressionNodeKind::STRING { contents: _ } => Ok(Type::new_const_str(target)),
+        ast::ExpressionNodeKind::INDEX { value, index } => {
+            let seq_type =
+                type_check_sequence(tld_env, type_env, expr_arena.clone(), *value, target)?;
+            type_check_index(tld_env, type_env, expr_arena, *index, target)?;
+            Ok(seq_type.element_type().clone())
+        }
+        ast::ExpressionNodeKind::SLICE { value, low, high } => {
+            let seq_type =
+                type_check_sequence(tld_env, type_env, expr_arena.clone(), *value, target)?;
+            for bound in low.iter().chain(high.iter()) {
+                type_check_index(tld_env, type_env, expr_arena.clone(), *bound, target)?;
+            }
+            Ok(Type::new_slice(seq_type.element_type().clone(), target))
+        }
+        ast::ExpressionNodeKind::LEN { value } => {
+            type_check_sequence(tld_env, type_env, expr_arena, *value, target)?;
+            Ok(Type::new_int64(target))
+        }
+        ast::ExpressionNodeKind::MEMBER { id: st_id, member } => {
+            let struct_node = expr_arena.lock().unwrap().get(*st_id).unwrap().clone();
+            type_check_member_expression(tld_env, type_env, expr_arena, struct_node, member, target)
+        }
+        ast::ExpressionNodeKind::METHODCALL { recv, method, args } => {
+            let recv_node = expr_arena.lock().unwrap().get(*recv).unwrap().clone();
+            type_check_method_call_expression(
+                tld_env, type_env, expr_arena, recv_node, method, args, target,
+            )
+        }
+        ast::ExpressionNodeKind::CALL {
+            names,
+            type_args,
+            args,
+        } => {
+            let func_name = names.join("::");
+
+            // 関数ポインタ経由の呼び出し
+            if let Some(var_type) = type_env.get(&func_name) {
+                if !var_type.is_function_pointer() {
+                    return Err(CompileError::new(
+                        TypeErrorKind::NotAFunction { name: func_name },
+                        expr.get_pos(),
+                    ));
+                }
+
+                let fn_type = var_type.pointer_to();
+                type_check_call_arguments(
+                    tld_env,
+                    type_env,
+                    expr_arena,
+                    &func_name,
+                    fn_type,
+                    args,
+                    target,
+                    expr.get_pos(),
+                )?;
+                return Ok(fn_type.get_return_type().clone());
+            }
+
+            match called_function(tld_env, names, type_args) {
+                Some((params, return_type)) => {
+                    let fn_type = resolve_function_type(tld_env, params, return_type, target)?;
+                    type_check_call_arguments(
+                        tld_env,
+                        type_env,
+                        expr_arena,
+                        &func_name,
+                        &fn_type,
+                        args,
+                        target,
+                        expr.get_pos(),
+                    )?;
+                    Ok(fn_type.get_return_type().clone())
+                }
+                None => Err(CompileError::new(
+                    TypeErrorKind::CannotResolve {
+                        type_name: func_name,
+                    },
+                    expr.get_pos(),
+                )),
+            }
+        }
+        ast::ExpressionNodeKind::ADDRESSOF { value } => {
+            let value_node = expr_arena.lock().unwrap().get(*value).unwrap().clone();
+            let value_type = type_check_expr(tld_env, type_env, expr_arena, &value_node, target)?;
+            Ok(Type::new_pointer(value_type, target))
+        }
+        ast::ExpressionNodeKind::DEREFERENCE { value } => {
+            let value_node = expr_arena.lock().unwrap().get(*value).unwrap().clone();
+            let value_type = type_check_expr(tld_env, type_env, expr_arena, &value_node, target)?;
+            Ok(value_type.pointer_to().clone())
+        }
+        ast::ExpressionNodeKind::NEG { value } => {
+            let value_node = expr_arena.lock().unwrap().get(*value).unwrap().clone();
+            let value_type =
+                type_check_expr(tld_env, type_env, expr_arena.clone(), &value_node, target)?;
+
+            // 負の型なし整数定数は，代入先で範囲を検査する
+            if !value_type.is_integer() {
+                return Err(CompileError::new(
+                    TypeErrorKind::NonIntegerOperand {
+                        actual: value_type.dump(),
+                    },
+                    value_node.get_pos(),
+                ));
+            }
+            if value_type.is_unsigned() && !is_untyped_constant(&expr_arena, &value_node) {
+                return Err(CompileError::new(
+                    TypeErrorKind::NegationOfUnsigned {
+                        actual: value_type.dump(),
+                    },
+                    expr.get_pos(),
+                ));
+            }
+
+            Ok(value_type)
+        }
+        ast::ExpressionNodeKind::ADD { lhs, rhs }
+        | ast::ExpressionNodeKind::SUB { lhs, rhs }
+        | ast::ExpressionNodeKind::MUL { lhs, rhs }
+        | ast::ExpressionNodeKind::DIV { lhs, rhs } => {
+            // 両辺は整数でなければならない
+            // 型なし整数定数は，もう一方の被演算子の型に合わせる
+            let lhs_node = expr_arena.lock().unwrap().get(*lhs).unwrap().clone();
+            let rhs_node = expr_arena.lock().unwrap().get(*rhs).unwrap().clone();
+            let lhs_type =
+                type_check_expr(tld_env, type_env, expr_arena.clone(), &lhs_node, target)?;
+            let rhs_type =
+                type_check_expr(tld_env, type_env, expr_arena.clone(), &rhs_node, target)?;
+
+            for (operand, operand_type) in [(&lhs_node, &lhs_type), (&rhs_node, &rhs_type)] {
+                if !operand_type.is_integer() {
+                    return Err(CompileError::new(
+                        TypeErrorKind::NonIntegerOperand {
+                            actual: operand_type.dump(),
+                        },
+                        operand.get_pos(),
+                    ));
+                }
+            }
+
+            let lhs_untyped = is_untyped_constant(&expr_arena, &lhs_node);
+            let rhs_untyped = is_untyped_constant(&expr_arena, &rhs_node);
+            if lhs_untyped {
+                check_untyped_constant_range(&expr_arena, &lhs_node, &rhs_type)?;
+                return Ok(rhs_type);
+            }
+            check_untyped_constant_range(&expr_arena, &rhs_node, &lhs_type)?;
+            if rhs_untyped || lhs_type == rhs_type {
+                return Ok(lhs_type);
+            }
+
+            // 配列/スライスから読み出したUint8は，もう一方の整数型に拡張される
+            match (&lhs_type.kind, &rhs_type.kind) {
+                (TypeKind::UINT8, _) => Ok(rhs_type),
+                (_, TypeKind::UINT8) => Ok(lhs_type),
+                _ => Err(CompileError::new(
+                    TypeErrorKind::MismatchedOperandTypes {
+                        lhs: lhs_type.dump(),
+                        rhs: rhs_type.dump(),
+                    },
+                    expr.get_pos(),
+                )),
+            }
+        }
+        ast::ExpressionNodeKind::ASSIGN { lhs, rhs } => {
+            // インタフェース型への代入は，interface_check_mainで検査済み
+            let lhs_node = expr_arena.lock().unwrap().get(*lhs).unwrap().clone();
+            let lhs_type =
+                type_check_expr(tld_env, type_env, expr_arena.clone(), &lhs_node, target)?;
+            let rhs_node = expr_arena.lock().unwrap().get(*rhs).unwrap().clone();
+            let rhs_type =
+                type_check_expr(tld_env, type_env, expr_arena.clone(), &rhs_node, target)?;
+
+            check_untyped_constant_range(&expr_arena, &rhs_node, &lhs_type)?;
+            let untyped = is_untyped_constant(&expr_arena, &rhs_node);
+            if !lhs_type.is_interface() && !is_assignable(&lhs_type, &rhs_type, untyped) {
+                return Err(CompileError::new(
+                    TypeErrorKind::MismatchedAssignmentType {
+                        expected: lhs_type.dump(),
+                        actual: rhs_type.dump(),
+                    },
+                    expr.get_pos(),
+                ));
+            }
+
+            // 型なし整数定数の代入は，代入先の型になる
+            Ok(if untyped { lhs_type } else { rhs_type })
+        }
+        ast::ExpressionNodeKind::STRUCTLITERAL {
+            type_name,
+            fields,
+            storage: _,
+        } => type_check_struct_literal(
+            tld_env,
+            type_env,
+            expr_arena,
+            type_name,
+            fields,
+            expr.get_pos(),
+            target,
+        ),
+        // ifretの値は現状Int64として扱われる
+        ast::ExpressionNodeKind::IF {
+            cond_ex,
+            body: _,
+            alter: _,
+        } => {
+            let cond_node = expr_arena.lock().unwrap().get(*cond_ex).unwrap().clone();
+            let cond_type = type_check_expr(tld_env, type_env, expr_arena, &cond_node, target)?;
+            if !cond_type.is_boolean() {
+                return Err(CompileError::new(
+                    TypeErrorKind::NonBooleanCondition {
+                        actual: cond_type.dump(),
+                    },
+                    cond_node.get_pos(),
+                ));
+            }
+
+            Ok(Type::new_int64(target))
+        }
+    }
+}
+
+fn type_check_method_call_expression(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
+    expr_arena: ast::ExprArena,
+    recv_node: ast::ExpressionNode,
+    method: &str,
+    args: &[ast::ExNodeId],
+    target: option::Target,
+) -> Result<Type, CompileError<TypeErrorKind>> {
+    // メソッド呼び出しでチェックすること
+    // - レシーバが構造体型か，構造体へのポインタ型か，インタフェース型であるか
+    // - メソッドが構造体に定義されているか
+    // - 実引数の数と型が，レシーバを除いた引数と一致しているか
+    let recv_type = type_check_expr(tld_env, type_env, expr_arena.clone(), &recv_node, target)?;
+    let err_pos = recv_node.get_pos();
+
+    // インタフェース型であれば，インタフェースにメソッドが宣言されていれば良い
+    if recv_type.is_interface() {
+        return match recv_type.get_interface_method(method) {
+            Some((_slot, return_type)) => type_check_method_arguments(
+                tld_env,
+                type_env,
+                expr_arena,
+                &recv_type,
+                method,
+                return_type.clone(),
+                args,
+                target,
+                err_pos,
+            ),
+            None => Err(CompileError::new(
+                TypeErrorKind::UndefinedSuchAMethod {
+                    struct_name: recv_type.get_interface_name().to_string(),
+                    method: method.to_string(),
+                },
+                err_pos,
+            )),
+        };
+    }
+
+    let struct_name = match recv_type.receiver_struct() {
+        Some(st_type) => st_type.get_struct_name().to_string(),
+        None => {
+            return Err(CompileError::new(
+                TypeErrorKind::CannotCallMethodWithNotAStruct { recv_node },
+                err_pos,
+            ))
+        }
+    };
+
+    let symbol = match tld_env.get(&struct_name).map(|entry| &entry.kind) {
+        Some(tld::TLDKind::STRUCT {
+            members: _,
+            methods,
+        }) => methods.get(method).cloned(),
+        _ => None,
+    };
+    let symbol = match symbol {
+        Some(symbol) => symbol,
+        None => {
+            // 関数ポインタ型のメンバであれば，それを介して呼び出す
+            if let Some(fn_type) = function_pointer_member(&recv_type, method) {
+                type_check_call_arguments(
+                    tld_env, type_env, expr_arena, method, fn_type, args, target, err_pos,
+                )?;
+                return Ok(fn_type.get_return_type().clone());
+            }
+
+            return Err(CompileError::new(
+                TypeErrorKind::UndefinedSuchAMethod {
+                    struct_name,
+                    method: method.to_string(),
+                },
+                err_pos,
+            ));
+        }
+    };
+
+    let return_type = match &tld_env.get(&symbol).unwrap().kind {
+        tld::TLDKind::FN {
+            return_type,
+            args: _,
+        } => resolve_type_string(tld_env, return_type.to_string(), target)?,
+        _ => unreachable!(),
+    };
+
+    type_check_method_arguments(
+        tld_env,
+        type_env,
+        expr_arena,
+        &recv_type,
+        method,
+        return_type,
+        args,
+        target,
+        err_pos,
+    )
+}
+
+/// メソッド呼び出しの実引数を，レシーバを除いた引数と突き合わせる
+#[allow(clippy::too_many_arguments)]
+fn type_check_method_arguments(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
+    expr_arena: ast::ExprArena,
+    recv_type: &Type,
+    method: &str,
+    return_type: Type,
+    args: &[ast::ExNodeId],
+    target: option::Target,
+    err_pos: Position,
+) -> Result<Type, CompileError<TypeErrorKind>> {
+    let params = method_parameters(tld_env, recv_type, method, target)?;
+    let fn_type = Type::new_function(return_type.clone(), params);
+    type_check_call_arguments(
+        tld_env, type_env, expr_arena, method, &fn_type, args, target, err_pos,
+    )?;
+
+    Ok(return_type)
+}
+
+fn type_check_struct_literal(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
+    expr_arena: ast::ExprArena,
+    type_name: &str,
+    fields: &[(String, ast::ExNodeId)],
+    literal_pos: Position,
+    target: option::Target,
+) -> Result<Type, CompileError<TypeErrorKind>> {
+    // 構造体リテラルでチェックすること
+    // - 型名が構造体型を指しているか
+    // - 各フィールドが構造体に存在し，重複していないか
+    // - 初期化式の型がメンバの型と一致しているか
+    // - すべてのメンバが初期化されているか
+    let st_type = resolve_type_string(tld_env, type_name.to_string(), target)?;
+    if !st_type.is_struct() {
+        return Err(CompileError::new(
+            TypeErrorKind::NotAStructType {
+                type_name: type_name.to_string(),
+            },
+            literal_pos,
+        ));
+    }
+    let struct_name = st_type.get_struct_name().to_string();
+
+    let mut initialized = BTreeSet::new();
+    for (member, value_id) in fields.iter() {
+        let value = expr_arena.lock().unwrap().get(*value_id).unwrap().clone();
+
+        let member_type = match st_type.get_member(member) {
+            Some((member_type, _member_offset)) => member_type,
+            None => {
+                return Err(CompileError::new(
+                    TypeErrorKind::UndefinedSuchAMember {
+                        member: member.to_string(),
+                    },
+                    value.get_pos(),
+                ))
+            }
+        };
+        if !initialized.insert(member.as_str()) {
+            return Err(CompileError::new(
+                TypeErrorKind::DuplicatedStructField {
+                    struct_name,
+                    member: member.to_string(),
+                },
+                value.get_pos(),
+            ));
+        }
+
+        // インタフェース型への変換は別途検査している
+        let value_type = type_check_expr(tld_env, type_env, expr_arena.clone(), &value, target)?;
+        check_untyped_constant_range(&expr_arena, &value, member_type)?;
+        let untyped = is_untyped_constant(&expr_arena, &value);
+        if !member_type.is_interface() && !is_assignable(member_type, &value_type, untyped) {
+            return Err(CompileError::new(
+                TypeErrorKind::MismatchedFieldType {
+                    member: member.to_string(),
+                    expected: member_type.dump(),
+                    actual: value_type.dump(),
+                },
+                value.get_pos(),
+            ));
+        }
+    }
+
+    let missing: Vec<String> = st_type
+        .get_members()
+        .iter()
+        .filter(|(member, _)| !initialized.contains(member.as_str()))
+        .map(|(member, _)| member.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(CompileError::new(
+            TypeErrorKind::MissingStructFields {
+                struct_name,
+                members: missing,
+            },
+            literal_pos,
+        ));
+    }
+
+    Ok(st_type)
+}
+
+fn type_check_member_expression(
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+    type_env: &BTreeMap<String, Type>,
+    expr_arena: ast::ExprArena,
+    struct_node: ast::ExpressionNode,
+    member: &str,
+    target: option::Target,
+) -> Result<Type, CompileError<TypeErrorKind>> {
+    // メンバ式でチェックすること
+    // - DOT前のノードが構造体型(か構造体へのポインタ型)であるか
+    // - メンバ名が構造体に存在するか
+    // DOT前は `a.b` や `(*p)` のような任意の式でよい
+    let node_type = type_check_expr(tld_env, type_env, expr_arena, &struct_node, target)?;
+
+    // 構造体へのポインタは自動的にデリファレンスされる
+    let node_type = match node_type.receiver_struct() {
+        Some(st_type) => st_type,
+        None => {
+            let err_pos = struct_node.get_pos();
+            return Err(CompileError::new(
+                TypeErrorKind::CannotAccessMemberWIthNotAStruct { struct_node },
+                err_pos,
+            ));
+        }
+    };
+
+    // メンバが存在するかチェック
+    match node_type.get_member(member) {
+        Some((member_type, _member_offset)) => Ok(*member_type.clone()),
+        None => {
+            let err_pos = struct_node.get_pos();
+            Err(CompileError::new(
+                TypeErrorKind::UndefinedSuchAMember {
+                    member: member.to_string(),
+                },
+                err_pos,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod type_check_tests {
+    use super::*;
+    use crate::common::analyze_resource::ast::FunctionTypeDef;
+    use crate::common::option::Target;
+    use crate::common::token::TokenKind;
+    use id_arena::Arena;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn type_check_main_fn_with_invalid_return_type_test() {
+        let invalid_return_func = new_func("main".to_string(), Default::default());
+        let tld_env = new_tld();
+
+        let env = type_env();
+
+        let actual =
+            type_check_main_fn(&tld_env, &env, &invalid_return_func, option::Target::X86_64);
+        assert!(actual.is_err());
+
+        if let Err(e) = actual {
+            assert_eq!(&TypeErrorKind::InvalidMainFunctionReturnType, e.get_kind());
+        }
+    }
+
+    #[test]
+    fn type_check_main_fn_with_invalid_args_test() {
+        let invalid_args_func = new_func(
+            "main".to_string(),
+            vec![("foo".to_string(), "Int64".to_string())],
+        );
+        let tld_env = new_tld();
         let env = type_env();
 
         let actual = type_check_main_fn(&tld_env, &env, &invalid_args_func, option::Target::X86_64);
         assert!(actual.is_err());
 
-        if let Err(e) = actual {
-            assert_eq!(
-                &TypeErrorKind::MAINFUNCMUSTNOTHAVEANYARGUMENTS,
-                e.get_kind()
-            );
-        }
+        if let Err(e) = actual {
+            assert_eq!(&TypeErrorKind::InvalidMainFunctionArguments, e.get_kind());
+        }
+    }
+
+    #[test]
+    fn test_function_check_test() {
+        let (fn_arena, _expr_arena) = new_allocators();
+        let mut ast_root: ast::ASTRoot = Default::default();
+
+        let mut valid = new_func("add_test".to_string(), Vec::new());
+        valid.fn_type.return_type = "Int64".to_string();
+        ast_root.funcs.push(fn_arena.lock().unwrap().alloc(valid));
+        ast_root.tests.push("add_test".to_string());
+        assert!(test_function_check(fn_arena.clone(), &ast_root).is_ok());
+
+        // Noreturnを返すテスト関数は受け付けない
+        let invalid = new_func("sub_test".to_string(), Vec::new());
+        ast_root.funcs.push(fn_arena.lock().unwrap().alloc(invalid));
+        ast_root.tests.push("sub_test".to_string());
+
+        let actual = test_function_check(fn_arena, &ast_root);
+        assert!(actual.is_err());
+        if let Err(e) = actual {
+            assert_eq!(
+                &TypeErrorKind::InvalidTestFunction {
+                    func_name: "sub_test".to_string()
+                },
+                e.get_kind()
+            );
+        }
+    }
+
+    #[test]
+    fn untyped_constant_test() {
+        let (_fn_arena, expr_arena) = new_allocators();
+        let tld_env = new_tld();
+        let env = new_func_env();
+
+        let one = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_integer(1, Default::default()));
+        let two = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_integer(2, Default::default()));
+        let u = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_identifier(
+                vec!["u".to_string()],
+                Default::default(),
+            ));
+
+        // 1 + 2 は型なし整数定数
+        let const_add =
+            ast::ExpressionNode::new_binop(&TokenKind::PLUS, one, two, Default::default());
+        assert!(is_untyped_constant(&expr_arena, &const_add));
+        assert!(is_assignable(
+            &Type::new_uint64(Target::X86_64),
+            &Type::new_int64(Target::X86_64),
+            true
+        ));
+
+        // 1 + u はuの型(Uint64)に合わせる
+        let mixed_add =
+            ast::ExpressionNode::new_binop(&TokenKind::PLUS, one, u, Default::default());
+        assert!(!is_untyped_constant(&expr_arena, &mixed_add));
+        let mixed_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &mixed_add,
+            Target::X86_64,
+        );
+        assert_eq!(Type::new_uint64(Target::X86_64), mixed_type.unwrap());
+
+        // 負の型なし整数定数は，符号なし整数型に変換できない
+        let minus_one =
+            ast::ExpressionNode::new_prefix_op(&TokenKind::MINUS, one, Default::default());
+        let e = check_untyped_constant_range(
+            &expr_arena,
+            &minus_one,
+            &Type::new_uint64(Target::X86_64),
+        )
+        .unwrap_err();
+        assert_eq!(
+            &TypeErrorKind::UntypedConstantOutOfRange {
+                value: "-1".to_string(),
+                type_name: "Uint64".to_string(),
+            },
+            e.get_kind()
+        );
+        assert!(check_untyped_constant_range(
+            &expr_arena,
+            &minus_one,
+            &Type::new_int64(Target::X86_64)
+        )
+        .is_ok());
+
+        // 型の付いた値は別の整数型に代入できない
+        assert!(!is_assignable(
+            &Type::new_uint64(Target::X86_64),
+            &Type::new_int64(Target::X86_64),
+            false
+        ));
+    }
+
+    #[test]
+    fn type_check_main_fn_return_type_test() {
+        let noreturn_main = Type::new_function(Type::new_noreturn(), Vec::new());
+        assert!(is_valid_main_return_type(&noreturn_main));
+
+        let int64_main = Type::new_function(Type::new_int64(Target::X86_64), Vec::new());
+        assert!(is_valid_main_return_type(&int64_main));
+
+        let boolean_main = Type::new_function(Type::new_boolean(Target::X86_64), Vec::new());
+        assert!(!is_valid_main_return_type(&boolean_main));
+    }
+
+    #[test]
+    fn type_check_main_fn_with_argc_argv_test() {
+        let argc_argv_func = new_func(
+            "main".to_string(),
+            vec![
+                ("argc".to_string(), "Int64".to_string()),
+                ("argv".to_string(), "*ConstStr".to_string()),
+            ],
+        );
+        assert!(is_valid_main_parameters(argc_argv_func.get_parameters()));
+
+        let with_envp = vec![
+            ("argc".to_string(), "Int64".to_string()),
+            ("argv".to_string(), "*ConstStr".to_string()),
+            ("envp".to_string(), "*ConstStr".to_string()),
+        ];
+        assert!(is_valid_main_parameters(&with_envp));
+
+        let swapped = vec![
+            ("argv".to_string(), "*ConstStr".to_string()),
+            ("argc".to_string(), "Int64".to_string()),
+        ];
+        assert!(!is_valid_main_parameters(&swapped));
+    }
+
+    #[test]
+    fn invalid_member_access_test() {
+        let (_fn_arena, expr_arena) = new_allocators();
+        let tld_env = new_tld();
+        let env = new_func_env();
+
+        // `3.foo`
+        let member_ex = new_member_node(
+            expr_arena.clone(),
+            ast::ExpressionNode::new_integer(3, Default::default()),
+            "foo".to_string(),
+        );
+        let member_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &member_ex,
+            option::Target::X86_64,
+        );
+        type_check_expr_error_test(
+            member_type,
+            TypeErrorKind::CannotAccessMemberWIthNotAStruct {
+                struct_node: ast::ExpressionNode::new_integer(3, Default::default()),
+            },
+        );
+
+        // `x.foo`
+        let member_ex = new_member_node(
+            expr_arena.clone(),
+            ast::ExpressionNode::new_identifier(vec!["x".to_string()], Default::default()),
+            "foo".to_string(),
+        );
+        let member_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &member_ex,
+            option::Target::X86_64,
+        );
+        type_check_expr_error_test(
+            member_type,
+            TypeErrorKind::CannotAccessMemberWIthNotAStruct {
+                struct_node: ast::ExpressionNode::new_identifier(
+                    vec!["x".to_string()],
+                    Default::default(),
+                ),
+            },
+        );
+
+        // `st.undefined`
+        let member_ex = new_member_node(
+            expr_arena.clone(),
+            ast::ExpressionNode::new_identifier(vec!["st".to_string()], Default::default()),
+            "undefined".to_string(),
+        );
+        let member_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &member_ex,
+            option::Target::X86_64,
+        );
+        type_check_expr_error_test(
+            member_type,
+            TypeErrorKind::UndefinedSuchAMember {
+                member: "undefined".to_string(),
+            },
+        );
+    }
+
+    #[test]
+    fn nested_member_access_test() {
+        let (_fn_arena, expr_arena) = new_allocators();
+        let tld_env = new_tld();
+        let mut env = new_func_env();
+        let st_type = env.get("st").unwrap().clone();
+        env.insert(
+            "pst".to_string(),
+            Type::new_pointer(st_type.clone(), Target::X86_64),
+        );
+        env.insert(
+            "outer".to_string(),
+            Type::new_struct(
+                "::Outer".to_string(),
+                vec![("inner".to_string(), (Box::new(st_type), 0))],
+                16,
+            ),
+        );
+
+        // `pst.bar` は `(*pst).bar` と同じ
+        let member_ex = new_member_node(
+            expr_arena.clone(),
+            ast::ExpressionNode::new_identifier(vec!["pst".to_string()], Default::default()),
+            "bar".to_string(),
+        );
+        let member_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &member_ex,
+            option::Target::X86_64,
+        );
+        assert!(member_type.is_ok());
+        assert_eq!(Type::new_int64(Target::X86_64), member_type.unwrap());
+
+        // `outer.inner.foo`
+        let inner_ex = new_member_node(
+            expr_arena.clone(),
+            ast::ExpressionNode::new_identifier(vec!["outer".to_string()], Default::default()),
+            "inner".to_string(),
+        );
+        let member_ex = new_member_node(expr_arena.clone(), inner_ex, "foo".to_string());
+        let member_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &member_ex,
+            option::Target::X86_64,
+        );
+        assert!(member_type.is_ok());
+        assert_eq!(Type::new_int64(Target::X86_64), member_type.unwrap());
+    }
+
+    #[test]
+    fn struct_literal_test() {
+        let (_fn_arena, expr_arena) = new_allocators();
+        let tld_env = new_tld();
+        let env = new_func_env();
+
+        // `S { bar: 1, foo: x }`
+        let literal_ex = new_struct_literal_node(
+            expr_arena.clone(),
+            vec![
+                (
+                    "bar",
+                    ast::ExpressionNode::new_integer(1, Default::default()),
+                ),
+                (
+                    "foo",
+                    ast::ExpressionNode::new_identifier(vec!["x".to_string()], Default::default()),
+                ),
+            ],
+        );
+        let literal_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &literal_ex,
+            option::Target::X86_64,
+        );
+        assert!(literal_type.is_ok());
+        assert_eq!(env.get("st").unwrap(), &literal_type.unwrap());
+
+        // `S { foo: 1 }`
+        let literal_ex = new_struct_literal_node(
+            expr_arena.clone(),
+            vec![(
+                "foo",
+                ast::ExpressionNode::new_integer(1, Default::default()),
+            )],
+        );
+        let literal_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &literal_ex,
+            option::Target::X86_64,
+        );
+        type_check_expr_error_test(
+            literal_type,
+            TypeErrorKind::MissingStructFields {
+                struct_name: "::S".to_string(),
+                members: vec!["bar".to_string()],
+            },
+        );
+
+        // `S { foo: 1, foo: 2, bar: 3 }`
+        let literal_ex = new_struct_literal_node(
+            expr_arena.clone(),
+            vec![
+                (
+                    "foo",
+                    ast::ExpressionNode::new_integer(1, Default::default()),
+                ),
+                (
+                    "foo",
+                    ast::ExpressionNode::new_integer(2, Default::default()),
+                ),
+                (
+                    "bar",
+                    ast::ExpressionNode::new_integer(3, Default::default()),
+                ),
+            ],
+        );
+        let literal_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &literal_ex,
+            option::Target::X86_64,
+        );
+        type_check_expr_error_test(
+            literal_type,
+            TypeErrorKind::DuplicatedStructField {
+                struct_name: "::S".to_string(),
+                member: "foo".to_string(),
+            },
+        );
+
+        // `S { foo: u, bar: 1 }`
+        let literal_ex = new_struct_literal_node(
+            expr_arena.clone(),
+            vec![
+                (
+                    "foo",
+                    ast::ExpressionNode::new_identifier(vec!["u".to_string()], Default::default()),
+                ),
+                (
+                    "bar",
+                    ast::ExpressionNode::new_integer(1, Default::default()),
+                ),
+            ],
+        );
+        let literal_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &literal_ex,
+            option::Target::X86_64,
+        );
+        type_check_expr_error_test(
+            literal_type,
+            TypeErrorKind::MismatchedFieldType {
+                member: "foo".to_string(),
+                expected: "Int64".to_string(),
+                actual: "Uint64".to_string(),
+            },
+        );
     }
 
     #[test]
-    fn invalid_member_access_test() {
+    fn method_call_test() {
         let (_fn_arena, expr_arena) = new_allocators();
         let tld_env = new_tld();
         let env = new_func_env();
 
-        // `3.foo`
-        let member_ex = new_member_node(
+        // `st.sum()`
+        let call_ex = new_method_call_node(
             expr_arena.clone(),
-            ast::ExpressionNode::new_integer(3, Default::default()),
-            "foo".to_string(),
+            ast::ExpressionNode::new_identifier(vec!["st".to_string()], Default::default()),
+            "sum".to_string(),
         );
-        let member_type = type_check_expr(
+        let call_type = type_check_expr(
             &tld_env,
             &env,
             expr_arena.clone(),
-            &member_ex,
+            &call_ex,
+            option::Target::X86_64,
+        );
+        assert!(call_type.is_ok());
+        assert_eq!(Type::new_int64(Target::X86_64), call_type.unwrap());
+
+        // `st.undefined()`
+        let call_ex = new_method_call_node(
+            expr_arena.clone(),
+            ast::ExpressionNode::new_identifier(vec!["st".to_string()], Default::default()),
+            "undefined".to_string(),
+        );
+        let call_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &call_ex,
             option::Target::X86_64,
         );
         type_check_expr_error_test(
-            member_type,
-            TypeErrorKind::CannotAccessMemberWithNotAnIdentifier {
-                struct_node: ast::ExpressionNode::new_integer(3, Default::default()),
+            call_type,
+            TypeErrorKind::UndefinedSuchAMethod {
+                struct_name: "::S".to_string(),
+                method: "undefined".to_string(),
             },
         );
 
-        // `x.foo`
-        let member_ex = new_member_node(
+        // `st.sum(x)`
+        let recv_id = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_identifier(
+                vec!["st".to_string()],
+                Default::default(),
+            ));
+        let x_id = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_identifier(
+                vec!["x".to_string()],
+                Default::default(),
+            ));
+        let call_ex = ast::ExpressionNode::new_method_call(
+            recv_id,
+            "sum".to_string(),
+            vec![x_id],
+            Default::default(),
+        );
+        let call_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &call_ex,
+            option::Target::X86_64,
+        );
+        type_check_expr_error_test(
+            call_type,
+            TypeErrorKind::MismatchedArgumentCount {
+                func_name: "sum".to_string(),
+                expected: 0,
+                actual: 1,
+            },
+        );
+
+        // `x.sum()`
+        let call_ex = new_method_call_node(
             expr_arena.clone(),
             ast::ExpressionNode::new_identifier(vec!["x".to_string()], Default::default()),
-            "foo".to_string(),
+            "sum".to_string(),
         );
-        let member_type = type_check_expr(
+        let call_type = type_check_expr(
             &tld_env,
             &env,
             expr_arena.clone(),
-            &member_ex,
+            &call_ex,
             option::Target::X86_64,
         );
         type_check_expr_error_test(
-            member_type,
-            TypeErrorKind::CannotAccessMemberWIthNotAStruct {
-                struct_node: ast::ExpressionNode::new_identifier(
+            call_type,
+            TypeErrorKind::CannotCallMethodWithNotAStruct {
+                recv_node: ast::ExpressionNode::new_identifier(
                     vec!["x".to_string()],
                     Default::default(),
                 ),
             },
         );
+    }
 
-        // `st.undefined`
-        let member_ex = new_member_node(
+    #[test]
+    fn function_pointer_call_test() {
+        let (_fn_arena, expr_arena) = new_allocators();
+        let tld_env = new_tld();
+        let env = new_func_env();
+
+        // `fp(x)`
+        let x_id = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_identifier(
+                vec!["x".to_string()],
+                Default::default(),
+            ));
+        let call_ex = ast::ExpressionNode::new_call(
+            vec!["fp".to_string()],
+            vec![],
+            vec![x_id],
+            Default::default(),
+        );
+        let call_type = type_check_expr(
+            &tld_env,
+            &env,
             expr_arena.clone(),
-            ast::ExpressionNode::new_identifier(vec!["st".to_string()], Default::default()),
-            "undefined".to_string(),
+            &call_ex,
+            option::Target::X86_64,
         );
-        let member_type = type_check_expr(
+        assert!(call_type.is_ok());
+        assert_eq!(Type::new_uint64(Target::X86_64), call_type.unwrap());
+
+        // `fp()`
+        let call_ex = ast::ExpressionNode::new_call(
+            vec!["fp".to_string()],
+            vec![],
+            vec![],
+            Default::default(),
+        );
+        let call_type = type_check_expr(
             &tld_env,
             &env,
             expr_arena.clone(),
-            &member_ex,
+            &call_ex,
             option::Target::X86_64,
         );
         type_check_expr_error_test(
-            member_type,
-            TypeErrorKind::UndefinedSuchAMember {
-                member: "undefined".to_string(),
+            call_type,
+            TypeErrorKind::MismatchedArgumentCount {
+                func_name: "fp".to_string(),
+                expected: 1,
+                actual: 0,
+            },
+        );
+
+        // `x()`
+        let call_ex = ast::ExpressionNode::new_call(
+            vec!["x".to_string()],
+            vec![],
+            vec![],
+            Default::default(),
+        );
+        let call_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &call_ex,
+            option::Target::X86_64,
+        );
+        type_check_expr_error_test(
+            call_type,
+            TypeErrorKind::NotAFunction {
+                name: "x".to_string(),
+            },
+        );
+    }
+
+    #[test]
+    fn function_call_test() {
+        let (_fn_arena, expr_arena) = new_allocators();
+        let tld_env = new_tld();
+        let env = new_func_env();
+
+        let x_id = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_identifier(
+                vec!["x".to_string()],
+                Default::default(),
+            ));
+        let limit_id = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_identifier(
+                vec!["LIMIT".to_string()],
+                Default::default(),
+            ));
+        let true_id = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_boolean(true, Default::default()));
+
+        // `add(x, LIMIT)`
+        let call_ex = ast::ExpressionNode::new_call(
+            vec!["add".to_string()],
+            vec![],
+            vec![x_id, limit_id],
+            Default::default(),
+        );
+        let call_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &call_ex,
+            option::Target::X86_64,
+        );
+        assert!(call_type.is_ok());
+        assert_eq!(Type::new_int64(Target::X86_64), call_type.unwrap());
+
+        // `add(x)`
+        let call_ex = ast::ExpressionNode::new_call(
+            vec!["add".to_string()],
+            vec![],
+            vec![x_id],
+            Default::default(),
+        );
+        let call_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &call_ex,
+            option::Target::X86_64,
+        );
+        type_check_expr_error_test(
+            call_type,
+            TypeErrorKind::MismatchedArgumentCount {
+                func_name: "add".to_string(),
+                expected: 2,
+                actual: 1,
+            },
+        );
+
+        // `add(x, true)`
+        let call_ex = ast::ExpressionNode::new_call(
+            vec!["add".to_string()],
+            vec![],
+            vec![x_id, true_id],
+            Default::default(),
+        );
+        let call_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &call_ex,
+            option::Target::X86_64,
+        );
+        type_check_expr_error_test(
+            call_type,
+            TypeErrorKind::MismatchedArgumentType {
+                func_name: "add".to_string(),
+                expected: "Int64".to_string(),
+                actual: "Boolean".to_string(),
+            },
+        );
+    }
+
+    #[test]
+    fn arithmetic_operand_test() {
+        let (_fn_arena, expr_arena) = new_allocators();
+        let tld_env = new_tld();
+        let env = new_func_env();
+
+        let alloc = |ex: ast::ExpressionNode| expr_arena.lock().unwrap().alloc(ex);
+        let x_id = alloc(ast::ExpressionNode::new_identifier(
+            vec!["x".to_string()],
+            Default::default(),
+        ));
+        let u_id = alloc(ast::ExpressionNode::new_identifier(
+            vec!["u".to_string()],
+            Default::default(),
+        ));
+        let true_id = alloc(ast::ExpressionNode::new_boolean(true, Default::default()));
+
+        // `x + true`
+        let add_ex =
+            ast::ExpressionNode::new_binop(&TokenKind::PLUS, x_id, true_id, Default::default());
+        type_check_expr_error_test(
+            type_check_expr(&tld_env, &env, expr_arena.clone(), &add_ex, Target::X86_64),
+            TypeErrorKind::NonIntegerOperand {
+                actual: "Boolean".to_string(),
+            },
+        );
+
+        // `x + u`
+        let add_ex =
+            ast::ExpressionNode::new_binop(&TokenKind::PLUS, x_id, u_id, Default::default());
+        type_check_expr_error_test(
+            type_check_expr(&tld_env, &env, expr_arena.clone(), &add_ex, Target::X86_64),
+            TypeErrorKind::MismatchedOperandTypes {
+                lhs: "Int64".to_string(),
+                rhs: "Uint64".to_string(),
+            },
+        );
+
+        // `-u`
+        let neg_ex =
+            ast::ExpressionNode::new_prefix_op(&TokenKind::MINUS, u_id, Default::default());
+        type_check_expr_error_test(
+            type_check_expr(&tld_env, &env, expr_arena.clone(), &neg_ex, Target::X86_64),
+            TypeErrorKind::NegationOfUnsigned {
+                actual: "Uint64".to_string(),
+            },
+        );
+
+        // `x = true`
+        let assign_ex =
+            ast::ExpressionNode::new_binop(&TokenKind::ASSIGN, x_id, true_id, Default::default());
+        type_check_expr_error_test(
+            type_check_expr(
+                &tld_env,
+                &env,
+                expr_arena.clone(),
+                &assign_ex,
+                Target::X86_64,
+            ),
+            TypeErrorKind::MismatchedAssignmentType {
+                expected: "Int64".to_string(),
+                actual: "Boolean".to_string(),
+            },
+        );
+
+        // `if (x) {}`
+        let if_ex = ast::ExpressionNode::new_if(x_id, vec![], None, Default::default());
+        type_check_expr_error_test(
+            type_check_expr(&tld_env, &env, expr_arena.clone(), &if_ex, Target::X86_64),
+            TypeErrorKind::NonBooleanCondition {
+                actual: "Int64".to_string(),
+            },
+        );
+    }
+
+    #[test]
+    fn type_check_return_stmt_test() {
+        let (_fn_arena, expr_arena) = new_allocators();
+        let tld_env = new_tld();
+        let env = new_func_env();
+
+        let int_id = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_integer(30, Default::default()));
+        let x_id = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_identifier(
+                vec!["x".to_string()],
+                Default::default(),
+            ));
+        let addr_id = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_prefix_op(
+                &TokenKind::AMPERSAND,
+                x_id,
+                Default::default(),
+            ));
+
+        // Noreturnな関数は値を返せない
+        let e = type_check_return_stmt(
+            &tld_env,
+            &env,
+            int_id,
+            expr_arena.clone(),
+            &Type::new_noreturn(),
+            Target::X86_64,
+        )
+        .unwrap_err();
+        assert_eq!(
+            &TypeErrorKind::MismatchedReturnType {
+                expected: "Noreturn".to_string(),
+                actual: "Int64".to_string(),
+            },
+            e.get_kind()
+        );
+        assert!(type_check_return_stmt(
+            &tld_env,
+            &env,
+            int_id,
+            expr_arena.clone(),
+            &Type::new_uint64(Target::X86_64),
+            Target::X86_64,
+        )
+        .is_ok());
+
+        // 局所変数のアドレスは返せない
+        let e = type_check_return_stmt(
+            &tld_env,
+            &env,
+            addr_id,
+            expr_arena.clone(),
+            &Type::new_pointer(Type::new_int64(Target::X86_64), Target::X86_64),
+            Target::X86_64,
+        )
+        .unwrap_err();
+        assert_eq!(
+            &TypeErrorKind::ReturnLocalAddress {
+                name: "x".to_string(),
             },
+            e.get_kind()
+        );
+    }
+
+    #[test]
+    fn interface_satisfaction_test() {
+        let tld_env = new_tld();
+        let st_type = new_func_env().get("st").unwrap().clone();
+        let summable =
+            resolve_type_string(&tld_env, "::Summable".to_string(), Target::X86_64).unwrap();
+        let printable =
+            resolve_type_string(&tld_env, "::Printable".to_string(), Target::X86_64).unwrap();
+
+        // `*::S` は `sum() Int64` を持つので満たす
+        let st_ptr = Type::new_pointer(st_type, Target::X86_64);
+        assert!(check_interface_satisfaction(&tld_env, &st_ptr, &summable, Target::X86_64).is_ok());
+
+        // `print()` は定義されていない
+        let actual = check_interface_satisfaction(&tld_env, &st_ptr, &printable, Target::X86_64);
+        assert!(actual.is_err());
+        if let Err(e) = actual {
+            assert_eq!(
+                &TypeErrorKind::DoesNotImplementInterface {
+                    type_name: "::S".to_string(),
+                    interface_name: "::Printable".to_string(),
+                    method: "print".to_string(),
+                },
+                e.get_kind()
+            );
+        }
+
+        // 構造体以外は変換できない
+        let actual = check_interface_satisfaction(
+            &tld_env,
+            &Type::new_int64(Target::X86_64),
+            &summable,
+            Target::X86_64,
         );
+        assert!(actual.is_err());
     }
 
     fn type_check_expr_error_test(
@@ -434,6 +2465,43 @@ mod type_check_tests {
         assert!(strlit_type.is_ok());
         assert_eq!(Type::new_const_str(Target::X86_64), strlit_type.unwrap());
 
+        // 文字列リテラルへの添字は []Uint8 の要素
+        let strlit_id = expr_arena.lock().unwrap().alloc(strlit_ex);
+        let index_id = expr_arena
+            .lock()
+            .unwrap()
+            .alloc(ast::ExpressionNode::new_integer(1, Default::default()));
+        let index_ex = ast::ExpressionNode::new_index(strlit_id, index_id, Default::default());
+        let index_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &index_ex,
+            option::Target::X86_64,
+        );
+        assert_eq!(Type::new_uint8(Target::X86_64), index_type.unwrap());
+
+        let len_ex = ast::ExpressionNode::new_len(strlit_id, Default::default());
+        let len_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &len_ex,
+            option::Target::X86_64,
+        );
+        assert_eq!(Type::new_int64(Target::X86_64), len_type.unwrap());
+
+        // 整数は添字の対象にならない
+        let not_seq_ex = ast::ExpressionNode::new_index(index_id, index_id, Default::default());
+        let not_seq_type = type_check_expr(
+            &tld_env,
+            &env,
+            expr_arena.clone(),
+            &not_seq_ex,
+            option::Target::X86_64,
+        );
+        assert!(not_seq_type.is_err());
+
         // member_expr
         let member_ex = new_member_node(
             expr_arena.clone(),
@@ -460,6 +2528,29 @@ mod type_check_tests {
         ast::ExpressionNode::new_postfix_op(&TokenKind::DOT, st_id, member, Default::default())
     }
 
+    fn new_struct_literal_node(
+        expr_arena: ast::ExprArena,
+        fields: Vec<(&str, ast::ExpressionNode)>,
+    ) -> ast::ExpressionNode {
+        let fields = fields
+            .into_iter()
+            .map(|(member, value)| {
+                let value_id = expr_arena.lock().unwrap().alloc(value);
+                (member.to_string(), value_id)
+            })
+            .collect();
+        ast::ExpressionNode::new_struct_literal("::S".to_string(), fields, Default::default())
+    }
+
+    fn new_method_call_node(
+        expr_arena: ast::ExprArena,
+        recv_node: ast::ExpressionNode,
+        method: String,
+    ) -> ast::ExpressionNode {
+        let recv_id = expr_arena.lock().unwrap().alloc(recv_node);
+        ast::ExpressionNode::new_method_call(recv_id, method, vec![], Default::default())
+    }
+
     fn new_func(name: String, args: Vec<(String, String)>) -> ast::Function {
         ast::Function {
             name,
@@ -470,6 +2561,8 @@ mod type_check_tests {
             },
             pos: Default::default(),
             module_name: "".to_string(),
+            method_of: None,
+            type_params: vec![],
             stmt_arena: Arc::new(Mutex::new(Default::default())),
             expr_arena: Arc::new(Mutex::new(Default::default())),
         }
@@ -486,28 +2579,42 @@ mod type_check_tests {
     fn new_func_env() -> BTreeMap<String, Type> {
         let mut func_env = BTreeMap::new();
         // invalidなmain関数の型
-        func_env.insert("main".to_string(), Type::new_int64(Target::X86_64));
+        func_env.insert(
+            "main".to_string(),
+            Type::new_function(Type::new_uint64(Target::X86_64), Vec::new()),
+        );
 
         // なんてことない変数
         func_env.insert("x".to_string(), Type::new_int64(Target::X86_64));
+        func_env.insert("u".to_string(), Type::new_uint64(Target::X86_64));
+
+        // 関数ポインタ変数
+        func_env.insert(
+            "fp".to_string(),
+            Type::new_pointer(
+                Type::new_function(
+                    Type::new_uint64(Target::X86_64),
+                    vec![Type::new_int64(Target::X86_64)],
+                ),
+                Target::X86_64,
+            ),
+        );
 
         // 構造体変数
         func_env.insert(
             "st".to_string(),
             Type::new_struct(
-                {
-                    let mut members = BTreeMap::new();
-                    members.insert(
+                "::S".to_string(),
+                vec![
+                    (
                         "foo".to_string(),
                         (Box::new(Type::new_int64(Target::X86_64)), 0),
-                    );
-
-                    members.insert(
+                    ),
+                    (
                         "bar".to_string(),
                         (Box::new(Type::new_int64(Target::X86_64)), 8),
-                    );
-                    members
-                },
+                    ),
+                ],
                 16,
             ),
         );
@@ -532,16 +2639,70 @@ mod type_check_tests {
             }),
         );
         m.insert(
-            "S1".to_string(),
+            "::S".to_string(),
             tld::TopLevelDecl::new(tld::TLDKind::STRUCT {
-                members: {
+                members: vec![
+                    ("foo".to_string(), "Int64".to_string()),
+                    ("bar".to_string(), "Int64".to_string()),
+                ],
+                methods: {
+                    let mut mm = BTreeMap::new();
+                    mm.insert("sum".to_string(), "::S.sum".to_string());
+                    mm
+                },
+            }),
+        );
+        m.insert(
+            "::S.sum".to_string(),
+            tld::TopLevelDecl::new(tld::TLDKind::FN {
+                return_type: "Int64".to_string(),
+                args: vec![("s".to_string(), "*::S".to_string())],
+            }),
+        );
+        m.insert(
+            "::Summable".to_string(),
+            tld::TopLevelDecl::new(tld::TLDKind::INTERFACE {
+                methods: {
                     let mut mm = BTreeMap::new();
-                    mm.insert("m1".to_string(), "Int64".to_string());
-                    mm.insert("m2".to_string(), "Uint64".to_string());
+                    mm.insert("sum".to_string(), (vec![], "Int64".to_string()));
                     mm
                 },
             }),
         );
+        m.insert(
+            "::Printable".to_string(),
+            tld::TopLevelDecl::new(tld::TLDKind::INTERFACE {
+                methods: {
+                    let mut mm = BTreeMap::new();
+                    mm.insert("print".to_string(), (vec![], "Noreturn".to_string()));
+                    mm
+                },
+            }),
+        );
+        m.insert(
+            "S1".to_string(),
+            tld::TopLevelDecl::new(tld::TLDKind::STRUCT {
+                members: vec![
+                    ("m1".to_string(), "Int64".to_string()),
+                    ("m2".to_string(), "Uint64".to_string()),
+                ],
+                methods: BTreeMap::new(),
+            }),
+        );
+        m.insert(
+            "add".to_string(),
+            tld::TopLevelDecl::new(tld::TLDKind::FN {
+                return_type: "Int64".to_string(),
+                args: vec![
+                    ("a".to_string(), "Int64".to_string()),
+                    ("b".to_string(), "Int64".to_string()),
+                ],
+            }),
+        );
+        m.insert(
+            "::LIMIT".to_string(),
+            tld::TopLevelDecl::new_const("Int64", "30".to_string()),
+        );
 
         m
     }
@@ -1,50 +1,156 @@
-use crate::common::{ast, error::CompileError, option, peachili_type::Type, tld};
+use crate::common::{ast, error::CompileError, frame_object, option, peachili_type::Type, tld};
 
 use crate::common::error::TypeErrorKind;
-use std::collections::BTreeMap;
+use crate::common::pass::analyzer::infer_expr_type;
+use crate::common::peachili_type::TypeKind;
+use crate::common::position::Position;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 関数名(グローバルは "global") => (識別子 => 型)
+type TypeEnv = BTreeMap<String, BTreeMap<String, Type>>;
+
+/// ジェネリック関数の実体化．
+/// 型引数付きの呼び出しを探し，型引数の組ごとにテンプレートを複製して `funcs` に加える
+/// 実体内の呼び出しも続けて走査するので，ジェネリック関数から呼ぶ場合も実体化される
+pub fn instantiate_generic_functions(
+    fn_arena: ast::FnArena,
+    tld_map: &mut BTreeMap<String, tld::TopLevelDecl>,
+    ast_root: &mut ast::ASTRoot,
+) {
+    let templates: BTreeMap<String, ast::FnId> = ast_root
+        .generic_funcs
+        .iter()
+        .map(|fn_id| {
+            (
+                fn_arena.lock().unwrap().get(*fn_id).unwrap().full_path(),
+                *fn_id,
+            )
+        })
+        .collect();
+
+    let mut instantiated = BTreeSet::new();
+    let mut worklist = ast_root.funcs.clone();
+
+    while let Some(fn_id) = worklist.pop() {
+        for (names, type_args, call_pos) in collect_generic_calls(&fn_arena, fn_id) {
+            let template_name = names.join("::");
+            let template = match templates.get(&template_name) {
+                Some(template_id) => fn_arena.lock().unwrap().get(*template_id).unwrap().clone(),
+                None => {
+                    CompileError::new(
+                        TypeErrorKind::NotAGenericFunction {
+                            func_name: template_name,
+                        },
+                        call_pos,
+                    )
+                    .output();
+                    std::process::exit(1);
+                }
+            };
+
+            if template.type_params.len() != type_args.len() {
+                CompileError::new(
+                    TypeErrorKind::WrongNumberOfTypeArguments {
+                        name: template_name,
+                        expected: template.type_params.len(),
+                        actual: type_args.len(),
+                    },
+                    call_pos,
+                )
+                .output();
+                std::process::exit(1);
+            }
+
+            let instance = template.instantiate(&type_args);
+            if !instantiated.insert(instance.full_path()) {
+                continue;
+            }
+
+            tld_map.insert(
+                instance.name.to_string(),
+                tld::TopLevelDecl::new_function_from_ast(instance.fn_type.clone()),
+            );
+            ast_root.called_functions.insert(instance.full_path());
+
+            let instance_id = fn_arena.lock().unwrap().alloc(instance);
+            ast_root.funcs.push(instance_id);
+            worklist.push(instance_id);
+        }
+    }
+}
+
+/// 関数内の，型引数を持つ呼び出しをすべて集める
+fn collect_generic_calls(
+    fn_arena: &ast::FnArena,
+    fn_id: ast::FnId,
+) -> Vec<(Vec<String>, Vec<String>, Position)> {
+    let expr_arena = fn_arena
+        .lock()
+        .unwrap()
+        .get(fn_id)
+        .unwrap()
+        .expr_arena
+        .clone();
+    let arena = expr_arena.lock().unwrap();
+
+    arena
+        .iter()
+        .filter_map(|(_, expr)| match expr.get_kind() {
+            ast::ExpressionNodeKind::CALL {
+                names,
+                type_args,
+                args: _,
+            } if !type_args.is_empty() => Some((names.clone(), type_args.clone(), expr.get_pos())),
+            _ => None,
+        })
+        .collect()
+}
 
 /// 型情報の収集．
+/// 各関数のスコープ木も合わせて返す
 pub fn type_resolve_main(
     fn_arena: ast::FnArena,
     tld_map: &BTreeMap<String, tld::TopLevelDecl>,
     ast_root: &ast::ASTRoot,
     target: option::Target,
-) -> BTreeMap<String, BTreeMap<String, Type>> {
+) -> Result<(TypeEnv, frame_object::ScopeEnv), CompileError<TypeErrorKind>> {
     let mut type_env = BTreeMap::new();
+    let mut scope_env = BTreeMap::new();
     type_env.insert("global".to_string(), BTreeMap::new());
 
     // 先に型定義，エイリアスをすべて処理してしまう
     for (alias_name, alias_type_str) in ast_root.alias.iter() {
-        let alias_type = resolve_type_string(tld_map, alias_type_str.to_string(), target);
-
-        if let Err(e) = &alias_type {
-            e.output();
-            std::process::exit(1);
-        }
+        let alias_type = resolve_type_string(tld_map, alias_type_str.to_string(), target)?;
 
         // グローバル領域に書き込んでおく
         if let Some(global_env) = type_env.get_mut("global") {
-            global_env.insert(alias_name.to_string(), alias_type.unwrap());
+            global_env.insert(alias_name.to_string(), alias_type);
         }
     }
 
     // 定数にも型をつける
     for (const_name, (const_type_name, const_expr)) in ast_root.constants.iter() {
-        let const_type = resolve_type_string(tld_map, const_type_name.to_string(), target);
-
-        if let Err(e) = &const_type {
-            e.output();
-            std::process::exit(1);
-        }
+        let const_type = resolve_type_string(tld_map, const_type_name.to_string(), target)?;
 
         if let Some(global_env) = type_env.get_mut("global") {
             global_env.insert(
                 const_name.to_string(),
-                Type::new_const(const_type.unwrap(), const_expr.to_string(), target),
+                Type::new_const(const_type, const_expr.to_string(), target),
             );
         }
     }
 
+    // モジュールで修飾された呼び出しを検査するため，定義済みの関数を集めておく
+    let functions: BTreeSet<String> = {
+        let arena = fn_arena.lock().unwrap();
+        ast_root
+            .funcs
+            .iter()
+            .chain(ast_root.generic_funcs.iter())
+            .map(|fn_id| arena.get(*fn_id).unwrap().full_path())
+            .collect()
+    };
+
     // 関数列を操作し，関数内の識別子に型をつけていく．
     for fn_id in ast_root.funcs.iter() {
         let mut func_env = BTreeMap::new();
@@ -52,198 +158,510 @@ pub fn type_resolve_main(
             let function = arena.get(*fn_id).unwrap();
 
             // 関数自体の型格納
-            let function_ret_type =
-                resolve_type_string(tld_map, function.copy_return_type(), target);
-            if let Err(e) = function_ret_type {
-                e.output();
-                std::process::exit(1);
-            }
+            let function_type = resolve_function_type(
+                tld_map,
+                function.get_parameters(),
+                &function.copy_return_type(),
+                target,
+            )?;
 
-            func_env.insert(
-                function.full_path(),
-                Type::new_function(function_ret_type.unwrap()),
-            );
+            func_env.insert(function.full_path(), function_type);
 
-            if let Err(e) = add_auto_var_to_env(tld_map, &mut type_env, function, target) {
-                e.output();
-                std::process::exit(1);
-            }
+            // メソッドのレシーバは構造体へのポインタでなければならない
+            check_receiver_type(tld_map, function, target)?;
 
-            // 自動変数の型格納
-            for (arg_name, arg_type_str) in function.get_parameters().iter() {
-                let var_type = resolve_type_string(tld_map, arg_type_str.clone(), target);
-                if let Err(e) = var_type {
-                    e.output();
-                    std::process::exit(1);
-                }
-
-                func_env.insert(arg_name.clone(), var_type.unwrap());
-            }
+            let fn_scope =
+                add_auto_var_to_env(tld_map, &functions, &mut type_env, function, target)?;
+            scope_env.insert(function.full_path(), fn_scope);
 
-            for stmt_id in function.stmts.iter() {
-                if let Ok(stmt_arena) = function.stmt_arena.lock() {
-                    let stmt = stmt_arena.get(*stmt_id).unwrap();
-
-                    match stmt.get_kind() {
-                        ast::StatementNodeKind::DECLARE {
-                            ident_name,
-                            type_name,
-                        } => {
-                            let var_type = resolve_type_string(tld_map, type_name.clone(), target);
-                            if let Err(e) = var_type {
-                                e.output();
-                                std::process::exit(1);
-                            }
-
-                            func_env.insert(ident_name.clone(), var_type.unwrap());
-                        }
-                        ast::StatementNodeKind::VARINIT {
-                            ident_name,
-                            type_name,
-                            expr: _,
-                        } => {
-                            let var_type = resolve_type_string(tld_map, type_name.clone(), target);
-                            if let Err(e) = var_type {
-                                e.output();
-                                std::process::exit(1);
-                            }
-
-                            func_env.insert(ident_name.clone(), var_type.unwrap());
-                        }
-                        ast::StatementNodeKind::CONST {
-                            ident_name,
-                            type_name,
-                            expr: _,
-                        } => {
-                            let var_type = resolve_type_string(tld_map, type_name.clone(), target);
-                            if let Err(e) = var_type {
-                                e.output();
-                                std::process::exit(1);
-                            }
-
-                            func_env.insert(ident_name.clone(), var_type.unwrap());
-                        }
-                        _ => {}
-                    }
-                }
+            if let Some(locals) = type_env.get_mut(&function.full_path()) {
+                locals.append(&mut func_env);
             }
-
-            type_env.insert(function.full_path(), func_env);
         }
     }
 
-    type_env
+    Ok((type_env, scope_env))
 }
 
+/// 関数内の自動変数に型をつける
+/// レキシカルスコープを解決し，関数本体のスコープ木を返す
 fn add_auto_var_to_env(
     tld_map: &BTreeMap<String, tld::TopLevelDecl>,
+    functions: &BTreeSet<String>,
     type_env: &mut BTreeMap<String, BTreeMap<String, Type>>,
     function: &ast::Function,
     target: option::Target,
-) -> Result<(), CompileError<TypeErrorKind>> {
-    let func_name = function.name.to_string();
-    type_env.insert(func_name.to_string(), BTreeMap::new());
+) -> Result<frame_object::LexicalScope, CompileError<TypeErrorKind>> {
+    let mut resolver = ScopeResolver {
+        tld_map,
+        functions,
+        function,
+        target,
+        locals: BTreeMap::new(),
+        constants: BTreeSet::new(),
+        scopes: Vec::new(),
+        scope_tree: Vec::new(),
+    };
+    resolver.push_scope();
 
     // 引数のデータ格納
     for (arg_name, arg_type_str) in function.get_parameters().iter() {
-        if let Some(locals) = type_env.get_mut(&func_name) {
-            let arg_type = resolve_type_string(tld_map, arg_type_str.to_string(), target)?;
-            locals.insert(arg_name.to_string(), arg_type);
-        }
+        let arg_type = resolve_type_string(tld_map, arg_type_str.to_string(), target)?;
+        resolver.declare(arg_name, arg_type);
     }
 
     // 変数宣言系のデータ格納
-    for stmt_id in function.stmts.iter() {
-        if let Ok(arena) = function.stmt_arena.lock() {
-            let stmt = arena.get(*stmt_id).unwrap();
-
-            match stmt.get_kind() {
-                ast::StatementNodeKind::DECLARE {
-                    ident_name,
-                    type_name,
-                } => {
-                    let var_type = resolve_type_string(tld_map, type_name.to_string(), target)?;
-
-                    if let Some(locals) = type_env.get_mut(&func_name) {
-                        locals.insert(ident_name.to_string(), var_type);
-                    }
-                }
-                ast::StatementNodeKind::CONST {
-                    ident_name,
-                    type_name,
-                    expr: _,
-                } => {
-                    let var_type = resolve_type_string(tld_map, type_name.to_string(), target)?;
-
-                    if let Some(locals) = type_env.get_mut(&func_name) {
-                        locals.insert(ident_name.to_string(), var_type);
-                    }
-                }
-                ast::StatementNodeKind::VARINIT {
-                    ident_name,
-                    type_name,
-                    expr: _,
-                } => {
-                    let var_type = resolve_type_string(tld_map, type_name.to_string(), target)?;
-
-                    if let Some(locals) = type_env.get_mut(&func_name) {
-                        locals.insert(ident_name.to_string(), var_type);
-                    }
+    resolver.resolve_stmts(&function.stmts)?;
+
+    let fn_scope = resolver.scope_tree.pop().unwrap();
+    type_env.insert(function.full_path(), resolver.locals);
+
+    Ok(fn_scope)
+}
+
+/// 構造体リテラルの一時領域に付ける変数名
+/// 識別子として書けない名前にして，ユーザの変数と衝突しないようにする
+const STRUCT_LITERAL_STORAGE: &str = ".literal";
+
+/// 関数内のレキシカルスコープを解決する
+/// 既に使われている名前を再宣言した変数は `x#1` のような一意な名前に付け替え，
+/// 以降の型環境/IRでは付け替えた名前で扱う
+struct ScopeResolver<'a> {
+    tld_map: &'a BTreeMap<String, tld::TopLevelDecl>,
+    /// 定義済みの関数のフルパス
+    functions: &'a BTreeSet<String>,
+    function: &'a ast::Function,
+    target: option::Target,
+    /// 一意な変数名 => 型
+    locals: BTreeMap<String, Type>,
+    /// const文で宣言された変数の一意な名前
+    constants: BTreeSet<String>,
+    /// ソース上の変数名 => 一意な変数名 (内側のスコープほど後ろ)
+    scopes: Vec<BTreeMap<String, String>>,
+    /// 構築中のスコープ木 (scopesと対応する)
+    scope_tree: Vec<frame_object::LexicalScope>,
+}
+
+impl<'a> ScopeResolver<'a> {
+    fn push_scope(&mut self) {
+        self.scopes.push(BTreeMap::new());
+        self.scope_tree.push(Default::default());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+        let inner = self.scope_tree.pop().unwrap();
+        self.scope_tree.last_mut().unwrap().children.push(inner);
+    }
+
+    /// 現在のスコープに変数を宣言し，一意な名前を返す
+    fn declare(&mut self, name: &str, var_type: Type) -> String {
+        let mut unique_name = name.to_string();
+        let mut suffix = 0;
+        while self.locals.contains_key(&unique_name) {
+            suffix += 1;
+            unique_name = format!("{}#{}", name, suffix);
+        }
+
+        self.locals.insert(unique_name.clone(), var_type);
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.to_string(), unique_name.clone());
+        self.scope_tree
+            .last_mut()
+            .unwrap()
+            .variables
+            .push(unique_name.clone());
+
+        unique_name
+    }
+
+    /// 内側のスコープから順に変数を探す
+    fn lookup(&self, name: &str) -> Option<&String> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn resolve_stmts(
+        &mut self,
+        stmts: &[ast::StNodeId],
+    ) -> Result<(), CompileError<TypeErrorKind>> {
+        for stmt_id in stmts.iter() {
+            self.resolve_stmt(*stmt_id)?;
+        }
+
+        Ok(())
+    }
+
+    fn resolve_stmt(&mut self, stmt_id: ast::StNodeId) -> Result<(), CompileError<TypeErrorKind>> {
+        let stmt = self
+            .function
+            .stmt_arena
+            .lock()
+            .unwrap()
+            .get(stmt_id)
+            .unwrap()
+            .clone();
+
+        match stmt.get_kind() {
+            ast::StatementNodeKind::DECLARE {
+                ident_name,
+                type_name,
+            } => {
+                let var_type =
+                    resolve_type_string(self.tld_map, type_name.to_string(), self.target)?;
+                let unique_name = self.declare(ident_name, var_type);
+                self.rename_declaration(stmt_id, unique_name);
+            }
+            ast::StatementNodeKind::CONST {
+                ident_name,
+                type_name,
+                expr,
+            }
+            | ast::StatementNodeKind::VARINIT {
+                ident_name,
+                type_name,
+                expr,
+            } => {
+                // 初期化式は宣言より前のスコープで解決する
+                self.resolve_expr(*expr)?;
+
+                let var_type = if type_name.is_empty() {
+                    // 型名が省略されていれば初期化式から推論する
+                    infer_expr_type(
+                        self.tld_map,
+                        &self.locals,
+                        self.function.expr_arena.clone(),
+                        *expr,
+                        self.target,
+                    )?
+                } else {
+                    resolve_type_string(self.tld_map, type_name.to_string(), self.target)?
+                };
+                let unique_name = self.declare(ident_name, var_type);
+                if let ast::StatementNodeKind::CONST { .. } = stmt.get_kind() {
+                    self.constants.insert(unique_name.clone());
                 }
+                self.rename_declaration(stmt_id, unique_name);
+            }
+            ast::StatementNodeKind::RETURN { expr }
+            | ast::StatementNodeKind::IFRET { expr }
+            | ast::StatementNodeKind::EXPR { expr } => self.resolve_expr(*expr)?,
+            ast::StatementNodeKind::COUNTUP {
+                ident_name,
+                begin_ex,
+                endpoint_ex,
+                body,
+            } => {
+                self.resolve_expr(*begin_ex)?;
+                self.resolve_expr(*endpoint_ex)?;
 
-                _ => {}
+                // ループ変数はループ本体のスコープに属する
+                self.push_scope();
+                let unique_name = self.declare(ident_name, Type::new_int64(self.target));
+                self.rename_declaration(stmt_id, unique_name);
+                self.resolve_stmts(body)?;
+                self.pop_scope();
             }
+            ast::StatementNodeKind::MATCH { expr, arms } => {
+                self.resolve_expr(*expr)?;
+
+                for arm in arms.values() {
+                    self.push_scope();
+                    self.resolve_stmts(arm)?;
+                    self.pop_scope();
+                }
+            }
+            ast::StatementNodeKind::ASM { stmts: _ } => {}
         }
+
+        Ok(())
     }
 
-    for stmt_id in function.stmts.iter() {
-        if let Ok(arena) = function.stmt_arena.lock() {
-            let stmt = arena.get(*stmt_id).unwrap();
+    fn resolve_expr(&mut self, expr_id: ast::ExNodeId) -> Result<(), CompileError<TypeErrorKind>> {
+        let expr = self
+            .function
+            .expr_arena
+            .lock()
+            .unwrap()
+            .get(expr_id)
+            .unwrap()
+            .clone();
 
-            match stmt.get_kind() {
-                ast::StatementNodeKind::DECLARE {
-                    ident_name,
-                    type_name,
-                } => {
-                    let var_type = resolve_type_string(tld_map, type_name.to_string(), target)?;
+        match expr.get_kind() {
+            ast::ExpressionNodeKind::IDENTIFIER { names } => {
+                if !self.rename_reference(expr_id, names) && !self.is_toplevel_name(names) {
+                    return Err(CompileError::new(
+                        TypeErrorKind::UndefinedSuchAVariable {
+                            name: names.join("::"),
+                        },
+                        expr.get_pos(),
+                    ));
+                }
+            }
+            ast::ExpressionNodeKind::CALL {
+                names,
+                type_args: _,
+                args,
+            } => {
+                // 関数ポインタ変数の呼び出しもありうる
+                if !self.rename_reference(expr_id, names) && !self.is_defined_function(names) {
+                    return Err(CompileError::new(
+                        TypeErrorKind::UndefinedSuchAFunction {
+                            name: names.join("::"),
+                        },
+                        expr.get_pos(),
+                    ));
+                }
+                for arg in args.iter() {
+                    self.resolve_expr(*arg)?;
+                }
+            }
+            ast::ExpressionNodeKind::METHODCALL {
+                recv,
+                method: _,
+                args,
+            } => {
+                self.resolve_expr(*recv)?;
+                for arg in args.iter() {
+                    self.resolve_expr(*arg)?;
+                }
+            }
+            ast::ExpressionNodeKind::IF {
+                cond_ex,
+                body,
+                alter,
+            } => {
+                self.resolve_expr(*cond_ex)?;
+
+                self.push_scope();
+                self.resolve_stmts(body)?;
+                self.pop_scope();
 
-                    if let Some(locals) = type_env.get_mut(&func_name) {
-                        locals.insert(ident_name.to_string(), var_type);
-                    }
+                if let Some(alter) = alter {
+                    self.push_scope();
+                    self.resolve_stmts(alter)?;
+                    self.pop_scope();
                 }
-                ast::StatementNodeKind::CONST {
-                    ident_name,
-                    type_name,
-                    expr: _,
-                } => {
-                    let var_type = resolve_type_string(tld_map, type_name.to_string(), target)?;
-
-                    if let Some(locals) = type_env.get_mut(&func_name) {
-                        locals.insert(ident_name.to_string(), var_type);
-                    }
+            }
+            ast::ExpressionNodeKind::ADD { lhs, rhs }
+            | ast::ExpressionNodeKind::SUB { lhs, rhs }
+            | ast::ExpressionNodeKind::MUL { lhs, rhs }
+            | ast::ExpressionNodeKind::DIV { lhs, rhs } => {
+                self.resolve_expr(*lhs)?;
+                self.resolve_expr(*rhs)?;
+            }
+            ast::ExpressionNodeKind::ASSIGN { lhs, rhs } => {
+                self.check_assignment_target(*lhs)?;
+                self.resolve_expr(*lhs)?;
+                self.resolve_expr(*rhs)?;
+            }
+            ast::ExpressionNodeKind::NEG { value }
+            | ast::ExpressionNodeKind::ADDRESSOF { value }
+            | ast::ExpressionNodeKind::DEREFERENCE { value } => self.resolve_expr(*value)?,
+            ast::ExpressionNodeKind::MEMBER { id, member: _ } => self.resolve_expr(*id)?,
+            ast::ExpressionNodeKind::INDEX { value, index } => {
+                self.resolve_expr(*value)?;
+                self.resolve_expr(*index)?;
+            }
+            ast::ExpressionNodeKind::SLICE { value, low, high } => {
+                self.resolve_expr(*value)?;
+                for bound in low.iter().chain(high.iter()) {
+                    self.resolve_expr(*bound)?;
                 }
-                ast::StatementNodeKind::VARINIT {
-                    ident_name,
-                    type_name,
-                    expr: _,
-                } => {
-                    let var_type = resolve_type_string(tld_map, type_name.to_string(), target)?;
-
-                    if let Some(locals) = type_env.get_mut(&func_name) {
-                        locals.insert(ident_name.to_string(), var_type);
-                    }
+            }
+            ast::ExpressionNodeKind::LEN { value } => self.resolve_expr(*value)?,
+            ast::ExpressionNodeKind::STRUCTLITERAL {
+                type_name,
+                fields,
+                storage: _,
+            } => {
+                for (_member, value) in fields.iter() {
+                    self.resolve_expr(*value)?;
                 }
 
-                _ => {}
+                // リテラルはスタック上の一時領域で組み立てる
+                let literal_type =
+                    resolve_type_string(self.tld_map, type_name.to_string(), self.target)?;
+                if !literal_type.is_struct() {
+                    return Err(CompileError::new(
+                        TypeErrorKind::NotAStructType {
+                            type_name: type_name.to_string(),
+                        },
+                        expr.get_pos(),
+                    ));
+                }
+                let storage = self.declare(STRUCT_LITERAL_STORAGE, literal_type);
+                self.function
+                    .expr_arena
+                    .lock()
+                    .unwrap()
+                    .get_mut(expr_id)
+                    .unwrap()
+                    .set_literal_storage(storage);
             }
+            ast::ExpressionNodeKind::INTEGER { value: _ }
+            | ast::ExpressionNodeKind::UINTEGER { value: _ }
+            | ast::ExpressionNodeKind::CHARACTER { value: _ }
+            | ast::ExpressionNodeKind::BOOLEAN { truth: _ }
+            | ast::ExpressionNodeKind::STRING { contents: _ } => {}
         }
+
+        Ok(())
+    }
+
+    /// 局所変数を指す識別子であれば，一意な名前に付け替える
+    /// 見えているスコープの局所変数であれば真を返す
+    fn rename_reference(&self, expr_id: ast::ExNodeId, names: &[String]) -> bool {
+        if names.len() != 1 {
+            return false;
+        }
+
+        match self.lookup(&names[0]) {
+            Some(unique_name) => {
+                if unique_name != &names[0] {
+                    let mut arena = self.function.expr_arena.lock().unwrap();
+                    arena
+                        .get_mut(expr_id)
+                        .unwrap()
+                        .rename_variable(unique_name.to_string());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 局所変数でない識別子が，関数/定数などのトップレベルの宣言を指しているか
+    /// パスで修飾された識別子はモジュールのメンバとして後段で解決する
+    fn is_toplevel_name(&self, names: &[String]) -> bool {
+        if names.len() != 1 {
+            return true;
+        }
+
+        let qualified = format!("{}::{}", self.function.module_name, names[0]);
+        self.tld_map.contains_key(&names[0]) || self.tld_map.contains_key(&qualified)
+    }
+
+    /// モジュールで修飾された呼び出しが，そのモジュールの関数を指しているか
+    /// 修飾のない呼び出しは型検査で解決する
+    fn is_defined_function(&self, names: &[String]) -> bool {
+        if names.len() == 1 {
+            return true;
+        }
+
+        let full_path = names.join("::");
+        self.functions.contains(&full_path)
+            || self.functions.contains(full_path.trim_start_matches("::"))
+    }
+
+    /// 代入先が定数(const文で宣言した変数，トップレベルの定数)でないか検査する
+    fn check_assignment_target(
+        &self,
+        lhs_id: ast::ExNodeId,
+    ) -> Result<(), CompileError<TypeErrorKind>> {
+        let lhs = self
+            .function
+            .expr_arena
+            .lock()
+            .unwrap()
+            .get(lhs_id)
+            .unwrap()
+            .clone();
+        let names = match lhs.get_kind() {
+            ast::ExpressionNodeKind::IDENTIFIER { names } => names,
+            _ => return Ok(()),
+        };
+
+        let is_constant = match names.as_slice() {
+            [name] => match self.lookup(name) {
+                Some(unique_name) => self.constants.contains(unique_name),
+                None => self.is_toplevel_constant(&[
+                    name.to_string(),
+                    format!("::{}", name),
+                    format!("{}::{}", self.function.module_name, name),
+                ]),
+            },
+            _ => self.is_toplevel_constant(&[names.join("::")]),
+        };
+
+        if is_constant {
+            return Err(CompileError::new(
+                TypeErrorKind::AssignmentToConstant {
+                    name: names.join("::"),
+                },
+                lhs.get_pos(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn is_toplevel_constant(&self, candidates: &[String]) -> bool {
+        candidates.iter().any(|name| {
+            matches!(
+                self.tld_map.get(name).map(|entry| &entry.kind),
+                Some(tld::TLDKind::CONST { .. })
+            )
+        })
+    }
+
+    fn rename_declaration(&self, stmt_id: ast::StNodeId, unique_name: String) {
+        let mut arena = self.function.stmt_arena.lock().unwrap();
+        arena
+            .get_mut(stmt_id)
+            .unwrap()
+            .rename_declared_variable(unique_name);
+    }
+}
+
+/// レシーバの型をチェックする
+fn check_receiver_type(
+    tld_map: &BTreeMap<String, tld::TopLevelDecl>,
+    function: &ast::Function,
+    target: option::Target,
+) -> Result<(), CompileError<TypeErrorKind>> {
+    if function.method_of.is_none() {
+        return Ok(());
     }
 
-    Ok(())
+    let (_recv_name, recv_type_str) = &function.get_parameters()[0];
+    let recv_type = resolve_type_string(tld_map, recv_type_str.to_string(), target)?;
+
+    match &recv_type.kind {
+        TypeKind::POINTER { to } if to.is_struct() => Ok(()),
+        _ => Err(CompileError::new(
+            TypeErrorKind::InvalidReceiverType {
+                method: function.name.to_string(),
+                receiver_type: recv_type_str.to_string(),
+            },
+            function.pos,
+        )),
+    }
+}
+
+/// 引数定義と返り値型から関数型を構築する
+pub fn resolve_function_type(
+    tld_map: &BTreeMap<String, tld::TopLevelDecl>,
+    args: &[(String, String)],
+    return_type: &str,
+    target: option::Target,
+) -> Result<Type, CompileError<TypeErrorKind>> {
+    let mut arg_types = Vec::new();
+    for (_arg_name, arg_type_str) in args.iter() {
+        arg_types.push(resolve_type_string(
+            tld_map,
+            arg_type_str.to_string(),
+            target,
+        )?);
+    }
+
+    let return_type = resolve_type_string(tld_map, return_type.to_string(), target)?;
+    Ok(Type::new_function(return_type, arg_types))
 }
 
-/// type_string -> `*` type_string | primitive_types
-fn resolve_type_string(
+/// type_string -> `*` type_string | function_type | primitive_types
+pub fn resolve_type_string(
     tld_map: &BTreeMap<String, tld::TopLevelDecl>,
     type_name_str: String,
     target: option::Target,
@@ -253,9 +671,33 @@ fn resolve_type_string(
         return Ok(Type::new_pointer(pointer_to, target));
     }
 
+    if let Some((length, elem_type)) = ast::split_array_type_name(&type_name_str) {
+        let elem_type = resolve_type_string(tld_map, elem_type, target)?;
+        return Ok(match length {
+            Some(length) => Type::new_array(elem_type, length, target),
+            None => Type::new_slice(elem_type, target),
+        });
+    }
+
+    if let Some((param_types, return_type)) = ast::split_function_type_name(&type_name_str) {
+        let mut arg_types = Vec::new();
+        for param_type in param_types {
+            arg_types.push(resolve_type_string(tld_map, param_type, target)?);
+        }
+
+        let return_type = resolve_type_string(tld_map, return_type, target)?;
+        return Ok(Type::new_function(return_type, arg_types));
+    }
+
+    if let Some((base_name, type_args)) = ast::split_generic_type_name(&type_name_str) {
+        return resolve_generic_struct(tld_map, type_name_str, &base_name, &type_args, target);
+    }
+
     match type_name_str.as_str() {
         "Int64" => Ok(Type::new_int64(target)),
         "Uint64" => Ok(Type::new_uint64(target)),
+        "Uint8" => Ok(Type::new_uint8(target)),
+        "Boolean" => Ok(Type::new_boolean(target)),
         "ConstStr" => Ok(Type::new_const_str(target)),
         "Noreturn" => Ok(Type::new_noreturn()),
         _ => {
@@ -269,6 +711,18 @@ fn resolve_type_string(
                 );
             }
 
+            // メインモジュールから他モジュールの型を参照した場合(`::x64::FileDescriptor`)
+            if let Some(other_module_type) = type_name_str.strip_prefix("::") {
+                if let Some(tld_entry) = tld_map.get(other_module_type) {
+                    return resolve_type_from_tld(
+                        other_module_type.to_string(),
+                        tld_map,
+                        tld_entry,
+                        target,
+                    );
+                }
+            }
+
             Err(CompileError::new(
                 TypeErrorKind::CannotResolve {
                     type_name: type_name_str,
@@ -279,6 +733,67 @@ fn resolve_type_string(
     }
 }
 
+/// `Pair[Int64]` のような型引数付きの型を解決する
+/// メンバの型パラメータを置換した構造体として扱い，型名には型引数を含めたものを用いる
+fn resolve_generic_struct(
+    tld_map: &BTreeMap<String, tld::TopLevelDecl>,
+    type_name_str: String,
+    base_name: &str,
+    type_args: &[String],
+    target: option::Target,
+) -> Result<Type, CompileError<TypeErrorKind>> {
+    let (type_params, members) = match tld_map.get(base_name).map(|entry| &entry.kind) {
+        Some(tld::TLDKind::GENERICSTRUCT {
+            type_params,
+            members,
+        }) => (type_params, members),
+        _ => {
+            return Err(CompileError::new(
+                TypeErrorKind::CannotResolve {
+                    type_name: type_name_str,
+                },
+                Default::default(),
+            ))
+        }
+    };
+
+    if type_params.len() != type_args.len() {
+        return Err(CompileError::new(
+            TypeErrorKind::WrongNumberOfTypeArguments {
+                name: base_name.to_string(),
+                expected: type_params.len(),
+                actual: type_args.len(),
+            },
+            Default::default(),
+        ));
+    }
+
+    let subst: BTreeMap<String, String> = type_params
+        .iter()
+        .cloned()
+        .zip(type_args.iter().cloned())
+        .collect();
+    let instance = tld::TopLevelDecl::new(tld::TLDKind::STRUCT {
+        members: members
+            .iter()
+            .map(|(member_n, member_t)| {
+                (
+                    member_n.to_string(),
+                    ast::substitute_type_string(member_t, &subst),
+                )
+            })
+            .collect(),
+        methods: BTreeMap::new(),
+    });
+
+    resolve_type_from_tld(type_name_str, tld_map, &instance, target)
+}
+
+/// offset を align の倍数に切り上げる
+fn align_to(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
+
 /// TopLevelDecl領域を探索して，対象の型を返す
 fn resolve_type_from_tld(
     type_name_str: String,
@@ -290,26 +805,63 @@ fn resolve_type_from_tld(
         tld::TLDKind::ALIAS { src_type } => {
             resolve_type_string(tld_map, src_type.to_string(), target)
         }
-        tld::TLDKind::STRUCT { members } => {
-            let mut member_types = BTreeMap::new();
+        tld::TLDKind::STRUCT {
+            members,
+            methods: _,
+        } => {
+            // Cと同様に，宣言順にアラインメントを揃えて並べる
+            let mut member_types = Vec::new();
             let mut total_size = 0;
+            let mut struct_align = 1;
 
             for (member_n, member_t) in members {
                 let member_type = resolve_type_string(tld_map, member_t.to_string(), target)?;
+                let member_align = member_type.alignment();
 
-                let member_offset = total_size;
-                total_size += member_type.size;
+                let member_offset = align_to(total_size, member_align);
+                total_size = member_offset + member_type.size;
+                struct_align = struct_align.max(member_align);
 
-                member_types.insert(member_n.to_string(), (Box::new(member_type), member_offset));
+                member_types.push((member_n.to_string(), (Box::new(member_type), member_offset)));
             }
 
-            Ok(Type::new_struct(member_types, total_size))
+            // 配列にしても各要素のアラインメントが保たれるよう，末尾を詰める
+            let total_size = align_to(total_size, struct_align);
+            Ok(Type::new_struct(type_name_str, member_types, total_size))
         }
         tld::TLDKind::ENUM { variants } => Ok(Type::new_enum(8)),
+        tld::TLDKind::INTERFACE { methods } => {
+            let mut method_types = Vec::new();
+
+            // BTreeMapなので，メソッド名順に並ぶ
+            for (method_name, (_args, return_type)) in methods {
+                let return_type = resolve_type_string(tld_map, return_type.to_string(), target)?;
+                method_types.push((method_name.to_string(), Box::new(return_type)));
+            }
+
+            Ok(Type::new_interface(type_name_str, method_types, target))
+        }
+        // 型引数なしでジェネリック構造体を使用した
+        tld::TLDKind::GENERICSTRUCT {
+            type_params,
+            members: _,
+        } => Err(CompileError::new(
+            TypeErrorKind::WrongNumberOfTypeArguments {
+                name: type_name_str,
+                expected: type_params.len(),
+                actual: 0,
+            },
+            Default::default(),
+        )),
         // 関数名だったときは何もしない．
         tld::TLDKind::FN {
             return_type: _,
             args: _,
+        }
+        | tld::TLDKind::GENERICFN {
+            type_params: _,
+            return_type: _,
+            args: _,
         } => Err(CompileError::new(
             TypeErrorKind::GotFunctionNameAsType {
                 func_name: type_name_str,
@@ -351,6 +903,12 @@ mod analyze_tests {
             "Uint64",
             option::Target::X86_64,
         );
+        check_types(
+            Type::new_boolean(Target::X86_64),
+            &m,
+            "Boolean",
+            option::Target::X86_64,
+        );
         check_types(
             Type::new_pointer(Type::new_int64(Target::X86_64), Target::X86_64),
             &m,
@@ -366,18 +924,17 @@ mod analyze_tests {
         );
         check_types(
             Type::new_struct(
-                {
-                    let mut mm = BTreeMap::new();
-                    mm.insert(
+                "S1".to_string(),
+                vec![
+                    (
                         "m1".to_string(),
                         (Box::new(Type::new_int64(Target::X86_64)), 0),
-                    );
-                    mm.insert(
+                    ),
+                    (
                         "m2".to_string(),
                         (Box::new(Type::new_uint64(Target::X86_64)), 8),
-                    );
-                    mm
-                },
+                    ),
+                ],
                 16,
             ),
             &m,
@@ -388,6 +945,182 @@ mod analyze_tests {
         assert!(resolve_type_string(&m, "T2".to_string(), option::Target::X86_64).is_err());
     }
 
+    #[test]
+    fn resolve_array_and_slice_test() {
+        let m = new_tld();
+
+        let bytes = resolve_type_string(&m, "[5]Uint8".to_string(), Target::X86_64).unwrap();
+        assert_eq!(
+            Type::new_array(Type::new_uint8(Target::X86_64), 5, Target::X86_64),
+            bytes
+        );
+        // 要素は詰めて並べ，全体はワード境界に揃える
+        assert_eq!(8, bytes.size);
+
+        let structs = resolve_type_string(&m, "[3]S1".to_string(), Target::X86_64).unwrap();
+        assert_eq!(48, structs.size);
+
+        let slice = resolve_type_string(&m, "[]*Int64".to_string(), Target::X86_64).unwrap();
+        assert!(slice.is_slice());
+        assert_eq!(16, slice.size);
+        assert!(resolve_type_string(&m, "[]T2".to_string(), Target::X86_64).is_err());
+    }
+
+    #[test]
+    fn resolve_struct_in_declaration_order_test() {
+        let mut m = new_tld();
+        m.insert(
+            "S2".to_string(),
+            TopLevelDecl::new(TLDKind::STRUCT {
+                members: vec![
+                    ("z".to_string(), "Int64".to_string()),
+                    ("inner".to_string(), "S1".to_string()),
+                    ("a".to_string(), "*Int64".to_string()),
+                ],
+                methods: BTreeMap::new(),
+            }),
+        );
+
+        let s2 = resolve_type_string(&m, "S2".to_string(), Target::X86_64).unwrap();
+        let offsets: Vec<(&str, usize)> = s2
+            .get_members()
+            .iter()
+            .map(|(member_name, (_, offset))| (member_name.as_str(), *offset))
+            .collect();
+
+        // 名前順ではなく，宣言順に並ぶ
+        assert_eq!(vec![("z", 0), ("inner", 8), ("a", 24)], offsets);
+        assert_eq!(32, s2.size);
+        assert_eq!(8, s2.alignment());
+
+        assert_eq!(0, align_to(0, 8));
+        assert_eq!(8, align_to(1, 8));
+        assert_eq!(16, align_to(16, 8));
+    }
+
+    #[test]
+    fn resolve_generic_struct_test() {
+        let m = new_tld();
+
+        check_types(
+            Type::new_struct(
+                "G1[Int64]".to_string(),
+                vec![
+                    (
+                        "first".to_string(),
+                        (Box::new(Type::new_int64(Target::X86_64)), 0),
+                    ),
+                    (
+                        "second".to_string(),
+                        (
+                            Box::new(Type::new_pointer(
+                                Type::new_int64(Target::X86_64),
+                                Target::X86_64,
+                            )),
+                            8,
+                        ),
+                    ),
+                ],
+                16,
+            ),
+            &m,
+            "G1[Int64]",
+            option::Target::X86_64,
+        );
+
+        // 型引数の数が合わない/型引数がない
+        assert!(resolve_type_string(&m, "G1[Int64,Int64]".to_string(), Target::X86_64).is_err());
+        assert!(resolve_type_string(&m, "G1".to_string(), Target::X86_64).is_err());
+        assert!(resolve_type_string(&m, "S1[Int64]".to_string(), Target::X86_64).is_err());
+    }
+
+    #[test]
+    fn resolve_function_type_test() {
+        let m = new_tld();
+
+        check_types(
+            Type::new_pointer(
+                Type::new_function(
+                    Type::new_int64(Target::X86_64),
+                    vec![
+                        Type::new_int64(Target::X86_64),
+                        Type::new_uint64(Target::X86_64),
+                    ],
+                ),
+                Target::X86_64,
+            ),
+            &m,
+            "*func(T1,Uint64)Int64",
+            option::Target::X86_64,
+        );
+    }
+
+    #[test]
+    fn variable_out_of_scope_test() {
+        // ブロック内で宣言した変数は，ブロックの外から見えない
+        let leaked = resolve_source(
+            "func main() Int64 { if (true) { varinit inner Int64 = 7; }; return inner; }",
+        );
+        assert!(matches!(
+            leaked.unwrap_err().get_kind(),
+            TypeErrorKind::UndefinedSuchAVariable { name } if name == "inner"
+        ));
+
+        // 宣言より前でも参照できない
+        assert!(resolve_source(
+            "func main() Int64 { varinit x Int64 = y; varinit y Int64 = 1; return x; }"
+        )
+        .is_err());
+
+        // 外側の変数やトップレベルの関数は参照できる
+        assert!(resolve_source(
+            "func f() Int64 { return 1; } func main() Int64 { varinit x Int64 = 1; if (true) { varinit y Int64 = x; }; varinit g *func() Int64 = &f; return x; }",
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn assignment_and_call_target_test() {
+        // const文で宣言した変数，トップレベルの定数には代入できない
+        let assigned = resolve_source("func main() Int64 { const y Int64 = 1; y = 2; return y; }");
+        assert!(matches!(
+            assigned.unwrap_err().get_kind(),
+            TypeErrorKind::AssignmentToConstant { name } if name == "y"
+        ));
+        let assigned = resolve_source(
+            "pubconst LIMIT : Int64 = 1; func main() Int64 { LIMIT = 2; return 0; }",
+        );
+        assert!(matches!(
+            assigned.unwrap_err().get_kind(),
+            TypeErrorKind::AssignmentToConstant { name } if name == "LIMIT"
+        ));
+
+        // モジュールで修飾した呼び出しは，そのモジュールの関数でなければならない
+        let called =
+            resolve_source("func f() Int64 { return 1; } func main() Int64 { return x64::f(); }");
+        assert!(matches!(
+            called.unwrap_err().get_kind(),
+            TypeErrorKind::UndefinedSuchAFunction { name } if name == "x64::f"
+        ));
+        assert!(resolve_source(
+            "func f() Int64 { return 1; } func main() Int64 { varinit y Int64 = 1; y = f(); return y; }"
+        )
+        .is_ok());
+    }
+
+    fn resolve_source(
+        source: &str,
+    ) -> Result<(TypeEnv, frame_object::ScopeEnv), CompileError<TypeErrorKind>> {
+        let fn_arena: ast::FnArena = std::sync::Arc::new(std::sync::Mutex::new(Default::default()));
+        let tokens = crate::common::pass::tokenizer::main(source.to_string());
+        let ast_root =
+            crate::common::pass::parser::parse(fn_arena.clone(), tokens, String::new()).unwrap();
+        let tld_map =
+            crate::common::pass::tld_collector::main(fn_arena.clone(), &ast_root).unwrap();
+
+        type_resolve_main(fn_arena, &tld_map, &ast_root, Target::X86_64)
+    }
+
     fn check_types(
         expected: Type,
         m: &BTreeMap<String, tld::TopLevelDecl>,
@@ -412,12 +1145,21 @@ mod analyze_tests {
         m.insert(
             "S1".to_string(),
             TopLevelDecl::new(TLDKind::STRUCT {
-                members: {
-                    let mut mm = BTreeMap::new();
-                    mm.insert("m1".to_string(), "Int64".to_string());
-                    mm.insert("m2".to_string(), "Uint64".to_string());
-                    mm
-                },
+                members: vec![
+                    ("m1".to_string(), "Int64".to_string()),
+                    ("m2".to_string(), "Uint64".to_string()),
+                ],
+                methods: BTreeMap::new(),
+            }),
+        );
+        m.insert(
+            "G1".to_string(),
+            TopLevelDecl::new(TLDKind::GENERICSTRUCT {
+                type_params: vec!["T".to_string()],
+                members: vec![
+                    ("first".to_string(), "T".to_string()),
+                    ("second".to_string(), "*T".to_string()),
+                ],
             }),
         );
 
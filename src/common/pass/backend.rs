@@ -16,7 +16,11 @@ pub fn backend(
     tac::IRModule,
     BTreeMap<tac::IRFunctionId, ar::cfg::LocalControlFlowGraph>,
 ) {
-    let ir_module = pass::translate_ir(fn_arena, ast_root, type_env, target, entry_point);
+    let mut ir_module = pass::translate_ir(fn_arena, ast_root, type_env, target, entry_point);
+
+    // 確実に代入される変数の，不要な0初期化を取り除く
+    let local_cfg = pass::build_local_cfg(&ir_module);
+    pass::definite_assignment_main(&mut ir_module, &local_cfg);
 
     if verbose_ir {
         eprintln!("{}", "dump HIR to 'hir_dump'...".bold().blue());
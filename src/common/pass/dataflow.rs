@@ -0,0 +1,2 @@
+mod definite_assignment;
+pub use definite_assignment::*;
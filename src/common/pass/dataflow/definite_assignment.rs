@@ -0,0 +1,540 @@
+use crate::common::analyze_resource::peachili_type::Type;
+use crate::common::cfg::LocalControlFlowGraph;
+use crate::common::three_address_code as tac;
+use std::collections::{BTreeMap, BTreeSet};
+use tac::CodeKind;
+
+/// 変数名の集合
+type VarSet = BTreeSet<String>;
+
+/// 定義代入解析
+/// すべての経路で書き込まれることが証明できた変数のZEROINITを取り除く
+/// 代入前の読み出しはフロントエンドで報告済みなので，ここでは0初期化を残すだけにする
+pub fn definite_assignment_main(
+    ir_module: &mut tac::IRModule,
+    local_cfg: &BTreeMap<tac::IRFunctionId, LocalControlFlowGraph>,
+) {
+    for fn_id in ir_module.funcs.clone().iter() {
+        let ir_fn = ir_module.fn_allocator.get_mut(*fn_id).unwrap();
+        let cfg = local_cfg.get(fn_id).unwrap();
+
+        let needs_zeroinit = analyze_fn(ir_fn, cfg);
+        remove_unneeded_zeroinit(ir_fn, &needs_zeroinit);
+    }
+}
+
+/// 関数内の解析を行い，0初期化が必要な変数の集合を返す
+fn analyze_fn(ir_fn: &tac::IRFunction, cfg: &LocalControlFlowGraph) -> VarSet {
+    let analyzer = Analyzer::new(ir_fn);
+
+    // 解析対象の変数がなければ何もしない
+    if analyzer.var_sizes.is_empty() {
+        return VarSet::new();
+    }
+
+    let in_sets = analyzer.solve(cfg);
+
+    let mut needs_zeroinit = VarSet::new();
+    for code_id in ir_fn.codes.iter() {
+        // 到達しないコードは検査しない
+        if let Some(Some(in_set)) = in_sets.get(code_id) {
+            let mut state = in_set.clone();
+            analyzer.transfer(code_id, &mut state, &mut needs_zeroinit);
+        }
+    }
+
+    needs_zeroinit
+}
+
+fn remove_unneeded_zeroinit(ir_fn: &mut tac::IRFunction, needs_zeroinit: &VarSet) {
+    let code_arena = ir_fn.code_allocator.clone();
+    let value_arena = ir_fn.value_allocator.clone();
+
+    ir_fn.codes.retain(|code_id| {
+        let code = code_arena.lock().unwrap().get(*code_id).unwrap().clone();
+        match code.kind {
+            CodeKind::ZEROINIT { value } => {
+                let name = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(value)
+                    .unwrap()
+                    .copy_contents();
+                needs_zeroinit.contains(&name)
+            }
+            _ => true,
+        }
+    });
+}
+
+/// 1回のSTOREで全体が書き込まれる型か
+fn is_scalar_type(ty: &Type) -> bool {
+    !ty.is_struct() && !ty.is_interface() && !ty.is_array() && !ty.is_slice()
+}
+
+struct Analyzer<'a> {
+    ir_fn: &'a tac::IRFunction,
+    /// 解析対象(declareされた変数)の変数名 => サイズ
+    var_sizes: BTreeMap<String, usize>,
+    /// 変数名 => スカラ型かどうか
+    var_is_scalar: BTreeMap<String, bool>,
+    /// 変数のアドレスを保持するTEMP => (変数名, 先頭アドレスかどうか)
+    addr_of: BTreeMap<tac::ValueId, (String, bool)>,
+}
+
+impl<'a> Analyzer<'a> {
+    fn new(ir_fn: &'a tac::IRFunction) -> Self {
+        let mut analyzer = Self {
+            ir_fn,
+            var_sizes: BTreeMap::new(),
+            var_is_scalar: BTreeMap::new(),
+            addr_of: BTreeMap::new(),
+        };
+
+        for code_id in ir_fn.codes.iter() {
+            if let CodeKind::ZEROINIT { value } = ir_fn.get_code(*code_id).kind {
+                let var = ir_fn.get_value(value);
+                let name = var.copy_contents();
+                analyzer
+                    .var_is_scalar
+                    .insert(name.clone(), is_scalar_type(&var.ty));
+                analyzer.var_sizes.insert(name, var.ty.size);
+            }
+        }
+
+        // TEMPは一度しか定義されないので，コード順に辿ればよい
+        for code_id in ir_fn.codes.iter() {
+            match ir_fn.get_code(*code_id).kind {
+                CodeKind::ADDRESSOF { value, result } => {
+                    if let Some(name) = analyzer.subject_var(value) {
+                        analyzer.addr_of.insert(result, (name, true));
+                    }
+                }
+                CodeKind::ADD {
+                    lop,
+                    rop: _,
+                    result,
+                } => {
+                    if let Some((name, _)) = analyzer.addr_of.get(&lop).cloned() {
+                        analyzer.addr_of.insert(result, (name, false));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        analyzer
+    }
+
+    /// 各コードの入口で確実に代入済みである変数の集合を求める
+    /// Noneは未到達(すべての変数が代入済みとみなす)を表す
+    fn solve(&self, cfg: &LocalControlFlowGraph) -> BTreeMap<tac::CodeId, Option<VarSet>> {
+        // 先行節は後続節から逆算する
+        let mut predecessors: BTreeMap<tac::CodeId, BTreeSet<tac::CodeId>> = BTreeMap::new();
+        for (src, dsts) in cfg.successors.iter() {
+            for dst in dsts.iter() {
+                predecessors.entry(*dst).or_default().insert(*src);
+            }
+        }
+
+        let mut in_sets: BTreeMap<tac::CodeId, Option<VarSet>> = BTreeMap::new();
+        let mut out_sets: BTreeMap<tac::CodeId, Option<VarSet>> = BTreeMap::new();
+        for code_id in self.ir_fn.codes.iter() {
+            in_sets.insert(*code_id, None);
+            out_sets.insert(*code_id, None);
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for (idx, code_id) in self.ir_fn.codes.iter().enumerate() {
+                let in_set = if idx == 0 {
+                    Some(VarSet::new())
+                } else {
+                    self.meet(predecessors.get(code_id), &out_sets)
+                };
+
+                let out_set = in_set.clone().map(|mut state| {
+                    // 0初期化の要否は最終的な入口集合を用いて求める
+                    let mut escaped = VarSet::new();
+                    self.transfer(code_id, &mut state, &mut escaped);
+                    state
+                });
+
+                if out_sets.get(code_id).unwrap() != &out_set {
+                    changed = true;
+                }
+                in_sets.insert(*code_id, in_set);
+                out_sets.insert(*code_id, out_set);
+            }
+        }
+
+        in_sets
+    }
+
+    /// 先行節の出口集合の積を取る
+    fn meet(
+        &self,
+        predecessors: Option<&BTreeSet<tac::CodeId>>,
+        out_sets: &BTreeMap<tac::CodeId, Option<VarSet>>,
+    ) -> Option<VarSet> {
+        let mut result: Option<VarSet> = None;
+
+        for pred in predecessors.into_iter().flatten() {
+            if let Some(out_set) = out_sets.get(pred).unwrap() {
+                result = match result {
+                    Some(acc) => Some(acc.intersection(out_set).cloned().collect()),
+                    None => Some(out_set.clone()),
+                };
+            }
+        }
+
+        result
+    }
+
+    /// 1コード分の状態遷移
+    /// 代入前に読まれうる変数や，書き込みより先にアドレスが漏れた変数は，needs_zeroinitに加える
+    fn transfer(&self, code_id: &tac::CodeId, state: &mut VarSet, needs_zeroinit: &mut VarSet) {
+        match self.ir_fn.get_code(*code_id).kind {
+            CodeKind::ZEROINIT { value } => {
+                state.remove(&self.ir_fn.get_value(value).copy_contents());
+            }
+            CodeKind::ADD {
+                lop,
+                rop,
+                result: _,
+            }
+            | CodeKind::SUB {
+                lop,
+                rop,
+                result: _,
+            }
+            | CodeKind::MUL {
+                lop,
+                rop,
+                result: _,
+            }
+            | CodeKind::DIV {
+                lop,
+                rop,
+                result: _,
+            } => {
+                // 変数アドレスへのオフセット加算は読み出しではない
+                if !self.addr_of.contains_key(&lop) {
+                    self.use_value(lop, state, needs_zeroinit);
+                }
+                self.use_value(rop, state, needs_zeroinit);
+            }
+            CodeKind::ASSIGN { value, result } => {
+                self.use_value(value, state, needs_zeroinit);
+                if let Some(name) = self.subject_var(result) {
+                    state.insert(name);
+                }
+            }
+            CodeKind::STORE { value, result } => {
+                self.use_value(value, state, needs_zeroinit);
+                let is_whole = self
+                    .addr_of
+                    .get(&result)
+                    .is_some_and(|(name, _)| self.var_is_scalar[name]);
+                self.write_through(result, is_whole, state, needs_zeroinit);
+            }
+            CodeKind::COPY { src, dst, size } => {
+                self.read_through(src, state, needs_zeroinit);
+                let is_whole = self
+                    .addr_of
+                    .get(&dst)
+                    .is_some_and(|(name, _)| self.var_sizes.get(name) == Some(&size));
+                self.write_through(dst, is_whole, state, needs_zeroinit);
+            }
+            CodeKind::STOREBYTE { value, addr } => {
+                self.use_value(value, state, needs_zeroinit);
+                self.write_through(addr, false, state, needs_zeroinit);
+            }
+            CodeKind::BOUNDSCHECK {
+                index,
+                len,
+                inclusive: _,
+            } => {
+                self.use_value(index, state, needs_zeroinit);
+                self.use_value(len, state, needs_zeroinit);
+            }
+            CodeKind::DEREFERENCE { value, result: _ }
+            | CodeKind::LOADBYTE {
+                addr: value,
+                result: _,
+            } => {
+                if self.addr_of.contains_key(&value) {
+                    self.read_through(value, state, needs_zeroinit);
+                } else {
+                    self.use_value(value, state, needs_zeroinit);
+                }
+            }
+            CodeKind::VCALL {
+                iface,
+                index: _,
+                result: _,
+            } => self.read_through(iface, state, needs_zeroinit),
+            CodeKind::NEG { value, result: _ }
+            | CodeKind::RETURN { value }
+            | CodeKind::PARAM { value }
+            | CodeKind::MEMBER {
+                id: value,
+                member: _,
+                result: _,
+            }
+            | CodeKind::ICALL {
+                callee: value,
+                result: _,
+            }
+            | CodeKind::JUMPIFFALSE {
+                label: _,
+                cond_result: value,
+            } => self.use_value(value, state, needs_zeroinit),
+            _ => {}
+        }
+    }
+
+    /// オペランドとしての使用
+    /// 変数そのものであれば読み出し，変数のアドレスであればアドレスの流出として扱う
+    fn use_value(&self, value: tac::ValueId, state: &mut VarSet, needs_zeroinit: &mut VarSet) {
+        if let Some(name) = self.subject_var(value) {
+            self.check_assigned(&name, state, needs_zeroinit);
+            return;
+        }
+
+        if let Some((name, _)) = self.addr_of.get(&value) {
+            // 以降どこで読み書きされるか追えないので，0初期化しておく
+            if !state.contains(name) {
+                needs_zeroinit.insert(name.clone());
+                state.insert(name.clone());
+            }
+        }
+    }
+
+    /// アドレスを介した読み出し
+    fn read_through(&self, addr: tac::ValueId, state: &VarSet, needs_zeroinit: &mut VarSet) {
+        if let Some((name, _)) = self.addr_of.get(&addr) {
+            self.check_assigned(name, state, needs_zeroinit);
+        }
+    }
+
+    /// アドレスを介した書き込み
+    /// is_wholeは，先頭アドレスからの書き込みが変数全体を覆うかどうか
+    /// 変数全体への書き込みでなければ，残りの領域を0初期化しておく
+    fn write_through(
+        &self,
+        addr: tac::ValueId,
+        is_whole: bool,
+        state: &mut VarSet,
+        needs_zeroinit: &mut VarSet,
+    ) {
+        if let Some((name, is_base)) = self.addr_of.get(&addr) {
+            let full_write = *is_base && is_whole;
+            if !full_write && !state.contains(name) {
+                needs_zeroinit.insert(name.clone());
+            }
+            state.insert(name.clone());
+        }
+    }
+
+    /// 代入前に読まれうる変数は，0初期化を残しておく
+    fn check_assigned(&self, name: &str, state: &VarSet, needs_zeroinit: &mut VarSet) {
+        if !state.contains(name) {
+            needs_zeroinit.insert(name.to_string());
+        }
+    }
+
+    /// 解析対象の変数であれば，その名前を返す
+    fn subject_var(&self, value: tac::ValueId) -> Option<String> {
+        let value = self.ir_fn.get_value(value);
+        if !value.is_id() {
+            return None;
+        }
+
+        let name = value.copy_contents();
+        if self.var_sizes.contains_key(&name) {
+            Some(name)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod definite_assignment_tests {
+    use super::*;
+    use crate::common::option::Target;
+    use crate::common::pass::build_local_cfg;
+    use crate::common::peachili_type::Type;
+    use id_arena::Arena;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn remove_zeroinit_of_assigned_var_test() {
+        // declare a Int64; a = 1; return a;
+        let mut ir_fn = new_ir_function();
+        let a = new_id(&ir_fn, "a", Type::new_int64(Target::X86_64));
+        let addr = new_temp(
+            &ir_fn,
+            0,
+            Type::new_pointer(Type::new_int64(Target::X86_64), Target::X86_64),
+        );
+        let one = new_value(&ir_fn, tac::Value::new_int64(1, Target::X86_64));
+        add_code(&mut ir_fn, CodeKind::ZEROINIT { value: a });
+        add_code(
+            &mut ir_fn,
+            CodeKind::ADDRESSOF {
+                value: a,
+                result: addr,
+            },
+        );
+        add_code(
+            &mut ir_fn,
+            CodeKind::STORE {
+                value: one,
+                result: addr,
+            },
+        );
+        add_code(&mut ir_fn, CodeKind::RETURN { value: a });
+
+        let needs_zeroinit = analyze(&ir_fn);
+        assert_eq!(VarSet::new(), needs_zeroinit);
+    }
+
+    #[test]
+    fn keep_zeroinit_of_partially_assigned_struct_test() {
+        // declare s S; s.x = 1; return s.y;
+        let mut ir_fn = new_ir_function();
+        let st_type = new_pair_type();
+        let s = new_id(&ir_fn, "s", st_type.clone());
+        let base = new_temp(&ir_fn, 0, Type::new_pointer(st_type, Target::X86_64));
+        let member = new_temp(&ir_fn, 1, Type::new_int64(Target::X86_64));
+        let zero = new_value(&ir_fn, tac::Value::new_int64(0, Target::X86_64));
+        let one = new_value(&ir_fn, tac::Value::new_int64(1, Target::X86_64));
+        let loaded = new_temp(&ir_fn, 2, Type::new_int64(Target::X86_64));
+        add_code(&mut ir_fn, CodeKind::ZEROINIT { value: s });
+        add_code(
+            &mut ir_fn,
+            CodeKind::ADDRESSOF {
+                value: s,
+                result: base,
+            },
+        );
+        add_code(
+            &mut ir_fn,
+            CodeKind::ADD {
+                lop: base,
+                rop: zero,
+                result: member,
+            },
+        );
+        add_code(
+            &mut ir_fn,
+            CodeKind::STORE {
+                value: one,
+                result: member,
+            },
+        );
+        add_code(
+            &mut ir_fn,
+            CodeKind::MEMBER {
+                id: s,
+                member: "y".to_string(),
+                result: loaded,
+            },
+        );
+        add_code(&mut ir_fn, CodeKind::RETURN { value: loaded });
+
+        let needs_zeroinit = analyze(&ir_fn);
+        assert_eq!(
+            vec!["s".to_string()].into_iter().collect::<VarSet>(),
+            needs_zeroinit
+        );
+    }
+
+    #[test]
+    fn keep_zeroinit_of_unassigned_var_test() {
+        // declare a#1 Int64; return a#1;
+        let mut ir_fn = new_ir_function();
+        let a = new_id(&ir_fn, "a#1", Type::new_int64(Target::X86_64));
+        add_code(&mut ir_fn, CodeKind::ZEROINIT { value: a });
+        add_code(&mut ir_fn, CodeKind::RETURN { value: a });
+
+        let needs_zeroinit = analyze(&ir_fn);
+        assert_eq!(
+            vec!["a#1".to_string()].into_iter().collect::<VarSet>(),
+            needs_zeroinit
+        );
+
+        // モジュール全体の解析でも，ZEROINITは取り除かれない
+        let mut ir_module: tac::IRModule = Default::default();
+        let fn_id = ir_module.fn_allocator.alloc(ir_fn);
+        ir_module.funcs.push(fn_id);
+        let local_cfg = build_local_cfg(&ir_module);
+        definite_assignment_main(&mut ir_module, &local_cfg);
+        assert_eq!(2, ir_module.fn_allocator.get(fn_id).unwrap().codes.len());
+    }
+
+    fn analyze(ir_fn: &tac::IRFunction) -> VarSet {
+        let mut ir_module: tac::IRModule = Default::default();
+        let fn_id = ir_module.fn_allocator.alloc(ir_fn.clone());
+        ir_module.funcs.push(fn_id);
+
+        let local_cfg = build_local_cfg(&ir_module);
+        analyze_fn(ir_fn, local_cfg.get(&fn_id).unwrap())
+    }
+
+    fn new_ir_function() -> tac::IRFunction {
+        tac::IRFunction {
+            name: "main".to_string(),
+            fn_ty: Type::new_function(Type::new_int64(Target::X86_64), Vec::new()),
+            codes: Vec::new(),
+            value_allocator: Arc::new(Mutex::new(Arena::new())),
+            code_allocator: Arc::new(Mutex::new(Arena::new())),
+            args: Vec::new(),
+        }
+    }
+
+    fn new_pair_type() -> Type {
+        let int64 = Box::new(Type::new_int64(Target::X86_64));
+        Type::new_struct(
+            "Pair".to_string(),
+            vec![
+                ("x".to_string(), (int64.clone(), 0)),
+                ("y".to_string(), (int64, 8)),
+            ],
+            16,
+        )
+    }
+
+    fn new_value(ir_fn: &tac::IRFunction, v: tac::Value) -> tac::ValueId {
+        ir_fn.value_allocator.lock().unwrap().alloc(v)
+    }
+
+    fn new_id(ir_fn: &tac::IRFunction, name: &str, ty: Type) -> tac::ValueId {
+        new_value(
+            ir_fn,
+            tac::Value::new(
+                tac::ValueKind::ID {
+                    name: name.to_string(),
+                },
+                ty,
+            ),
+        )
+    }
+
+    fn new_temp(ir_fn: &tac::IRFunction, number: usize, ty: Type) -> tac::ValueId {
+        new_value(ir_fn, tac::Value::new_temp(number, ty))
+    }
+
+    fn add_code(ir_fn: &mut tac::IRFunction, kind: CodeKind) {
+        let code_id = ir_fn
+            .code_allocator
+            .lock()
+            .unwrap()
+            .alloc(tac::Code { kind });
+        ir_fn.codes.push(code_id);
+    }
+}
@@ -1,5 +1,6 @@
-use crate::common::pass::{analyzer, parser, tld_collector, tokenizer};
-use crate::common::{ast, file_util, frame_object, module, peachili_type};
+use crate::common::pass::{analyzer, parser, test_harness, tld_collector, tokenizer};
+use crate::common::token::TokenKind;
+use crate::common::{ast, file_util, frame_object, module, peachili_type, tld};
 use crate::setup;
 use id_arena::Arena;
 use std::collections::BTreeMap;
@@ -23,54 +24,216 @@ pub fn frontend(
     BTreeMap<String, BTreeMap<String, peachili_type::Type>>,
     frame_object::StackFrame,
 ) {
-    let mut manager = FrontendManager {
-        module_arena,
-        fn_arena: Arc::new(Mutex::new(Arena::new())),
-        full_ast: Default::default(),
-    };
+    FrontendManager::parse_all(module_arena, main_module_id).analyze(debug)
+}
 
-    let source = manager.read_module_contents(main_module_id);
+/// `peachili test` 用のフロントエンド
+/// `#[test]` 関数を検査し，それらを実行するハーネスを `startup` モジュールに加えてから解析する
+pub fn test_frontend(
+    module_arena: module::ModuleArena,
+    main_module_id: module::ModuleId,
+) -> (
+    ast::FnArena,
+    ast::ASTRoot,
+    BTreeMap<String, BTreeMap<String, peachili_type::Type>>,
+    frame_object::StackFrame,
+) {
+    let mut manager = FrontendManager::parse_all(module_arena, main_module_id);
+
+    if let Err(e) = analyzer::test_function_check(manager.fn_arena.clone(), &manager.full_ast) {
+        e.output();
+        std::process::exit(1);
+    }
+
+    let harness = test_harness::generate_test_harness(&manager.full_ast.tests);
+    manager.parse_file(harness, "startup".to_string());
 
-    // 初期値として空のStringを渡しておく
-    manager.parse_file(source, String::new());
+    manager.analyze(false)
+}
 
-    // メインモジュールが参照する各モジュールも同様にパース
-    manager.parse_requires(main_module_id, String::new());
+/// 字句解析からTLD解析までを行う
+/// ドキュメント生成のように，宣言だけが必要な場合に用いる
+pub fn collect_declarations(
+    module_arena: module::ModuleArena,
+    main_module_id: module::ModuleId,
+) -> (
+    ast::FnArena,
+    ast::ASTRoot,
+    BTreeMap<String, tld::TopLevelDecl>,
+) {
+    let manager = FrontendManager::parse_all(module_arena, main_module_id);
 
-    // ASTレベルのconstant-folding
     analyzer::constant_folding(manager.fn_arena.clone(), &manager.full_ast);
+    let tld_env = match tld_collector::main(manager.fn_arena.clone(), &manager.full_ast) {
+        Ok(tld_env) => tld_env,
+        Err(e) => {
+            e.output();
+            std::process::exit(1);
+        }
+    };
+
+    (manager.fn_arena, manager.full_ast, tld_env)
+}
+
+/// メインモジュールと，それが参照するすべてのモジュールのファイルを列挙する
+/// `(モジュール名, ファイルパス)` の組で，メインモジュールのモジュール名は空文字列
+pub fn module_sources(
+    module_arena: module::ModuleArena,
+    main_module_id: module::ModuleId,
+) -> Vec<(String, String)> {
+    let arena = module_arena.lock().unwrap();
+    let mut sources = vec![(
+        String::new(),
+        arena.get(main_module_id).unwrap().copy_path(),
+    )];
+
+    collect_module_sources(&arena, main_module_id, String::new(), &mut sources);
+    sources
+}
+
+/// 1つのファイルの内容を，メインモジュールとして字句解析/パースする
+/// インポート宣言はASTに残らないので，インポートしたモジュール名も記述順に返す
+pub fn parse_source(source: String) -> (ast::FnArena, ast::ASTRoot, Vec<String>) {
+    let tokens = tokenizer::main(source);
+    let imports = tokens
+        .windows(2)
+        .filter_map(|pair| match (pair[0].get_kind(), pair[1].get_kind()) {
+            (TokenKind::IMPORT, TokenKind::IDENTIFIER { name }) => Some(name.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let fn_arena: ast::FnArena = Arc::new(Mutex::new(Arena::new()));
+    let ast_root = parser::main(fn_arena.clone(), tokens, String::new());
+    (fn_arena, ast_root, imports)
+}
+
+/// パースと同じ順序でモジュールを辿る
+fn collect_module_sources(
+    arena: &Arena<module::Module>,
+    mod_id: module::ModuleId,
+    module_name: String,
+    sources: &mut Vec<(String, String)>,
+) {
+    let m = arena.get(mod_id).unwrap();
+    let refs = m.refs.lock().unwrap().clone();
+    let children = m.children.lock().unwrap().clone();
 
-    // TLD解析
-    let tld_env = tld_collector::main(manager.fn_arena.clone(), &manager.full_ast);
-
-    // 意味解析
-    // 先に型環境を構築してから，型検査を行う
-    let type_env = analyzer::type_resolve_main(
-        manager.fn_arena.clone(),
-        &tld_env,
-        &manager.full_ast,
-        setup::BUILD_OPTION.target,
-    );
-
-    if debug {
-        analyzer::type_check_main(
-            manager.fn_arena.clone(),
+    for ext_id in refs.iter().chain(children.iter()) {
+        let ext_module = arena.get(*ext_id).unwrap();
+        let mut ext_name = module_name.clone();
+        construct_full_path(&mut ext_name, ext_module.copy_name());
+
+        if ext_module.child_count() == 0 {
+            sources.push((ext_name.clone(), ext_module.copy_path()));
+        }
+        collect_module_sources(arena, *ext_id, ext_name, sources);
+    }
+}
+
+impl FrontendManager {
+    /// メインモジュールと，それが参照するすべてのモジュールをパースする
+    fn parse_all(module_arena: module::ModuleArena, main_module_id: module::ModuleId) -> Self {
+        let mut manager = FrontendManager {
+            module_arena,
+            fn_arena: Arc::new(Mutex::new(Arena::new())),
+            full_ast: Default::default(),
+        };
+
+        let source = manager.read_module_contents(main_module_id);
+
+        // 初期値として空のStringを渡しておく
+        manager.parse_file(source, String::new());
+
+        // メインモジュールが参照する各モジュールも同様にパース
+        manager.parse_requires(main_module_id, String::new());
+
+        manager
+    }
+
+    /// パース済みのASTに対して，constant-foldingからスタック割付までを行う
+    fn analyze(
+        mut self,
+        debug: bool,
+    ) -> (
+        ast::FnArena,
+        ast::ASTRoot,
+        BTreeMap<String, BTreeMap<String, peachili_type::Type>>,
+        frame_object::StackFrame,
+    ) {
+        // ASTレベルのconstant-folding
+        analyzer::constant_folding(self.fn_arena.clone(), &self.full_ast);
+
+        // TLD解析
+        let mut tld_env = match tld_collector::main(self.fn_arena.clone(), &self.full_ast) {
+            Ok(tld_env) => tld_env,
+            Err(e) => {
+                e.output();
+                std::process::exit(1);
+            }
+        };
+
+        // ジェネリック関数を，呼び出し側の型引数ごとに実体化
+        analyzer::instantiate_generic_functions(
+            self.fn_arena.clone(),
+            &mut tld_env,
+            &mut self.full_ast,
+        );
+
+        // 意味解析
+        // 先に型環境を構築してから，型検査を行う
+        let (type_env, scope_env) = match analyzer::type_resolve_main(
+            self.fn_arena.clone(),
+            &tld_env,
+            &self.full_ast,
+            setup::BUILD_OPTION.target,
+        ) {
+            Ok(envs) => envs,
+            Err(e) => {
+                e.output();
+                std::process::exit(1);
+            }
+        };
+
+        // インタフェースへの暗黙の変換はコード生成に関わるため，常に検査する
+        analyzer::interface_check_main(
+            self.fn_arena.clone(),
             &tld_env,
             &type_env,
-            &manager.full_ast,
+            &self.full_ast,
             setup::BUILD_OPTION.target,
         );
-    }
 
-    // スタック割付
-    // 通常はローカル変数をすべてスタックに．
-    // 最適化を有効化にしたらレジスタ割付したい
-    let func_frame = analyzer::allocate_stack_frame(&tld_env, &type_env);
+        if debug {
+            if let Err(e) = analyzer::type_check_main(
+                self.fn_arena.clone(),
+                &tld_env,
+                &type_env,
+                &self.full_ast,
+                setup::BUILD_OPTION.target,
+            ) {
+                e.output();
+                std::process::exit(1);
+            }
+        }
 
-    (manager.fn_arena, manager.full_ast, type_env, func_frame)
-}
+        // 代入前の読み出しは型検査では見つからないので，コンパイル時にも検査する
+        if let Err(e) =
+            analyzer::definite_assignment_check(self.fn_arena.clone(), &type_env, &self.full_ast)
+        {
+            e.output();
+            std::process::exit(1);
+        }
+
+        // スタック割付
+        // 通常はローカル変数をすべてスタックに．
+        // 最適化を有効化にしたらレジスタ割付したい
+        // 互いに重ならないスコープの変数は同じ領域を使う
+        let func_frame = analyzer::allocate_stack_frame(&tld_env, &type_env, &scope_env);
+
+        (self.fn_arena, self.full_ast, type_env, func_frame)
+    }
 
-impl FrontendManager {
     /// モジュールの内容(Peachiliコード)を読み出す
     fn read_module_contents(&self, module_id: module::ModuleId) -> String {
         if let Ok(arena) = self.module_arena.lock() {
@@ -171,6 +334,8 @@ fn construct_full_path(full_path: &mut String, module_name: String) {
 #[cfg(test)]
 mod frontend_tests {
     use super::*;
+    use crate::common::option::Target;
+    use crate::fuzz;
 
     #[test]
     fn construct_full_path_test() {
@@ -182,4 +347,35 @@ mod frontend_tests {
         construct_full_path(&mut s2, "os".to_string());
         assert_eq!("std::os", s2);
     }
+
+    /// ファザが生成するプログラムは，型検査まで通らなければならない
+    /// 通常のコンパイルでは型検査を省略するので，ここで直接確かめる
+    #[test]
+    fn generated_programs_pass_type_check_test() {
+        let target = Target::X86_64;
+
+        for seed in 0..50 {
+            let fn_arena: ast::FnArena = Arc::new(Mutex::new(Arena::new()));
+            let tokens = tokenizer::main(fuzz::generate_program(seed));
+            let mut ast_root = parser::parse(fn_arena.clone(), tokens, String::new())
+                .unwrap_or_else(|e| panic!("seed {}: {}", seed, e.get_kind()));
+
+            analyzer::constant_folding(fn_arena.clone(), &ast_root);
+            let mut tld_env = tld_collector::main(fn_arena.clone(), &ast_root)
+                .unwrap_or_else(|e| panic!("seed {}: {}", seed, e.get_kind()));
+            analyzer::instantiate_generic_functions(fn_arena.clone(), &mut tld_env, &mut ast_root);
+
+            let (type_env, _scope_env) =
+                analyzer::type_resolve_main(fn_arena.clone(), &tld_env, &ast_root, target)
+                    .unwrap_or_else(|e| panic!("seed {}: {}", seed, e.get_kind()));
+            let result =
+                analyzer::type_check_main(fn_arena.clone(), &tld_env, &type_env, &ast_root, target);
+            assert!(
+                result.is_ok(),
+                "seed {}: {}",
+                seed,
+                result.unwrap_err().get_kind()
+            );
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use crate::common::{
-    ast::{ExNodeId, ExpressionNode},
+    ast::{generic_instance_name, ExNodeId, ExpressionNode, INDEX_OUT_OF_RANGE_ROUTINE},
+    position::Position,
     token::{Token, TokenKind},
 };
 
@@ -129,6 +130,11 @@ impl Context {
             TokenKind::AMPERSAND => {
                 parser_util::eat_token(&mut tokens);
                 let (value, rest_tokens) = self.prefix(tokens);
+
+                // 関数のアドレスを取る場合に備え，参照された関数としても登録しておく
+                if let Some(names) = self.identifier_names(value) {
+                    self.called_functions.insert(names.join("::"));
+                }
                 (
                     self.expr_arena
                         .lock()
@@ -161,6 +167,7 @@ impl Context {
     }
 
     /// postfix -> primary (postfix_op postfix)*
+    /// postfix_op -> `.` identifier | `.` identifier `(` call_arguments | `[` expression `]` | `[` expression? `:` expression? `]`
     fn postfix(&mut self, tokens: Vec<Token>) -> (ExNodeId, Vec<Token>) {
         let (mut value, mut rest_tokens) = self.primary(tokens);
 
@@ -175,6 +182,24 @@ impl Context {
                     let (names, rk) = parser_util::expect_identifier(rest_tokens);
                     rest_tokens = rk;
 
+                    // メソッド呼び出し
+                    if parser_util::consume(TokenKind::LPAREN, &mut rest_tokens) {
+                        let (args, rk) = self.call_arguments(rest_tokens);
+                        rest_tokens = rk;
+
+                        value =
+                            self.expr_arena
+                                .lock()
+                                .unwrap()
+                                .alloc(ExpressionNode::new_method_call(
+                                    value,
+                                    names.join("::"),
+                                    args,
+                                    postfix_pos,
+                                ));
+                        continue;
+                    }
+
                     value = self
                         .expr_arena
                         .lock()
@@ -186,13 +211,59 @@ impl Context {
                             postfix_pos,
                         ));
                 }
+                TokenKind::LBRACKET => {
+                    parser_util::eat_token(&mut rest_tokens);
+
+                    // 添字/スライスは範囲外で中断ルーチンを呼び出しうる
+                    self.called_functions
+                        .insert(INDEX_OUT_OF_RANGE_ROUTINE.to_string());
+
+                    let (low, rk) = self.optional_slice_bound(rest_tokens);
+                    rest_tokens = rk;
+
+                    // a[lo:hi] のようなスライス式
+                    if parser_util::consume(TokenKind::COLON, &mut rest_tokens) {
+                        let (high, rk) = self.optional_slice_bound(rest_tokens);
+                        rest_tokens = rk;
+                        parser_util::expect(TokenKind::RBRACKET, &mut rest_tokens);
+
+                        value = self
+                            .expr_arena
+                            .lock()
+                            .unwrap()
+                            .alloc(ExpressionNode::new_slice(value, low, high, postfix_pos));
+                        continue;
+                    }
+
+                    parser_util::expect(TokenKind::RBRACKET, &mut rest_tokens);
+                    let index = match low {
+                        Some(index) => index,
+                        None => panic!("index expression is required in `[]`"),
+                    };
+                    value = self
+                        .expr_arena
+                        .lock()
+                        .unwrap()
+                        .alloc(ExpressionNode::new_index(value, index, postfix_pos));
+                }
                 _ => break,
             }
         }
         (value, rest_tokens)
     }
 
-    /// primary -> integer_literal | uinteger_literal | "true" | "false" | string_literal | identifier_path | paren_expr
+    /// スライスの境界は省略できる
+    fn optional_slice_bound(&mut self, tokens: Vec<Token>) -> (Option<ExNodeId>, Vec<Token>) {
+        match parser_util::head(&tokens).get_kind() {
+            TokenKind::COLON | TokenKind::RBRACKET => (None, tokens),
+            _ => {
+                let (bound, rest_tokens) = self.expression(tokens);
+                (Some(bound), rest_tokens)
+            }
+        }
+    }
+
+    /// primary -> integer_literal | uinteger_literal | "true" | "false" | string_literal | identifier_path | struct_literal | paren_expr
     fn primary(&mut self, mut tokens: Vec<Token>) -> (ExNodeId, Vec<Token>) {
         let head = parser_util::head(&tokens);
         let pos = head.get_position();
@@ -219,39 +290,74 @@ impl Context {
                     tokens,
                 )
             }
+            TokenKind::CHARLIT { value } => {
+                parser_util::eat_token(&mut tokens);
+                (
+                    self.expr_arena
+                        .lock()
+                        .unwrap()
+                        .alloc(ExpressionNode::new_character(*value, pos)),
+                    tokens,
+                )
+            }
             TokenKind::IDENTIFIER { name: _ } => {
                 let (names, mut tokens) = parser_util::expect_identifier(tokens);
 
-                if !parser_util::consume(TokenKind::LPAREN, &mut tokens) {
+                // 組み込みの len(x)
+                if names == ["len"] && parser_util::consume(TokenKind::LPAREN, &mut tokens) {
+                    let (value, mut tokens) = self.expression(tokens);
+                    parser_util::expect(TokenKind::RPAREN, &mut tokens);
                     return (
                         self.expr_arena
                             .lock()
                             .unwrap()
-                            .alloc(ExpressionNode::new_identifier(names, pos)),
+                            .alloc(ExpressionNode::new_len(value, pos)),
                         tokens,
                     );
                 }
 
-                // 呼び出し式
-                self.called_functions.insert(names.join("::"));
-                let mut args = Vec::new();
-
-                loop {
-                    if parser_util::consume(TokenKind::RPAREN, &mut tokens) {
-                        break;
-                    }
+                // max[Int64](a, b) のように型引数を明示した呼び出し
+                // 直後が `(` や構造体リテラルでない `[...]` は添字として postfix で扱う
+                let (type_args, mut tokens) = if starts_type_arg_list(&tokens) {
+                    self.expect_type_arg_list(tokens)
+                } else {
+                    (Vec::new(), tokens)
+                };
+
+                // Point { x: 1, y: 2 } のような構造体リテラル
+                if starts_struct_literal(&tokens) {
+                    let type_name = format!("{}::{}", self.module_name, names.join("::"));
+                    let type_name = if type_args.is_empty() {
+                        type_name
+                    } else {
+                        generic_instance_name(&type_name, &type_args)
+                    };
+                    return self.struct_literal(type_name, tokens, pos);
+                }
 
-                    let (arg_id, rk) = self.expression(tokens);
-                    args.push(arg_id);
-                    tokens = rk;
+                if type_args.is_empty() && !parser_util::consume(TokenKind::LPAREN, &mut tokens) {
+                    return (
+                        self.expr_arena
+                            .lock()
+                            .unwrap()
+                            .alloc(ExpressionNode::new_identifier(names, pos)),
+                        tokens,
+                    );
+                }
 
-                    parser_util::consume(TokenKind::COMMA, &mut tokens);
+                // 呼び出し式
+                // ジェネリック関数の実体は，実体化時に呼び出し済みとして登録する
+                if type_args.is_empty() {
+                    self.called_functions.insert(names.join("::"));
+                } else {
+                    parser_util::expect(TokenKind::LPAREN, &mut tokens);
                 }
+                let (args, tokens) = self.call_arguments(tokens);
                 (
                     self.expr_arena
                         .lock()
                         .unwrap()
-                        .alloc(ExpressionNode::new_call(names, args, pos)),
+                        .alloc(ExpressionNode::new_call(names, type_args, args, pos)),
                     tokens,
                 )
             }
@@ -292,6 +398,72 @@ impl Context {
         }
     }
 
+    /// call_arguments -> (expression `,`?)* `)`
+    /// `(` は呼び出し側で消費済み
+    fn call_arguments(&mut self, mut tokens: Vec<Token>) -> (Vec<ExNodeId>, Vec<Token>) {
+        let mut args = Vec::new();
+
+        loop {
+            if parser_util::consume(TokenKind::RPAREN, &mut tokens) {
+                break;
+            }
+
+            let (arg_id, rk) = self.expression(tokens);
+            args.push(arg_id);
+            tokens = rk;
+
+            parser_util::consume(TokenKind::COMMA, &mut tokens);
+        }
+
+        (args, tokens)
+    }
+
+    /// struct_literal -> identifier_path type_arg_list? `{` (identifier `:` expression `,`?)+ `}`
+    /// 型名は呼び出し側で消費済み
+    fn struct_literal(
+        &mut self,
+        type_name: String,
+        mut tokens: Vec<Token>,
+        pos: Position,
+    ) -> (ExNodeId, Vec<Token>) {
+        parser_util::expect(TokenKind::LBRACE, &mut tokens);
+
+        let mut fields = Vec::new();
+
+        loop {
+            if parser_util::consume(TokenKind::RBRACE, &mut tokens) {
+                break;
+            }
+
+            let (member, mut rk) = parser_util::expect_identifier(tokens);
+            parser_util::expect(TokenKind::COLON, &mut rk);
+            let (value_id, mut rk) = self.expression(rk);
+            fields.push((member.join("::"), value_id));
+
+            parser_util::consume(TokenKind::COMMA, &mut rk);
+            tokens = rk;
+        }
+
+        (
+            self.expr_arena
+                .lock()
+                .unwrap()
+                .alloc(ExpressionNode::new_struct_literal(type_name, fields, pos)),
+            tokens,
+        )
+    }
+
+    /// 識別子ノードであれば，そのパスを取り出す
+    fn identifier_names(&self, expr_id: ExNodeId) -> Option<Vec<String>> {
+        let arena = self.expr_arena.lock().unwrap();
+        let expr = arena.get(expr_id).unwrap();
+        if !expr.is_identifier() {
+            return None;
+        }
+
+        Some(expr.copy_names())
+    }
+
     /// paren_expr -> `(` expression `)`
     fn paren_expr(&mut self, mut tokens: Vec<Token>) -> (ExNodeId, Vec<Token>) {
         parser_util::eat_token(&mut tokens);
@@ -303,8 +475,141 @@ impl Context {
     }
 }
 
+/// 型名の直後が `{ identifier :` であれば構造体リテラルとみなす
+/// `countup ... exclude n {` や `match x {` のブロックと区別するため，空のリテラルは書けない
+fn starts_struct_literal(tokens: &[Token]) -> bool {
+    let kind_at = |idx: usize| tokens.get(idx).map(|t| t.get_kind().clone());
+
+    kind_at(0) == Some(TokenKind::LBRACE)
+        && matches!(kind_at(1), Some(TokenKind::IDENTIFIER { name: _ }))
+        && kind_at(2) == Some(TokenKind::COLON)
+}
+
+/// `[` から対応する `]` までの直後が `(` か構造体リテラルであれば型引数リストとみなす
+fn starts_type_arg_list(tokens: &[Token]) -> bool {
+    if tokens.first().map(|t| t.get_kind()) != Some(&TokenKind::LBRACKET) {
+        return false;
+    }
+
+    let mut depth = 0;
+    for (idx, t) in tokens.iter().enumerate() {
+        match t.get_kind() {
+            TokenKind::LBRACKET => depth += 1,
+            TokenKind::RBRACKET => {
+                depth -= 1;
+                if depth == 0 {
+                    let rest = &tokens[idx + 1..];
+                    return rest.first().map(|t| t.get_kind()) == Some(&TokenKind::LPAREN)
+                        || starts_struct_literal(rest);
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
 #[cfg(test)]
 mod expression_tests {
+    use super::*;
+    use crate::common::ast::ExpressionNodeKind;
+
+    #[test]
+    fn struct_literal_test() {
+        // Point { x: 1, y: 2 }
+        let tokens = vec![
+            Token::new_identifier("Point".to_string(), Default::default()),
+            Token::new(TokenKind::LBRACE, Default::default()),
+            Token::new_identifier("x".to_string(), Default::default()),
+            Token::new(TokenKind::COLON, Default::default()),
+            Token::new(TokenKind::Integer { value: 1 }, Default::default()),
+            Token::new(TokenKind::COMMA, Default::default()),
+            Token::new_identifier("y".to_string(), Default::default()),
+            Token::new(TokenKind::COLON, Default::default()),
+            Token::new(TokenKind::Integer { value: 2 }, Default::default()),
+            Token::new(TokenKind::RBRACE, Default::default()),
+            Token::new(TokenKind::EOF, Default::default()),
+        ];
+
+        let mut ctxt: Context = Default::default();
+        let (literal_id, rest_tokens) = ctxt.expression(tokens);
+        assert_eq!(1, rest_tokens.len());
+
+        let arena = ctxt.expr_arena.lock().unwrap();
+        match arena.get(literal_id).unwrap().get_kind() {
+            ExpressionNodeKind::STRUCTLITERAL {
+                type_name,
+                fields,
+                storage: _,
+            } => {
+                assert_eq!("::Point", type_name);
+                let members: Vec<&str> = fields.iter().map(|(m, _)| m.as_str()).collect();
+                assert_eq!(vec!["x", "y"], members);
+            }
+            k => panic!("expected a struct literal, got {:?}", k),
+        }
+    }
+
+    #[test]
+    fn block_after_identifier_is_not_struct_literal_test() {
+        // countup i begin 0 exclude n { x = 1; }; の `n {` はリテラルではない
+        let tokens = vec![
+            Token::new(TokenKind::LBRACE, Default::default()),
+            Token::new_identifier("x".to_string(), Default::default()),
+            Token::new(TokenKind::ASSIGN, Default::default()),
+        ];
+        assert!(!starts_struct_literal(&tokens));
+        assert!(!starts_struct_literal(&tokens[..1]));
+    }
+
+    #[test]
+    fn index_and_slice_test() {
+        // a[1][2:] は INDEX を対象とした SLICE
+        let tokens = vec![
+            Token::new_identifier("a".to_string(), Default::default()),
+            Token::new(TokenKind::LBRACKET, Default::default()),
+            Token::new(TokenKind::Integer { value: 1 }, Default::default()),
+            Token::new(TokenKind::RBRACKET, Default::default()),
+            Token::new(TokenKind::LBRACKET, Default::default()),
+            Token::new(TokenKind::Integer { value: 2 }, Default::default()),
+            Token::new(TokenKind::COLON, Default::default()),
+            Token::new(TokenKind::RBRACKET, Default::default()),
+            Token::new(TokenKind::EOF, Default::default()),
+        ];
+        assert!(!starts_type_arg_list(&tokens[1..]));
+
+        let mut ctxt: Context = Default::default();
+        let (slice_id, rest_tokens) = ctxt.expression(tokens);
+        assert_eq!(1, rest_tokens.len());
+        assert!(ctxt.called_functions.contains(INDEX_OUT_OF_RANGE_ROUTINE));
+
+        let arena = ctxt.expr_arena.lock().unwrap();
+        match arena.get(slice_id).unwrap().get_kind() {
+            ExpressionNodeKind::SLICE { value, low, high } => {
+                assert!(low.is_some());
+                assert!(high.is_none());
+                assert!(matches!(
+                    arena.get(*value).unwrap().get_kind(),
+                    ExpressionNodeKind::INDEX { value: _, index: _ }
+                ));
+            }
+            k => panic!("expected a slice expression, got {:?}", k),
+        }
+    }
+
+    #[test]
+    fn type_arg_list_is_followed_by_call_test() {
+        // max[Int64](...) の `[Int64]` は型引数リスト
+        let tokens = vec![
+            Token::new(TokenKind::LBRACKET, Default::default()),
+            Token::new(TokenKind::INT64, Default::default()),
+            Token::new(TokenKind::RBRACKET, Default::default()),
+            Token::new(TokenKind::LPAREN, Default::default()),
+        ];
+        assert!(starts_type_arg_list(&tokens));
+        assert!(!starts_type_arg_list(&tokens[..3]));
+    }
+
     #[test]
     fn primary_integer_test() {}
 
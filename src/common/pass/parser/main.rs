@@ -1,6 +1,8 @@
 use crate::common::ast::{
-    ASTRoot, EnumDef, FnArena, FnId, Function, FunctionTypeDef, StructDef, VariantDef,
+    ASTRoot, EnumDef, FnArena, FnId, Function, FunctionTypeDef, InterfaceDef, StructDef, VariantDef,
 };
+use crate::common::error::{CompileError as CE, ParseErrorKind as PEK};
+use crate::common::position::Position;
 use crate::common::token::{Token, TokenKind};
 
 use crate::common::pass::parser::context::Context;
@@ -9,67 +11,162 @@ use id_arena::Arena;
 use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
 
-pub fn main(fn_arena: FnArena, mut tokens: Vec<Token>, module_name: String) -> ASTRoot {
+/// パーサのメインルーチン
+pub fn main(fn_arena: FnArena, tokens: Vec<Token>, module_name: String) -> ASTRoot {
+    match parse(fn_arena, tokens, module_name) {
+        Ok(ast_root) => ast_root,
+        // 構文解析エラーなので，出力して終了
+        Err(e) => {
+            e.output();
+            std::process::exit(1);
+        }
+    }
+}
+
+/// トークン列をパースしてASTを構築する
+pub fn parse(
+    fn_arena: FnArena,
+    tokens: Vec<Token>,
+    module_name: String,
+) -> Result<ASTRoot, CE<PEK>> {
     let mut ast_root: ASTRoot = Default::default();
     let mut ctxt: Context = Default::default();
     ctxt.fn_arena = fn_arena;
     ctxt.module_name = module_name;
 
+    let (mut tokens, mut doc_comments) = extract_doc_comments(tokens);
+
+    // 直前に `#[test]` があったか
+    let mut is_test = false;
+
     // program -> toplevel*
     loop {
         let t = parser_util::head(&tokens);
+        let doc = doc_comments.remove(&t.get_position());
 
-        match t.get_kind() {
-            TokenKind::IMPORT => skip_import_directive(&mut tokens),
+        // ドキュメントコメントを付与する宣言の完全修飾名
+        let decl_name = match t.get_kind() {
+            TokenKind::IMPORT => {
+                skip_import_directive(&mut tokens);
+                None
+            }
+            TokenKind::SHARP => {
+                expect_test_attribute(&mut tokens)?;
+                let func_t = parser_util::head(&tokens);
+                if func_t.get_kind() != &TokenKind::FUNC {
+                    return Err(CE::new(
+                        PEK::TESTATTRIBUTEWITHOUTFUNCTION,
+                        func_t.get_position(),
+                    ));
+                }
+                is_test = true;
+
+                // ドキュメントコメントは属性の後ろの関数定義に付与する
+                if let Some(doc) = doc {
+                    doc_comments.insert(func_t.get_position(), doc);
+                }
+                continue;
+            }
             TokenKind::FUNC => {
-                let (fn_id, rest_tokens) = ctxt.func_def(tokens);
+                let (fn_id, rest_tokens) = ctxt.func_def(tokens)?;
                 tokens = rest_tokens;
-                ast_root.funcs.push(fn_id);
+
+                let arena = ctxt.fn_arena.lock().unwrap();
+                let func = arena.get(fn_id).unwrap();
+
+                if std::mem::take(&mut is_test) {
+                    ast_root.tests.push(func.full_path());
+                }
+
+                // ジェネリック関数は呼び出し側の型引数ごとに後で実体化する
+                if func.is_generic() {
+                    ast_root.generic_funcs.push(fn_id);
+                } else {
+                    ast_root.funcs.push(fn_id);
+                }
+                Some(func.full_path())
             }
             TokenKind::STRUCT => {
                 let (type_name, struct_def, rest_tokens) = ctxt.struct_def(tokens);
                 tokens = rest_tokens;
 
-                ast_root
-                    .typedefs
-                    .insert(format!("{}::{}", ctxt.module_name, type_name), struct_def);
+                let full_name = format!("{}::{}", ctxt.module_name, type_name);
+                ast_root.typedefs.insert(full_name.clone(), struct_def);
+                Some(full_name)
             }
             TokenKind::PUBENUM => {
                 let (enum_name, variants, rest_tokens) = ctxt.enum_declaration(tokens);
                 tokens = rest_tokens;
 
-                ast_root
-                    .enum_decls
-                    .insert(format!("{}::{}", ctxt.module_name, enum_name), variants);
+                let full_name = format!("{}::{}", ctxt.module_name, enum_name);
+                ast_root.enum_decls.insert(full_name.clone(), variants);
+                Some(full_name)
+            }
+            TokenKind::PUBINTERFACE => {
+                let (interface_name, interface_def, rest_tokens) = ctxt.interface_def(tokens);
+                tokens = rest_tokens;
+
+                let full_name = format!("{}::{}", ctxt.module_name, interface_name);
+                ast_root.interfaces.insert(full_name.clone(), interface_def);
+                Some(full_name)
             }
             TokenKind::PUBCONST => {
                 let (const_name, type_name, expr, rest_tokens) = ctxt.const_declaration(tokens);
                 tokens = rest_tokens;
 
-                ast_root.constants.insert(
-                    format!("{}::{}", ctxt.module_name, const_name),
-                    (type_name, expr),
-                );
+                let full_name = format!("{}::{}", ctxt.module_name, const_name);
+                ast_root
+                    .constants
+                    .insert(full_name.clone(), (type_name, expr));
+                Some(full_name)
             }
             TokenKind::PUBTYPE => {
                 let (alias_name, src_name, rest_tokens) = ctxt.type_alias(tokens);
                 tokens = rest_tokens;
 
-                ast_root
-                    .alias
-                    .insert(format!("{}::{}", ctxt.module_name, alias_name), src_name);
+                let full_name = format!("{}::{}", ctxt.module_name, alias_name);
+                ast_root.alias.insert(full_name.clone(), src_name);
+                Some(full_name)
             }
             _ => break,
+        };
+
+        if let (Some(decl_name), Some(doc)) = (decl_name, doc) {
+            ast_root.docs.insert(decl_name, doc);
         }
     }
 
     ast_root.called_functions = ctxt.called_functions;
-    ast_root
+    Ok(ast_root)
+}
+
+/// ドキュメントコメントをトークン列から取り除き，直後のトークンの位置と対応付ける
+/// 連続するコメントは改行で連結する
+/// トップレベル宣言の直前にないものは，普通のコメントと同様に読み捨てられる
+fn extract_doc_comments(tokens: Vec<Token>) -> (Vec<Token>, BTreeMap<Position, String>) {
+    let mut rest_tokens = Vec::with_capacity(tokens.len());
+    let mut doc_comments = BTreeMap::new();
+    let mut pending: Vec<String> = Vec::new();
+
+    for t in tokens {
+        if let TokenKind::DOCCOMMENT { contents } = t.get_kind() {
+            pending.push(contents.clone());
+            continue;
+        }
+
+        if !pending.is_empty() {
+            doc_comments.insert(t.get_position(), pending.join("\n"));
+            pending.clear();
+        }
+        rest_tokens.push(t);
+    }
+
+    (rest_tokens, doc_comments)
 }
 
 impl Context {
     /// 関数定義をパースする関数
-    fn func_def(&mut self, mut tokens: Vec<Token>) -> (FnId, Vec<Token>) {
+    fn func_def(&mut self, mut tokens: Vec<Token>) -> Result<(FnId, Vec<Token>), CE<PEK>> {
         // 関数ごとにStmt/ExprArenaは初期化する
         self.expr_arena = Arc::new(Mutex::new(Arena::new()));
         self.stmt_arena = Arc::new(Mutex::new(Arena::new()));
@@ -77,27 +174,53 @@ impl Context {
         let func_pos = parser_util::current_position(&tokens);
         parser_util::eat_token(&mut tokens);
 
+        // func (p *Point) norm() のようにレシーバが書かれていればメソッド
+        let (receiver, tokens) = if parser_util::head(&tokens).get_kind() == &TokenKind::LPAREN {
+            let receiver_pos = parser_util::current_position(&tokens);
+            let (mut receivers, rest_tokens) = self.arg_list(tokens);
+            if receivers.len() != 1 {
+                return Err(CE::new(
+                    PEK::INVALIDRECEIVERCOUNT(receivers.len()),
+                    receiver_pos,
+                ));
+            }
+            (receivers.pop(), rest_tokens)
+        } else {
+            (None, tokens)
+        };
+
         let (func_names, rest_tokens) = parser_util::expect_identifier(tokens);
         let func_name = func_names[0].clone();
 
-        let (arg_map, rest_tokens) = self.arg_list(rest_tokens);
+        let (type_params, rest_tokens) = self.type_param_list(rest_tokens);
+
+        let (mut arg_map, rest_tokens) = self.arg_list(rest_tokens);
+
+        // レシーバは第一引数として扱う
+        let method_of = receiver.map(|(recv_name, recv_type)| {
+            let struct_name = recv_type.trim_start_matches('*').to_string();
+            arg_map.insert(0, (recv_name, recv_type));
+            struct_name
+        });
 
         let (return_type, rest_tokens) = self.expect_type(rest_tokens);
 
         let (stmts, rest_tokens) = self.expect_block(rest_tokens);
 
-        (
+        Ok((
             self.fn_arena.lock().unwrap().alloc(Function {
                 name: func_name,
                 fn_type: FunctionTypeDef::new(return_type, arg_map),
                 stmts,
                 pos: func_pos,
                 module_name: self.module_name.clone(),
+                method_of,
+                type_params,
                 stmt_arena: self.stmt_arena.clone(),
                 expr_arena: self.expr_arena.clone(),
             }),
             rest_tokens,
-        )
+        ))
     }
 
     /// 引数定義リストをパースする関数
@@ -136,8 +259,74 @@ impl Context {
         let (type_names, rest_tokens) = parser_util::expect_identifier(tokens);
         let type_name = type_names[0].clone();
 
+        let (type_params, rest_tokens) = self.type_param_list(rest_tokens);
+
         let (members, rest_tokens) = self.member_block(rest_tokens);
-        (type_name, StructDef { members }, rest_tokens)
+        (
+            type_name,
+            StructDef {
+                members,
+                type_params,
+            },
+            rest_tokens,
+        )
+    }
+
+    /// type_param_list -> (`[` identifier (`,` identifier)* `]`)?
+    /// 型パラメータは他の型名と同様にモジュール名で修飾しておく
+    fn type_param_list(&mut self, mut tokens: Vec<Token>) -> (Vec<String>, Vec<Token>) {
+        let mut type_params = Vec::new();
+
+        if !parser_util::consume(TokenKind::LBRACKET, &mut tokens) {
+            return (type_params, tokens);
+        }
+
+        loop {
+            if parser_util::consume(TokenKind::RBRACKET, &mut tokens) {
+                break;
+            }
+
+            let (param_names, rest_tokens) = parser_util::expect_identifier(tokens);
+            tokens = rest_tokens;
+            type_params.push(format!("{}::{}", self.module_name, param_names[0]));
+
+            parser_util::consume(TokenKind::COMMA, &mut tokens);
+        }
+
+        (type_params, tokens)
+    }
+
+    /// インタフェース型の定義をパースする．
+    /// メソッドシグネチャをメンバ定義と同様に区切りなしで並べる
+    fn interface_def(&mut self, mut tokens: Vec<Token>) -> (String, InterfaceDef, Vec<Token>) {
+        parser_util::eat_token(&mut tokens);
+
+        let (interface_names, mut rest_tokens) = parser_util::expect_identifier(tokens);
+        parser_util::expect(TokenKind::LBRACE, &mut rest_tokens);
+
+        let mut methods = BTreeMap::new();
+
+        loop {
+            if parser_util::consume(TokenKind::RBRACE, &mut rest_tokens) {
+                break;
+            }
+
+            let (method_names, r) = parser_util::expect_identifier(rest_tokens);
+            let (args, r) = self.arg_list(r);
+            let (return_type, r) = self.expect_type(r);
+            rest_tokens = r;
+
+            methods.insert(
+                method_names[0].clone(),
+                FunctionTypeDef::new(return_type, args),
+            );
+        }
+
+        (
+            interface_names[0].clone(),
+            InterfaceDef { methods },
+            rest_tokens,
+        )
     }
 
     /// Enum型をパースする．
@@ -169,8 +358,9 @@ impl Context {
 
     /// 構造体型内のメンバ定義列をパースする．
     /// 引数のように，リスト構造をパースするメタ関数を作ってもいいかも．
-    fn member_block(&mut self, mut tokens: Vec<Token>) -> (BTreeMap<String, String>, Vec<Token>) {
-        let mut members = BTreeMap::new();
+    /// メンバは宣言順に並べる
+    fn member_block(&mut self, mut tokens: Vec<Token>) -> (Vec<(String, String)>, Vec<Token>) {
+        let mut members = Vec::new();
         parser_util::expect(TokenKind::LBRACE, &mut tokens);
 
         loop {
@@ -187,7 +377,7 @@ impl Context {
             let (member_type, rest_tokens) = self.expect_type(tokens);
             tokens = rest_tokens;
 
-            members.insert(member_name, member_type);
+            members.push((member_name, member_type));
         }
 
         (members, tokens)
@@ -230,6 +420,23 @@ impl Context {
     }
 }
 
+/// attribute -> `#` `[` "test" `]`
+/// 今のところ，テスト関数を示す `#[test]` だけを受け付ける
+fn expect_test_attribute(tokens: &mut Vec<Token>) -> Result<(), CE<PEK>> {
+    parser_util::eat_token(tokens);
+    parser_util::expect(TokenKind::LBRACKET, tokens);
+
+    let attr_pos = parser_util::current_position(tokens);
+    let (names, mut rest_tokens) = parser_util::expect_identifier(std::mem::take(tokens));
+    if names != ["test"] {
+        return Err(CE::new(PEK::UNKNOWNATTRIBUTE(names.join("::")), attr_pos));
+    }
+    parser_util::expect(TokenKind::RBRACKET, &mut rest_tokens);
+
+    *tokens = rest_tokens;
+    Ok(())
+}
+
 /// コンパイラ内部では用いないのでスキップする．
 /// ASTRootに情報を含めることで，ルートがインポートしていないパッケージはバイナリに含めない，みたいなことができるかも.
 fn skip_import_directive(tokens: &mut Vec<Token>) {
@@ -260,6 +467,115 @@ mod toplevel_tests {
     #[test]
     fn func_def_test() {}
 
+    #[test]
+    fn method_def_test() {
+        // func (p *Point) norm() Int64 {}
+        let tokens = vec![
+            Token::new(TokenKind::FUNC, Default::default()),
+            Token::new(TokenKind::LPAREN, Default::default()),
+            Token::new_identifier("p".to_string(), Default::default()),
+            Token::new(TokenKind::ASTERISK, Default::default()),
+            Token::new_identifier("Point".to_string(), Default::default()),
+            Token::new(TokenKind::RPAREN, Default::default()),
+            Token::new_identifier("norm".to_string(), Default::default()),
+            Token::new(TokenKind::LPAREN, Default::default()),
+            Token::new(TokenKind::RPAREN, Default::default()),
+            Token::new(TokenKind::INT64, Default::default()),
+            Token::new(TokenKind::LBRACE, Default::default()),
+            Token::new(TokenKind::RBRACE, Default::default()),
+            Token::new(TokenKind::EOF, Default::default()),
+        ];
+        let fn_arena = new_allocators();
+
+        let root = main(fn_arena.clone(), tokens, "geo".to_string());
+        assert_eq!(1, root.funcs.len());
+
+        let arena = fn_arena.lock().unwrap();
+        let method = arena.get(root.funcs[0]).unwrap();
+        assert_eq!(Some("geo::Point".to_string()), method.method_of);
+        assert_eq!(
+            &vec![("p".to_string(), "*geo::Point".to_string())],
+            method.get_parameters()
+        );
+        assert_eq!("geo::Point.norm", method.full_path());
+    }
+
+    #[test]
+    fn interface_def_test() {
+        // pubinterface Shape { area() Int64 scale(k Int64) Noreturn }
+        let tokens = vec![
+            Token::new(TokenKind::PUBINTERFACE, Default::default()),
+            Token::new_identifier("Shape".to_string(), Default::default()),
+            Token::new(TokenKind::LBRACE, Default::default()),
+            Token::new_identifier("area".to_string(), Default::default()),
+            Token::new(TokenKind::LPAREN, Default::default()),
+            Token::new(TokenKind::RPAREN, Default::default()),
+            Token::new(TokenKind::INT64, Default::default()),
+            Token::new_identifier("scale".to_string(), Default::default()),
+            Token::new(TokenKind::LPAREN, Default::default()),
+            Token::new_identifier("k".to_string(), Default::default()),
+            Token::new(TokenKind::INT64, Default::default()),
+            Token::new(TokenKind::RPAREN, Default::default()),
+            Token::new(TokenKind::NORETURN, Default::default()),
+            Token::new(TokenKind::RBRACE, Default::default()),
+            Token::new(TokenKind::EOF, Default::default()),
+        ];
+
+        let root = main(new_allocators(), tokens, "geo".to_string());
+        let shape = root.interfaces.get("geo::Shape").unwrap();
+
+        assert_eq!(2, shape.methods.len());
+        assert_eq!("Int64", shape.methods.get("area").unwrap().return_type);
+        assert_eq!(
+            vec![("k".to_string(), "Int64".to_string())],
+            shape.methods.get("scale").unwrap().args
+        );
+    }
+
+    #[test]
+    fn generic_def_test() {
+        // func max[T](a T) T {}
+        // struct Pair[T] { first T }
+        let tokens = vec![
+            Token::new(TokenKind::FUNC, Default::default()),
+            Token::new_identifier("max".to_string(), Default::default()),
+            Token::new(TokenKind::LBRACKET, Default::default()),
+            Token::new_identifier("T".to_string(), Default::default()),
+            Token::new(TokenKind::RBRACKET, Default::default()),
+            Token::new(TokenKind::LPAREN, Default::default()),
+            Token::new_identifier("a".to_string(), Default::default()),
+            Token::new_identifier("T".to_string(), Default::default()),
+            Token::new(TokenKind::RPAREN, Default::default()),
+            Token::new_identifier("T".to_string(), Default::default()),
+            Token::new(TokenKind::LBRACE, Default::default()),
+            Token::new(TokenKind::RBRACE, Default::default()),
+            Token::new(TokenKind::STRUCT, Default::default()),
+            Token::new_identifier("Pair".to_string(), Default::default()),
+            Token::new(TokenKind::LBRACKET, Default::default()),
+            Token::new_identifier("T".to_string(), Default::default()),
+            Token::new(TokenKind::RBRACKET, Default::default()),
+            Token::new(TokenKind::LBRACE, Default::default()),
+            Token::new_identifier("first".to_string(), Default::default()),
+            Token::new_identifier("T".to_string(), Default::default()),
+            Token::new(TokenKind::RBRACE, Default::default()),
+            Token::new(TokenKind::EOF, Default::default()),
+        ];
+        let fn_arena = new_allocators();
+
+        let root = main(fn_arena.clone(), tokens, "geo".to_string());
+        assert_eq!(0, root.funcs.len());
+        assert_eq!(1, root.generic_funcs.len());
+
+        let arena = fn_arena.lock().unwrap();
+        let max = arena.get(root.generic_funcs[0]).unwrap();
+        assert_eq!(vec!["geo::T".to_string()], max.type_params);
+        assert_eq!("geo::T", max.copy_return_type());
+
+        let pair = root.typedefs.get("geo::Pair").unwrap();
+        assert_eq!(vec!["geo::T".to_string()], pair.type_params);
+        assert_eq!(("first".to_string(), "geo::T".to_string()), pair.members[0]);
+    }
+
     #[test]
     fn main_test() {
         let tokens = vec![
@@ -302,7 +618,130 @@ mod toplevel_tests {
         assert_eq!(2, root.funcs.len());
     }
 
+    #[test]
+    fn doc_comment_test() {
+        // /// 原点
+        // /// x/y ともに0
+        // pubconst ORIGIN : Int64 = 0;
+        // /// 関数本体の中は対象外
+        // func f() Noreturn { }
+        let pos = |column| Position::new(1, column);
+        let tokens = vec![
+            Token::new(
+                TokenKind::DOCCOMMENT {
+                    contents: "原点".to_string(),
+                },
+                pos(1),
+            ),
+            Token::new(
+                TokenKind::DOCCOMMENT {
+                    contents: "x/y ともに0".to_string(),
+                },
+                pos(2),
+            ),
+            Token::new(TokenKind::PUBCONST, pos(3)),
+            Token::new_identifier("ORIGIN".to_string(), pos(4)),
+            Token::new(TokenKind::COLON, pos(5)),
+            Token::new(TokenKind::INT64, pos(6)),
+            Token::new(TokenKind::ASSIGN, pos(7)),
+            Token::new_int_literal(0, pos(8)),
+            Token::new(TokenKind::SEMICOLON, pos(9)),
+            Token::new(TokenKind::FUNC, pos(10)),
+            Token::new_identifier("f".to_string(), pos(11)),
+            Token::new(TokenKind::LPAREN, pos(12)),
+            Token::new(TokenKind::RPAREN, pos(13)),
+            Token::new(TokenKind::NORETURN, pos(14)),
+            Token::new(TokenKind::LBRACE, pos(15)),
+            Token::new(
+                TokenKind::DOCCOMMENT {
+                    contents: "ignored".to_string(),
+                },
+                pos(16),
+            ),
+            Token::new(TokenKind::RBRACE, pos(17)),
+            Token::new(TokenKind::EOF, pos(18)),
+        ];
+
+        let root = main(new_allocators(), tokens, "geo".to_string());
+        assert_eq!(1, root.docs.len());
+        assert_eq!(
+            Some(&"原点\nx/y ともに0".to_string()),
+            root.docs.get("geo::ORIGIN")
+        );
+        assert_eq!(1, root.funcs.len());
+    }
+
+    #[test]
+    fn test_attribute_test() {
+        // /// 加算のテスト
+        // #[test]
+        // func add_test() Int64 {}
+        // func helper() Int64 {}
+        let pos = |column| Position::new(1, column);
+        let tokens = vec![
+            Token::new(
+                TokenKind::DOCCOMMENT {
+                    contents: "加算のテスト".to_string(),
+                },
+                pos(1),
+            ),
+            Token::new(TokenKind::SHARP, pos(2)),
+            Token::new(TokenKind::LBRACKET, pos(3)),
+            Token::new_identifier("test".to_string(), pos(4)),
+            Token::new(TokenKind::RBRACKET, pos(5)),
+            Token::new(TokenKind::FUNC, pos(6)),
+            Token::new_identifier("add_test".to_string(), pos(7)),
+            Token::new(TokenKind::LPAREN, pos(8)),
+            Token::new(TokenKind::RPAREN, pos(9)),
+            Token::new(TokenKind::INT64, pos(10)),
+            Token::new(TokenKind::LBRACE, pos(11)),
+            Token::new(TokenKind::RBRACE, pos(12)),
+            Token::new(TokenKind::FUNC, pos(13)),
+            Token::new_identifier("helper".to_string(), pos(14)),
+            Token::new(TokenKind::LPAREN, pos(15)),
+            Token::new(TokenKind::RPAREN, pos(16)),
+            Token::new(TokenKind::INT64, pos(17)),
+            Token::new(TokenKind::LBRACE, pos(18)),
+            Token::new(TokenKind::RBRACE, pos(19)),
+            Token::new(TokenKind::EOF, pos(20)),
+        ];
+
+        let root = main(new_allocators(), tokens, "math".to_string());
+        assert_eq!(2, root.funcs.len());
+        assert_eq!(vec!["math::add_test".to_string()], root.tests);
+        assert_eq!(
+            Some(&"加算のテスト".to_string()),
+            root.docs.get("math::add_test")
+        );
+    }
+
     fn new_allocators() -> FnArena {
         Arc::new(Mutex::new(Arena::new()))
     }
 }
+
+#[cfg(test)]
+mod parser_main_tests {
+    use super::*;
+    use crate::common::pass::tokenizer;
+
+    fn parse_source(source: &str) -> Result<ASTRoot, CE<PEK>> {
+        let tokens = tokenizer::main(source.to_string());
+        parse(Arc::new(Mutex::new(Arena::new())), tokens, String::new())
+    }
+
+    #[test]
+    fn invalid_receiver_count_test() {
+        let e = parse_source("func (a Int64, b Int64) f() Int64 { return a; }").unwrap_err();
+        assert_eq!(&PEK::INVALIDRECEIVERCOUNT(2), e.get_kind());
+    }
+
+    #[test]
+    fn invalid_attribute_test() {
+        let e = parse_source("#[test] struct S { a Int64 }").unwrap_err();
+        assert_eq!(&PEK::TESTATTRIBUTEWITHOUTFUNCTION, e.get_kind());
+
+        let e = parse_source("#[bench] func f() Int64 { return 0; }").unwrap_err();
+        assert_eq!(&PEK::UNKNOWNATTRIBUTE("bench".to_string()), e.get_kind());
+    }
+}
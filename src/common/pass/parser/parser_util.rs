@@ -1,4 +1,6 @@
-use crate::common::ast::{ExNodeId, ExpressionNode, StNodeId};
+use crate::common::ast::{
+    function_type_name, generic_instance_name, ExNodeId, ExpressionNode, StNodeId,
+};
 use crate::common::position::Position;
 use crate::common::token::{Token, TokenKind};
 use std::sync::MutexGuard;
@@ -10,7 +12,7 @@ type ChildParser = fn(&mut Context, Vec<Token>) -> (ExNodeId, Vec<Token>);
 type OperatorParser = fn(&mut Context, Vec<Token>) -> (Option<TokenKind>, Vec<Token>);
 
 impl Context {
-    /// type -> "Int64" | "Uint64" | "ConstStr" | "Noreturn" | "Boolean" |`*` type | identifier-path
+    /// type -> "Int64" | "Uint64" | "Uint8" | "ConstStr" | "Noreturn" | "Boolean" |`*` type | `[` integer? `]` type | function_type | identifier-path type_arg_list?
     pub fn expect_type(&self, mut tokens: Vec<Token>) -> (String, Vec<Token>) {
         let type_t = head(&tokens);
 
@@ -23,6 +25,10 @@ impl Context {
                 eat_token(&mut tokens);
                 ("Uint64".to_string(), tokens)
             }
+            TokenKind::UINT8 => {
+                eat_token(&mut tokens);
+                ("Uint8".to_string(), tokens)
+            }
             TokenKind::CONSTSTR => {
                 eat_token(&mut tokens);
                 ("ConstStr".to_string(), tokens)
@@ -41,17 +47,82 @@ impl Context {
                 let (inner_type, rest_tokens) = self.expect_type(tokens);
                 (format!("*{}", inner_type), rest_tokens)
             }
+            // `[]T` はスライス型，`[N]T` は配列型
+            TokenKind::LBRACKET => {
+                eat_token(&mut tokens);
+                let length = match head(&tokens).get_kind() {
+                    TokenKind::Integer { value } => {
+                        let length = value.to_string();
+                        eat_token(&mut tokens);
+                        length
+                    }
+                    _ => String::new(),
+                };
+                expect(TokenKind::RBRACKET, &mut tokens);
+
+                let (elem_type, rest_tokens) = self.expect_type(tokens);
+                (format!("[{}]{}", length, elem_type), rest_tokens)
+            }
+            TokenKind::FUNC => self.expect_function_type(tokens),
             TokenKind::IDENTIFIER { name: _ } => {
                 let (names, rest_tokens) = expect_identifier(tokens);
-                (
-                    format!("{}::{}", self.module_name, names.join("::")),
-                    rest_tokens,
-                )
+                let type_name = format!("{}::{}", self.module_name, names.join("::"));
+
+                if head(&rest_tokens).get_kind() != &TokenKind::LBRACKET {
+                    return (type_name, rest_tokens);
+                }
+
+                let (type_args, rest_tokens) = self.expect_type_arg_list(rest_tokens);
+                (generic_instance_name(&type_name, &type_args), rest_tokens)
             }
             _ => panic!("TODO we must compile error when got difference token in expect_type()"),
         }
     }
 
+    /// function_type -> "func" `(` (type `,`?)* `)` type
+    fn expect_function_type(&self, mut tokens: Vec<Token>) -> (String, Vec<Token>) {
+        eat_token(&mut tokens);
+        expect(TokenKind::LPAREN, &mut tokens);
+
+        let mut param_types = Vec::new();
+
+        loop {
+            if consume(TokenKind::RPAREN, &mut tokens) {
+                break;
+            }
+
+            let (param_type, rest_tokens) = self.expect_type(tokens);
+            tokens = rest_tokens;
+            param_types.push(param_type);
+
+            consume(TokenKind::COMMA, &mut tokens);
+        }
+
+        let (return_type, rest_tokens) = self.expect_type(tokens);
+        (function_type_name(&param_types, &return_type), rest_tokens)
+    }
+
+    /// type_arg_list -> `[` type (`,` type)* `]`
+    pub fn expect_type_arg_list(&self, mut tokens: Vec<Token>) -> (Vec<String>, Vec<Token>) {
+        expect(TokenKind::LBRACKET, &mut tokens);
+
+        let mut type_args = Vec::new();
+
+        loop {
+            if consume(TokenKind::RBRACKET, &mut tokens) {
+                break;
+            }
+
+            let (type_arg, rest_tokens) = self.expect_type(tokens);
+            tokens = rest_tokens;
+            type_args.push(type_arg);
+
+            consume(TokenKind::COMMA, &mut tokens);
+        }
+
+        (type_args, tokens)
+    }
+
     /// block -> `{` statement* `}`
     pub fn expect_block(&mut self, mut tokens: Vec<Token>) -> (Vec<StNodeId>, Vec<Token>) {
         eat_token(&mut tokens);
@@ -219,7 +290,67 @@ mod parser_util_tests {
     }
 
     #[test]
-    fn expect_type_test() {}
+    fn expect_type_test() {
+        // Pair[Int64, *Pair[Uint64]]
+        let tokens = vec![
+            Token::new_identifier("Pair".to_string(), Default::default()),
+            Token::new(TokenKind::LBRACKET, Default::default()),
+            Token::new(TokenKind::INT64, Default::default()),
+            Token::new(TokenKind::COMMA, Default::default()),
+            Token::new(TokenKind::ASTERISK, Default::default()),
+            Token::new_identifier("Pair".to_string(), Default::default()),
+            Token::new(TokenKind::LBRACKET, Default::default()),
+            Token::new(TokenKind::UINT64, Default::default()),
+            Token::new(TokenKind::RBRACKET, Default::default()),
+            Token::new(TokenKind::RBRACKET, Default::default()),
+            Token::new(TokenKind::EOF, Default::default()),
+        ];
+        let ctxt: Context = Default::default();
+
+        let (type_name, rest_tokens) = ctxt.expect_type(tokens);
+        assert_eq!(1, rest_tokens.len());
+        assert_eq!("::Pair[Int64,*::Pair[Uint64]]", type_name);
+    }
+
+    #[test]
+    fn expect_array_type_test() {
+        // [4][]Uint8
+        let tokens = vec![
+            Token::new(TokenKind::LBRACKET, Default::default()),
+            Token::new(TokenKind::Integer { value: 4 }, Default::default()),
+            Token::new(TokenKind::RBRACKET, Default::default()),
+            Token::new(TokenKind::LBRACKET, Default::default()),
+            Token::new(TokenKind::RBRACKET, Default::default()),
+            Token::new(TokenKind::UINT8, Default::default()),
+            Token::new(TokenKind::EOF, Default::default()),
+        ];
+        let ctxt: Context = Default::default();
+
+        let (type_name, rest_tokens) = ctxt.expect_type(tokens);
+        assert_eq!(1, rest_tokens.len());
+        assert_eq!("[4][]Uint8", type_name);
+    }
+
+    #[test]
+    fn expect_function_type_test() {
+        // *func(Int64, Point) Int64
+        let tokens = vec![
+            Token::new(TokenKind::ASTERISK, Default::default()),
+            Token::new(TokenKind::FUNC, Default::default()),
+            Token::new(TokenKind::LPAREN, Default::default()),
+            Token::new(TokenKind::INT64, Default::default()),
+            Token::new(TokenKind::COMMA, Default::default()),
+            Token::new_identifier("Point".to_string(), Default::default()),
+            Token::new(TokenKind::RPAREN, Default::default()),
+            Token::new(TokenKind::INT64, Default::default()),
+            Token::new(TokenKind::EOF, Default::default()),
+        ];
+        let ctxt: Context = Default::default();
+
+        let (type_name, rest_tokens) = ctxt.expect_type(tokens);
+        assert_eq!(1, rest_tokens.len());
+        assert_eq!("*func(Int64,::Point)Int64", type_name);
+    }
 
     #[test]
     fn expect_block_test() {}
@@ -102,8 +102,15 @@ impl Context {
         parser_util::eat_token(&mut tokens);
 
         let (declared_names, mut rest_tokens) = parser_util::expect_identifier(tokens);
-        let (type_name, rt) = self.expect_type(rest_tokens);
-        rest_tokens = rt;
+
+        // 型名が省略された場合は空文字列とし，型解決時に初期化式から推論する
+        let type_name = if parser_util::head(&rest_tokens).get_kind() == &TokenKind::ASSIGN {
+            String::new()
+        } else {
+            let (type_name, rt) = self.expect_type(rest_tokens);
+            rest_tokens = rt;
+            type_name
+        };
         parser_util::expect(TokenKind::SEMICOLON, &mut rest_tokens);
 
         (
@@ -181,7 +188,7 @@ impl Context {
         )
     }
 
-    /// varinit -> "varinit" identifier type `=` expression `;`
+    /// varinit -> "varinit" identifier type? `=` expression `;`
     fn varinit_statement(&mut self, tokens: Vec<Token>) -> (StNodeId, Vec<Token>) {
         let stmt_pos = parser_util::current_position(&tokens);
         let (ident, type_name, ex_id, rest_tokens) = self.initialize_statement(tokens);
@@ -199,7 +206,7 @@ impl Context {
         )
     }
 
-    /// const -> "const" identifier type `=` expression `;`
+    /// const -> "const" identifier type? `=` expression `;`
     fn const_statement(&mut self, tokens: Vec<Token>) -> (StNodeId, Vec<Token>) {
         let stmt_pos = parser_util::current_position(&tokens);
         let (ident, type_name, ex_id, rest_tokens) = self.initialize_statement(tokens);
@@ -224,8 +231,15 @@ impl Context {
         parser_util::eat_token(&mut tokens);
 
         let (declared_names, mut rest_tokens) = parser_util::expect_identifier(tokens);
-        let (type_name, rt) = self.expect_type(rest_tokens);
-        rest_tokens = rt;
+
+        // 型名が省略された場合は空文字列とし，型解決時に初期化式から推論する
+        let type_name = if parser_util::head(&rest_tokens).get_kind() == &TokenKind::ASSIGN {
+            String::new()
+        } else {
+            let (type_name, rt) = self.expect_type(rest_tokens);
+            rest_tokens = rt;
+            type_name
+        };
 
         parser_util::expect(TokenKind::ASSIGN, &mut rest_tokens);
 
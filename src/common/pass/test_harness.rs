@@ -0,0 +1,58 @@
+/// `#[test]` 関数を順に実行するハーネス `startup::run_tests` のPeachiliコードを生成する
+/// 各テストはforkした子プロセスで呼び出し，その返り値を終了コードとする
+/// 終了コード0(パニック等で異常終了していない)なら成功とみなす
+pub fn generate_test_harness(tests: &[String]) -> String {
+    let mut harness = String::from("func run_tests() Noreturn {\n");
+    harness += "    varinit all_passed Boolean = true;\n";
+    harness += "    varinit in_parent Boolean = true;\n";
+    harness += "    varinit failed Boolean = false;\n";
+    harness += &format!(
+        "    startup::print(\"running {} test{}\\n\");\n",
+        tests.len(),
+        if tests.len() == 1 { "" } else { "s" }
+    );
+
+    for test in tests.iter() {
+        harness += "    startup::fork(&in_parent);\n";
+        harness += "    if (in_parent) {\n";
+        harness += "        startup::wait_child(&failed);\n";
+        harness += "    } else {\n";
+        harness += &format!("        startup::exit({}());\n", test);
+        harness += "    };\n";
+        harness += "    if (failed) {\n";
+        harness += &format!("        startup::print(\"test {} ... FAILED\\n\");\n", test);
+        harness += "        all_passed = false;\n";
+        harness += "    } else {\n";
+        harness += &format!("        startup::print(\"test {} ... ok\\n\");\n", test);
+        harness += "    };\n";
+    }
+
+    harness += "    if (all_passed) {\n";
+    harness += "        startup::print(\"\\ntest result: ok\\n\");\n";
+    harness += "        startup::exit(0);\n";
+    harness += "    } else {\n";
+    harness += "        startup::print(\"\\ntest result: FAILED\\n\");\n";
+    harness += "        startup::exit(1);\n";
+    harness += "    };\n";
+    harness += "}\n";
+
+    harness
+}
+
+#[cfg(test)]
+mod test_harness_tests {
+    use super::*;
+
+    #[test]
+    fn generate_test_harness_test() {
+        let harness =
+            generate_test_harness(&["add_test".to_string(), "math::sub_test".to_string()]);
+
+        assert!(harness.starts_with("func run_tests() Noreturn {\n"));
+        assert!(harness.contains("startup::print(\"running 2 tests\\n\");"));
+        assert!(harness.contains("startup::exit(add_test());"));
+        assert!(harness.contains("startup::exit(math::sub_test());"));
+        assert!(harness.contains("startup::print(\"test math::sub_test ... FAILED\\n\");"));
+        assert_eq!(2, harness.matches("startup::fork(&in_parent);").count());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::common::ast;
+use crate::common::error::{CompileError, TypeErrorKind};
 use crate::common::tld;
 use std::collections::BTreeMap;
 
@@ -6,7 +7,7 @@ use std::collections::BTreeMap;
 pub fn main(
     fn_arena: ast::FnArena,
     full_ast: &ast::ASTRoot,
-) -> BTreeMap<String, tld::TopLevelDecl> {
+) -> Result<BTreeMap<String, tld::TopLevelDecl>, CompileError<TypeErrorKind>> {
     let mut tld_map: BTreeMap<String, tld::TopLevelDecl> = BTreeMap::new();
 
     for (alias_name, alias_type) in full_ast.alias.iter() {
@@ -23,6 +24,13 @@ pub fn main(
         );
     }
 
+    for (interface_name, interface_def) in full_ast.interfaces.iter() {
+        tld_map.insert(
+            interface_name.to_string(),
+            tld::TopLevelDecl::new_interface_from_ast(interface_def.clone()),
+        );
+    }
+
     for (const_name, (const_type_name, const_expr)) in full_ast.constants.iter() {
         tld_map.insert(
             const_name.to_string(),
@@ -40,11 +48,93 @@ pub fn main(
     for fn_id in full_ast.funcs.iter() {
         let ast_function = fn_arena.lock().unwrap().get(*fn_id).unwrap().clone();
 
+        // メソッドはマングリングされた名前で登録し，構造体側にも紐付けておく
+        if let Some(struct_name) = &ast_function.method_of {
+            // 型名はメソッドと同じモジュールで修飾されているので，
+            // 見つからなければ未定義か，別モジュールの構造体である
+            // 組み込み型や構造体以外の型は，型解決で不正なレシーバとして報告する
+            match tld_map.get_mut(struct_name) {
+                Some(struct_entry) => {
+                    struct_entry.add_method(&ast_function.name, ast_function.full_path());
+                }
+                None if struct_name.contains("::") => {
+                    return Err(CompileError::new(
+                        TypeErrorKind::UndefinedReceiverType {
+                            method: ast_function.name.clone(),
+                            type_name: struct_name.clone(),
+                        },
+                        ast_function.pos,
+                    ));
+                }
+                None => {}
+            }
+
+            tld_map.insert(
+                ast_function.full_path(),
+                tld::TopLevelDecl::new_function_from_ast(ast_function.fn_type),
+            );
+            continue;
+        }
+
         tld_map.insert(
             ast_function.name.to_string(),
             tld::TopLevelDecl::new_function_from_ast(ast_function.fn_type),
         );
     }
 
-    tld_map
+    for fn_id in full_ast.generic_funcs.iter() {
+        let ast_function = fn_arena.lock().unwrap().get(*fn_id).unwrap().clone();
+
+        tld_map.insert(
+            ast_function.name.to_string(),
+            tld::TopLevelDecl::new_generic_function_from_ast(&ast_function),
+        );
+    }
+
+    Ok(tld_map)
+}
+
+#[cfg(test)]
+mod tld_collector_tests {
+    use super::*;
+    use crate::common::pass::{parser, tokenizer};
+    use id_arena::Arena;
+    use std::sync::{Arc, Mutex};
+
+    fn collect(
+        source: &str,
+    ) -> Result<BTreeMap<String, tld::TopLevelDecl>, CompileError<TypeErrorKind>> {
+        let fn_arena: ast::FnArena = Arc::new(Mutex::new(Arena::new()));
+        let tokens = tokenizer::main(source.to_string());
+        let ast_root = parser::parse(fn_arena.clone(), tokens, String::new()).unwrap();
+        main(fn_arena, &ast_root)
+    }
+
+    #[test]
+    fn method_is_registered_to_its_struct_test() {
+        let tld_map =
+            collect("struct Point { x Int64 } func (p *Point) norm() Int64 { return 0; }").unwrap();
+
+        match &tld_map.get("::Point").unwrap().kind {
+            tld::TLDKind::STRUCT {
+                members: _,
+                methods,
+            } => assert_eq!(Some(&"::Point.norm".to_string()), methods.get("norm")),
+            kind => panic!("unexpected kind {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn undefined_receiver_type_test() {
+        let result = collect("func (p *Nope) norm() Int64 { return 0; }");
+        assert!(matches!(
+            result.unwrap_err().get_kind(),
+            TypeErrorKind::UndefinedReceiverType { method, type_name }
+                if method == "norm" && type_name == "::Nope"
+        ));
+
+        // 別モジュールの構造体にはメソッドを定義できない
+        let result = collect("func (p *geo::Point) norm() Int64 { return 0; }");
+        assert!(result.is_err());
+    }
 }
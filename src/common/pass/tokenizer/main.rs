@@ -1,12 +1,25 @@
 use crate::common::{
     error::{CompileError as CE, TokenizeErrorKind as TEK},
     position::Position,
-    token::{Token, TokenKind},
+    token::{byte_to_literal_char, push_literal_char, Token, TokenKind},
 };
 
 /// トークナイザのメインルーチン
 pub fn main(source: String) -> Vec<Token> {
-    tokenize(source)
+    match tokenize(source, false) {
+        Ok(tokens) => tokens.into_iter().map(|(t, _)| t).collect(),
+        // 字句解析エラーなので，出力して終了
+        Err(e) => {
+            e.output();
+            std::process::exit(1);
+        }
+    }
+}
+
+/// 空白やコメントも読み飛ばさずに，各トークンと元の文字列の組を返す
+/// フォーマッタのように，ソースの見た目を保つ必要がある場合に用いる
+pub fn tokenize_with_trivia(source: String) -> Result<Vec<(Token, String)>, CE<TEK>> {
+    tokenize(source, true)
 }
 
 /// トークンに与える情報等を集約
@@ -18,8 +31,9 @@ struct Tokenization {
     cur_token_length: usize,
 }
 
-fn tokenize(mut source: String) -> Vec<Token> {
+fn tokenize(mut source: String, keep_trivia: bool) -> Result<Vec<(Token, String)>, CE<TEK>> {
     let mut tokens = Vec::new();
+    let original = source.clone();
 
     // トークンやエラーの位置用
     let mut tokenization = Tokenization {
@@ -29,34 +43,33 @@ fn tokenize(mut source: String) -> Vec<Token> {
     };
 
     loop {
+        let offset = original.len() - source.len();
         let t = tokenization.scan(&mut source);
 
         if let Err(e) = t {
             match e.get_kind() {
                 // 単純にトークナイズ終了とする
                 TEK::SOURCEISEMPTY => {
-                    push_eof_token(&mut tokens, tokenization.row, tokenization.column);
+                    let eof_pos = Position::new(tokenization.row, tokenization.column);
+                    tokens.push((Token::new(TokenKind::EOF, eof_pos), String::new()));
                     break;
                 }
-                // 字句解析エラーなので，出力して終了
-                _ => {
-                    e.output();
-                    std::process::exit(1);
-                }
+                _ => return Err(e),
             }
         }
 
         let t = t.unwrap();
 
         // 空白類文字は読み飛ばす
-        if t.should_ignore() {
+        if t.should_ignore() && !keep_trivia {
             continue;
         }
 
-        tokens.push(t);
+        let text = original[offset..original.len() - source.len()].to_string();
+        tokens.push((t, text));
     }
 
-    tokens
+    Ok(tokens)
 }
 
 impl Tokenization {
@@ -71,7 +84,14 @@ impl Tokenization {
         match cur_char as char {
             // 文字列リテラル
             '"' => {
-                let t = self.scan_string_literal(source);
+                let t = self.scan_string_literal(source)?;
+                source.drain(..self.cur_token_length);
+                Ok(t)
+            }
+
+            // 文字リテラル
+            '\'' => {
+                let t = self.scan_char_literal(source)?;
                 source.drain(..self.cur_token_length);
                 Ok(t)
             }
@@ -107,9 +127,16 @@ impl Tokenization {
                 Ok(Token::new(TokenKind::NEWLINE, Position::new(0, 0)))
             }
 
+            // ブロックコメント
+            '/' if source.starts_with("/*") => {
+                let t = self.scan_block_comment(source)?;
+                source.drain(..self.cur_token_length);
+                Ok(t)
+            }
+
             // コメントまたは記号とする
             _ => {
-                let t = self.scan_symbol(source);
+                let t = self.scan_symbol(source)?;
                 if let TokenKind::DOUBLESLASH = &t.get_kind() {
                     let t = self.scan_comment(source);
                     source.drain(..self.cur_token_length);
@@ -123,17 +150,58 @@ impl Tokenization {
     }
 
     /// 文字列リテラルのトークン化
-    fn scan_string_literal(&mut self, s: &str) -> Token {
+    /// エスケープシーケンスはここで解釈し，トークンには実際の文字列を持たせる
+    fn scan_string_literal(&mut self, s: &str) -> Result<Token, CE<TEK>> {
         let literal_pos = Position::new(self.row, self.column);
 
-        // 文字列を切り取る
-        let contents_str = cut_string_while(&s[1..], |c| c != &'"');
-        // +2 -> 先頭/終端の `"` 分
-        let len = contents_str.len() + 2;
+        let mut contents = String::new();
+        let mut chars = s.char_indices().skip(1);
 
-        self.condition_position(len);
+        loop {
+            let (idx, c) = match chars.next() {
+                Some(entry) => entry,
+                None => return Err(CE::new(TEK::UNTERMINATEDSTRINGLITERAL, literal_pos)),
+            };
 
-        Token::new_string_literal(contents_str, literal_pos)
+            match c {
+                '"' => {
+                    // +1 -> 終端の `"` 分
+                    self.condition_position(idx + 1);
+                    return Ok(Token::new_string_literal(contents, literal_pos));
+                }
+                '\\' => {
+                    let decoded = decode_escape(&mut chars).map_err(|sequence| {
+                        CE::new(TEK::INVALIDESCAPESEQUENCE(sequence), literal_pos)
+                    })?;
+                    contents.push(byte_to_literal_char(decoded));
+                }
+                '\n' => return Err(CE::new(TEK::UNTERMINATEDSTRINGLITERAL, literal_pos)),
+                c => push_literal_char(&mut contents, c),
+            }
+        }
+    }
+
+    /// 文字リテラルのトークン化
+    /// 1バイトに収まるASCII文字か，エスケープシーケンスのみを許す
+    fn scan_char_literal(&mut self, s: &str) -> Result<Token, CE<TEK>> {
+        let literal_pos = Position::new(self.row, self.column);
+        let mut chars = s.char_indices().skip(1);
+
+        let value = match chars.next() {
+            Some((_, '\\')) => decode_escape(&mut chars)
+                .map_err(|sequence| CE::new(TEK::INVALIDESCAPESEQUENCE(sequence), literal_pos))?,
+            Some((_, c)) if c.is_ascii() && c != '\'' && c != '\n' => c as u8,
+            _ => return Err(CE::new(TEK::INVALIDCHARACTERLITERAL, literal_pos)),
+        };
+
+        match chars.next() {
+            Some((idx, '\'')) => {
+                // +1 -> 終端の `'` 分
+                self.condition_position(idx + 1);
+                Ok(Token::new_char_literal(value, literal_pos))
+            }
+            _ => Err(CE::new(TEK::INVALIDCHARACTERLITERAL, literal_pos)),
+        }
     }
 
     /// 識別子 or 予約語
@@ -155,9 +223,17 @@ impl Tokenization {
     }
 
     /// 記号
-    fn scan_symbol(&mut self, s: &str) -> Token {
+    fn scan_symbol(&mut self, s: &str) -> Result<Token, CE<TEK>> {
         let symbol_pos = Position::new(self.row, self.column);
-        let symbol_str = s[..2].to_string();
+
+        // 先頭がマルチバイト文字なら記号ではない
+        let first = s.chars().next().unwrap();
+        if !first.is_ascii() {
+            return Err(CE::new(TEK::UNDEFINEDSYMBOL(first), symbol_pos));
+        }
+
+        // ファイル末尾の記号や，直後がマルチバイト文字の場合も考慮する
+        let symbol_str = s.get(..2).unwrap_or(&s[..1]).to_string();
 
         let symbol_kind = match symbol_str.as_str() {
             "->" | "::" | "//" => {
@@ -170,26 +246,39 @@ impl Tokenization {
                 let symbol_str = symbol_str.as_bytes()[0];
 
                 match symbol_str as char {
-                    '+' | '-' | '*' | '/' | ':' | ';' | '(' | ')' | '{' | '}' | '=' | ',' | '&'
-                    | '.' => {
+                    '+' | '-' | '*' | '/' | ':' | ';' | '(' | ')' | '{' | '}' | '[' | ']' | '='
+                    | ',' | '&' | '.' | '#' => {
                         self.condition_position(1);
                         TokenKind::new_symbol_from_str(&(symbol_str as char).to_string())
                     }
-                    _ => panic!("undefined such an symbol => '{}'", symbol_str as char),
+                    c => return Err(CE::new(TEK::UNDEFINEDSYMBOL(c), symbol_pos)),
                 }
             }
         };
 
-        Token::new(symbol_kind, symbol_pos)
+        Ok(Token::new(symbol_kind, symbol_pos))
     }
 
     /// コメント
+    /// `///` で始まるものはドキュメントコメントとしてパーサに渡す
     fn scan_comment(&mut self, s: &str) -> Token {
         let comment_pos = Position::new(self.row, self.column);
         let comment_str = cut_string_while(s, |c| c != &'\n');
         let len = comment_str.len();
         self.condition_position(len);
 
+        if comment_str.starts_with("///") && !comment_str.starts_with("////") {
+            let contents = comment_str[3..]
+                .strip_prefix(' ')
+                .unwrap_or(&comment_str[3..]);
+            return Token::new(
+                TokenKind::DOCCOMMENT {
+                    contents: contents.to_string(),
+                },
+                comment_pos,
+            );
+        }
+
         Token::new(
             TokenKind::COMMENT {
                 contents: comment_str,
@@ -198,33 +287,104 @@ impl Tokenization {
         )
     }
 
+    /// ブロックコメント `/* */`
+    /// 入れ子にでき，改行を含んでも良い
+    fn scan_block_comment(&mut self, s: &str) -> Result<Token, CE<TEK>> {
+        let comment_pos = Position::new(self.row, self.column);
+        let bytes = s.as_bytes();
+
+        let mut depth = 0;
+        let mut idx = 0;
+        while idx < bytes.len() {
+            match &bytes[idx..] {
+                [b'/', b'*', ..] => {
+                    depth += 1;
+                    idx += 2;
+                }
+                [b'*', b'/', ..] => {
+                    depth -= 1;
+                    idx += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => idx += 1,
+            }
+        }
+
+        if depth != 0 {
+            return Err(CE::new(TEK::UNTERMINATEDBLOCKCOMMENT, comment_pos));
+        }
+
+        // コメント中の改行の分，位置を進める
+        let comment_str = &s[..idx];
+        match comment_str.rfind('\n') {
+            Some(last_newline) => {
+                self.row += comment_str.matches('\n').count();
+                self.column = idx - last_newline;
+                self.cur_token_length = idx;
+            }
+            None => self.condition_position(idx),
+        }
+
+        Ok(Token::new(
+            TokenKind::COMMENT {
+                contents: comment_str.to_string(),
+            },
+            comment_pos,
+        ))
+    }
+
     /// 整数/非符号付き整数のトークン化
+    /// `0x`/`0o`/`0b` の接頭辞で基数を，`_` で桁の区切りを表せる
     fn scan_number(&mut self, s: &str) -> Result<Token, CE<TEK>> {
         let literal_pos = Position::new(self.row, self.column);
 
-        // 文字列を切り取る
-        let number_str = cut_string_while(s, |c| c.is_ascii_digit());
-        let len = number_str.len();
-        self.condition_position(len);
+        let radix = match s.get(..2) {
+            Some("0x") | Some("0X") => 16,
+            Some("0o") | Some("0O") => 8,
+            Some("0b") | Some("0B") => 2,
+            _ => 10,
+        };
+        let prefix_len = if radix == 10 { 0 } else { 2 };
+
+        // 接尾辞を含めて，英数字と `_` が続く限り一つのリテラルとみなす
+        let body = cut_string_while(&s[prefix_len..], |c| c.is_ascii_alphanumeric() || c == &'_');
+        let literal_str = format!("{}{}", &s[..prefix_len], body);
+        self.condition_position(literal_str.len());
 
-        let value = number_str.parse::<i64>();
+        // `100u` のようにuがついていればuint-literalとして処理
+        let (digits, unsigned) = match body.strip_suffix('u') {
+            Some(digits) => (digits, true),
+            None => (body.as_str(), false),
+        };
 
-        // 64bit整数として文字列を処理できなかった場合
-        if value.is_err() {
-            let err_pos = Position::new(self.row, self.column);
-            return Err(CE::new(TEK::INTEGERLITERALOUTOFRANGE(number_str), err_pos));
+        if !is_valid_digits(digits, radix) {
+            return Err(CE::new(
+                TEK::INVALIDINTEGERLITERAL(literal_str),
+                literal_pos,
+            ));
         }
 
-        // `100u` のようにuがついていればuint-literalとして処理
-        if s.len() > len && s.as_bytes()[len] == b'u' {
-            self.column += 1;
-            self.cur_token_length += 1;
-            let u_value = number_str.parse::<u64>();
+        let digits = digits.replace('_', "");
+        let out_of_range = || {
+            CE::new(
+                TEK::INTEGERLITERALOUTOFRANGE {
+                    literal: literal_str.clone(),
+                    radix,
+                    unsigned,
+                },
+                literal_pos,
+            )
+        };
 
-            return Ok(Token::new_uint_literal(u_value.unwrap(), literal_pos));
+        if unsigned {
+            let value = u64::from_str_radix(&digits, radix).map_err(|_| out_of_range())?;
+            return Ok(Token::new_uint_literal(value, literal_pos));
         }
 
-        Ok(Token::new_int_literal(value.unwrap(), literal_pos))
+        let value = i64::from_str_radix(&digits, radix).map_err(|_| out_of_range())?;
+        Ok(Token::new_int_literal(value, literal_pos))
     }
 
     /// 空白類文字のトークン化
@@ -250,14 +410,55 @@ fn cut_string_while(s: &str, f: fn(&char) -> bool) -> String {
     s.chars().take_while(f).collect::<String>()
 }
 
-fn push_eof_token(tokens: &mut Vec<Token>, row: usize, column: usize) {
-    let eof_pos = Position::new(row, column);
-    tokens.push(Token::new(TokenKind::EOF, eof_pos));
+/// 数字の並びが基数に対して正しいか
+/// `_` は桁と桁の間にのみ置ける(接頭辞の直後は許す)
+fn is_valid_digits(digits: &str, radix: u32) -> bool {
+    !digits.replace('_', "").is_empty()
+        && digits.chars().all(|c| c == '_' || c.is_digit(radix))
+        && !digits.ends_with('_')
+        && !digits.contains("__")
+        && (radix != 10 || !digits.starts_with('_'))
+}
+
+/// `\` に続くエスケープシーケンスを解釈する
+/// どのエスケープシーケンスも1バイトを表す
+/// 不正な場合は，エラー表示用にそのシーケンスを返す
+fn decode_escape(chars: &mut impl Iterator<Item = (usize, char)>) -> Result<u8, String> {
+    let escaped = match chars.next() {
+        Some((_, c)) => c,
+        None => return Err("\\".to_string()),
+    };
+
+    match escaped {
+        'n' => Ok(b'\n'),
+        't' => Ok(b'\t'),
+        '\\' => Ok(b'\\'),
+        '"' => Ok(b'"'),
+        '\'' => Ok(b'\''),
+        '0' => Ok(b'\0'),
+        'x' => {
+            let digits: String = chars.take(2).map(|(_, c)| c).collect();
+            decode_hex_escape(&digits)
+        }
+        c => Err(format!("\\{}", c)),
+    }
+}
+
+/// `\xNN` の16進2桁を解釈する
+/// `\x00` ~ `\xff` の任意のバイトを表せる
+fn decode_hex_escape(digits: &str) -> Result<u8, String> {
+    let sequence = format!("\\x{}", digits);
+    if digits.len() != 2 {
+        return Err(sequence);
+    }
+
+    u8::from_str_radix(digits, 16).map_err(|_| sequence)
 }
 
 #[cfg(test)]
 mod tokenizer_tests {
     use super::*;
+    use crate::common::token::literal_bytes;
 
     #[test]
     fn scan_number_test() {
@@ -268,7 +469,93 @@ mod tokenizer_tests {
         int_literal_helper(actual, 1000, Position::new(1, 1));
 
         let actual = tokenization.scan_number("1000u");
-        uint_literal_helper(actual, 1000, Position::new(1, 5))
+        uint_literal_helper(actual, 1000, Position::new(1, 5));
+
+        // 基数の接頭辞と桁区切り
+        let mut tokenization = new_tokenization();
+        let actual = tokenization.scan_number("0xff_FF;");
+        int_literal_helper(actual, 0xffff, Position::new(1, 1));
+        assert_eq!(7, tokenization.cur_token_length);
+
+        let actual = tokenization.scan_number("0o755");
+        int_literal_helper(actual, 0o755, Position::new(1, 8));
+
+        let actual = tokenization.scan_number("0b1010u");
+        uint_literal_helper(actual, 10, Position::new(1, 13));
+
+        let actual = tokenization.scan_number("1_000_000");
+        int_literal_helper(actual, 1_000_000, Position::new(1, 20));
+
+        let actual = tokenization.scan_number("18446744073709551615u");
+        uint_literal_helper(actual, u64::MAX, Position::new(1, 29));
+    }
+
+    #[test]
+    fn invalid_number_test() {
+        let mut tokenization = new_tokenization();
+
+        for literal in ["0x", "0b102", "1__0", "10_", "0o8", "12ab"] {
+            let actual = tokenization.scan_number(literal);
+            assert_eq!(
+                &TEK::INVALIDINTEGERLITERAL(literal.to_string()),
+                actual.unwrap_err().get_kind()
+            );
+        }
+
+        let actual = tokenization.scan_number("0x8000000000000000");
+        let err = actual.unwrap_err();
+        assert_eq!(
+            &TEK::INTEGERLITERALOUTOFRANGE {
+                literal: "0x8000000000000000".to_string(),
+                radix: 16,
+                unsigned: false,
+            },
+            err.get_kind()
+        );
+        assert_eq!(
+            "a hexadecimal int-literal `0x8000000000000000` out of range Int64 (max 0x7fffffffffffffff)",
+            err.get_kind().to_string()
+        );
+
+        let actual = tokenization
+            .scan_number("0b1_0000000000000000000000000000000000000000000000000000000000000000u");
+        assert!(matches!(
+            actual.unwrap_err().get_kind(),
+            TEK::INTEGERLITERALOUTOFRANGE {
+                radix: 2,
+                unsigned: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn scan_char_literal_test() {
+        let mut tokenization = new_tokenization();
+        let t = tokenization.scan_char_literal("'a')").unwrap();
+        assert_eq!(&TokenKind::CHARLIT { value: b'a' }, t.get_kind());
+        assert_eq!(3, tokenization.cur_token_length);
+
+        let t = tokenization.scan_char_literal("'\\n'").unwrap();
+        assert_eq!(&TokenKind::CHARLIT { value: b'\n' }, t.get_kind());
+        assert_eq!(4, tokenization.cur_token_length);
+
+        let t = tokenization.scan_char_literal("'\\''").unwrap();
+        assert_eq!(&TokenKind::CHARLIT { value: b'\'' }, t.get_kind());
+
+        let t = tokenization.scan_char_literal("'\\xff'").unwrap();
+        assert_eq!(&TokenKind::CHARLIT { value: 0xff }, t.get_kind());
+
+        for literal in ["''", "'ab'", "'a", "'あ'"] {
+            let t = tokenization.scan_char_literal(literal);
+            assert_eq!(&TEK::INVALIDCHARACTERLITERAL, t.unwrap_err().get_kind());
+        }
+
+        let t = tokenization.scan_char_literal("'\\q'");
+        assert_eq!(
+            &TEK::INVALIDESCAPESEQUENCE("\\q".to_string()),
+            t.unwrap_err().get_kind()
+        );
     }
 
     #[test]
@@ -291,7 +578,43 @@ mod tokenizer_tests {
     fn scan_string_literal_test() {
         let mut tokenization = new_tokenization();
         let t = tokenization.scan_string_literal("\"Drum\"");
-        string_literal_helper(t, "Drum", Position::new(1, 1));
+        string_literal_helper(t.unwrap(), "Drum", Position::new(1, 1));
+        assert_eq!(6, tokenization.cur_token_length);
+
+        // エスケープシーケンスは解釈済みの文字になる
+        let mut tokenization = new_tokenization();
+        let t = tokenization.scan_string_literal(r#""a\n\t\\\"\x41\0" + 1"#);
+        string_literal_helper(t.unwrap(), "a\n\t\\\"A\0", Position::new(1, 1));
+        assert_eq!(17, tokenization.cur_token_length);
+
+        // `\x80` 以上のバイトも，そのまま1バイトとして保持する
+        let mut tokenization = new_tokenization();
+        let t = tokenization.scan_string_literal(r#""\xff\x80é""#).unwrap();
+        match t.get_kind() {
+            TokenKind::STRLIT { contents } => {
+                assert_eq!(vec![0xff, 0x80, 0xc3, 0xa9], literal_bytes(contents))
+            }
+            _ => panic!("expected a string literal"),
+        }
+    }
+
+    #[test]
+    fn invalid_string_literal_test() {
+        let mut tokenization = new_tokenization();
+        let t = tokenization.scan_string_literal(r#""\q""#);
+        assert_eq!(
+            &TEK::INVALIDESCAPESEQUENCE("\\q".to_string()),
+            t.unwrap_err().get_kind()
+        );
+
+        let t = tokenization.scan_string_literal(r#""\xZZ""#);
+        assert_eq!(
+            &TEK::INVALIDESCAPESEQUENCE("\\xZZ".to_string()),
+            t.unwrap_err().get_kind()
+        );
+
+        let t = tokenization.scan_string_literal("\"abc");
+        assert_eq!(&TEK::UNTERMINATEDSTRINGLITERAL, t.unwrap_err().get_kind());
     }
 
     #[test]
@@ -310,11 +633,26 @@ mod tokenizer_tests {
     #[test]
     fn scan_symbol_test() {
         let mut tokenization = new_tokenization();
-        let t = tokenization.scan_symbol("+ ");
+        let t = tokenization.scan_symbol("+ ").unwrap();
         symbol_helper(t, TokenKind::PLUS, Position::new(1, 1));
 
-        let t = tokenization.scan_symbol("::");
+        let t = tokenization.scan_symbol("::").unwrap();
         symbol_helper(t, TokenKind::DOUBLECOLON, Position::new(1, 2));
+
+        let t = tokenization.scan_symbol("[T").unwrap();
+        symbol_helper(t, TokenKind::LBRACKET, Position::new(1, 4));
+
+        let t = tokenization.scan_symbol("}").unwrap();
+        symbol_helper(t, TokenKind::RBRACE, Position::new(1, 5));
+
+        let t = tokenization.scan_symbol("#[").unwrap();
+        symbol_helper(t, TokenKind::SHARP, Position::new(1, 6));
+
+        // 未定義の記号やマルチバイト文字はパニックせずエラーにする
+        let e = tokenization.scan_symbol("@").unwrap_err();
+        assert_eq!(&TEK::UNDEFINEDSYMBOL('@'), e.get_kind());
+        let e = tokenization.scan_symbol("あ").unwrap_err();
+        assert_eq!(&TEK::UNDEFINEDSYMBOL('あ'), e.get_kind());
     }
 
     #[test]
@@ -325,6 +663,40 @@ mod tokenizer_tests {
         assert_eq!(18, tokenization.cur_token_length);
     }
 
+    #[test]
+    fn scan_doc_comment_test() {
+        let mut tokenization = new_tokenization();
+        let t = tokenization.scan_comment("/// returns x\n");
+        assert!(!t.should_ignore());
+        assert_eq!(
+            &TokenKind::DOCCOMMENT {
+                contents: "returns x".to_string()
+            },
+            t.get_kind()
+        );
+
+        let t = tokenization.scan_comment("//// not a doc\n");
+        assert!(t.should_ignore());
+    }
+
+    #[test]
+    fn scan_block_comment_test() {
+        let mut tokenization = new_tokenization();
+        let t = tokenization.scan_block_comment("/* a /* nested */ b */ x");
+        assert!(t.unwrap().should_ignore());
+        assert_eq!(22, tokenization.cur_token_length);
+        assert_eq!(23, tokenization.column);
+
+        let mut tokenization = new_tokenization();
+        let t = tokenization.scan_block_comment("/* a\n  b */x");
+        assert!(t.unwrap().should_ignore());
+        assert_eq!(11, tokenization.cur_token_length);
+        assert_eq!((2, 7), (tokenization.row, tokenization.column));
+
+        let t = tokenization.scan_block_comment("/* /* */");
+        assert_eq!(&TEK::UNTERMINATEDBLOCKCOMMENT, t.unwrap_err().get_kind());
+    }
+
     #[test]
     fn scan_test() {
         let mut case = "100 200u \"String\"\nreturn_value ConstStr".to_string();
@@ -3,8 +3,9 @@ use std::sync::{Arc, Mutex};
 
 use id_arena::Arena;
 
-use crate::common::analyze_resource::peachili_type::Type;
+use crate::common::analyze_resource::peachili_type::{Type, TypeKind};
 use crate::common::option;
+use crate::common::token::literal_bytes;
 use crate::common::{ast, peachili_type, three_address_code as tac};
 
 type ValueCache = BTreeMap<ast::ExpressionNode, tac::ValueId>;
@@ -19,6 +20,9 @@ pub fn translate_ir(
 ) -> tac::IRModule {
     let mut ir_module: tac::IRModule = Default::default();
 
+    // 呼び出し側で引数の型(インタフェースかどうか)を知るために，仮引数の型を集めておく
+    let param_types = collect_param_types(fn_arena.clone(), &ast_root, type_env);
+
     // 関数列をイテレートし，IRFunctionの列に変換する
     for fn_id in ast_root.funcs.iter() {
         if let Ok(fn_arena) = fn_arena.lock() {
@@ -26,15 +30,24 @@ pub fn translate_ir(
                 // 呼び出されていない関数はコンパイル対象としない
                 // スタートアップルーチンやメイン関数は明示的に呼び出されないがコンパイルする
                 let not_startup_routine = ast_fn.full_path() != startup;
+                // メソッドは呼び出し先がレシーバの型で決まるため，常にコンパイルする
                 let not_main = ast_fn.name != "main";
+                let not_method = ast_fn.method_of.is_none();
                 if not_startup_routine
                     && not_main
+                    && not_method
                     && !ast_root.called_functions.contains(&ast_fn.full_path())
                 {
                     continue;
                 }
 
-                let ir_fn = gen_ir_fn(ast_fn, type_env, target);
+                let ir_fn = gen_ir_fn(
+                    ast_fn,
+                    type_env,
+                    &param_types,
+                    &mut ir_module.vtables,
+                    target,
+                );
                 let ir_fn_id = ir_module.fn_allocator.alloc(ir_fn);
                 ir_module.funcs.push(ir_fn_id);
             }
@@ -44,10 +57,38 @@ pub fn translate_ir(
     ir_module
 }
 
+/// 関数名 => 仮引数の型列 を集める
+fn collect_param_types(
+    fn_arena: ast::FnArena,
+    ast_root: &ast::ASTRoot,
+    type_env: &BTreeMap<String, BTreeMap<String, peachili_type::Type>>,
+) -> BTreeMap<String, Vec<Type>> {
+    let mut param_types = BTreeMap::new();
+    let fn_arena = fn_arena.lock().unwrap();
+
+    for fn_id in ast_root.funcs.iter() {
+        let ast_fn = fn_arena.get(*fn_id).unwrap();
+        let fn_env = type_env.get(&ast_fn.full_path()).unwrap();
+
+        param_types.insert(
+            ast_fn.full_path(),
+            ast_fn
+                .get_parameters()
+                .iter()
+                .map(|(name, _)| fn_env.get(name).unwrap().clone())
+                .collect(),
+        );
+    }
+
+    param_types
+}
+
 /// 関数単位でIRに変換する
 fn gen_ir_fn(
     ast_fn: &ast::Function,
     type_env: &BTreeMap<String, BTreeMap<String, peachili_type::Type>>,
+    param_types: &BTreeMap<String, Vec<Type>>,
+    vtables: &mut BTreeMap<String, Vec<String>>,
     target: option::Target,
 ) -> tac::IRFunction {
     // コード生成に必要な情報が多いので，構造体にまとめてメンバでやり取りする
@@ -55,7 +96,8 @@ fn gen_ir_fn(
         ast_fn.expr_arena.clone(),
         ast_fn.stmt_arena.clone(),
         type_env,
-        ast_fn.name.clone(),
+        param_types,
+        ast_fn.full_path(),
         target,
     );
 
@@ -64,6 +106,9 @@ fn gen_ir_fn(
         function_translator.gen_ir_from_stmt(&stmt_id);
     }
 
+    // 関数内のインタフェース変換で必要になったvtableを集める
+    vtables.append(&mut function_translator.vtables);
+
     // IRFunctionの生成
     tac::IRFunction {
         name: ast_fn.full_path(),
@@ -79,7 +124,8 @@ fn gen_ir_fn(
         args: ast_fn
             .get_parameters()
             .iter()
-            .map(|(name, _)| name.to_string())
+            .zip(param_types.get(&ast_fn.full_path()).unwrap().iter())
+            .map(|((name, _), ty)| (name.to_string(), ty.clone()))
             .collect(),
     }
 }
@@ -104,6 +150,10 @@ struct FunctionTranslator<'a> {
     expr_arena: ast::ExprArena,
     stmt_arena: ast::StmtArena,
     type_env: &'a BTreeMap<String, BTreeMap<String, peachili_type::Type>>,
+    /// 関数名 => 仮引数の型列
+    param_types: &'a BTreeMap<String, Vec<Type>>,
+    /// vtableのラベル => メソッドシンボル列
+    vtables: BTreeMap<String, Vec<String>>,
     target: option::Target,
 }
 
@@ -147,9 +197,19 @@ impl<'a> FunctionTranslator<'a> {
             }
             ast::StatementNodeKind::EXPR { expr: expr_id } => Some(self.gen_ir_from_expr(expr_id)),
             ast::StatementNodeKind::DECLARE {
-                ident_name: _,
+                ident_name,
                 type_name: _,
-            } => None,
+            } => {
+                // 不要なものは定義代入解析で取り除かれる
+                let id_value = self.value_arena.alloc(tac::Value::new(
+                    tac::ValueKind::ID {
+                        name: ident_name.to_string(),
+                    },
+                    self.search_identifier_type(ident_name),
+                ));
+                self.add_code_with_allocation(tac::CodeKind::ZEROINIT { value: id_value });
+                None
+            }
             _ => unimplemented!(),
         }
     }
@@ -171,9 +231,30 @@ impl<'a> FunctionTranslator<'a> {
     ) -> Option<tac::ValueId> {
         let id_type = self.search_identifier_type(&id_name);
         let id_value = self.value_arena.alloc(tac::Value {
-            kind: tac::ValueKind::ID { name: id_name },
-            ty: id_type,
+            kind: tac::ValueKind::ID {
+                name: id_name.clone(),
+            },
+            ty: id_type.clone(),
         });
+
+        // インタフェース型の変数は2ワードをそれぞれ格納する
+        if id_type.is_interface() {
+            let (data_v, vtable_v) = self.gen_interface_words(expr_id, &id_type);
+            let id_addr = self.gen_result_temp(Type::new_pointer(id_type, self.target));
+            self.add_code_with_allocation(tac::CodeKind::ADDRESSOF {
+                value: id_value,
+                result: id_addr,
+            });
+            self.gen_store_words(id_addr, data_v, vtable_v);
+            return None;
+        }
+
+        // 構造体型/配列型の変数には初期化式の値をブロック転送し，スライス型の変数には2ワードを格納する
+        if id_type.is_struct() || id_type.is_array() || id_type.is_slice() {
+            self.gen_store_value(expr_id, &id_type, |this| this.gen_local_address(&id_name));
+            return None;
+        }
+
         let expr_id = self.gen_ir_from_expr(expr_id);
         self.add_code_with_allocation(tac::CodeKind::ASSIGN {
             value: expr_id,
@@ -208,18 +289,17 @@ impl<'a> FunctionTranslator<'a> {
             }
             ast::ExpressionNodeKind::MEMBER { id, member } => {
                 // 構造体のベースアドレスをレジスタにロード
-                let id_v = self.gen_lvalue(id);
+                let (id_v, id_type) = self.gen_struct_base(id);
+
                 // メンバオフセットをプラスする
-                let id_names = self.copy_ast_expr(id).copy_names();
-                let id_type = self.search_identifier_type(&id_names.join("::"));
-                let member_type = id_type.get_members().get(member).unwrap();
+                let member_type = id_type.get_member(member).unwrap();
 
                 let member_addr =
                     self.gen_result_temp(Type::new_pointer(*member_type.0.clone(), self.target));
                 let member_offset_id = self
                     .value_arena
                     .alloc(tac::Value::new_int64(member_type.1 as i64, self.target));
-                self.add_code_with_allocation(tac::CodeKind::SUB {
+                self.add_code_with_allocation(tac::CodeKind::ADD {
                     lop: id_v,
                     rop: member_offset_id,
                     result: member_addr,
@@ -227,6 +307,15 @@ impl<'a> FunctionTranslator<'a> {
 
                 member_addr
             }
+            ast::ExpressionNodeKind::STRUCTLITERAL {
+                type_name: _,
+                fields,
+                storage,
+            } => {
+                self.gen_struct_literal(storage, fields);
+                self.gen_local_address(storage)
+            }
+            ast::ExpressionNodeKind::INDEX { value, index } => self.gen_element_addr(value, index),
             _ => unreachable!(),
         }
     }
@@ -243,6 +332,9 @@ impl<'a> FunctionTranslator<'a> {
             ast::ExpressionNodeKind::UINTEGER { value } => self
                 .value_arena
                 .alloc(tac::Value::new_uint64(*value, self.target)),
+            ast::ExpressionNodeKind::CHARACTER { value } => self
+                .value_arena
+                .alloc(tac::Value::new_uint8(*value, self.target)),
             ast::ExpressionNodeKind::IDENTIFIER { names } => {
                 self.value_arena.alloc(tac::Value::new(
                     tac::ValueKind::ID {
@@ -258,12 +350,26 @@ impl<'a> FunctionTranslator<'a> {
                 tac::Value::new_string_literal(contents.to_string(), self.target),
             ),
             ast::ExpressionNodeKind::MEMBER { id, member } => {
+                // 構造体変数の直接のメンバ以外(ポインタ経由やメンバの連鎖)は，
+                // アドレスを計算してからデリファレンスする
+                if !self.is_struct_variable(id) {
+                    let member_addr = self.gen_lvalue(expr_id);
+                    let member_type = self.value_arena.get(member_addr).unwrap().ty.clone();
+                    let result_v = self.gen_result_temp(member_type.pointer_to().clone());
+
+                    self.add_code_with_allocation(tac::CodeKind::DEREFERENCE {
+                        value: member_addr,
+                        result: result_v,
+                    });
+                    return result_v;
+                }
+
                 // 両方のオペランドをIRに変換する
                 let id = self.gen_ir_from_expr(id);
 
                 // 計算結果をTEMP変数に格納するコードを生成
                 let st_type = self.value_arena.get(id).unwrap().ty.clone();
-                let member_type = st_type.get_members().get(member).unwrap().0.clone();
+                let member_type = st_type.get_member(member).unwrap().0.clone();
                 let result_v = self.gen_result_temp(*member_type);
 
                 self.add_code_with_allocation(tac::CodeKind::MEMBER {
@@ -275,10 +381,83 @@ impl<'a> FunctionTranslator<'a> {
                 result_v
             }
 
+            ast::ExpressionNodeKind::STRUCTLITERAL {
+                type_name: _,
+                fields,
+                storage,
+            } => {
+                self.gen_struct_literal(storage, fields);
+                self.value_arena.alloc(tac::Value::new(
+                    tac::ValueKind::ID {
+                        name: storage.to_string(),
+                    },
+                    self.search_identifier_type(storage),
+                ))
+            }
+
+            ast::ExpressionNodeKind::INDEX { value, index } => {
+                let elem_addr = self.gen_element_addr(value, index);
+                let elem_type = self
+                    .value_arena
+                    .get(elem_addr)
+                    .unwrap()
+                    .ty
+                    .pointer_to()
+                    .clone();
+                let result_v = self.gen_result_temp(elem_type.clone());
+
+                // Uint8の要素は1バイトに詰められている
+                if elem_type.element_stride() == 1 {
+                    self.add_code_with_allocation(tac::CodeKind::LOADBYTE {
+                        addr: elem_addr,
+                        result: result_v,
+                    });
+                } else {
+                    self.add_code_with_allocation(tac::CodeKind::DEREFERENCE {
+                        value: elem_addr,
+                        result: result_v,
+                    });
+                }
+                result_v
+            }
+            ast::ExpressionNodeKind::SLICE {
+                value: _,
+                low: _,
+                high: _,
+            } => unreachable!("a slice expression is only used through its two words"),
+            ast::ExpressionNodeKind::LEN { value } => self.gen_len(value),
+
             // 代入式
             ast::ExpressionNodeKind::ASSIGN { lhs, rhs } => {
+                // 構造体/配列全体の代入はブロック転送に，スライスの代入は2ワードの格納になる
+                let lhs_type = self.addressable_type(lhs);
+                if lhs_type.is_struct() || lhs_type.is_array() || lhs_type.is_slice() {
+                    let lhs = *lhs;
+                    return self.gen_store_value(rhs, &lhs_type, |this| this.gen_lvalue(&lhs));
+                }
+
+                // Uint8の要素には1バイトだけ書き込む
+                if self.is_byte_element(lhs) {
+                    let value_id = self.gen_ir_from_expr(rhs);
+                    let elem_addr = self.gen_lvalue(lhs);
+                    self.add_code_with_allocation(tac::CodeKind::STOREBYTE {
+                        value: value_id,
+                        addr: elem_addr,
+                    });
+                    return value_id;
+                }
+
                 // オペランドをIRに変換する
                 let ident_id = self.gen_lvalue(lhs);
+
+                let lhs_type = self.value_arena.get(ident_id).unwrap().ty.clone();
+                if lhs_type.pointer_to().is_interface() {
+                    let (data_v, vtable_v) =
+                        self.gen_interface_words(rhs, &lhs_type.pointer_to().clone());
+                    self.gen_store_words(ident_id, data_v, vtable_v);
+                    return data_v;
+                }
+
                 let value_id = self.gen_ir_from_expr(rhs);
 
                 self.add_code_with_allocation(tac::CodeKind::STORE {
@@ -292,6 +471,16 @@ impl<'a> FunctionTranslator<'a> {
             // 計算結果を格納するTMP変数を返す
             ast::ExpressionNodeKind::NEG { value } => self.gen_ir_from_unop_expr("-", &expr, value),
             ast::ExpressionNodeKind::ADDRESSOF { value } => {
+                // 関数名のアドレスは，関数ポインタになる
+                if let Some((name, fn_type)) = self.function_named_by(value) {
+                    let result_v = self.gen_result_temp(Type::new_pointer(fn_type, self.target));
+                    self.add_code_with_allocation(tac::CodeKind::FUNCADDR {
+                        name,
+                        result: result_v,
+                    });
+                    return result_v;
+                }
+
                 self.gen_ir_from_unop_expr("&", &expr, value)
             }
             ast::ExpressionNodeKind::DEREFERENCE { value } => {
@@ -312,8 +501,36 @@ impl<'a> FunctionTranslator<'a> {
             ast::ExpressionNodeKind::DIV { lhs, rhs } => {
                 self.gen_ir_from_binop_expr("/", &expr, lhs, rhs)
             }
-            ast::ExpressionNodeKind::CALL { names, args } => {
-                self.gen_ir_from_call_expr(names.join("::"), args)
+            ast::ExpressionNodeKind::CALL {
+                names,
+                type_args,
+                args,
+            } => {
+                // 関数ポインタ型の変数を介した呼び出し
+                if let Some(var_type) = self.function_pointer_variable(&names.join("::")) {
+                    let callee_v = self.value_arena.alloc(tac::Value::new(
+                        tac::ValueKind::ID {
+                            name: names.join("::"),
+                        },
+                        var_type.clone(),
+                    ));
+                    return self.gen_ir_from_indirect_call_expr(
+                        callee_v,
+                        var_type.pointer_to(),
+                        args,
+                    );
+                }
+
+                // ジェネリック関数の呼び出しは，実体化された関数を呼ぶ
+                let name = if type_args.is_empty() {
+                    names.join("::")
+                } else {
+                    ast::generic_instance_name(&names.join("::"), type_args)
+                };
+                self.gen_ir_from_call_expr(name, args, 0)
+            }
+            ast::ExpressionNodeKind::METHODCALL { recv, method, args } => {
+                self.gen_ir_from_method_call_expr(recv, method, args)
             }
             ast::ExpressionNodeKind::IF {
                 cond_ex,
@@ -324,9 +541,15 @@ impl<'a> FunctionTranslator<'a> {
     }
 
     /// 呼び出し式のIRを生成する
-    fn gen_ir_from_call_expr(&mut self, name: String, args: &[ast::ExNodeId]) -> tac::ValueId {
+    /// first_paramは，argsが何番目の仮引数から対応するか(メソッドならレシーバの分ずれる)
+    fn gen_ir_from_call_expr(
+        &mut self,
+        name: String,
+        args: &[ast::ExNodeId],
+        first_param: usize,
+    ) -> tac::ValueId {
         // 引数を順にIRに変換 -> param {value} を生成
-        self.gen_parameters(args);
+        self.gen_parameters(&name, args, first_param);
 
         // 計算結果をTEMP変数に格納するコードを生成
         let call_fn_type = self.copy_type_in_called_func(&name, &name);
@@ -345,13 +568,683 @@ impl<'a> FunctionTranslator<'a> {
         result_v
     }
 
-    /// 各パラメータをコンパイルする
-    fn gen_parameters(&mut self, args: &[ast::ExNodeId]) {
-        for arg_id in args.iter() {
-            let arg_value_id = self.gen_ir_from_expr(arg_id);
-            self.add_code_with_allocation(tac::CodeKind::PARAM {
-                value: arg_value_id,
+    /// メソッド呼び出し式のIRを生成する
+    /// レシーバのアドレスを第一引数として渡す
+    fn gen_ir_from_method_call_expr(
+        &mut self,
+        recv_id: &ast::ExNodeId,
+        method: &str,
+        args: &[ast::ExNodeId],
+    ) -> tac::ValueId {
+        let recv_v = self.gen_receiver(recv_id);
+        let recv_type = self
+            .value_arena
+            .get(recv_v)
+            .unwrap()
+            .ty
+            .pointer_to()
+            .clone();
+
+        if recv_type.is_interface() {
+            return self.gen_ir_from_interface_call_expr(recv_v, &recv_type, method, args);
+        }
+
+        let struct_name = recv_type.get_struct_name().to_string();
+        let symbol = ast::method_symbol(&struct_name, method);
+
+        // メソッドがなければ，関数ポインタ型のメンバを介した呼び出し
+        if !self.param_types.contains_key(&symbol) {
+            let callee_v = self.gen_member_load(recv_v, &recv_type, method);
+            let fn_type = self
+                .value_arena
+                .get(callee_v)
+                .unwrap()
+                .ty
+                .pointer_to()
+                .clone();
+            return self.gen_ir_from_indirect_call_expr(callee_v, &fn_type, args);
+        }
+
+        self.add_code_with_allocation(tac::CodeKind::PARAM { value: recv_v });
+        self.gen_ir_from_call_expr(symbol, args, 1)
+    }
+
+    /// 関数ポインタを介した呼び出しのIRを生成する
+    fn gen_ir_from_indirect_call_expr(
+        &mut self,
+        callee_v: tac::ValueId,
+        fn_type: &Type,
+        args: &[ast::ExNodeId],
+    ) -> tac::ValueId {
+        self.gen_parameters_with_types(fn_type.get_function_args(), args, 0);
+
+        let result_v = self.gen_result_temp(fn_type.get_return_type().clone());
+        self.add_code_with_allocation(tac::CodeKind::ICALL {
+            callee: callee_v,
+            result: result_v,
+        });
+
+        result_v
+    }
+
+    /// メンバアクセスの対象となる構造体のベースアドレスと，構造体型を求める
+    /// 構造体へのポインタであれば自動的にデリファレンスする(`p.x` は `(*p).x` と同じ)
+    fn gen_struct_base(&mut self, expr_id: &ast::ExNodeId) -> (tac::ValueId, Type) {
+        // 呼び出し結果などアドレスを持たない式は，構造体へのポインタ値そのものを使う
+        if !self.is_addressable(expr_id) {
+            let ptr_v = self.gen_ir_from_expr(expr_id);
+            let st_type = self.value_arena.get(ptr_v).unwrap().ty.pointer_to().clone();
+            return (ptr_v, st_type);
+        }
+
+        let addr_v = self.gen_lvalue(expr_id);
+        let base_type = self
+            .value_arena
+            .get(addr_v)
+            .unwrap()
+            .ty
+            .pointer_to()
+            .clone();
+        if base_type.is_struct() {
+            return (addr_v, base_type);
+        }
+
+        let ptr_v = self.gen_result_temp(base_type.clone());
+        self.add_code_with_allocation(tac::CodeKind::DEREFERENCE {
+            value: addr_v,
+            result: ptr_v,
+        });
+        (ptr_v, base_type.pointer_to().clone())
+    }
+
+    /// 値を評価してから，dst_addrで求めたアドレスに格納する
+    /// 値を先に評価して格納先アドレスの寿命を短くし，一時レジスタの再利用と衝突しないようにする
+    /// 格納先のアドレスを返す
+    fn gen_store_value<F>(
+        &mut self,
+        value_id: &ast::ExNodeId,
+        dst_type: &Type,
+        dst_addr: F,
+    ) -> tac::ValueId
+    where
+        F: FnOnce(&mut Self) -> tac::ValueId,
+    {
+        if dst_type.is_struct() || dst_type.is_array() {
+            let src_v = self.gen_lvalue(value_id);
+            let dst_v = dst_addr(self);
+            self.add_code_with_allocation(tac::CodeKind::COPY {
+                src: src_v,
+                dst: dst_v,
+                size: dst_type.size,
+            });
+            return dst_v;
+        }
+
+        if dst_type.is_interface() {
+            let (data_v, vtable_v) = self.gen_interface_words(value_id, dst_type);
+            let dst_v = dst_addr(self);
+            self.gen_store_words(dst_v, data_v, vtable_v);
+            return dst_v;
+        }
+
+        if dst_type.is_slice() {
+            let (ptr_v, len_v) = self.gen_sequence_words(value_id);
+            let dst_v = dst_addr(self);
+            self.gen_store_words(dst_v, ptr_v, len_v);
+            return dst_v;
+        }
+
+        let value_v = self.gen_ir_from_expr(value_id);
+        let dst_v = dst_addr(self);
+        self.add_code_with_allocation(tac::CodeKind::STORE {
+            value: value_v,
+            result: dst_v,
+        });
+        dst_v
+    }
+
+    /// 構造体リテラルを一時領域に組み立てる
+    fn gen_struct_literal(&mut self, storage: &str, fields: &[(String, ast::ExNodeId)]) {
+        let st_type = self.search_identifier_type(storage);
+
+        for (member, value_id) in fields.iter() {
+            let (member_type, member_offset) = st_type.get_member(member).unwrap().clone();
+
+            // メンバごとに一時領域のアドレスを計算し直す
+            self.gen_store_value(value_id, &member_type, |this| {
+                let base_v = this.gen_local_address(storage);
+                let member_addr =
+                    this.gen_result_temp(Type::new_pointer(*member_type.clone(), this.target));
+                let member_offset_id = this
+                    .value_arena
+                    .alloc(tac::Value::new_int64(member_offset as i64, this.target));
+                this.add_code_with_allocation(tac::CodeKind::ADD {
+                    lop: base_v,
+                    rop: member_offset_id,
+                    result: member_addr,
+                });
+                member_addr
+            });
+        }
+    }
+
+    /// ローカル変数のアドレスを計算する
+    fn gen_local_address(&mut self, name: &str) -> tac::ValueId {
+        let var_type = self.search_identifier_type(name);
+        let var_v = self.value_arena.alloc(tac::Value::new(
+            tac::ValueKind::ID {
+                name: name.to_string(),
+            },
+            var_type.clone(),
+        ));
+        let addr_v = self.gen_result_temp(Type::new_pointer(var_type, self.target));
+        self.add_code_with_allocation(tac::CodeKind::ADDRESSOF {
+            value: var_v,
+            result: addr_v,
+        });
+        addr_v
+    }
+
+    /// アドレスを持つ式の型を，コードを生成せずに求める
+    fn addressable_type(&self, expr_id: &ast::ExNodeId) -> Type {
+        match self.copy_ast_expr(expr_id).get_kind() {
+            ast::ExpressionNodeKind::IDENTIFIER { names } => {
+                self.search_identifier_type(&names.join("::"))
+            }
+            ast::ExpressionNodeKind::DEREFERENCE { value } => {
+                self.addressable_type(value).pointer_to().clone()
+            }
+            ast::ExpressionNodeKind::MEMBER { id, member } => {
+                let id_type = self.addressable_type(id);
+                let st_type = id_type.receiver_struct().unwrap();
+                *st_type.get_member(member).unwrap().0.clone()
+            }
+            ast::ExpressionNodeKind::STRUCTLITERAL {
+                type_name: _,
+                fields: _,
+                storage,
+            } => self.search_identifier_type(storage),
+            ast::ExpressionNodeKind::INDEX { value, index: _ } => {
+                self.sequence_type(value).element_type().clone()
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// 添字/スライス/lenの対象となる式の型を，コードを生成せずに求める
+    /// 文字列リテラルは `[]Uint8` として扱う
+    fn sequence_type(&self, expr_id: &ast::ExNodeId) -> Type {
+        match self.copy_ast_expr(expr_id).get_kind() {
+            ast::ExpressionNodeKind::STRING { contents: _ } => {
+                Type::new_slice(Type::new_uint8(self.target), self.target)
+            }
+            ast::ExpressionNodeKind::SLICE {
+                value,
+                low: _,
+                high: _,
+            } => Type::new_slice(
+                self.sequence_type(value).element_type().clone(),
+                self.target,
+            ),
+            _ => self.addressable_type(expr_id),
+        }
+    }
+
+    /// 列を構成する (先頭要素のアドレス, 要素数) を生成する
+    fn gen_sequence_words(&mut self, expr_id: &ast::ExNodeId) -> (tac::ValueId, tac::ValueId) {
+        let expr = self.copy_ast_expr(expr_id);
+
+        match expr.get_kind() {
+            ast::ExpressionNodeKind::STRING { contents } => {
+                // 文字列リテラルのアドレスはraxに置かれるので，TEMPに退避しておく
+                let literal_v = self.gen_ir_from_expr(expr_id);
+                let ptr_v = self
+                    .gen_result_temp(Type::new_pointer(Type::new_uint8(self.target), self.target));
+                self.add_code_with_allocation(tac::CodeKind::ASSIGN {
+                    value: literal_v,
+                    result: ptr_v,
+                });
+
+                let len_v = self.value_arena.alloc(tac::Value::new_int64(
+                    literal_bytes(contents).len() as i64,
+                    self.target,
+                ));
+                (ptr_v, len_v)
+            }
+            ast::ExpressionNodeKind::SLICE { value, low, high } => {
+                self.gen_slice_words(value, low, high)
+            }
+            // スライス型の変数は，2ワードを直接読み出す
+            ast::ExpressionNodeKind::IDENTIFIER { names }
+                if self.addressable_type(expr_id).is_slice() =>
+            {
+                let seq_v = self.gen_ir_from_expr(expr_id);
+                let seq_type = self.search_identifier_type(&names.join("::"));
+
+                let ptr_v = self.gen_result_temp(Type::new_pointer(
+                    seq_type.element_type().clone(),
+                    self.target,
+                ));
+                self.add_code_with_allocation(tac::CodeKind::MEMBER {
+                    id: seq_v,
+                    member: "ptr".to_string(),
+                    result: ptr_v,
+                });
+                let len_v = self.gen_result_temp(Type::new_int64(self.target));
+                self.add_code_with_allocation(tac::CodeKind::MEMBER {
+                    id: seq_v,
+                    member: "len".to_string(),
+                    result: len_v,
+                });
+                (ptr_v, len_v)
+            }
+            _ => {
+                let seq_addr = self.gen_lvalue(expr_id);
+                let seq_type = self
+                    .value_arena
+                    .get(seq_addr)
+                    .unwrap()
+                    .ty
+                    .pointer_to()
+                    .clone();
+
+                // 配列は変数の領域そのものが要素列で，要素数は型から決まる
+                if seq_type.is_array() {
+                    let len_v = self.value_arena.alloc(tac::Value::new_int64(
+                        seq_type.array_length() as i64,
+                        self.target,
+                    ));
+                    return (seq_addr, len_v);
+                }
+
+                let ptr_v = self.gen_result_temp(Type::new_pointer(
+                    seq_type.element_type().clone(),
+                    self.target,
+                ));
+                self.add_code_with_allocation(tac::CodeKind::DEREFERENCE {
+                    value: seq_addr,
+                    result: ptr_v,
+                });
+
+                let len_addr = self.gen_second_word_addr(seq_addr);
+                let len_v = self.gen_result_temp(Type::new_int64(self.target));
+                self.add_code_with_allocation(tac::CodeKind::DEREFERENCE {
+                    value: len_addr,
+                    result: len_v,
+                });
+                (ptr_v, len_v)
+            }
+        }
+    }
+
+    /// スライス式 `value[low:high]` の2ワードを生成する
+    /// 0 <= low <= high <= len(value) でなければ中断する
+    fn gen_slice_words(
+        &mut self,
+        value_id: &ast::ExNodeId,
+        low: &Option<ast::ExNodeId>,
+        high: &Option<ast::ExNodeId>,
+    ) -> (tac::ValueId, tac::ValueId) {
+        let elem_type = self.sequence_type(value_id).element_type().clone();
+        let (ptr_v, len_v) = self.gen_sequence_words(value_id);
+
+        let low_v = match low {
+            Some(low) => self.gen_ir_from_expr(low),
+            None => self
+                .value_arena
+                .alloc(tac::Value::new_int64(0, self.target)),
+        };
+        let high_v = match high {
+            Some(high) => self.gen_ir_from_expr(high),
+            None => len_v,
+        };
+
+        self.add_code_with_allocation(tac::CodeKind::BOUNDSCHECK {
+            index: high_v,
+            len: len_v,
+            inclusive: true,
+        });
+        self.add_code_with_allocation(tac::CodeKind::BOUNDSCHECK {
+            index: low_v,
+            len: high_v,
+            inclusive: true,
+        });
+
+        let new_len = self.gen_result_temp(Type::new_int64(self.target));
+        self.add_code_with_allocation(tac::CodeKind::SUB {
+            lop: high_v,
+            rop: low_v,
+            result: new_len,
+        });
+        let new_ptr = self.gen_element_offset(ptr_v, low_v, &elem_type);
+
+        (new_ptr, new_len)
+    }
+
+    /// 添字式 `value[index]` の要素のアドレスを生成する
+    /// 0 <= index < len(value) でなければ中断する
+    fn gen_element_addr(
+        &mut self,
+        value_id: &ast::ExNodeId,
+        index_id: &ast::ExNodeId,
+    ) -> tac::ValueId {
+        // 添字を先に評価して，列の2ワードの寿命を短くする
+        let index_v = self.gen_ir_from_expr(index_id);
+        let elem_type = self.sequence_type(value_id).element_type().clone();
+        let (ptr_v, len_v) = self.gen_sequence_words(value_id);
+
+        self.add_code_with_allocation(tac::CodeKind::BOUNDSCHECK {
+            index: index_v,
+            len: len_v,
+            inclusive: false,
+        });
+
+        self.gen_element_offset(ptr_v, index_v, &elem_type)
+    }
+
+    /// 先頭要素のアドレスからindex個先の要素のアドレスを求める
+    fn gen_element_offset(
+        &mut self,
+        ptr_v: tac::ValueId,
+        index_v: tac::ValueId,
+        elem_type: &Type,
+    ) -> tac::ValueId {
+        let stride = elem_type.element_stride();
+        let offset_v = if stride == 1 {
+            index_v
+        } else if let tac::ValueKind::INTLITERAL { value } =
+            self.value_arena.get(index_v).unwrap().kind
+        {
+            // 定数の添字はオフセットも定数になる
+            self.value_arena
+                .alloc(tac::Value::new_int64(value * stride as i64, self.target))
+        } else {
+            let stride_v = self
+                .value_arena
+                .alloc(tac::Value::new_int64(stride as i64, self.target));
+            let offset_v = self.gen_result_temp(Type::new_int64(self.target));
+            self.add_code_with_allocation(tac::CodeKind::MUL {
+                lop: index_v,
+                rop: stride_v,
+                result: offset_v,
             });
+            offset_v
+        };
+
+        let elem_addr = self.gen_result_temp(Type::new_pointer(elem_type.clone(), self.target));
+        self.add_code_with_allocation(tac::CodeKind::ADD {
+            lop: ptr_v,
+            rop: offset_v,
+            result: elem_addr,
+        });
+        elem_addr
+    }
+
+    /// 組み込みのlen
+    /// 配列と文字列リテラルの長さはコンパイル時に決まる
+    fn gen_len(&mut self, value_id: &ast::ExNodeId) -> tac::ValueId {
+        let seq_type = self.sequence_type(value_id);
+        if seq_type.is_array() {
+            return self.value_arena.alloc(tac::Value::new_int64(
+                seq_type.array_length() as i64,
+                self.target,
+            ));
+        }
+        if let ast::ExpressionNodeKind::STRING { contents } =
+            self.copy_ast_expr(value_id).get_kind()
+        {
+            return self.value_arena.alloc(tac::Value::new_int64(
+                literal_bytes(contents).len() as i64,
+                self.target,
+            ));
+        }
+
+        self.gen_sequence_words(value_id).1
+    }
+
+    /// 構造体のアドレスからメンバの値をロードする
+    fn gen_member_load(
+        &mut self,
+        st_addr: tac::ValueId,
+        st_type: &Type,
+        member: &str,
+    ) -> tac::ValueId {
+        let (member_type, member_offset) = st_type.get_member(member).unwrap().clone();
+
+        let member_addr =
+            self.gen_result_temp(Type::new_pointer(*member_type.clone(), self.target));
+        let member_offset_id = self
+            .value_arena
+            .alloc(tac::Value::new_int64(member_offset as i64, self.target));
+        self.add_code_with_allocation(tac::CodeKind::ADD {
+            lop: st_addr,
+            rop: member_offset_id,
+            result: member_addr,
+        });
+
+        let result_v = self.gen_result_temp(*member_type);
+        self.add_code_with_allocation(tac::CodeKind::DEREFERENCE {
+            value: member_addr,
+            result: result_v,
+        });
+        result_v
+    }
+
+    /// インタフェース値を介したメソッド呼び出しのIRを生成する
+    /// データポインタを第一引数とし，vtableを引いて間接呼び出しする
+    fn gen_ir_from_interface_call_expr(
+        &mut self,
+        iface_addr: tac::ValueId,
+        iface_type: &Type,
+        method: &str,
+        args: &[ast::ExNodeId],
+    ) -> tac::ValueId {
+        let data_v = self.gen_result_temp(self.interface_word_type());
+        self.add_code_with_allocation(tac::CodeKind::DEREFERENCE {
+            value: iface_addr,
+            result: data_v,
+        });
+        self.add_code_with_allocation(tac::CodeKind::PARAM { value: data_v });
+
+        self.gen_parameters("", args, 0);
+
+        let (index, return_type) = iface_type.get_interface_method(method).unwrap();
+        let result_v = self.gen_result_temp(return_type.clone());
+        self.add_code_with_allocation(tac::CodeKind::VCALL {
+            iface: iface_addr,
+            index,
+            result: result_v,
+        });
+
+        result_v
+    }
+
+    /// インタフェース値を構成する (データポインタ, vtableのアドレス) を生成する
+    /// 構造体(へのポインタ)からの変換であれば，対応するvtableを登録する
+    fn gen_interface_words(
+        &mut self,
+        expr_id: &ast::ExNodeId,
+        iface_type: &Type,
+    ) -> (tac::ValueId, tac::ValueId) {
+        let expr = self.copy_ast_expr(expr_id);
+
+        let data_v = match expr.get_kind() {
+            ast::ExpressionNodeKind::IDENTIFIER { names: _ }
+            | ast::ExpressionNodeKind::MEMBER { id: _, member: _ }
+            | ast::ExpressionNodeKind::DEREFERENCE { value: _ } => {
+                let src_addr = self.gen_lvalue(expr_id);
+                let src_type = self.value_arena.get(src_addr).unwrap().ty.clone();
+
+                // 同じインタフェース型の値であれば2ワードをそのまま読み出す
+                if src_type.pointer_to().is_interface() {
+                    return self.gen_load_interface_words(src_addr);
+                }
+
+                if src_type.pointer_to().is_struct() {
+                    src_addr
+                } else {
+                    let ptr_v = self.gen_result_temp(src_type.pointer_to().clone());
+                    self.add_code_with_allocation(tac::CodeKind::DEREFERENCE {
+                        value: src_addr,
+                        result: ptr_v,
+                    });
+                    ptr_v
+                }
+            }
+            _ => self.gen_ir_from_expr(expr_id),
+        };
+
+        let struct_name = self
+            .value_arena
+            .get(data_v)
+            .unwrap()
+            .ty
+            .pointer_to()
+            .get_struct_name()
+            .to_string();
+        let label = self.register_vtable(&struct_name, iface_type);
+
+        let vtable_v = self.gen_result_temp(self.interface_word_type());
+        self.add_code_with_allocation(tac::CodeKind::VTABLE {
+            label,
+            result: vtable_v,
+        });
+
+        (data_v, vtable_v)
+    }
+
+    /// インタフェース値のアドレスから2ワードを読み出す
+    fn gen_load_interface_words(
+        &mut self,
+        iface_addr: tac::ValueId,
+    ) -> (tac::ValueId, tac::ValueId) {
+        let data_v = self.gen_result_temp(self.interface_word_type());
+        self.add_code_with_allocation(tac::CodeKind::DEREFERENCE {
+            value: iface_addr,
+            result: data_v,
+        });
+
+        let vtable_addr = self.gen_second_word_addr(iface_addr);
+        let vtable_v = self.gen_result_temp(self.interface_word_type());
+        self.add_code_with_allocation(tac::CodeKind::DEREFERENCE {
+            value: vtable_addr,
+            result: vtable_v,
+        });
+
+        (data_v, vtable_v)
+    }
+
+    /// 2ワードからなる値(インタフェース値/スライス)のアドレスに，2ワードを書き込む
+    fn gen_store_words(
+        &mut self,
+        dst_addr: tac::ValueId,
+        first_v: tac::ValueId,
+        second_v: tac::ValueId,
+    ) {
+        self.add_code_with_allocation(tac::CodeKind::STORE {
+            value: first_v,
+            result: dst_addr,
+        });
+
+        let second_addr = self.gen_second_word_addr(dst_addr);
+        self.add_code_with_allocation(tac::CodeKind::STORE {
+            value: second_v,
+            result: second_addr,
+        });
+    }
+
+    /// 2ワード目(vtableポインタ/スライスの長さ)は，1ワード目の1ワード上位に置かれる
+    fn gen_second_word_addr(&mut self, first_addr: tac::ValueId) -> tac::ValueId {
+        let word_size = self
+            .value_arena
+            .alloc(tac::Value::new_int64(8, self.target));
+        let second_addr =
+            self.gen_result_temp(Type::new_pointer(self.interface_word_type(), self.target));
+        self.add_code_with_allocation(tac::CodeKind::ADD {
+            lop: first_addr,
+            rop: word_size,
+            result: second_addr,
+        });
+
+        second_addr
+    }
+
+    /// (構造体, インタフェース) の組に対するvtableを登録し，ラベルを返す
+    fn register_vtable(&mut self, struct_name: &str, iface_type: &Type) -> String {
+        let label = tac::IRModule::vtable_label(struct_name, iface_type.get_interface_name());
+
+        if let TypeKind::INTERFACE { name: _, methods } = &iface_type.kind {
+            let symbols = methods
+                .iter()
+                .map(|(method_name, _)| ast::method_symbol(struct_name, method_name))
+                .collect();
+            self.vtables.insert(label.clone(), symbols);
+        }
+
+        label
+    }
+
+    /// インタフェース値を構成するワードは指す先の型を持たないので，Uint64として扱う
+    fn interface_word_type(&self) -> Type {
+        Type::new_uint64(self.target)
+    }
+
+    /// レシーバをコンパイルする
+    /// 構造体型/インタフェース型の値であれば自動的にアドレスを取る
+    fn gen_receiver(&mut self, recv_id: &ast::ExNodeId) -> tac::ValueId {
+        let recv = self.copy_ast_expr(recv_id);
+
+        match recv.get_kind() {
+            ast::ExpressionNodeKind::IDENTIFIER { names: _ }
+            | ast::ExpressionNodeKind::MEMBER { id: _, member: _ }
+            | ast::ExpressionNodeKind::DEREFERENCE { value: _ } => {
+                let recv_addr = self.gen_lvalue(recv_id);
+                let recv_type = self.value_arena.get(recv_addr).unwrap().ty.clone();
+
+                if recv_type.pointer_to().is_struct() || recv_type.pointer_to().is_interface() {
+                    return recv_addr;
+                }
+
+                self.gen_ir_from_expr(recv_id)
+            }
+            _ => self.gen_ir_from_expr(recv_id),
+        }
+    }
+
+    /// 各パラメータをコンパイルする
+    /// インタフェース型/スライス型の仮引数には2ワードを順に渡す
+    fn gen_parameters(&mut self, callee: &str, args: &[ast::ExNodeId], first_param: usize) {
+        let param_types = self.param_types.get(callee).cloned().unwrap_or_default();
+        self.gen_parameters_with_types(&param_types, args, first_param);
+    }
+
+    /// 仮引数の型列に従って各パラメータをコンパイルする
+    /// スライス型の仮引数には (先頭要素のアドレス, 要素数) を順に渡す
+    fn gen_parameters_with_types(
+        &mut self,
+        param_types: &[Type],
+        args: &[ast::ExNodeId],
+        first_param: usize,
+    ) {
+        for (arg_idx, arg_id) in args.iter().enumerate() {
+            match param_types.get(first_param + arg_idx) {
+                Some(param_type) if param_type.is_interface() => {
+                    let (data_v, vtable_v) = self.gen_interface_words(arg_id, param_type);
+                    self.add_code_with_allocation(tac::CodeKind::PARAM { value: data_v });
+                    self.add_code_with_allocation(tac::CodeKind::PARAM { value: vtable_v });
+                }
+                Some(param_type) if param_type.is_slice() => {
+                    let (ptr_v, len_v) = self.gen_sequence_words(arg_id);
+                    self.add_code_with_allocation(tac::CodeKind::PARAM { value: ptr_v });
+                    self.add_code_with_allocation(tac::CodeKind::PARAM { value: len_v });
+                }
+                _ => {
+                    let arg_value_id = self.gen_ir_from_expr(arg_id);
+                    self.add_code_with_allocation(tac::CodeKind::PARAM {
+                        value: arg_value_id,
+                    });
+                }
+            }
         }
     }
 
@@ -574,6 +1467,71 @@ impl<'a> FunctionTranslator<'a> {
             },
         }
     }
+    /// 関数ポインタ型の変数であれば，その型を返す
+    fn function_pointer_variable(&self, id_name: &str) -> Option<Type> {
+        let locals = self.type_env.get(&self.fn_name).unwrap();
+        match locals.get(id_name) {
+            Some(var_type) if var_type.is_function_pointer() => Some(var_type.clone()),
+            _ => None,
+        }
+    }
+    /// 変数でなく関数を指す識別子であれば，関数名と関数型を返す
+    fn function_named_by(&self, expr_id: &ast::ExNodeId) -> Option<(String, Type)> {
+        let expr = self.copy_ast_expr(expr_id);
+        if !expr.is_identifier() {
+            return None;
+        }
+
+        let name = expr.copy_names().join("::");
+        if self
+            .type_env
+            .get(&self.fn_name)
+            .unwrap()
+            .contains_key(&name)
+        {
+            return None;
+        }
+
+        // 関数自体の型は，関数名のスコープに関数名で格納されている
+        let fn_type = self.type_env.get(&name)?.get(&name)?;
+        if !fn_type.is_function() {
+            return None;
+        }
+
+        Some((name, fn_type.clone()))
+    }
+    /// 構造体型の変数を指す識別子であるか
+    fn is_struct_variable(&self, expr_id: &ast::ExNodeId) -> bool {
+        let expr = self.copy_ast_expr(expr_id);
+        if !expr.is_identifier() {
+            return false;
+        }
+
+        self.search_identifier_type(&expr.copy_names().join("::"))
+            .is_struct()
+    }
+    /// アドレスを持つ式(変数，デリファレンス，メンバアクセス，添字，構造体リテラル)であるか
+    fn is_addressable(&self, expr_id: &ast::ExNodeId) -> bool {
+        matches!(
+            self.copy_ast_expr(expr_id).get_kind(),
+            ast::ExpressionNodeKind::IDENTIFIER { names: _ }
+                | ast::ExpressionNodeKind::DEREFERENCE { value: _ }
+                | ast::ExpressionNodeKind::MEMBER { id: _, member: _ }
+                | ast::ExpressionNodeKind::INDEX { value: _, index: _ }
+                | ast::ExpressionNodeKind::STRUCTLITERAL {
+                    type_name: _,
+                    fields: _,
+                    storage: _,
+                }
+        )
+    }
+    /// 1バイトに詰められたUint8の要素を指す添字式であるか
+    fn is_byte_element(&self, expr_id: &ast::ExNodeId) -> bool {
+        matches!(
+            self.copy_ast_expr(expr_id).get_kind(),
+            ast::ExpressionNodeKind::INDEX { value: _, index: _ }
+        ) && self.addressable_type(expr_id).element_stride() == 1
+    }
     fn copy_type_in_called_func(&self, called_fn: &str, id_name: &str) -> Type {
         self.type_env
             .get(called_fn)
@@ -595,6 +1553,7 @@ impl<'a> FunctionTranslator<'a> {
         expr_arena: ast::ExprArena,
         stmt_arena: ast::StmtArena,
         type_env: &'a BTreeMap<String, BTreeMap<String, peachili_type::Type>>,
+        param_types: &'a BTreeMap<String, Vec<Type>>,
         fn_name: String,
         target: option::Target,
     ) -> Self {
@@ -608,11 +1567,10 @@ impl<'a> FunctionTranslator<'a> {
             expr_arena,
             stmt_arena,
             type_env,
+            param_types,
+            vtables: BTreeMap::new(),
             fn_name,
             target,
         }
     }
 }
-
-#[cfg(test)]
-mod translate_tests {}
@@ -62,6 +62,34 @@ pub enum CodeKind {
     ALLOC {
         temp: ValueId,
     },
+    /// declareされた変数の領域を0で埋める
+    /// 定義代入解析で，代入前に読まれうる変数のものだけが残る
+    ZEROINIT {
+        value: ValueId,
+    },
+    /// srcのアドレスからdstのアドレスへ，sizeバイトをブロック転送する
+    COPY {
+        src: ValueId,
+        dst: ValueId,
+        size: usize,
+    },
+    /// addrの1バイトをゼロ拡張して読み出す
+    LOADBYTE {
+        addr: ValueId,
+        result: ValueId,
+    },
+    /// valueの下位1バイトをaddrに書き込む
+    STOREBYTE {
+        value: ValueId,
+        addr: ValueId,
+    },
+    /// index < len (inclusiveなら index <= len) でなければ中断ルーチンへ飛ぶ
+    /// 符号なしで比較するので，負の添字も範囲外になる
+    BOUNDSCHECK {
+        index: ValueId,
+        len: ValueId,
+        inclusive: bool,
+    },
     LABEL {
         name: String,
     },
@@ -75,6 +103,28 @@ pub enum CodeKind {
     ASM {
         value: ValueId,
     },
+    /// vtableのアドレスをロードする
+    VTABLE {
+        label: String,
+        result: ValueId,
+    },
+    /// 関数のアドレスをロードする
+    FUNCADDR {
+        name: String,
+        result: ValueId,
+    },
+    /// 関数ポインタを介した間接呼び出し
+    ICALL {
+        callee: ValueId,
+        result: ValueId,
+    },
+    /// インタフェース値を介した間接呼び出し
+    /// ifaceはインタフェース値のアドレス，indexはvtable内のスロット
+    VCALL {
+        iface: ValueId,
+        index: usize,
+        result: ValueId,
+    },
 }
 
 impl CodeKind {
@@ -213,6 +263,73 @@ impl CodeKind {
                     .dump();
                 format!("alloc {}", allocated,)
             }
+            CodeKind::ZEROINIT { value } => {
+                let v = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*value)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                format!("zeroinit {}", v)
+            }
+            CodeKind::COPY { src, dst, size } => {
+                let src = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*src)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                let dst = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*dst)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                format!("copy {} bytes from {} to {}", size, src, dst)
+            }
+            CodeKind::LOADBYTE { addr, result } => Self::unop("byte *", result, addr, value_arena),
+            CodeKind::STOREBYTE { value, addr } => {
+                let addr = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*addr)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                let value = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*value)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                format!("store byte {} into {}", value, addr)
+            }
+            CodeKind::BOUNDSCHECK {
+                index,
+                len,
+                inclusive,
+            } => {
+                let index = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*index)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                let len = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*len)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                let op = if *inclusive { "<=" } else { "<" };
+                format!("check 0 <= {} {} {}", index, op, len)
+            }
             CodeKind::LABEL { name } => format!("label {}", name,),
             CodeKind::JUMPIFFALSE { label, cond_result } => {
                 let cond = value_arena
@@ -235,6 +352,64 @@ impl CodeKind {
                     .dump();
                 format!("asm {}", v)
             }
+            CodeKind::VTABLE { label, result } => {
+                let result = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*result)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                format!("{} <- vtable {}", result, label)
+            }
+            CodeKind::FUNCADDR { name, result } => {
+                let result = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*result)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                format!("{} <- &{}", result, name)
+            }
+            CodeKind::ICALL { callee, result } => {
+                let result = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*result)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                let callee = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*callee)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                format!("{} <- icall {}", result, callee)
+            }
+            CodeKind::VCALL {
+                iface,
+                index,
+                result,
+            } => {
+                let result = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*result)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                let iface = value_arena
+                    .lock()
+                    .unwrap()
+                    .get(*iface)
+                    .unwrap()
+                    .clone()
+                    .dump();
+                format!("{} <- vcall {}[{}]", result, iface, index)
+            }
         }
     }
 }
@@ -18,7 +18,8 @@ pub struct IRFunction {
 
     pub value_allocator: ValueArena,
     pub code_allocator: CodeArena,
-    pub args: Vec<String>,
+    /// 仮引数名と型の組
+    pub args: Vec<(String, peachili_type::Type)>,
 }
 
 #[allow(dead_code)]
@@ -1,10 +1,13 @@
 use crate::common::three_address_code::*;
 use id_arena::Arena;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
 pub struct IRModule {
     pub funcs: Vec<function::IRFunctionId>,
     pub fn_allocator: Arena<function::IRFunction>,
+    /// vtableのラベル => メソッドシンボル列(スロット順)
+    pub vtables: BTreeMap<String, Vec<String>>,
 }
 
 impl Default for IRModule {
@@ -12,11 +15,17 @@ impl Default for IRModule {
         Self {
             funcs: Vec::new(),
             fn_allocator: Arena::new(),
+            vtables: BTreeMap::new(),
         }
     }
 }
 
 impl IRModule {
+    /// (構造体, インタフェース) の組に対するvtableのラベル
+    pub fn vtable_label(struct_name: &str, interface_name: &str) -> String {
+        format!("{}.vtable.{}", struct_name, interface_name)
+    }
+
     pub fn get_fn(&self, fn_id: &function::IRFunctionId) -> &function::IRFunction {
         self.fn_allocator.get(*fn_id).unwrap()
     }
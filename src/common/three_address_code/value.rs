@@ -33,6 +33,14 @@ impl Value {
             Type::new_uint64(target),
         )
     }
+    pub fn new_uint8(value: u8, target: Target) -> Self {
+        Self::new(
+            value_kind::ValueKind::UINTLITERAL {
+                value: value as u64,
+            },
+            Type::new_uint8(target),
+        )
+    }
     pub fn new_boolean(truth: bool, target: Target) -> Self {
         Self::new(
             value_kind::ValueKind::BOOLEANLITERAL { truth },
@@ -0,0 +1,120 @@
+mod outcome;
+
+pub use outcome::*;
+
+use std::path::{Path, PathBuf};
+
+/// `peachili difftest` のメインルーチン
+/// 各プログラムをx64のネイティブコードとインタプリタの両方で実行し，
+/// 終了コードと標準出力が食い違ったものがあれば終了コード1で終了する
+pub fn main(paths: Vec<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let mut sources = Vec::new();
+    for path in paths.iter() {
+        collect_sources(Path::new(path), &mut sources)?;
+    }
+
+    let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+
+    for source in sources.iter() {
+        let source = source.to_string_lossy();
+
+        // 型検査を通らないもの(エラーを確かめる例など)は比較しない
+        let checked = run_subcommand(&["check", &source, "--target", "x86_64"])?;
+        if checked.exit_code != 0 {
+            eprintln!("{} => skipped (does not compile)", source);
+            skipped += 1;
+            continue;
+        }
+
+        let native = run_subcommand(&["run", &source, "--target", "x86_64"])?;
+        let interp = run_subcommand(&["run", "--interp", &source, "--target", "x86_64"])?;
+
+        // 両方が同じように失敗しても，一致とはみなさない
+        let mismatches = compare(&native, &interp);
+        if native.ran() && interp.ran() && mismatches.is_empty() {
+            eprintln!("{} => ok ({})", source, native.exit_code);
+            passed += 1;
+            continue;
+        }
+
+        if native.ran() && interp.ran() {
+            eprintln!("{} => mismatch", source);
+            for mismatch in mismatches.iter() {
+                eprintln!("    {}", mismatch);
+            }
+        } else {
+            eprintln!("{} => failed to run", source);
+        }
+        for (name, outcome) in [("native", &native), ("interp", &interp)].iter() {
+            if !outcome.stderr.is_empty() {
+                eprintln!("    {} stderr:", name);
+                for line in String::from_utf8_lossy(&outcome.stderr).lines() {
+                    eprintln!("        {}", line);
+                }
+            }
+        }
+        failed += 1;
+    }
+
+    eprintln!(
+        "\n{} passed, {} failed, {} skipped",
+        passed, failed, skipped
+    );
+    if failed != 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// ディレクトリは再帰的に辿り，含まれる.goファイルを名前順に集める
+fn collect_sources(path: &Path, sources: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.is_dir() {
+        sources.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    entries.sort();
+
+    for entry in entries.iter() {
+        if entry.is_dir() {
+            collect_sources(entry, sources)?;
+        } else if entry.extension().is_some_and(|ext| ext == "go") {
+            sources.push(entry.clone());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod difftest_tests {
+    use super::*;
+
+    #[test]
+    fn collect_sources_test() {
+        let dir = std::env::temp_dir().join(format!("peachili-difftest-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        for file in ["b.go", "a.go", "README.md", "nested/c.go"].iter() {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+
+        let mut sources = Vec::new();
+        collect_sources(&dir, &mut sources).unwrap();
+        collect_sources(Path::new("explicit.txt"), &mut sources).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            vec![
+                dir.join("a.go"),
+                dir.join("b.go"),
+                dir.join("nested/c.go"),
+                PathBuf::from("explicit.txt"),
+            ],
+            sources
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::interpreter;
+use std::os::unix::process::ExitStatusExt;
+use std::process::Command;
+
+/// プログラムを実行した結果のうち，比較に用いるもの
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Outcome {
+    pub exit_code: i32,
+    pub stdout: Vec<u8>,
+    /// 比較はせず，食い違いの報告に添える
+    pub stderr: Vec<u8>,
+}
+
+/// Rustのパニックによる終了コード
+const PANICKED: i32 = 101;
+
+impl Outcome {
+    /// コンパイラ/インタプリタ自身が失敗せず，プログラムが終了まで実行されたか
+    pub fn ran(&self) -> bool {
+        self.exit_code != interpreter::FAILED_TO_RUN && self.exit_code != PANICKED
+    }
+}
+
+/// ネイティブ実行とインタプリタ実行の食い違い
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Mismatch {
+    EXITCODE { native: i32, interp: i32 },
+    STDOUT { native: String, interp: String },
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mismatch::EXITCODE { native, interp } => write!(
+                f,
+                "exit code differs: native => {}, interp => {}",
+                native, interp
+            ),
+            Mismatch::STDOUT { native, interp } => write!(
+                f,
+                "stdout differs: native => {:?}, interp => {:?}",
+                native, interp
+            ),
+        }
+    }
+}
+
+/// 終了コードと標準出力を比べる
+pub fn compare(native: &Outcome, interp: &Outcome) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+
+    if native.exit_code != interp.exit_code {
+        mismatches.push(Mismatch::EXITCODE {
+            native: native.exit_code,
+            interp: interp.exit_code,
+        });
+    }
+    if native.stdout != interp.stdout {
+        mismatches.push(Mismatch::STDOUT {
+            native: String::from_utf8_lossy(&native.stdout).to_string(),
+            interp: String::from_utf8_lossy(&interp.stdout).to_string(),
+        });
+    }
+
+    mismatches
+}
+
+/// 自身のサブコマンドを子プロセスとして実行する
+/// コンパイラ内部のパニックやexitが，他のファイルの検査に波及しないようにするため
+pub fn run_subcommand(args: &[&str]) -> std::io::Result<Outcome> {
+    let exe = std::env::current_exe()?;
+    let output = Command::new(exe)
+        .args(args)
+        .env("NO_COLOR", "1")
+        .env("RUST_BACKTRACE", "0")
+        .output()?;
+
+    // シグナルで終了した場合は，シェルと同じく128+シグナル番号とする
+    let exit_code = output
+        .status
+        .code()
+        .or_else(|| output.status.signal().map(|sig| 128 + sig))
+        .unwrap_or(1);
+
+    Ok(Outcome {
+        exit_code,
+        stdout: output.stdout,
+        stderr: output.stderr,
+    })
+}
+
+#[cfg(test)]
+mod outcome_tests {
+    use super::*;
+
+    #[test]
+    fn compare_test() {
+        let native = new_outcome(0, "Hello, world!\n");
+
+        assert!(compare(&native, &new_outcome(0, "Hello, world!\n")).is_empty());
+        assert_eq!(
+            vec![
+                Mismatch::EXITCODE {
+                    native: 0,
+                    interp: 1
+                },
+                Mismatch::STDOUT {
+                    native: "Hello, world!\n".to_string(),
+                    interp: String::new(),
+                },
+            ],
+            compare(&native, &new_outcome(1, "")),
+        );
+    }
+
+    #[test]
+    fn ran_test() {
+        assert!(new_outcome(1, "").ran());
+        assert!(!new_outcome(interpreter::FAILED_TO_RUN, "").ran());
+        assert!(!new_outcome(PANICKED, "").ran());
+    }
+
+    fn new_outcome(exit_code: i32, stdout: &str) -> Outcome {
+        Outcome {
+            exit_code,
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: b"ignored".to_vec(),
+        }
+    }
+}
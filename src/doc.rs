@@ -0,0 +1,51 @@
+mod api_doc;
+mod render;
+
+pub use api_doc::*;
+pub use render::*;
+
+use crate::common::{file_util, module, pass};
+use std::fs;
+use std::path::Path;
+
+/// `peachili doc` のメインルーチン
+/// モジュールごとにMarkdownとHTMLを出力ディレクトリへ書き出す
+pub fn main(
+    module_arena: module::ModuleArena,
+    main_module_id: module::ModuleId,
+    output_dir: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (fn_arena, ast_root, tld_env) = pass::collect_declarations(module_arena, main_module_id);
+    let module_docs = collect_module_docs(fn_arena, &ast_root, &tld_env);
+
+    fs::create_dir_all(output_dir)?;
+    let output_path = |file_name: String| {
+        Path::new(output_dir)
+            .join(file_name)
+            .to_string_lossy()
+            .to_string()
+    };
+
+    for module_doc in module_docs.iter() {
+        let stem = module_doc.file_stem();
+        file_util::write_program_into(
+            &output_path(format!("{}.md", stem)),
+            render_markdown(module_doc),
+        );
+        file_util::write_program_into(
+            &output_path(format!("{}.html", stem)),
+            render_html(module_doc),
+        );
+    }
+
+    file_util::write_program_into(
+        &output_path("index.md".to_string()),
+        render_index_markdown(&module_docs),
+    );
+    file_util::write_program_into(
+        &output_path("index.html".to_string()),
+        render_index_html(&module_docs),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,385 @@
+use crate::common::{ast, tld};
+use std::collections::BTreeMap;
+
+/// モジュール単位のAPIドキュメント
+pub struct ModuleDoc {
+    /// `std::os` のようなモジュール名．メインモジュールは空文字列
+    pub name: String,
+    pub items: Vec<ItemDoc>,
+}
+
+/// 宣言1つ分のドキュメント
+pub struct ItemDoc {
+    pub kind: ItemKind,
+    pub name: String,
+    /// Peachiliの構文で書いた宣言
+    pub signature: String,
+    pub doc: Option<String>,
+}
+
+/// 宣言の種類
+/// この順に節を分けて出力する
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Copy)]
+pub enum ItemKind {
+    CONST,
+    ALIAS,
+    STRUCT,
+    ENUM,
+    INTERFACE,
+    FUNCTION,
+}
+
+impl ItemKind {
+    pub fn section_title(&self) -> &'static str {
+        match self {
+            ItemKind::CONST => "Constants",
+            ItemKind::ALIAS => "Type Aliases",
+            ItemKind::STRUCT => "Structs",
+            ItemKind::ENUM => "Enums",
+            ItemKind::INTERFACE => "Interfaces",
+            ItemKind::FUNCTION => "Functions",
+        }
+    }
+}
+
+impl ModuleDoc {
+    /// 表示用のモジュール名
+    pub fn display_name(&self) -> &str {
+        if self.name.is_empty() {
+            return "main";
+        }
+        &self.name
+    }
+
+    /// 出力ファイル名(拡張子なし)
+    pub fn file_stem(&self) -> String {
+        self.display_name().replace("::", ".")
+    }
+}
+
+/// TLD解析の結果とASTから，モジュールごとのドキュメントを構築する
+/// 関数はTLDではモジュール名が失われるので，ASTから直接集める
+pub fn collect_module_docs(
+    fn_arena: ast::FnArena,
+    ast_root: &ast::ASTRoot,
+    tld_env: &BTreeMap<String, tld::TopLevelDecl>,
+) -> Vec<ModuleDoc> {
+    let mut modules: BTreeMap<String, Vec<ItemDoc>> = BTreeMap::new();
+
+    for (full_name, decl) in tld_env.iter() {
+        let (kind, signature) = match decl_signature(full_name, &decl.kind) {
+            Some(entry) => entry,
+            None => continue,
+        };
+        let (module_name, name) = split_module(full_name);
+
+        modules.entry(module_name).or_default().push(ItemDoc {
+            kind,
+            name,
+            signature,
+            doc: ast_root.docs.get(full_name).cloned(),
+        });
+    }
+
+    for fn_id in ast_root.funcs.iter().chain(ast_root.generic_funcs.iter()) {
+        let func = fn_arena.lock().unwrap().get(*fn_id).unwrap().clone();
+
+        let name = match &func.method_of {
+            Some(struct_name) => ast::method_symbol(&split_module(struct_name).1, &func.name),
+            None => func.name.clone(),
+        };
+
+        modules
+            .entry(func.module_name.clone())
+            .or_default()
+            .push(ItemDoc {
+                kind: ItemKind::FUNCTION,
+                name,
+                signature: function_signature(&func),
+                doc: ast_root.docs.get(&func.full_path()).cloned(),
+            });
+    }
+
+    modules
+        .into_iter()
+        .map(|(name, mut items)| {
+            items.sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
+            ModuleDoc { name, items }
+        })
+        .collect()
+}
+
+/// 関数以外の宣言を，Peachiliの構文で表す
+fn decl_signature(full_name: &str, kind: &tld::TLDKind) -> Option<(ItemKind, String)> {
+    let name = split_module(full_name).1;
+
+    let entry = match kind {
+        tld::TLDKind::CONST { type_name, expr } => (
+            ItemKind::CONST,
+            format!(
+                "pubconst {} : {} = {};",
+                name,
+                display_type(type_name),
+                expr
+            ),
+        ),
+        tld::TLDKind::ALIAS { src_type } => (
+            ItemKind::ALIAS,
+            format!("pubtype {} = {};", name, display_type(src_type)),
+        ),
+        tld::TLDKind::STRUCT {
+            members,
+            methods: _,
+        } => (ItemKind::STRUCT, struct_signature(&name, &[], members)),
+        tld::TLDKind::GENERICSTRUCT {
+            type_params,
+            members,
+        } => (
+            ItemKind::STRUCT,
+            struct_signature(&name, type_params, members),
+        ),
+        tld::TLDKind::ENUM { variants } => {
+            let mut variants: Vec<(&String, &usize)> = variants.iter().collect();
+            variants.sort_by_key(|(_, tag)| **tag);
+
+            let mut signature = format!("pubenum {} {{\n", name);
+            for (variant, _) in variants {
+                signature += &format!("    {},\n", variant);
+            }
+            signature += "}";
+            (ItemKind::ENUM, signature)
+        }
+        tld::TLDKind::INTERFACE { methods } => {
+            let mut signature = format!("pubinterface {} {{\n", name);
+            for (method, (args, return_type)) in methods.iter() {
+                signature += &format!(
+                    "    {}({}) {}\n",
+                    method,
+                    arg_list(args),
+                    display_type(return_type)
+                );
+            }
+            signature += "}";
+            (ItemKind::INTERFACE, signature)
+        }
+        // 関数はASTから集める
+        tld::TLDKind::FN { .. } | tld::TLDKind::GENERICFN { .. } => return None,
+    };
+
+    Some(entry)
+}
+
+fn struct_signature(name: &str, type_params: &[String], members: &[(String, String)]) -> String {
+    let mut signature = format!("struct {}{} {{\n", name, type_param_list(type_params));
+    for (member, member_type) in members.iter() {
+        signature += &format!(
+            "    {} {}\n",
+            member,
+            unqualify_type_params(member_type, type_params)
+        );
+    }
+    signature += "}";
+    signature
+}
+
+/// `func (p *Point) norm() Int64` のように，関数のシグネチャを表す
+fn function_signature(func: &ast::Function) -> String {
+    let mut args = func.get_parameters().clone();
+    for (_, arg_type) in args.iter_mut() {
+        *arg_type = unqualify_type_params(arg_type, &func.type_params);
+    }
+
+    // レシーバは第一引数として保持されている
+    let receiver = if func.method_of.is_some() && !args.is_empty() {
+        let (recv_name, recv_type) = args.remove(0);
+        format!("({} {}) ", recv_name, display_type(&recv_type))
+    } else {
+        String::new()
+    };
+
+    format!(
+        "func {}{}{}({}) {}",
+        receiver,
+        func.name,
+        type_param_list(&func.type_params),
+        arg_list(&args),
+        unqualify_type_params(&func.copy_return_type(), &func.type_params)
+    )
+}
+
+fn arg_list(args: &[(String, String)]) -> String {
+    args.iter()
+        .map(|(arg_name, arg_type)| format!("{} {}", arg_name, display_type(arg_type)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// 型パラメータはモジュール名で修飾されているので，宣言時の名前に戻す
+fn type_param_list(type_params: &[String]) -> String {
+    if type_params.is_empty() {
+        return String::new();
+    }
+
+    let names: Vec<String> = type_params
+        .iter()
+        .map(|param| split_module(param).1)
+        .collect();
+    format!("[{}]", names.join(", "))
+}
+
+fn unqualify_type_params(type_name: &str, type_params: &[String]) -> String {
+    let type_name = type_params
+        .iter()
+        .fold(type_name.to_string(), |acc, param| {
+            acc.replace(param.as_str(), &split_module(param).1)
+        });
+    display_type(&type_name)
+}
+
+/// メインモジュールの型は `::Point` のように空のモジュール名で修飾されているので，
+/// 先頭の `::` を取り除いて表示する
+pub fn display_type(type_name: &str) -> String {
+    let mut displayed = String::new();
+    let mut rest = type_name;
+
+    while let Some(idx) = rest.find("::") {
+        let qualified = displayed
+            .chars()
+            .chain(rest[..idx].chars())
+            .last()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        displayed += &rest[..idx];
+        if qualified {
+            displayed += "::";
+        }
+        rest = &rest[idx + 2..];
+    }
+
+    displayed + rest
+}
+
+/// `std::os::exit` を (`std::os`, `exit`) に分ける
+pub fn split_module(full_name: &str) -> (String, String) {
+    match full_name.rsplit_once("::") {
+        Some((module_name, name)) => (module_name.to_string(), name.to_string()),
+        None => (String::new(), full_name.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod api_doc_tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn decl_signature_test() {
+        let (kind, signature) = decl_signature(
+            "x64::STDOUT",
+            &tld::TopLevelDecl::new_const("x64::FileDescriptor", "1".to_string()).kind,
+        )
+        .unwrap();
+        assert_eq!(ItemKind::CONST, kind);
+        assert_eq!("pubconst STDOUT : x64::FileDescriptor = 1;", signature);
+
+        let mut variants = BTreeMap::new();
+        variants.insert("RED".to_string(), 1);
+        variants.insert("BLUE".to_string(), 0);
+        let (_, signature) =
+            decl_signature("color::Color", &tld::TLDKind::ENUM { variants }).unwrap();
+        assert_eq!("pubenum Color {\n    BLUE,\n    RED,\n}", signature);
+
+        let (_, signature) = decl_signature(
+            "geo::Pair",
+            &tld::TLDKind::GENERICSTRUCT {
+                type_params: vec!["geo::T".to_string()],
+                members: vec![("first".to_string(), "geo::T".to_string())],
+            },
+        )
+        .unwrap();
+        assert_eq!("struct Pair[T] {\n    first T\n}", signature);
+    }
+
+    #[test]
+    fn display_type_test() {
+        assert_eq!("Point", display_type("::Point"));
+        assert_eq!("*Point", display_type("*::Point"));
+        assert_eq!("[]std::os::Fd", display_type("[]std::os::Fd"));
+        assert_eq!("*Pair[Int64]", display_type("*::Pair[Int64]"));
+    }
+
+    #[test]
+    fn collect_module_docs_test() {
+        let fn_arena: ast::FnArena = Arc::new(Mutex::new(Default::default()));
+        let mut ast_root: ast::ASTRoot = Default::default();
+
+        let norm = new_func(
+            "norm",
+            "geo",
+            Some("geo::Point"),
+            vec![("p".to_string(), "*geo::Point".to_string())],
+        );
+        let exit = new_func(
+            "exit",
+            "",
+            None,
+            vec![("status".to_string(), "Int64".to_string())],
+        );
+        ast_root.docs.insert(norm.full_path(), "長さ".to_string());
+        ast_root.funcs.push(fn_arena.lock().unwrap().alloc(norm));
+        ast_root.funcs.push(fn_arena.lock().unwrap().alloc(exit));
+
+        let mut tld_env = BTreeMap::new();
+        tld_env.insert(
+            "geo::Point".to_string(),
+            tld::TopLevelDecl::new(tld::TLDKind::STRUCT {
+                members: vec![("x".to_string(), "Int64".to_string())],
+                methods: BTreeMap::new(),
+            }),
+        );
+        tld_env.insert(
+            "exit".to_string(),
+            tld::TopLevelDecl::new(tld::TLDKind::FN {
+                return_type: "Noreturn".to_string(),
+                args: Vec::new(),
+            }),
+        );
+
+        let docs = collect_module_docs(fn_arena, &ast_root, &tld_env);
+        assert_eq!(2, docs.len());
+
+        assert_eq!("main", docs[0].display_name());
+        assert_eq!(1, docs[0].items.len());
+        assert_eq!(
+            "func exit(status Int64) Noreturn",
+            docs[0].items[0].signature
+        );
+
+        assert_eq!("geo", docs[1].file_stem());
+        let names: Vec<&str> = docs[1].items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(vec!["Point", "Point.norm"], names);
+        assert_eq!(
+            "func (p *geo::Point) norm() Noreturn",
+            docs[1].items[1].signature
+        );
+        assert_eq!(Some("長さ".to_string()), docs[1].items[1].doc);
+    }
+
+    fn new_func(
+        name: &str,
+        module_name: &str,
+        method_of: Option<&str>,
+        args: Vec<(String, String)>,
+    ) -> ast::Function {
+        ast::Function {
+            name: name.to_string(),
+            stmts: Vec::new(),
+            pos: Default::default(),
+            module_name: module_name.to_string(),
+            method_of: method_of.map(|s| s.to_string()),
+            type_params: Vec::new(),
+            fn_type: ast::FunctionTypeDef::new("Noreturn".to_string(), args),
+            stmt_arena: Arc::new(Mutex::new(Default::default())),
+            expr_arena: Arc::new(Mutex::new(Default::default())),
+        }
+    }
+}
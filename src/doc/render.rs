@@ -0,0 +1,146 @@
+use crate::doc::{ItemDoc, ItemKind, ModuleDoc};
+
+/// モジュールのドキュメントをMarkdownで表す
+pub fn render_markdown(module_doc: &ModuleDoc) -> String {
+    let mut output = format!("# Module `{}`\n", module_doc.display_name());
+
+    for (kind, items) in sections(module_doc) {
+        output += &format!("\n## {}\n", kind.section_title());
+
+        for item in items {
+            output += &format!("\n### `{}`\n\n", item.name);
+            output += &format!("```go\n{}\n```\n", item.signature);
+            if let Some(doc) = &item.doc {
+                output += &format!("\n{}\n", doc);
+            }
+        }
+    }
+
+    output
+}
+
+/// モジュールのドキュメントをHTMLで表す
+pub fn render_html(module_doc: &ModuleDoc) -> String {
+    let title = format!("Module {}", escape_html(module_doc.display_name()));
+    let mut body = format!(
+        "<h1>{}</h1>\n<p><a href=\"index.html\">index</a></p>\n",
+        title
+    );
+
+    for (kind, items) in sections(module_doc) {
+        body += &format!("<h2>{}</h2>\n", kind.section_title());
+
+        for item in items {
+            body += &format!(
+                "<h3 id=\"{0}\"><code>{0}</code></h3>\n",
+                escape_html(&item.name)
+            );
+            body += &format!("<pre><code>{}</code></pre>\n", escape_html(&item.signature));
+            if let Some(doc) = &item.doc {
+                body += &format!("<p>{}</p>\n", escape_html(doc).replace('\n', "<br>\n"));
+            }
+        }
+    }
+
+    html_page(&title, &body)
+}
+
+/// モジュール一覧をMarkdownで表す
+pub fn render_index_markdown(module_docs: &[ModuleDoc]) -> String {
+    let mut output = "# Modules\n\n".to_string();
+    for module_doc in module_docs.iter() {
+        output += &format!(
+            "- [{}]({}.md)\n",
+            module_doc.display_name(),
+            module_doc.file_stem()
+        );
+    }
+    output
+}
+
+/// モジュール一覧をHTMLで表す
+pub fn render_index_html(module_docs: &[ModuleDoc]) -> String {
+    let mut body = "<h1>Modules</h1>\n<ul>\n".to_string();
+    for module_doc in module_docs.iter() {
+        body += &format!(
+            "<li><a href=\"{}.html\">{}</a></li>\n",
+            escape_html(&module_doc.file_stem()),
+            escape_html(module_doc.display_name())
+        );
+    }
+    body += "</ul>\n";
+
+    html_page("Modules", &body)
+}
+
+/// 宣言の種類ごとにまとめる
+/// itemsは種類順に並んでいる前提
+fn sections(module_doc: &ModuleDoc) -> Vec<(ItemKind, Vec<&ItemDoc>)> {
+    let mut sections: Vec<(ItemKind, Vec<&ItemDoc>)> = Vec::new();
+
+    for item in module_doc.items.iter() {
+        match sections.last_mut() {
+            Some((kind, items)) if *kind == item.kind => items.push(item),
+            _ => sections.push((item.kind, vec![item])),
+        }
+    }
+
+    sections
+}
+
+fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        title, body
+    )
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod render_tests {
+    use super::*;
+
+    #[test]
+    fn render_markdown_test() {
+        let module_doc = ModuleDoc {
+            name: "x64".to_string(),
+            items: vec![
+                ItemDoc {
+                    kind: ItemKind::CONST,
+                    name: "STDOUT".to_string(),
+                    signature: "pubconst STDOUT : x64::FileDescriptor = 1;".to_string(),
+                    doc: Some("標準出力".to_string()),
+                },
+                ItemDoc {
+                    kind: ItemKind::FUNCTION,
+                    name: "exit_with".to_string(),
+                    signature: "func exit_with(status Int64) Noreturn".to_string(),
+                    doc: None,
+                },
+            ],
+        };
+
+        assert_eq!(
+            "# Module `x64`\n\n## Constants\n\n### `STDOUT`\n\n```go\npubconst STDOUT : x64::FileDescriptor = 1;\n```\n\n標準出力\n\n## Functions\n\n### `exit_with`\n\n```go\nfunc exit_with(status Int64) Noreturn\n```\n",
+            render_markdown(&module_doc)
+        );
+
+        let html = render_html(&module_doc);
+        assert!(html.contains("<h2>Constants</h2>"));
+        assert!(html.contains("<pre><code>func exit_with(status Int64) Noreturn</code></pre>"));
+    }
+
+    #[test]
+    fn escape_html_test() {
+        assert_eq!(
+            "func f(p *Pair[T]) &lt;&amp;&gt; &quot;",
+            escape_html("func f(p *Pair[T]) <&> \"")
+        );
+    }
+}
@@ -0,0 +1,49 @@
+mod layout;
+
+pub use layout::*;
+
+use crate::common::file_util;
+
+/// `peachili fmt` のメインルーチン
+/// checkが真のときはファイルを書き換えず，整形されていないファイルがあれば終了コード1で終了する
+pub fn main(files: Vec<&str>, check: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut unformatted = Vec::new();
+
+    for file in files.iter() {
+        let source = match file_util::read_program_from_file(file) {
+            Some(source) => source,
+            None => {
+                eprintln!("{} not found", file);
+                std::process::exit(1);
+            }
+        };
+
+        let formatted = match format_source(&source) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("in {}:", file);
+                e.output();
+                std::process::exit(1);
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            unformatted.push(*file);
+        } else {
+            file_util::write_program_into(file, formatted);
+        }
+    }
+
+    if !unformatted.is_empty() {
+        for file in unformatted.iter() {
+            eprintln!("{} is not formatted", file);
+        }
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,671 @@
+use crate::common::{
+    error::{CompileError as CE, TokenizeErrorKind as TEK},
+    pass,
+    token::{Token, TokenKind},
+};
+
+/// インデント1段分
+const INDENT: &str = "    ";
+
+/// ソースコードを正規のレイアウトに整形する
+/// コメントは残し，連続する空行は1行にまとめる
+pub fn format_source(source: &str) -> Result<String, CE<TEK>> {
+    let lexemes = collect_lexemes(pass::tokenize_with_trivia(source.to_string())?);
+    let mut layout = Layout::new(lexemes);
+
+    for i in 0..layout.lexemes.len() {
+        layout.put(i);
+    }
+
+    Ok(layout.finish())
+}
+
+/// 整形に必要な情報を付与したトークン
+struct Lexeme {
+    kind: TokenKind,
+    /// ソース上の綴り
+    text: String,
+    /// 直前に空白類文字があったか
+    space_before: bool,
+    /// 直後に空白類文字があったか
+    space_after: bool,
+    /// 直前にあった改行の数
+    newlines_before: usize,
+    /// 単項演算子として使われているか
+    unary: bool,
+    /// コメントを除いた直前のトークン
+    prev: Option<usize>,
+}
+
+impl Lexeme {
+    fn is_comment(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::COMMENT { .. } | TokenKind::DOCCOMMENT { .. }
+        )
+    }
+}
+
+/// 空白や改行をトークンの属性にまとめる
+fn collect_lexemes(tokens: Vec<(Token, String)>) -> Vec<Lexeme> {
+    let mut lexemes: Vec<Lexeme> = Vec::new();
+    let mut space = false;
+    let mut newlines = 0;
+    let mut prev = None;
+
+    for (t, text) in tokens {
+        match t.get_kind() {
+            TokenKind::EOF => break,
+            // 空白トークンは後続の改行も含むことがある
+            TokenKind::BLANK | TokenKind::NEWLINE => {
+                space = true;
+                newlines += text.matches('\n').count();
+            }
+            kind => {
+                if let Some(last) = lexemes.last_mut() {
+                    last.space_after = space;
+                }
+
+                let lexeme = Lexeme {
+                    kind: kind.clone(),
+                    text: text.trim_end().to_string(),
+                    space_before: space,
+                    space_after: false,
+                    newlines_before: newlines,
+                    unary: false,
+                    prev,
+                };
+                if !lexeme.is_comment() {
+                    prev = Some(lexemes.len());
+                }
+                lexemes.push(lexeme);
+
+                space = false;
+                newlines = 0;
+            }
+        }
+    }
+
+    for i in 0..lexemes.len() {
+        lexemes[i].unary = is_unary(&lexemes, i);
+    }
+
+    lexemes
+}
+
+/// `-` `+` `*` `&` が単項演算子(または型の一部)かどうか
+/// 識別子の後ろでは判別できないので，元の空白の付き方に従う
+fn is_unary(lexemes: &[Lexeme], i: usize) -> bool {
+    let lexeme = &lexemes[i];
+    if !matches!(
+        lexeme.kind,
+        TokenKind::PLUS | TokenKind::MINUS | TokenKind::ASTERISK | TokenKind::AMPERSAND
+    ) {
+        return false;
+    }
+
+    let prev = match lexeme.prev {
+        Some(prev) => &lexemes[prev],
+        None => return true,
+    };
+
+    match prev.kind {
+        TokenKind::Integer { .. }
+        | TokenKind::UNSIGNEDINTEGER { .. }
+        | TokenKind::CHARLIT { .. }
+        | TokenKind::STRLIT { .. }
+        | TokenKind::TRUE
+        | TokenKind::FALSE
+        | TokenKind::RPAREN
+        | TokenKind::RBRACE => false,
+        TokenKind::IDENTIFIER { .. } | TokenKind::RBRACKET => {
+            lexeme.space_before && !lexeme.space_after
+        }
+        _ => !is_type_keyword(&prev.kind),
+    }
+}
+
+fn is_type_keyword(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::INT64
+            | TokenKind::UINT64
+            | TokenKind::UINT8
+            | TokenKind::BOOLEAN
+            | TokenKind::CONSTSTR
+            | TokenKind::NORETURN
+    )
+}
+
+/// `{ }` の種類
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum BlockKind {
+    /// ファイル全体
+    TOPLEVEL,
+    /// 文の並び(関数本体，if，countup，asm，matchの各アーム)
+    CODE,
+    /// 構造体のメンバやインタフェースのメソッド
+    MEMBERS,
+    /// 列挙型のバリアント
+    ENUM,
+    /// matchのアーム
+    MATCH,
+    /// 構造体リテラル
+    /// 元のソースで `{` の直後に改行していれば，1メンバ1行で出力する
+    LITERAL { multiline: bool },
+}
+
+impl BlockKind {
+    /// ブロック内で改行・インデントするか
+    fn indents(&self) -> bool {
+        !matches!(
+            self,
+            BlockKind::TOPLEVEL | BlockKind::LITERAL { multiline: false }
+        )
+    }
+
+    /// 区切りの `,` の後で改行するか
+    fn breaks_after_comma(&self) -> bool {
+        matches!(
+            self,
+            BlockKind::ENUM | BlockKind::MATCH | BlockKind::LITERAL { multiline: true }
+        )
+    }
+}
+
+/// ブロック内で開いている `(` や `[`
+#[derive(Clone, Copy)]
+enum Group {
+    PAREN,
+    BRACKET { type_args: bool },
+}
+
+struct Block {
+    kind: BlockKind,
+    groups: Vec<Group>,
+    /// 現在の文(宣言)の先頭トークン
+    stmt_head: Option<usize>,
+    /// 現在の文の中で開いた `{` の数
+    braces_in_stmt: usize,
+}
+
+impl Block {
+    fn new(kind: BlockKind) -> Self {
+        Self {
+            kind,
+            groups: Vec::new(),
+            stmt_head: None,
+            braces_in_stmt: 0,
+        }
+    }
+
+    fn end_stmt(&mut self) {
+        self.stmt_head = None;
+        self.braces_in_stmt = 0;
+    }
+
+    fn in_bracket(&self) -> bool {
+        matches!(self.groups.last(), Some(Group::BRACKET { .. }))
+    }
+}
+
+struct Layout {
+    lexemes: Vec<Lexeme>,
+    lines: Vec<String>,
+    cur: String,
+    /// 現在行に書いたトークンの数
+    cur_tokens: usize,
+    blocks: Vec<Block>,
+    /// 次のトークンの前で改行する
+    pending_break: bool,
+    /// 直前に閉じた `]` が型引数のものか
+    closed_type_args: bool,
+    /// トップレベルの宣言本体を閉じた直後か
+    after_toplevel_body: bool,
+    /// 直前に行中のブロックコメントを書いたか
+    after_inline_comment: bool,
+    /// 最後に書いたトークンが `{` か
+    opened_block: bool,
+}
+
+impl Layout {
+    fn new(lexemes: Vec<Lexeme>) -> Self {
+        Self {
+            lexemes,
+            lines: Vec::new(),
+            cur: String::new(),
+            cur_tokens: 0,
+            blocks: vec![Block::new(BlockKind::TOPLEVEL)],
+            pending_break: false,
+            closed_type_args: false,
+            after_toplevel_body: false,
+            after_inline_comment: false,
+            opened_block: false,
+        }
+    }
+
+    fn put(&mut self, i: usize) {
+        if self.lexemes[i].is_comment() {
+            self.put_comment(i);
+        } else {
+            self.put_token(i);
+        }
+    }
+
+    fn put_comment(&mut self, i: usize) {
+        let lexeme = &self.lexemes[i];
+        let is_line_comment = lexeme.text.starts_with("//");
+        let text = lexeme.text.clone();
+
+        // 行末，あるいは行中のコメント
+        if lexeme.newlines_before == 0 && !self.cur.is_empty() {
+            self.cur += " ";
+            self.cur += &text;
+            self.cur_tokens += 1;
+            if is_line_comment {
+                self.pending_break = true;
+            } else {
+                self.after_inline_comment = true;
+            }
+            return;
+        }
+
+        let newlines_before = lexeme.newlines_before;
+        let next_on_same_line = self
+            .lexemes
+            .get(i + 1)
+            .is_some_and(|next| next.newlines_before == 0);
+
+        self.break_line(newlines_before, false);
+        if self.at_toplevel_head() && self.after_toplevel_body {
+            self.ensure_blank_line();
+        }
+        self.after_toplevel_body = false;
+
+        self.write(&text, false);
+        self.opened_block = false;
+        if is_line_comment || !next_on_same_line {
+            self.pending_break = true;
+        } else {
+            self.after_inline_comment = true;
+        }
+    }
+
+    fn put_token(&mut self, i: usize) {
+        let kind = self.lexemes[i].kind.clone();
+
+        // 閉じ括弧は，対応するブロックを抜けてから書く
+        let mut closed = None;
+        if kind == TokenKind::RBRACE && self.blocks.len() > 1 {
+            closed = self.blocks.pop().map(|b| b.kind);
+        }
+        let empty_block =
+            kind == TokenKind::RBRACE && i > 0 && self.lexemes[i - 1].kind == TokenKind::LBRACE;
+
+        let break_before = self.pending_break
+            || closed.is_some_and(|c| c.indents()) && !empty_block
+            || self.starts_member(i);
+        if break_before {
+            self.break_line(self.lexemes[i].newlines_before, kind == TokenKind::RBRACE);
+        }
+
+        if self.at_toplevel_head() && !self.lines.is_empty() && self.cur.is_empty() {
+            // 属性は直後の宣言と同様に，コメントと組になる
+            let last_is_comment = self.lines.last().is_some_and(|l| {
+                let l = l.trim_start();
+                l.starts_with("//") || l.starts_with("/*") || l.starts_with("#[")
+            });
+            let block_decl = matches!(
+                kind,
+                TokenKind::FUNC
+                    | TokenKind::STRUCT
+                    | TokenKind::PUBENUM
+                    | TokenKind::PUBINTERFACE
+                    | TokenKind::SHARP
+            );
+            if self.after_toplevel_body || block_decl && !last_is_comment {
+                self.ensure_blank_line();
+            }
+        }
+        self.after_toplevel_body = false;
+
+        let space = self.needs_space(i);
+        let text = self.lexemes[i].text.clone();
+        self.write(&text, space || self.after_inline_comment);
+        self.after_inline_comment = false;
+        self.opened_block = kind == TokenKind::LBRACE;
+
+        let block = self.blocks.last_mut().unwrap();
+        if block.stmt_head.is_none() {
+            block.stmt_head = Some(i);
+        }
+
+        self.after_token(i, closed);
+    }
+
+    /// トークンを書いた後の，ブロックや文の状態の更新
+    fn after_token(&mut self, i: usize, closed: Option<BlockKind>) {
+        let kind = self.lexemes[i].kind.clone();
+        let next_kind = self.lexemes.get(i + 1).map(|l| l.kind.clone());
+
+        match kind {
+            TokenKind::LPAREN => self.top().groups.push(Group::PAREN),
+            TokenKind::LBRACKET => {
+                let lexeme = &self.lexemes[i];
+                let type_args = !lexeme.space_before
+                    && lexeme.prev.is_some_and(|p| {
+                        matches!(self.lexemes[p].kind, TokenKind::IDENTIFIER { .. })
+                    })
+                    && self.cur_tokens >= 3;
+                self.top().groups.push(Group::BRACKET { type_args });
+            }
+            TokenKind::RPAREN => {
+                self.top().groups.pop();
+            }
+            TokenKind::RBRACKET => {
+                self.closed_type_args = matches!(
+                    self.top().groups.pop(),
+                    Some(Group::BRACKET { type_args: true })
+                );
+
+                // `#[test]` のような属性は1行に置く
+                let lexemes = &self.lexemes;
+                let top = self.blocks.last_mut().unwrap();
+                let head = top.stmt_head.map(|h| &lexemes[h].kind);
+                if top.kind == BlockKind::TOPLEVEL
+                    && top.groups.is_empty()
+                    && head == Some(&TokenKind::SHARP)
+                {
+                    top.end_stmt();
+                    self.pending_break = true;
+                }
+            }
+            TokenKind::LBRACE => {
+                let block_kind = self.open_block_kind(i);
+                self.top().braces_in_stmt += 1;
+                self.blocks.push(Block::new(block_kind));
+
+                if block_kind.indents() && next_kind != Some(TokenKind::RBRACE) {
+                    self.pending_break = true;
+                }
+            }
+            TokenKind::RBRACE => {
+                let top = self.blocks.last_mut().unwrap();
+                if top.kind == BlockKind::TOPLEVEL && top.groups.is_empty() {
+                    // トップレベルの宣言の終わり
+                    top.end_stmt();
+                    self.after_toplevel_body = true;
+                    self.pending_break = true;
+                } else if closed.is_some_and(|c| c.indents())
+                    && !matches!(
+                        next_kind,
+                        Some(TokenKind::SEMICOLON)
+                            | Some(TokenKind::COMMA)
+                            | Some(TokenKind::ELSE)
+                            | Some(TokenKind::DOT)
+                            | Some(TokenKind::RPAREN)
+                            | Some(TokenKind::RBRACKET)
+                    )
+                {
+                    self.pending_break = true;
+                }
+            }
+            TokenKind::SEMICOLON => {
+                let top = self.blocks.last_mut().unwrap();
+                if top.groups.is_empty()
+                    && matches!(top.kind, BlockKind::TOPLEVEL | BlockKind::CODE)
+                {
+                    top.end_stmt();
+                    self.pending_break = true;
+                }
+            }
+            TokenKind::COMMA => {
+                let top = self.blocks.last().unwrap();
+                if top.groups.is_empty() && top.kind.breaks_after_comma() {
+                    self.pending_break = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// `{` が開くブロックの種類を，文脈から決める
+    fn open_block_kind(&self, i: usize) -> BlockKind {
+        let block = self.blocks.last().unwrap();
+        let head = block.stmt_head.map(|h| &self.lexemes[h].kind);
+        let prev = self.lexemes[i].prev.map(|p| &self.lexemes[p].kind);
+
+        match block.kind {
+            BlockKind::TOPLEVEL => {
+                return match head {
+                    Some(TokenKind::STRUCT) | Some(TokenKind::PUBINTERFACE) => BlockKind::MEMBERS,
+                    Some(TokenKind::PUBENUM) => BlockKind::ENUM,
+                    _ => BlockKind::CODE,
+                };
+            }
+            BlockKind::MATCH => return BlockKind::CODE,
+            _ => {}
+        }
+
+        if block.groups.is_empty() && block.braces_in_stmt == 0 {
+            match head {
+                Some(TokenKind::MATCH) => return BlockKind::MATCH,
+                Some(TokenKind::COUNTUP) => return BlockKind::CODE,
+                _ => {}
+            }
+        }
+
+        match prev {
+            Some(TokenKind::RPAREN)
+            | Some(TokenKind::ELSE)
+            | Some(TokenKind::ASM)
+            | Some(TokenKind::ARROW) => BlockKind::CODE,
+            _ => {
+                let multiline = self
+                    .lexemes
+                    .get(i + 1)
+                    .is_some_and(|next| next.newlines_before > 0 || next.is_comment());
+                BlockKind::LITERAL { multiline }
+            }
+        }
+    }
+
+    /// 構造体メンバやインタフェースメソッドの区切りか
+    /// メンバ間には区切り文字がないので，型を書き終えた後の識別子で判断する
+    fn starts_member(&self, i: usize) -> bool {
+        let block = self.blocks.last().unwrap();
+        if block.kind != BlockKind::MEMBERS || !block.groups.is_empty() || self.cur_tokens < 2 {
+            return false;
+        }
+        if !matches!(self.lexemes[i].kind, TokenKind::IDENTIFIER { .. }) {
+            return false;
+        }
+
+        match self.lexemes[i].prev.map(|p| &self.lexemes[p].kind) {
+            Some(TokenKind::IDENTIFIER { .. }) => true,
+            Some(TokenKind::RBRACKET) => self.closed_type_args,
+            Some(kind) => is_type_keyword(kind),
+            None => false,
+        }
+    }
+
+    /// 直前のトークンとの間に空白を入れるか
+    fn needs_space(&self, i: usize) -> bool {
+        let lexeme = &self.lexemes[i];
+        let prev_idx = match lexeme.prev {
+            Some(prev) => prev,
+            None => return false,
+        };
+        let prev = &self.lexemes[prev_idx];
+        let block = self.blocks.last().unwrap();
+
+        match lexeme.kind {
+            TokenKind::RPAREN
+            | TokenKind::RBRACKET
+            | TokenKind::COMMA
+            | TokenKind::SEMICOLON
+            | TokenKind::DOT
+            | TokenKind::DOUBLECOLON => return false,
+            _ => {}
+        }
+        match prev.kind {
+            TokenKind::LPAREN | TokenKind::LBRACKET | TokenKind::DOT | TokenKind::DOUBLECOLON => {
+                return false
+            }
+            _ if prev.unary => return false,
+            _ => {}
+        }
+
+        match (&prev.kind, &lexeme.kind) {
+            (TokenKind::LBRACE, TokenKind::RBRACE) => false,
+            (TokenKind::SHARP, TokenKind::LBRACKET) => false,
+            // 関数呼び出しや型引数の適用
+            (TokenKind::IDENTIFIER { .. }, TokenKind::LPAREN)
+            | (TokenKind::RBRACKET, TokenKind::LPAREN)
+            | (TokenKind::RPAREN, TokenKind::LPAREN) => false,
+            // メソッド定義のレシーバ以外は関数型
+            (TokenKind::FUNC, TokenKind::LPAREN) => block.stmt_head == Some(prev_idx),
+            // 添字アクセスと配列型を区別する
+            (TokenKind::IDENTIFIER { .. }, TokenKind::LBRACKET)
+            | (TokenKind::RBRACKET, TokenKind::LBRACKET)
+            | (TokenKind::RPAREN, TokenKind::LBRACKET)
+            | (TokenKind::STRLIT { .. }, TokenKind::LBRACKET) => lexeme.space_before,
+            (_, TokenKind::COLON) => {
+                !block.in_bracket() && !matches!(block.kind, BlockKind::LITERAL { .. })
+            }
+            (TokenKind::COLON, _) => !block.in_bracket(),
+            // 配列型やスライス型の要素型
+            (TokenKind::RBRACKET, TokenKind::IDENTIFIER { .. })
+            | (TokenKind::RBRACKET, TokenKind::FUNC) => false,
+            (TokenKind::RBRACKET, kind) if is_type_keyword(kind) || lexeme.unary => false,
+            _ => true,
+        }
+    }
+
+    fn top(&mut self) -> &mut Block {
+        self.blocks.last_mut().unwrap()
+    }
+
+    /// トップレベルの宣言の先頭か
+    fn at_toplevel_head(&self) -> bool {
+        self.blocks.len() == 1 && self.blocks[0].stmt_head.is_none()
+    }
+
+    fn depth(&self) -> usize {
+        self.blocks.iter().filter(|b| b.kind.indents()).count()
+    }
+
+    fn write(&mut self, text: &str, space: bool) {
+        if self.cur.is_empty() {
+            self.cur = INDENT.repeat(self.depth());
+        } else if space {
+            self.cur += " ";
+        }
+        self.cur += text;
+        self.cur_tokens += 1;
+    }
+
+    /// 改行する
+    /// 元のソースで空行があった場合は，1行だけ空行を残す
+    fn break_line(&mut self, newlines_before: usize, closing: bool) {
+        self.flush();
+        self.pending_break = false;
+
+        if newlines_before >= 2 && !closing && !self.opened_block && !self.lines.is_empty() {
+            self.ensure_blank_line();
+        }
+    }
+
+    fn ensure_blank_line(&mut self) {
+        self.flush();
+        if self.lines.last().is_some_and(|l| !l.is_empty()) {
+            self.lines.push(String::new());
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.cur.is_empty() {
+            self.lines.push(self.cur.trim_end().to_string());
+            self.cur.clear();
+        }
+        self.cur_tokens = 0;
+    }
+
+    fn finish(mut self) -> String {
+        self.flush();
+        while self.lines.last().is_some_and(|l| l.is_empty()) {
+            self.lines.pop();
+        }
+        if self.lines.is_empty() {
+            return String::new();
+        }
+
+        self.lines.join("\n") + "\n"
+    }
+}
+
+#[cfg(test)]
+mod layout_tests {
+    use super::*;
+
+    #[test]
+    fn format_function_test() {
+        let source = "import x64;\nfunc main() Noreturn {\n\tdeclare x Int64;\n   x = if(true) {ifret 1;} else {\n ifret -2;};\n\n\n\tx64::exit_with(x*2);\n}\n";
+        let expected = "import x64;\n\nfunc main() Noreturn {\n    declare x Int64;\n    x = if (true) {\n        ifret 1;\n    } else {\n        ifret -2;\n    };\n\n    x64::exit_with(x * 2);\n}\n";
+        assert_eq!(expected, format_source(source).unwrap());
+    }
+
+    #[test]
+    fn format_declarations_test() {
+        let source = "struct Pair[T] { first T second *Pair[T]\n  xs [5]Point op *func(Int64, Int64) Int64 }\npubinterface Shape {area() Int64 scale(k Int64) Int64}\npubenum A { B, C, }\npubtype Fd = Uint64;\npubconst STDOUT:Fd = 1;\nfunc (p *Pair[T]) first() T {}\n";
+        let expected = "struct Pair[T] {\n    first T\n    second *Pair[T]\n    xs [5]Point\n    op *func(Int64, Int64) Int64\n}\n\npubinterface Shape {\n    area() Int64\n    scale(k Int64) Int64\n}\n\npubenum A {\n    B,\n    C,\n}\n\npubtype Fd = Uint64;\npubconst STDOUT : Fd = 1;\n\nfunc (p *Pair[T]) first() T {}\n";
+        assert_eq!(expected, format_source(source).unwrap());
+    }
+
+    #[test]
+    fn format_statements_test() {
+        let source = "func main() Int64 {\n  match x { A::B -> { return 1; }, A::C -> {return 2;}, };\n  countup i Int64 from 0 to 10 { s = s + a[i:]; };\n  asm { \"movq $60, %rax\"; \"syscall\"; };\n  varinit p = Point{x: 1, y: -2}.x;\n  s = Segment {\n from: p, to: *q };\n  return (*p).y - f(&a, b[0], sum[Int64](&ip));\n}\n";
+        let expected = "func main() Int64 {\n    match x {\n        A::B -> {\n            return 1;\n        },\n        A::C -> {\n            return 2;\n        },\n    };\n    countup i Int64 from 0 to 10 {\n        s = s + a[i:];\n    };\n    asm {\n        \"movq $60, %rax\";\n        \"syscall\";\n    };\n    varinit p = Point { x: 1, y: -2 }.x;\n    s = Segment {\n        from: p,\n        to: *q\n    };\n    return (*p).y - f(&a, b[0], sum[Int64](&ip));\n}\n";
+        assert_eq!(expected, format_source(source).unwrap());
+    }
+
+    #[test]
+    fn format_comments_test() {
+        let source = "/// 終了する\n// 詳細\nfunc exit() Noreturn { // 本体\n\n  /* 入れ子 /* コメント */ */ return;\n\n\n  // 末尾\n}\n";
+        let expected = "/// 終了する\n// 詳細\nfunc exit() Noreturn { // 本体\n    /* 入れ子 /* コメント */ */ return;\n\n    // 末尾\n}\n";
+        assert_eq!(expected, format_source(source).unwrap());
+    }
+
+    #[test]
+    fn format_attribute_test() {
+        let source = "func add() Int64 {}\n// 足し算\n#  [ test ] func add_test() Int64 {}\n";
+        let expected = "func add() Int64 {}\n\n// 足し算\n#[test]\nfunc add_test() Int64 {}\n";
+        assert_eq!(expected, format_source(source).unwrap());
+    }
+
+    #[test]
+    fn idempotent_test() {
+        for dir in ["examples/x64", "examples/aarch64", "lib"].iter() {
+            let entries = match std::fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+
+            for entry in entries {
+                let path = entry.unwrap().path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("go") {
+                    continue;
+                }
+
+                let source = std::fs::read_to_string(&path).unwrap();
+                let formatted = format_source(&source).unwrap();
+                assert_eq!(
+                    formatted,
+                    format_source(&formatted).unwrap(),
+                    "{:?} is not formatted idempotently",
+                    path
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+mod generator;
+mod random;
+
+pub use generator::*;
+pub use random::*;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+/// 生成したプログラムのコンパイルで見つかった問題
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum Failure {
+    /// コンパイラがパニックした
+    PANIC { location: String },
+    /// 正しいはずのプログラムが拒否された，あるいはパニック以外で異常終了した
+    REJECTED { exit_code: Option<i32> },
+    /// 同じプログラムから異なるアセンブリが生成された
+    NONDETERMINISTIC,
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::PANIC { location } => write!(f, "compiler panicked at {}", location),
+            Failure::REJECTED {
+                exit_code: Some(code),
+            } => write!(f, "compilation failed with exit code {}", code),
+            Failure::REJECTED { exit_code: None } => {
+                write!(f, "compilation was terminated by a signal")
+            }
+            Failure::NONDETERMINISTIC => {
+                write!(f, "compiling twice generated different assembly")
+            }
+        }
+    }
+}
+
+/// `peachili fuzz` のメインルーチン
+/// seedから順にcount個のプログラムを生成してx64向けにコンパイルし，
+/// 問題を起こしたプログラムをoutput_dirに `seed-<seed>.go` として保存する
+pub fn main(seed: u64, count: u64, output_dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let work_dir = std::env::temp_dir().join(format!("peachili-fuzz-{}", std::process::id()));
+    let result = fuzz_all(seed, count, output_dir, &work_dir);
+    let _ = std::fs::remove_dir_all(&work_dir);
+
+    let failures = result?;
+    eprintln!(
+        "\n{} programs (seed {}..{}), {} failed",
+        count,
+        seed,
+        seed.wrapping_add(count),
+        failures.values().map(|seeds| seeds.len()).sum::<usize>()
+    );
+
+    // 同じ箇所でのパニックはまとめて報告する
+    for (failure, seeds) in failures.iter() {
+        eprintln!(
+            "    {} program(s): {} (e.g. seed {})",
+            seeds.len(),
+            failure,
+            seeds[0]
+        );
+    }
+    if !failures.is_empty() {
+        eprintln!("failing programs are saved in {}", output_dir);
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// 問題の種類 => それを起こしたプログラムのシード群
+fn fuzz_all(
+    seed: u64,
+    count: u64,
+    output_dir: &str,
+    work_dir: &Path,
+) -> Result<BTreeMap<Failure, Vec<u64>>, Box<dyn std::error::Error>> {
+    let mut failures: BTreeMap<Failure, Vec<u64>> = BTreeMap::new();
+
+    for case_seed in (0..count).map(|n| seed.wrapping_add(n)) {
+        let program = generate_program(case_seed);
+        let failure = match fuzz_one(&program, work_dir)? {
+            Some(failure) => failure,
+            None => continue,
+        };
+
+        eprintln!("seed {} => {}", case_seed, failure);
+        std::fs::create_dir_all(output_dir)?;
+        std::fs::write(
+            Path::new(output_dir).join(format!("seed-{}.go", case_seed)),
+            program,
+        )?;
+        failures.entry(failure).or_default().push(case_seed);
+    }
+
+    Ok(failures)
+}
+
+/// プログラムを別々の作業ディレクトリで2回コンパイルし，結果を比べる
+fn fuzz_one(program: &str, work_dir: &Path) -> std::io::Result<Option<Failure>> {
+    let exe = std::env::current_exe()?;
+    let mut assemblies = Vec::new();
+
+    for name in ["first", "second"].iter() {
+        let dir = work_dir.join(name);
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join("fuzz.go"), program)?;
+
+        let output = Command::new(&exe)
+            .args(["compile", "fuzz.go", "--target", "x86_64"])
+            .current_dir(&dir)
+            .env("NO_COLOR", "1")
+            .env("RUST_BACKTRACE", "0")
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Ok(Some(match panic_location(&stderr) {
+                Some(location) => Failure::PANIC { location },
+                None => Failure::REJECTED {
+                    exit_code: output.status.code(),
+                },
+            }));
+        }
+
+        assemblies.push(std::fs::read(dir.join("asm.s"))?);
+    }
+
+    if assemblies[0] != assemblies[1] {
+        return Ok(Some(Failure::NONDETERMINISTIC));
+    }
+    Ok(None)
+}
+
+/// パニックメッセージから `src/...rs:line:column` を取り出す
+fn panic_location(stderr: &str) -> Option<String> {
+    let line = stderr.lines().find(|l| l.contains("panicked at"))?;
+    let start = line.find("src/")?;
+
+    // 行番号と列番号の2つのコロンまでを含める
+    let rest = &line[start..];
+    let file_end = rest.find(".rs:")? + ".rs".len();
+    let numbers: String = rest[file_end..]
+        .chars()
+        .take_while(|c| *c == ':' || c.is_ascii_digit())
+        .collect();
+
+    Some(format!(
+        "{}{}",
+        &rest[..file_end],
+        numbers.trim_end_matches(':')
+    ))
+}
+
+#[cfg(test)]
+mod fuzz_tests {
+    use super::*;
+
+    #[test]
+    fn panic_location_test() {
+        let stderr = "thread 'main' panicked at src/common/pass/translator/translate.rs:120:14:\ncalled `Option::unwrap()` on a `None` value\n";
+        assert_eq!(
+            Some("src/common/pass/translator/translate.rs:120:14".to_string()),
+            panic_location(stderr)
+        );
+
+        let stderr =
+            "thread 'main' panicked at 'not implemented', src/arch/x64/pass/codegen.rs:88:5\n";
+        assert_eq!(
+            Some("src/arch/x64/pass/codegen.rs:88:5".to_string()),
+            panic_location(stderr)
+        );
+
+        assert_eq!(None, panic_location("TypeError(3, 5) : mismatched types\n"));
+    }
+}
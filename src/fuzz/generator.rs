@@ -0,0 +1,553 @@
+use crate::fuzz::Random;
+
+/// 生成するプログラムで使う型
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum GenType {
+    INT64,
+    BOOLEAN,
+    /// structsの添字
+    STRUCT(usize),
+    POINTER(Box<GenType>),
+}
+
+struct StructDecl {
+    name: String,
+    /// メンバはInt64か，先に定義された構造体
+    members: Vec<(String, GenType)>,
+}
+
+struct FunctionDecl {
+    name: String,
+    params: Vec<(String, GenType)>,
+}
+
+struct Variable {
+    name: String,
+    ty: GenType,
+    /// countupの変数と仮引数には代入しない
+    mutable: bool,
+}
+
+/// 変数から辿れる，読み書きできる場所
+struct Place {
+    expr: String,
+    ty: GenType,
+    mutable: bool,
+}
+
+const MAX_STATEMENT_DEPTH: usize = 3;
+const MAX_EXPRESSION_DEPTH: usize = 3;
+const MAX_LOOP_DEPTH: usize = 2;
+
+/// シードから，型検査を通るランダムなプログラムを生成する
+/// 構造体，ポインタ，if式，countup，関数呼び出しを含み，mainの返り値が終了コードになる
+/// 未定義動作(0除算，範囲外アクセス，無限ループ)は生成しない
+pub fn generate_program(seed: u64) -> String {
+    let mut generator = Generator::new(seed);
+    generator.gen_program();
+    generator.lines.join("\n") + "\n"
+}
+
+struct Generator {
+    rng: Random,
+    structs: Vec<StructDecl>,
+    functions: Vec<FunctionDecl>,
+    /// 現在の関数から呼び出せる関数の数(functions[..callable])
+    /// 先に定義された関数だけを呼ぶので，再帰は起こらない
+    callable: usize,
+    scopes: Vec<Vec<Variable>>,
+    loop_depth: usize,
+    next_id: usize,
+    lines: Vec<String>,
+    indent: usize,
+}
+
+impl Generator {
+    fn new(seed: u64) -> Self {
+        Self {
+            rng: Random::new(seed),
+            structs: Vec::new(),
+            functions: Vec::new(),
+            callable: 0,
+            scopes: Vec::new(),
+            loop_depth: 0,
+            next_id: 0,
+            lines: Vec::new(),
+            indent: 0,
+        }
+    }
+
+    fn gen_program(&mut self) {
+        let struct_number = self.rng.range(1, 3) as usize;
+        for idx in 0..struct_number {
+            self.gen_struct(idx);
+        }
+
+        let function_number = self.rng.range(0, 4) as usize;
+        for idx in 0..function_number {
+            self.gen_function(idx);
+        }
+
+        self.callable = self.functions.len();
+        self.emit("func main() Int64 {");
+        self.gen_body(Vec::new());
+        self.emit("}");
+    }
+
+    fn gen_struct(&mut self, idx: usize) {
+        let mut members = Vec::new();
+        for member_idx in 0..self.rng.range(1, 3) {
+            members.push((format!("f{}", member_idx), GenType::INT64));
+        }
+        if idx != 0 && self.rng.chance(60) {
+            let inner = self.rng.range(0, idx as u64 - 1) as usize;
+            members.push((format!("s{}", inner), GenType::STRUCT(inner)));
+        }
+
+        let name = format!("S{}", idx);
+        self.emit(&format!("struct {} {{", name));
+        for (member_name, member_type) in members.iter() {
+            let line = format!("    {} {}", member_name, self.type_name(member_type));
+            self.emit(&line);
+        }
+        self.emit("}");
+        self.emit("");
+
+        self.structs.push(StructDecl { name, members });
+    }
+
+    fn gen_function(&mut self, idx: usize) {
+        let mut params = Vec::new();
+        for param_idx in 0..self.rng.range(0, 3) {
+            let ty = match self.rng.range(0, 3) {
+                0 | 1 => GenType::INT64,
+                2 => GenType::BOOLEAN,
+                _ => GenType::POINTER(Box::new(self.random_struct_type())),
+            };
+            params.push((format!("a{}", param_idx), ty));
+        }
+
+        let name = format!("fn{}", idx);
+        let param_list: Vec<String> = params
+            .iter()
+            .map(|(param_name, ty)| format!("{} {}", param_name, self.type_name(ty)))
+            .collect();
+        self.emit(&format!(
+            "func {}({}) Int64 {{",
+            name,
+            param_list.join(", ")
+        ));
+
+        self.callable = idx;
+        let param_vars = params
+            .iter()
+            .map(|(param_name, ty)| Variable {
+                name: param_name.clone(),
+                ty: ty.clone(),
+                mutable: false,
+            })
+            .collect();
+        self.gen_body(param_vars);
+        self.emit("}");
+        self.emit("");
+
+        self.functions.push(FunctionDecl { name, params });
+    }
+
+    /// 関数本体を生成し，Int64の値を返す
+    fn gen_body(&mut self, params: Vec<Variable>) {
+        self.indent += 1;
+        self.scopes.push(params);
+
+        for _ in 0..self.rng.range(2, 6) {
+            self.gen_statement(0);
+        }
+        let returned = self.gen_int_expr(0);
+        self.emit(&format!("return {};", returned));
+
+        self.scopes.pop();
+        self.indent -= 1;
+    }
+
+    fn gen_block(&mut self, depth: usize) {
+        self.indent += 1;
+        self.scopes.push(Vec::new());
+
+        for _ in 0..self.rng.range(1, 3) {
+            self.gen_statement(depth);
+        }
+
+        self.scopes.pop();
+        self.indent -= 1;
+    }
+
+    fn gen_statement(&mut self, depth: usize) {
+        let nestable = depth < MAX_STATEMENT_DEPTH;
+
+        match self.rng.range(0, 9) {
+            0..=3 => self.gen_varinit(depth),
+            4..=6 => self.gen_assignment(),
+            7 if nestable => {
+                let cond = self.gen_bool_expr();
+                self.emit(&format!("if ({}) {{", cond));
+                self.gen_block(depth + 1);
+                self.emit("} else {");
+                self.gen_block(depth + 1);
+                self.emit("};");
+            }
+            8 | 9 if nestable && self.loop_depth < MAX_LOOP_DEPTH => {
+                let name = self.fresh_name("i");
+                let from = self.rng.range(0, 3);
+                let to = from + self.rng.range(0, 4);
+                self.emit(&format!(
+                    "countup {} begin {} exclude {} {{",
+                    name, from, to
+                ));
+
+                // 添字は本体のスコープでだけ読める
+                self.loop_depth += 1;
+                self.scopes.push(vec![Variable {
+                    name,
+                    ty: GenType::INT64,
+                    mutable: false,
+                }]);
+                self.gen_block(depth + 1);
+                self.scopes.pop();
+                self.loop_depth -= 1;
+
+                self.emit("};");
+            }
+            _ => self.gen_varinit(depth),
+        }
+    }
+
+    fn gen_varinit(&mut self, depth: usize) {
+        let name = self.fresh_name("v");
+
+        let ty = match self.rng.range(0, 5) {
+            0 | 1 => GenType::INT64,
+            2 => GenType::BOOLEAN,
+            3 => self.random_struct_type(),
+            _ => {
+                let pointee = if self.rng.chance(50) {
+                    GenType::INT64
+                } else {
+                    self.random_struct_type()
+                };
+                GenType::POINTER(Box::new(pointee))
+            }
+        };
+
+        let type_name = self.type_name(&ty);
+        let init = match &ty {
+            GenType::INT64 if depth < MAX_STATEMENT_DEPTH && self.rng.chance(25) => {
+                let head = format!("varinit {} {} = ", name, type_name);
+                self.gen_if_expr(&head, depth);
+                self.declare(name, ty);
+                return;
+            }
+            GenType::POINTER(pointee) => match self.gen_address_of(pointee) {
+                Some(init) => init,
+                // 指せる場所がなければInt64の変数にする
+                None => {
+                    let init = self.gen_int_expr(0);
+                    self.emit(&format!("varinit {} Int64 = {};", name, init));
+                    self.declare(name, GenType::INT64);
+                    return;
+                }
+            },
+            _ => self.gen_expr(&ty, 0),
+        };
+
+        // 型推論にも任せる
+        if self.rng.chance(30) {
+            self.emit(&format!("varinit {} = {};", name, init));
+        } else {
+            self.emit(&format!("varinit {} {} = {};", name, type_name, init));
+        }
+        self.declare(name, ty);
+    }
+
+    fn gen_assignment(&mut self) {
+        let places: Vec<Place> = self
+            .places()
+            .into_iter()
+            .filter(|p| p.mutable && !matches!(p.ty, GenType::POINTER(_)))
+            .collect();
+        if places.is_empty() {
+            return self.gen_varinit(MAX_STATEMENT_DEPTH);
+        }
+
+        let place = self.rng.choose(&places);
+        let (expr, ty) = (place.expr.clone(), place.ty.clone());
+        let value = self.gen_expr(&ty, 0);
+        self.emit(&format!("{} = {};", expr, value));
+    }
+
+    /// `<head>if (c) { ...; ifret e; } else { ...; ifret e; };` を出力する
+    fn gen_if_expr(&mut self, head: &str, depth: usize) {
+        let cond = self.gen_bool_expr();
+        self.emit(&format!("{}if ({}) {{", head, cond));
+
+        for (idx, _) in ["then", "else"].iter().enumerate() {
+            if idx == 1 {
+                self.emit("} else {");
+            }
+            self.indent += 1;
+            self.scopes.push(Vec::new());
+            for _ in 0..self.rng.range(0, 2) {
+                self.gen_statement(depth + 1);
+            }
+            let value = self.gen_int_expr(0);
+            self.emit(&format!("ifret {};", value));
+            self.scopes.pop();
+            self.indent -= 1;
+        }
+        self.emit("};");
+    }
+
+    fn gen_expr(&mut self, ty: &GenType, depth: usize) -> String {
+        match ty {
+            GenType::INT64 => self.gen_int_expr(depth),
+            GenType::BOOLEAN => self.gen_bool_expr(),
+            GenType::STRUCT(idx) => {
+                let copies: Vec<String> = self
+                    .places()
+                    .into_iter()
+                    .filter(|p| &p.ty == ty)
+                    .map(|p| p.expr)
+                    .collect();
+                if !copies.is_empty() && self.rng.chance(30) {
+                    return self.rng.choose(&copies).clone();
+                }
+                self.gen_struct_literal(*idx, depth)
+            }
+            GenType::POINTER(_) => unreachable!(),
+        }
+    }
+
+    fn gen_struct_literal(&mut self, idx: usize, depth: usize) -> String {
+        let members: Vec<(String, GenType)> = self.structs[idx].members.clone();
+        let mut inits = Vec::new();
+        for (member_name, member_type) in members.iter() {
+            let value = match member_type {
+                GenType::STRUCT(inner) => self.gen_struct_literal(*inner, depth + 1),
+                _ => self.gen_int_expr(depth + 1),
+            };
+            inits.push(format!("{}: {}", member_name, value));
+        }
+
+        format!("{} {{ {} }}", self.structs[idx].name, inits.join(", "))
+    }
+
+    fn gen_int_expr(&mut self, depth: usize) -> String {
+        if depth >= MAX_EXPRESSION_DEPTH || self.rng.chance(30) {
+            return self.gen_int_leaf();
+        }
+
+        match self.rng.range(0, 9) {
+            0..=2 => {
+                let op = self.rng.choose(&["+", "-", "*"]).to_string();
+                let lhs = self.gen_int_expr(depth + 1);
+                let rhs = self.gen_int_expr(depth + 1);
+                format!("({} {} {})", lhs, op, rhs)
+            }
+            // 0除算を避けるため，除数は正の整数リテラルに限る
+            3 => {
+                let lhs = self.gen_int_expr(depth + 1);
+                format!("({} / {})", lhs, self.rng.range(1, 9))
+            }
+            4 => format!("-{}", self.gen_int_leaf()),
+            5 | 6 => match self.gen_call(depth) {
+                Some(call) => call,
+                None => self.gen_int_leaf(),
+            },
+            7 => {
+                let idx = self.random_struct_type();
+                let literal = match idx {
+                    GenType::STRUCT(idx) => self.gen_struct_literal(idx, depth + 1),
+                    _ => unreachable!(),
+                };
+                format!("{}.f0", literal)
+            }
+            _ => self.gen_int_leaf(),
+        }
+    }
+
+    fn gen_int_leaf(&mut self) -> String {
+        let readable: Vec<String> = self
+            .places()
+            .into_iter()
+            .filter(|p| p.ty == GenType::INT64)
+            .map(|p| p.expr)
+            .collect();
+        if !readable.is_empty() && self.rng.chance(60) {
+            return self.rng.choose(&readable).clone();
+        }
+
+        if self.rng.chance(10) {
+            return self.rng.range(0, 1 << 40).to_string();
+        }
+        self.rng.range(0, 100).to_string()
+    }
+
+    fn gen_bool_expr(&mut self) -> String {
+        let readable: Vec<String> = self
+            .places()
+            .into_iter()
+            .filter(|p| p.ty == GenType::BOOLEAN)
+            .map(|p| p.expr)
+            .collect();
+        if !readable.is_empty() && self.rng.chance(60) {
+            return self.rng.choose(&readable).clone();
+        }
+
+        self.rng.choose(&["true", "false"]).to_string()
+    }
+
+    /// 呼び出せる関数がなければNone
+    fn gen_call(&mut self, depth: usize) -> Option<String> {
+        if self.callable == 0 {
+            return None;
+        }
+
+        let idx = self.rng.range(0, self.callable as u64 - 1) as usize;
+        let params: Vec<GenType> = self.functions[idx]
+            .params
+            .iter()
+            .map(|(_, ty)| ty.clone())
+            .collect();
+
+        let mut args = Vec::new();
+        for param_type in params.iter() {
+            let arg = match param_type {
+                GenType::POINTER(pointee) => self.gen_address_of(pointee)?,
+                _ => self.gen_expr(param_type, depth + 1),
+            };
+            args.push(arg);
+        }
+
+        Some(format!("{}({})", self.functions[idx].name, args.join(", ")))
+    }
+
+    /// pointeeを指すポインタの式
+    /// 書き込まれても良い場所か，同じ型のポインタ変数から選ぶ
+    fn gen_address_of(&mut self, pointee: &GenType) -> Option<String> {
+        let pointer_type = GenType::POINTER(Box::new(pointee.clone()));
+        let candidates: Vec<String> = self
+            .places()
+            .into_iter()
+            .filter_map(|p| {
+                if p.mutable && &p.ty == pointee {
+                    Some(format!("&{}", p.expr))
+                } else if p.ty == pointer_type {
+                    Some(p.expr)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(self.rng.choose(&candidates).clone())
+    }
+
+    /// 見えている変数から辿れる場所を列挙する
+    fn places(&self) -> Vec<Place> {
+        let mut places = Vec::new();
+        for var in self.scopes.iter().flatten() {
+            self.expand_place(var.name.clone(), &var.ty, var.mutable, &mut places);
+        }
+        places
+    }
+
+    fn expand_place(&self, expr: String, ty: &GenType, mutable: bool, places: &mut Vec<Place>) {
+        match ty {
+            GenType::STRUCT(idx) => {
+                for (member_name, member_type) in self.structs[*idx].members.iter() {
+                    let member = format!("{}.{}", expr, member_name);
+                    self.expand_place(member, member_type, mutable, places);
+                }
+            }
+            // ポインタを介した場所には，ポインタ変数が書き換えられなくても書き込める
+            // 構造体へのポインタはメンバアクセスで自動的に参照外しされる
+            GenType::POINTER(pointee) => match pointee.as_ref() {
+                GenType::STRUCT(idx) => {
+                    for (member_name, member_type) in self.structs[*idx].members.iter() {
+                        let member = format!("{}.{}", expr, member_name);
+                        self.expand_place(member, member_type, true, places);
+                    }
+                }
+                _ => places.push(Place {
+                    expr: format!("*{}", expr),
+                    ty: *pointee.clone(),
+                    mutable: true,
+                }),
+            },
+            _ => {}
+        }
+
+        places.push(Place {
+            expr,
+            ty: ty.clone(),
+            mutable,
+        });
+    }
+
+    fn declare(&mut self, name: String, ty: GenType) {
+        self.scopes.last_mut().unwrap().push(Variable {
+            name,
+            ty,
+            mutable: true,
+        });
+    }
+
+    fn random_struct_type(&mut self) -> GenType {
+        GenType::STRUCT(self.rng.range(0, self.structs.len() as u64 - 1) as usize)
+    }
+
+    fn type_name(&self, ty: &GenType) -> String {
+        match ty {
+            GenType::INT64 => "Int64".to_string(),
+            GenType::BOOLEAN => "Boolean".to_string(),
+            GenType::STRUCT(idx) => self.structs[*idx].name.clone(),
+            GenType::POINTER(pointee) => format!("*{}", self.type_name(pointee)),
+        }
+    }
+
+    fn fresh_name(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}{}", prefix, self.next_id)
+    }
+
+    fn emit(&mut self, line: &str) {
+        if line.is_empty() {
+            self.lines.push(String::new());
+        } else {
+            self.lines
+                .push(format!("{}{}", "    ".repeat(self.indent), line));
+        }
+    }
+}
+
+#[cfg(test)]
+mod generator_tests {
+    use super::*;
+
+    #[test]
+    fn deterministic_test() {
+        assert_eq!(generate_program(7), generate_program(7));
+        assert_ne!(generate_program(7), generate_program(8));
+    }
+
+    #[test]
+    fn generate_main_test() {
+        for seed in 0..20 {
+            let program = generate_program(seed);
+            assert!(program.contains("func main() Int64 {"));
+            assert!(program.contains("struct S0 {"));
+        }
+    }
+}
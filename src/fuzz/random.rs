@@ -0,0 +1,63 @@
+/// シードから決定的に擬似乱数を生成する(xorshift64*)
+/// 同じシードからは，どの環境でも同じプログラムが生成される
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // 状態が0だと0しか生成しないので，splitmix64で攪拌してから使う
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        Self {
+            state: if z == 0 { 1 } else { z },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// [low, high] の整数
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next_u64() % (high - low + 1)
+    }
+
+    /// percent% の確率で真
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.range(1, 100) <= percent
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0, items.len() as u64 - 1) as usize]
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::*;
+
+    #[test]
+    fn deterministic_test() {
+        let mut r1 = Random::new(42);
+        let mut r2 = Random::new(42);
+        let mut r3 = Random::new(43);
+
+        let s1: Vec<u64> = (0..8).map(|_| r1.next_u64()).collect();
+        let s2: Vec<u64> = (0..8).map(|_| r2.next_u64()).collect();
+        let s3: Vec<u64> = (0..8).map(|_| r3.next_u64()).collect();
+        assert_eq!(s1, s2);
+        assert_ne!(s1, s3);
+
+        let mut r = Random::new(0);
+        assert!((0..100)
+            .map(|_| r.range(3, 5))
+            .all(|n| (3..=5).contains(&n)));
+    }
+}
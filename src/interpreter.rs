@@ -0,0 +1,51 @@
+mod machine;
+mod memory;
+
+pub use machine::*;
+
+use crate::common::{module, pass};
+use crate::setup;
+use std::io;
+
+/// `peachili run` がプログラムを実行できなかったときの終了コード
+/// プログラム自身の終了コードと区別するため，envやtimeoutの慣習にならう
+pub const FAILED_TO_RUN: i32 = 125;
+
+/// `peachili run --interp` のメインルーチン
+/// 機械語を生成せずに三番地コードを実行し，プログラムの終了コードを返す
+/// program_argsはargvとして渡す(先頭はプログラム名)
+pub fn main(
+    module_arena: module::ModuleArena,
+    main_module_id: module::ModuleId,
+    debug: bool,
+    program_args: Vec<String>,
+) -> i32 {
+    let (fn_arena, ast_root, type_env, stack_frame) =
+        pass::frontend(module_arena, main_module_id, debug);
+    let (ir_module, _local_cfg) = pass::backend(
+        fn_arena,
+        ast_root,
+        &type_env,
+        setup::BUILD_OPTION.target,
+        false,
+        String::new(),
+    );
+
+    let stdout = io::stdout();
+    let stderr = io::stderr();
+    let mut machine = Machine::new(
+        &ir_module,
+        &stack_frame,
+        setup::BUILD_OPTION.target,
+        stdout.lock(),
+        stderr.lock(),
+    );
+
+    match machine.run(&program_args) {
+        Ok(exit_code) => exit_code,
+        Err(e) => {
+            e.output();
+            FAILED_TO_RUN
+        }
+    }
+}
@@ -0,0 +1,830 @@
+use crate::common::{
+    ast,
+    error::{CompileError, RuntimeErrorKind},
+    frame_object::{FrameObject, StackFrame},
+    option::Target,
+    peachili_type::{Type, TypeKind},
+    position::Position,
+    three_address_code as tac,
+    token::literal_bytes,
+};
+use crate::interpreter::memory::{self, Memory};
+use id_arena::Arena;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::rc::Rc;
+
+/// 実行を中断する理由
+enum Halt {
+    /// exitシステムコールによる終了
+    EXIT(i32),
+    ERROR(RuntimeErrorKind),
+}
+
+impl From<RuntimeErrorKind> for Halt {
+    fn from(kind: RuntimeErrorKind) -> Self {
+        Halt::ERROR(kind)
+    }
+}
+
+/// 実行用に展開した関数
+struct FunctionImage {
+    name: String,
+    codes: Vec<tac::CodeKind>,
+    values: Arena<tac::Value>,
+    args: Vec<(String, Type)>,
+    /// ラベル名 => 直後のコードの位置
+    labels: BTreeMap<String, usize>,
+    /// 変数名 => フレームポインタからのオフセット
+    /// 関数名のエントリはフレームの大きさ
+    frame: BTreeMap<String, FrameObject>,
+}
+
+impl FunctionImage {
+    fn frame_size(&self) -> u64 {
+        self.frame.get(&self.name).map_or(0, |f| f.offset as u64)
+    }
+}
+
+/// 呼び出し中の関数の状態
+struct Frame {
+    func: Rc<FunctionImage>,
+    pc: usize,
+    /// ネイティブコードのrbpに相当し，変数はここから下位に置かれる
+    base: u64,
+    temps: BTreeMap<usize, u64>,
+    /// 次の呼び出しに渡す引数
+    params: Vec<u64>,
+    /// インラインアセンブリから見えるレジスタ
+    /// 関数の入口では，引数レジスタに引数が入っている
+    registers: BTreeMap<String, u64>,
+    /// 返り値を格納する，呼び出し側のTEMP
+    result: Option<tac::ValueId>,
+}
+
+/// 三番地コードを直接実行する仮想機械
+/// 標準出力/標準エラー出力への書き込みはstdout/stderrに送る
+pub struct Machine<O: Write, E: Write> {
+    target: Target,
+    functions: BTreeMap<String, Rc<FunctionImage>>,
+    /// 関数ポインタの値からの逆引き
+    function_names: Vec<String>,
+    vtables: BTreeMap<String, Vec<String>>,
+    vtable_addresses: BTreeMap<String, u64>,
+    strings: BTreeMap<String, u64>,
+    memory: Memory,
+    frames: Vec<Frame>,
+    stack_pointer: u64,
+    stdout: O,
+    stderr: E,
+}
+
+impl<O: Write, E: Write> Machine<O, E> {
+    pub fn new(
+        ir_module: &tac::IRModule,
+        stack_frame: &StackFrame,
+        target: Target,
+        stdout: O,
+        stderr: E,
+    ) -> Self {
+        let mut functions = BTreeMap::new();
+        let mut function_names = Vec::new();
+
+        for fn_id in ir_module.funcs.iter() {
+            let ir_fn = ir_module.get_fn(fn_id);
+            let codes: Vec<tac::CodeKind> = ir_fn
+                .codes
+                .iter()
+                .map(|code_id| ir_fn.get_code(*code_id).kind)
+                .collect();
+            let labels = codes
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, code)| match code {
+                    tac::CodeKind::LABEL { name } => Some((name.clone(), idx)),
+                    _ => None,
+                })
+                .collect();
+
+            let image = FunctionImage {
+                name: ir_fn.name.clone(),
+                codes,
+                values: ir_fn.value_allocator.lock().unwrap().clone(),
+                args: ir_fn.args.clone(),
+                labels,
+                frame: stack_frame.get(&ir_fn.name).cloned().unwrap_or_default(),
+            };
+            function_names.push(ir_fn.name.clone());
+            functions.insert(ir_fn.name.clone(), Rc::new(image));
+        }
+
+        Self {
+            target,
+            functions,
+            function_names,
+            vtables: ir_module.vtables.clone(),
+            vtable_addresses: BTreeMap::new(),
+            strings: BTreeMap::new(),
+            memory: Default::default(),
+            frames: Vec::new(),
+            stack_pointer: memory::STACK_TOP,
+            stdout,
+            stderr,
+        }
+    }
+
+    /// main関数を呼び出し，プログラムの終了コードを返す
+    /// program_argsはargvとして渡す(先頭はプログラム名)
+    pub fn run(&mut self, program_args: &[String]) -> Result<i32, CompileError<RuntimeErrorKind>> {
+        let result = self.run_main(program_args);
+        let _ = self.stdout.flush();
+        let _ = self.stderr.flush();
+
+        match result {
+            // スタートアップルーチンと同じく，mainの返り値で終了する
+            Ok(returned) => Ok((returned & 0xff) as i32),
+            Err(Halt::EXIT(code)) => Ok(code),
+            Err(Halt::ERROR(kind)) => Err(CompileError::new(kind, Position::default())),
+        }
+    }
+
+    fn run_main(&mut self, program_args: &[String]) -> Result<u64, Halt> {
+        // argc, argv, envp をスタートアップルーチンと同じように用意する
+        let mut argv: Vec<u64> = program_args
+            .iter()
+            .map(|arg| self.intern_string(arg))
+            .collect();
+        argv.push(0);
+        let argv_bytes: Vec<u8> = argv.iter().flat_map(|ptr| ptr.to_le_bytes()).collect();
+        let argv_address = self.memory.alloc_data(&argv_bytes);
+        let envp_address = self.memory.alloc_data(&0u64.to_le_bytes());
+
+        let main_args = vec![program_args.len() as u64, argv_address, envp_address];
+        self.call("main", main_args, None)?;
+        self.execute()
+    }
+
+    /// 呼び出し元に戻るまで，あるいはmainから戻るまで実行する
+    fn execute(&mut self) -> Result<u64, Halt> {
+        loop {
+            let frame = self.frames.last_mut().unwrap();
+            let func = frame.func.clone();
+
+            // Noreturnな関数は末尾に到達すると戻る
+            if frame.pc >= func.codes.len() {
+                if let Some(returned) = self.return_from(0)? {
+                    return Ok(returned);
+                }
+                continue;
+            }
+            frame.pc += 1;
+
+            match &func.codes[frame.pc - 1] {
+                tac::CodeKind::ADD { lop, rop, result } => {
+                    let (lop, rop) = (self.read(&func, *lop)?, self.read(&func, *rop)?);
+                    self.write(&func, *result, lop.wrapping_add(rop))?;
+                }
+                tac::CodeKind::SUB { lop, rop, result } => {
+                    let (lop, rop) = (self.read(&func, *lop)?, self.read(&func, *rop)?);
+                    self.write(&func, *result, lop.wrapping_sub(rop))?;
+                }
+                tac::CodeKind::MUL { lop, rop, result } => {
+                    let (lop, rop) = (self.read(&func, *lop)?, self.read(&func, *rop)?);
+                    self.write(&func, *result, lop.wrapping_mul(rop))?;
+                }
+                tac::CodeKind::DIV { lop, rop, result } => {
+                    // idivと同じく符号付きで割る
+                    let (lop, rop) = (self.read(&func, *lop)?, self.read(&func, *rop)?);
+                    let quotient = (lop as i64).checked_div(rop as i64).ok_or_else(|| {
+                        RuntimeErrorKind::DivisionError {
+                            func_name: func.name.clone(),
+                        }
+                    })?;
+                    self.write(&func, *result, quotient as u64)?;
+                }
+                tac::CodeKind::NEG { value, result } => {
+                    let value = self.read(&func, *value)?;
+                    self.write(&func, *result, value.wrapping_neg())?;
+                }
+                tac::CodeKind::ASSIGN { value, result } => {
+                    let value = self.read(&func, *value)?;
+                    self.write(&func, *result, value)?;
+                }
+                tac::CodeKind::STORE { value, result } => {
+                    let value = self.read(&func, *value)?;
+                    let address = self.read(&func, *result)?;
+                    self.memory.write_word(address, value)?;
+                }
+                tac::CodeKind::ADDRESSOF { value, result } => {
+                    let address = self.variable_address(&func, *value);
+                    self.write(&func, *result, address)?;
+                }
+                tac::CodeKind::DEREFERENCE { value, result } => {
+                    let address = self.read(&func, *value)?;
+                    let value = self.memory.read_word(address)?;
+                    self.write(&func, *result, value)?;
+                }
+                tac::CodeKind::MEMBER { id, member, result } => {
+                    let offset = func.values[*id].ty.member_offset(member).unwrap() as u64;
+                    let address = self.variable_address(&func, *id) + offset;
+                    let value = self.memory.read_word(address)?;
+                    self.write(&func, *result, value)?;
+                }
+                tac::CodeKind::ZEROINIT { value } => {
+                    let address = self.variable_address(&func, *value);
+                    for word_offset in (0..func.values[*value].ty.size).step_by(8) {
+                        self.memory.write_word(address + word_offset as u64, 0)?;
+                    }
+                }
+                tac::CodeKind::COPY { src, dst, size } => {
+                    let (src, dst) = (self.read(&func, *src)?, self.read(&func, *dst)?);
+                    for word_offset in (0..*size as u64).step_by(8) {
+                        let word = self.memory.read_word(src + word_offset)?;
+                        self.memory.write_word(dst + word_offset, word)?;
+                    }
+                }
+                tac::CodeKind::LOADBYTE { addr, result } => {
+                    let address = self.read(&func, *addr)?;
+                    let byte = self.memory.read_byte(address)?;
+                    self.write(&func, *result, byte as u64)?;
+                }
+                tac::CodeKind::STOREBYTE { value, addr } => {
+                    let value = self.read(&func, *value)?;
+                    let address = self.read(&func, *addr)?;
+                    self.memory.write_byte(address, value as u8)?;
+                }
+                tac::CodeKind::BOUNDSCHECK {
+                    index,
+                    len,
+                    inclusive,
+                } => {
+                    let (index, len) = (self.read(&func, *index)?, self.read(&func, *len)?);
+                    let in_range = if *inclusive {
+                        index <= len
+                    } else {
+                        index < len
+                    };
+                    if !in_range {
+                        self.call(ast::INDEX_OUT_OF_RANGE_ROUTINE, Vec::new(), None)?;
+                    }
+                }
+                tac::CodeKind::LABEL { name: _ } | tac::CodeKind::ALLOC { temp: _ } => {}
+                tac::CodeKind::JUMP { label } => self.jump(&func, label),
+                tac::CodeKind::JUMPIFFALSE { label, cond_result } => {
+                    if self.read(&func, *cond_result)? == 0 {
+                        self.jump(&func, label);
+                    }
+                }
+                tac::CodeKind::RETURN { value } => {
+                    let value = self.read(&func, *value)?;
+                    if let Some(returned) = self.return_from(value)? {
+                        return Ok(returned);
+                    }
+                }
+                tac::CodeKind::PARAM { value } => {
+                    let value = self.read(&func, *value)?;
+                    self.frames.last_mut().unwrap().params.push(value);
+                }
+                tac::CodeKind::CALL { name, result } => {
+                    let args = std::mem::take(&mut self.frames.last_mut().unwrap().params);
+                    self.call(&func.values[*name].copy_contents(), args, Some(*result))?;
+                }
+                tac::CodeKind::ICALL { callee, result } => {
+                    let callee = self.read(&func, *callee)?;
+                    let name = self.function_at(callee)?;
+                    let args = std::mem::take(&mut self.frames.last_mut().unwrap().params);
+                    self.call(&name, args, Some(*result))?;
+                }
+                tac::CodeKind::VCALL {
+                    iface,
+                    index,
+                    result,
+                } => {
+                    // インタフェース値の2ワード目がvtableのアドレス
+                    let iface = self.read(&func, *iface)?;
+                    let vtable = self.memory.read_word(iface + 8)?;
+                    let method = self.memory.read_word(vtable + *index as u64 * 8)?;
+                    let name = self.function_at(method)?;
+                    let args = std::mem::take(&mut self.frames.last_mut().unwrap().params);
+                    self.call(&name, args, Some(*result))?;
+                }
+                tac::CodeKind::VTABLE { label, result } => {
+                    let address = self.vtable_address(label)?;
+                    self.write(&func, *result, address)?;
+                }
+                tac::CodeKind::FUNCADDR { name, result } => {
+                    let address = self.function_address(name)?;
+                    self.write(&func, *result, address)?;
+                }
+                tac::CodeKind::ASM { value } => {
+                    let asm = func.values[*value].copy_contents();
+                    self.execute_asm(&func.name, &asm)?;
+                }
+            }
+        }
+    }
+
+    /// 新しいフレームを積む
+    /// ネイティブコードと同じく，戻り番地とrbpの分を空けてからフレームを確保する
+    fn call(
+        &mut self,
+        name: &str,
+        args: Vec<u64>,
+        result: Option<tac::ValueId>,
+    ) -> Result<(), Halt> {
+        let func = match self.functions.get(name) {
+            Some(func) => func.clone(),
+            None => {
+                return Err(Halt::ERROR(RuntimeErrorKind::UndefinedFunction {
+                    name: name.to_string(),
+                }))
+            }
+        };
+
+        let base = self.stack_pointer - 16;
+        let frame_size = func.frame_size().next_multiple_of(16);
+        if base < Memory::stack_limit() + frame_size {
+            return Err(Halt::ERROR(RuntimeErrorKind::StackOverflow {
+                func_name: func.name.clone(),
+            }));
+        }
+        self.stack_pointer = base - frame_size;
+
+        // 引数レジスタの内容をスタックに格納する
+        let mut words = args.iter().copied();
+        for (arg_name, arg_type) in func.args.iter() {
+            let address = base - func.frame.get(arg_name).unwrap().offset as u64;
+            self.memory
+                .write_word(address, words.next().unwrap_or_default())?;
+
+            // インタフェース型/スライス型の引数は2ワードを受け取る
+            if arg_type.is_interface() || arg_type.is_slice() {
+                self.memory
+                    .write_word(address + 8, words.next().unwrap_or_default())?;
+            }
+        }
+
+        let registers = self
+            .param_registers()
+            .iter()
+            .zip(args.iter())
+            .map(|(reg, word)| (reg.to_string(), *word))
+            .collect();
+
+        self.frames.push(Frame {
+            func,
+            pc: 0,
+            base,
+            temps: BTreeMap::new(),
+            params: Vec::new(),
+            registers,
+            result,
+        });
+        Ok(())
+    }
+
+    /// フレームを降ろし，返り値を呼び出し側に格納する
+    /// mainから戻った場合は返り値を返す
+    fn return_from(&mut self, value: u64) -> Result<Option<u64>, Halt> {
+        let frame = self.frames.pop().unwrap();
+        self.stack_pointer = frame.base + 16;
+
+        let caller = match self.frames.last() {
+            Some(caller) => caller.func.clone(),
+            None => return Ok(Some(value)),
+        };
+        if let Some(result) = frame.result {
+            self.write(&caller, result, value)?;
+        }
+        Ok(None)
+    }
+
+    fn jump(&mut self, func: &FunctionImage, label: &str) {
+        self.frames.last_mut().unwrap().pc = *func.labels.get(label).unwrap();
+    }
+
+    fn read(&mut self, func: &FunctionImage, value_id: tac::ValueId) -> Result<u64, Halt> {
+        let value = &func.values[value_id];
+
+        let word = match &value.kind {
+            tac::ValueKind::INTLITERAL { value } => *value as u64,
+            tac::ValueKind::UINTLITERAL { value } => *value,
+            tac::ValueKind::BOOLEANLITERAL { truth } => *truth as u64,
+            tac::ValueKind::STRINGLITERAL { contents } => self.intern_string(contents),
+            tac::ValueKind::TEMP { number } => {
+                let frame = self.frames.last().unwrap();
+                frame.temps.get(number).copied().unwrap_or_default()
+            }
+            tac::ValueKind::ID { name: _ } if value.ty.is_constant() => constant_word(&value.ty),
+            tac::ValueKind::ID { name: _ } => {
+                let address = self.variable_address(func, value_id);
+                self.memory.read_word(address)?
+            }
+        };
+
+        Ok(word)
+    }
+
+    fn write(
+        &mut self,
+        func: &FunctionImage,
+        value_id: tac::ValueId,
+        word: u64,
+    ) -> Result<(), Halt> {
+        match &func.values[value_id].kind {
+            tac::ValueKind::TEMP { number } => {
+                let frame = self.frames.last_mut().unwrap();
+                frame.temps.insert(*number, word);
+            }
+            tac::ValueKind::ID { name: _ } => {
+                let address = self.variable_address(func, value_id);
+                self.memory.write_word(address, word)?;
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    /// 変数の先頭(最下位)アドレス
+    fn variable_address(&self, func: &FunctionImage, value_id: tac::ValueId) -> u64 {
+        let name = func.values[value_id].copy_contents();
+        let offset = func.frame.get(&name).unwrap().offset as u64;
+        self.frames.last().unwrap().base - offset
+    }
+
+    /// 文字列リテラルをNUL終端して静的データ領域に置く
+    fn intern_string(&mut self, contents: &str) -> u64 {
+        if let Some(address) = self.strings.get(contents) {
+            return *address;
+        }
+
+        let mut bytes = literal_bytes(contents);
+        bytes.push(0);
+        let address = self.memory.alloc_data(&bytes);
+        self.strings.insert(contents.to_string(), address);
+        address
+    }
+
+    fn function_address(&self, name: &str) -> Result<u64, Halt> {
+        match self.function_names.iter().position(|f| f == name) {
+            Some(idx) => Ok(memory::FUNCTION_BASE + idx as u64 * 16),
+            None => Err(Halt::ERROR(RuntimeErrorKind::UndefinedFunction {
+                name: name.to_string(),
+            })),
+        }
+    }
+
+    fn function_at(&self, address: u64) -> Result<String, Halt> {
+        let invalid = RuntimeErrorKind::InvalidMemoryAccess { address };
+        let offset = address
+            .checked_sub(memory::FUNCTION_BASE)
+            .ok_or_else(|| invalid.clone())?;
+        if offset % 16 != 0 {
+            return Err(Halt::ERROR(invalid));
+        }
+
+        match self.function_names.get((offset / 16) as usize) {
+            Some(name) => Ok(name.clone()),
+            None => Err(Halt::ERROR(invalid)),
+        }
+    }
+
+    /// vtableは初めて参照されたときに静的データ領域に置く
+    fn vtable_address(&mut self, label: &str) -> Result<u64, Halt> {
+        if let Some(address) = self.vtable_addresses.get(label) {
+            return Ok(*address);
+        }
+
+        let mut bytes = Vec::new();
+        for symbol in self.vtables.get(label).cloned().unwrap_or_default().iter() {
+            bytes.extend_from_slice(&self.function_address(symbol)?.to_le_bytes());
+        }
+        let address = self.memory.alloc_data(&bytes);
+        self.vtable_addresses.insert(label.to_string(), address);
+        Ok(address)
+    }
+
+    fn param_registers(&self) -> [&'static str; 6] {
+        match self.target {
+            Target::X86_64 => ["rdi", "rsi", "rdx", "rcx", "r8", "r9"],
+            Target::AARCH64 => ["x0", "x1", "x2", "x3", "x4", "x5"],
+        }
+    }
+
+    /// ライブラリのインラインアセンブリで使われる，レジスタ間/即値の転送とシステムコールだけを解釈する
+    fn execute_asm(&mut self, func_name: &str, asm: &str) -> Result<(), Halt> {
+        let unsupported = || {
+            Halt::ERROR(RuntimeErrorKind::UnsupportedAssembly {
+                func_name: func_name.to_string(),
+                asm: asm.to_string(),
+            })
+        };
+
+        let asm = asm.trim();
+        let (mnemonic, operands) = asm.split_once(' ').unwrap_or((asm, ""));
+        let operands: Vec<&str> = operands
+            .split(',')
+            .map(|op| op.trim())
+            .filter(|op| !op.is_empty())
+            .collect();
+
+        // (転送命令, 即値の接頭辞, レジスタの接頭辞, システムコール命令, システムコール番号のレジスタ)
+        let (mov, imm_prefix, reg_prefix, trap, number_reg) = match self.target {
+            Target::X86_64 => ("movq", "$", "%", "syscall", "rax"),
+            Target::AARCH64 => ("mov", "#", "", "svc", "x8"),
+        };
+
+        if mnemonic == trap {
+            let registers = &self.frames.last().unwrap().registers;
+            let reg = |name: &str| registers.get(name).copied().unwrap_or_default();
+            let number = reg(number_reg);
+            let [arg0, arg1, arg2, _, _, _] = self.param_registers();
+            let args = [reg(arg0), reg(arg1), reg(arg2)];
+
+            let returned = self.syscall(number, args)?;
+            let returned_reg = match self.target {
+                Target::X86_64 => "rax",
+                Target::AARCH64 => "x0",
+            };
+            let frame = self.frames.last_mut().unwrap();
+            frame.registers.insert(returned_reg.to_string(), returned);
+            return Ok(());
+        }
+
+        if mnemonic != mov || operands.len() != 2 {
+            return Err(unsupported());
+        }
+        let dst = operands[1]
+            .strip_prefix(reg_prefix)
+            .ok_or_else(unsupported)?;
+        let value = match operands[0].strip_prefix(imm_prefix) {
+            Some(imm) => imm.parse::<i64>().map_err(|_| unsupported())? as u64,
+            None => {
+                let src = operands[0]
+                    .strip_prefix(reg_prefix)
+                    .ok_or_else(unsupported)?;
+                let frame = self.frames.last().unwrap();
+                frame.registers.get(src).copied().unwrap_or_default()
+            }
+        };
+
+        let frame = self.frames.last_mut().unwrap();
+        frame.registers.insert(dst.to_string(), value);
+        Ok(())
+    }
+
+    /// exitとwriteを模倣する
+    fn syscall(&mut self, number: u64, args: [u64; 3]) -> Result<u64, Halt> {
+        let (exit, write) = match self.target {
+            Target::X86_64 => (60, 1),
+            Target::AARCH64 => (93, 64),
+        };
+
+        if number == exit {
+            return Err(Halt::EXIT((args[0] & 0xff) as i32));
+        }
+        if number != write {
+            return Err(Halt::ERROR(RuntimeErrorKind::UnsupportedSyscall { number }));
+        }
+
+        let [fd, buf, count] = args;
+        let bytes = self.memory.read_bytes(buf, count)?;
+        let written = match fd {
+            1 => self.stdout.write_all(bytes),
+            2 => self.stderr.write_all(bytes),
+            // EBADF
+            _ => return Ok(-9i64 as u64),
+        };
+
+        Ok(match written {
+            Ok(()) => count,
+            // EIO
+            Err(_) => -5i64 as u64,
+        })
+    }
+}
+
+/// 定数の値を1ワードで表す
+fn constant_word(const_type: &Type) -> u64 {
+    let value = const_type.get_const_value();
+    match const_type.get_const_type().kind {
+        TypeKind::INT64 => value.parse::<i64>().unwrap() as u64,
+        TypeKind::UINT64 => value.parse::<u64>().unwrap(),
+        TypeKind::BOOLEAN => (value == "true") as u64,
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod machine_tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn return_from_main_test() {
+        // func add(a Int64) Int64 { return a + 1; }
+        // func main() Int64 { return add(299); }  (299 + 1) & 0xff == 44
+        let int64 = Type::new_int64(Target::X86_64);
+        let mut add_fn = new_ir_function("add", vec![("a".to_string(), int64.clone())]);
+        let a = new_id(&add_fn, "a", int64.clone());
+        let one = new_value(&add_fn, tac::Value::new_int64(1, Target::X86_64));
+        let sum = new_value(&add_fn, tac::Value::new_temp(0, int64.clone()));
+        add_code(
+            &mut add_fn,
+            tac::CodeKind::ADD {
+                lop: a,
+                rop: one,
+                result: sum,
+            },
+        );
+        add_code(&mut add_fn, tac::CodeKind::RETURN { value: sum });
+
+        let mut main_fn = new_ir_function("main", Vec::new());
+        let callee = new_id(&main_fn, "add", int64.clone());
+        let arg = new_value(&main_fn, tac::Value::new_int64(299, Target::X86_64));
+        let returned = new_value(&main_fn, tac::Value::new_temp(0, int64));
+        add_code(&mut main_fn, tac::CodeKind::PARAM { value: arg });
+        add_code(
+            &mut main_fn,
+            tac::CodeKind::CALL {
+                name: callee,
+                result: returned,
+            },
+        );
+        add_code(&mut main_fn, tac::CodeKind::RETURN { value: returned });
+
+        let (ir_module, stack_frame) = new_module(vec![(main_fn, 0), (add_fn, 8)]);
+        let mut machine = Machine::new(
+            &ir_module,
+            &stack_frame,
+            Target::X86_64,
+            Vec::new(),
+            Vec::new(),
+        );
+        assert_eq!(Ok(44), machine.run(&["a.out".to_string()]));
+    }
+
+    #[test]
+    fn write_and_exit_syscall_test() {
+        // func write(buf ConstStr, len Int64) Noreturn { asm { ... } }
+        // func main() Noreturn { write("hi\n", 3); asm { exit(7) } }
+        let int64 = Type::new_int64(Target::X86_64);
+        let mut write_fn = new_ir_function(
+            "write",
+            vec![
+                ("buf".to_string(), Type::new_const_str(Target::X86_64)),
+                ("len".to_string(), int64.clone()),
+            ],
+        );
+        add_asm(
+            &mut write_fn,
+            &[
+                "movq %rsi, %rdx",
+                "movq %rdi, %rsi",
+                "movq $1, %rdi",
+                "movq $1, %rax",
+                "syscall",
+            ],
+        );
+
+        let mut main_fn = new_ir_function("main", Vec::new());
+        let callee = new_id(&main_fn, "write", int64.clone());
+        let buf = new_value(
+            &main_fn,
+            tac::Value::new_string_literal("hi\n".to_string(), Target::X86_64),
+        );
+        let len = new_value(&main_fn, tac::Value::new_int64(3, Target::X86_64));
+        let returned = new_value(&main_fn, tac::Value::new_temp(0, int64));
+        add_code(&mut main_fn, tac::CodeKind::PARAM { value: buf });
+        add_code(&mut main_fn, tac::CodeKind::PARAM { value: len });
+        add_code(
+            &mut main_fn,
+            tac::CodeKind::CALL {
+                name: callee,
+                result: returned,
+            },
+        );
+        add_asm(
+            &mut main_fn,
+            &["movq $7, %rdi", "movq $60, %rax", "syscall"],
+        );
+
+        let (ir_module, stack_frame) = new_module(vec![(main_fn, 0), (write_fn, 16)]);
+        let mut machine = Machine::new(
+            &ir_module,
+            &stack_frame,
+            Target::X86_64,
+            Vec::new(),
+            Vec::new(),
+        );
+        assert_eq!(Ok(7), machine.run(&[]));
+        assert_eq!(b"hi\n".to_vec(), machine.stdout);
+        assert!(machine.stderr.is_empty());
+    }
+
+    #[test]
+    fn division_by_zero_test() {
+        let int64 = Type::new_int64(Target::X86_64);
+        let mut main_fn = new_ir_function("main", Vec::new());
+        let one = new_value(&main_fn, tac::Value::new_int64(1, Target::X86_64));
+        let zero = new_value(&main_fn, tac::Value::new_int64(0, Target::X86_64));
+        let quotient = new_value(&main_fn, tac::Value::new_temp(0, int64));
+        add_code(
+            &mut main_fn,
+            tac::CodeKind::DIV {
+                lop: one,
+                rop: zero,
+                result: quotient,
+            },
+        );
+        add_code(&mut main_fn, tac::CodeKind::RETURN { value: quotient });
+
+        let (ir_module, stack_frame) = new_module(vec![(main_fn, 0)]);
+        let mut machine = Machine::new(
+            &ir_module,
+            &stack_frame,
+            Target::X86_64,
+            Vec::new(),
+            Vec::new(),
+        );
+        let err = machine.run(&[]).unwrap_err();
+        assert_eq!(
+            &RuntimeErrorKind::DivisionError {
+                func_name: "main".to_string()
+            },
+            err.get_kind()
+        );
+    }
+
+    fn new_module(funcs: Vec<(tac::IRFunction, usize)>) -> (tac::IRModule, StackFrame) {
+        let mut ir_module: tac::IRModule = Default::default();
+        let mut stack_frame = StackFrame::new();
+
+        for (ir_fn, frame_size) in funcs {
+            // 引数は8バイトずつ順に配置する
+            let mut frame = BTreeMap::new();
+            for (idx, (arg_name, _)) in ir_fn.args.iter().enumerate() {
+                frame.insert(
+                    arg_name.clone(),
+                    FrameObject {
+                        offset: 8 * (idx + 1),
+                    },
+                );
+            }
+            frame.insert(ir_fn.name.clone(), FrameObject { offset: frame_size });
+            stack_frame.insert(ir_fn.name.clone(), frame);
+
+            let fn_id = ir_module.fn_allocator.alloc(ir_fn);
+            ir_module.funcs.push(fn_id);
+        }
+
+        (ir_module, stack_frame)
+    }
+
+    fn new_ir_function(name: &str, args: Vec<(String, Type)>) -> tac::IRFunction {
+        tac::IRFunction {
+            name: name.to_string(),
+            fn_ty: Type::new_function(Type::new_int64(Target::X86_64), Vec::new()),
+            codes: Vec::new(),
+            value_allocator: Arc::new(Mutex::new(Arena::new())),
+            code_allocator: Arc::new(Mutex::new(Arena::new())),
+            args,
+        }
+    }
+
+    fn new_value(ir_fn: &tac::IRFunction, v: tac::Value) -> tac::ValueId {
+        ir_fn.value_allocator.lock().unwrap().alloc(v)
+    }
+
+    fn new_id(ir_fn: &tac::IRFunction, name: &str, ty: Type) -> tac::ValueId {
+        new_value(
+            ir_fn,
+            tac::Value::new(
+                tac::ValueKind::ID {
+                    name: name.to_string(),
+                },
+                ty,
+            ),
+        )
+    }
+
+    fn add_asm(ir_fn: &mut tac::IRFunction, stmts: &[&str]) {
+        for stmt in stmts.iter() {
+            let asm = new_value(
+                ir_fn,
+                tac::Value::new_string_literal(stmt.to_string(), Target::X86_64),
+            );
+            add_code(ir_fn, tac::CodeKind::ASM { value: asm });
+        }
+    }
+
+    fn add_code(ir_fn: &mut tac::IRFunction, kind: tac::CodeKind) {
+        let code_id = ir_fn
+            .code_allocator
+            .lock()
+            .unwrap()
+            .alloc(tac::Code { kind });
+        ir_fn.codes.push(code_id);
+    }
+}
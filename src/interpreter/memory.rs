@@ -0,0 +1,137 @@
+use crate::common::error::RuntimeErrorKind;
+
+/// 文字列リテラル/vtable/コマンドライン引数を置く領域の先頭
+/// 0番地付近は割り当てず，ヌルポインタの参照を検出できるようにする
+pub const DATA_BASE: u64 = 0x1000;
+/// スタックの底(最上位アドレス)
+pub const STACK_TOP: u64 = 0x7fff_0000;
+/// スタックの大きさ(Linuxの既定値と揃える)
+pub const STACK_SIZE: u64 = 8 * 1024 * 1024;
+/// 関数のアドレスとして用いる値の先頭
+/// 関数ポインタやvtableに格納されるだけで，この領域は読み書きできない
+pub const FUNCTION_BASE: u64 = 0x4000_0000;
+
+/// インタプリタのアドレス空間
+/// 静的データ領域とスタック領域からなり，どちらもリトルエンディアンで読み書きする
+pub struct Memory {
+    data: Vec<u8>,
+    stack: Vec<u8>,
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self {
+            data: Vec::new(),
+            stack: vec![0; STACK_SIZE as usize],
+        }
+    }
+}
+
+impl Memory {
+    /// スタックの上端(最下位アドレス)
+    pub fn stack_limit() -> u64 {
+        STACK_TOP - STACK_SIZE
+    }
+
+    /// 静的データ領域に8バイト境界で配置し，先頭アドレスを返す
+    pub fn alloc_data(&mut self, bytes: &[u8]) -> u64 {
+        let padding = (8 - self.data.len() % 8) % 8;
+        self.data.resize(self.data.len() + padding, 0);
+
+        let address = DATA_BASE + self.data.len() as u64;
+        self.data.extend_from_slice(bytes);
+        address
+    }
+
+    pub fn read_word(&self, address: u64) -> Result<u64, RuntimeErrorKind> {
+        let mut word = [0; 8];
+        word.copy_from_slice(self.read_bytes(address, 8)?);
+        Ok(u64::from_le_bytes(word))
+    }
+
+    pub fn write_word(&mut self, address: u64, word: u64) -> Result<(), RuntimeErrorKind> {
+        self.slice_mut(address, 8)?
+            .copy_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    pub fn read_byte(&self, address: u64) -> Result<u8, RuntimeErrorKind> {
+        Ok(self.read_bytes(address, 1)?[0])
+    }
+
+    pub fn write_byte(&mut self, address: u64, byte: u8) -> Result<(), RuntimeErrorKind> {
+        self.slice_mut(address, 1)?[0] = byte;
+        Ok(())
+    }
+
+    pub fn read_bytes(&self, address: u64, length: u64) -> Result<&[u8], RuntimeErrorKind> {
+        let (segment, base) = if address >= Self::stack_limit() {
+            (&self.stack, Self::stack_limit())
+        } else {
+            (&self.data, DATA_BASE)
+        };
+
+        let range = Self::range_in(segment.len(), base, address, length)?;
+        Ok(&segment[range])
+    }
+
+    fn slice_mut(&mut self, address: u64, length: u64) -> Result<&mut [u8], RuntimeErrorKind> {
+        let (segment, base) = if address >= Self::stack_limit() {
+            (&mut self.stack, Self::stack_limit())
+        } else {
+            (&mut self.data, DATA_BASE)
+        };
+
+        let range = Self::range_in(segment.len(), base, address, length)?;
+        Ok(&mut segment[range])
+    }
+
+    /// 領域内に収まっていれば，領域内の範囲を返す
+    fn range_in(
+        segment_length: usize,
+        base: u64,
+        address: u64,
+        length: u64,
+    ) -> Result<std::ops::Range<usize>, RuntimeErrorKind> {
+        let invalid = RuntimeErrorKind::InvalidMemoryAccess { address };
+        let start = address.checked_sub(base).ok_or_else(|| invalid.clone())?;
+        let end = start.checked_add(length).ok_or_else(|| invalid.clone())?;
+        if end > segment_length as u64 {
+            return Err(invalid);
+        }
+
+        Ok(start as usize..end as usize)
+    }
+}
+
+#[cfg(test)]
+mod memory_tests {
+    use super::*;
+
+    #[test]
+    fn read_write_test() {
+        let mut memory: Memory = Default::default();
+
+        let s = memory.alloc_data(b"abc\0");
+        assert_eq!(DATA_BASE, s);
+        assert_eq!(Ok(b'b'), memory.read_byte(s + 1));
+        // 8バイト境界に揃えて配置される
+        assert_eq!(DATA_BASE + 8, memory.alloc_data(&[0; 8]));
+
+        let top_word = STACK_TOP - 8;
+        assert!(memory.write_word(top_word, 0x1122_3344_5566_7788).is_ok());
+        assert_eq!(Ok(0x88), memory.read_byte(top_word));
+        assert_eq!(Ok(0x1122_3344_5566_7788), memory.read_word(top_word));
+
+        assert_eq!(
+            Err(RuntimeErrorKind::InvalidMemoryAccess { address: 0 }),
+            memory.read_word(0)
+        );
+        assert_eq!(
+            Err(RuntimeErrorKind::InvalidMemoryAccess {
+                address: STACK_TOP - 4
+            }),
+            memory.read_word(STACK_TOP - 4)
+        );
+    }
+}
@@ -2,6 +2,13 @@ pub mod arch;
 pub mod bundler;
 pub mod common;
 pub mod debug;
+pub mod difftest;
+pub mod doc;
+pub mod formatter;
+pub mod fuzz;
+pub mod interpreter;
+pub mod lsp;
+pub mod reducer;
 pub mod setup;
 
 #[macro_use]
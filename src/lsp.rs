@@ -0,0 +1,37 @@
+mod analysis;
+mod json;
+mod server;
+mod transport;
+
+pub use json::*;
+pub use server::*;
+pub use transport::*;
+
+use std::io;
+
+/// `peachili lsp` のメインルーチン
+/// 標準入出力でLSPのメッセージをやり取りし，`exit` 通知で終了する
+pub fn main(target: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+
+    let mut server = Server::new(target);
+
+    // 入力が途切れた場合は，shutdownなしでの終了とみなす
+    let mut exit_code = 1;
+    while let Some(message) = read_message(&mut reader)? {
+        for response in server.handle(&message) {
+            write_message(&mut writer, &response)?;
+        }
+
+        if let Some(code) = server.exit_code() {
+            exit_code = code;
+            break;
+        }
+    }
+
+    server.cleanup();
+    std::process::exit(exit_code);
+}
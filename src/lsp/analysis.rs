@@ -0,0 +1,671 @@
+use crate::bundler;
+use crate::common::{
+    ast,
+    error::TypeErrorKind,
+    pass,
+    peachili_type::{Type, TypeKind},
+    position::Position,
+    token::{Token, TokenKind},
+};
+use crate::doc::{self, ItemKind, ModuleDoc};
+use id_arena::Arena;
+use std::collections::BTreeMap;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+/// コンパイラが出力した診断
+/// 位置が分からないもの(パーサのpanic等)は行・列とも0
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    pub row: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// `peachili check` を別プロセスで実行し，診断を集める
+/// 各パスはエラー時にプロセスを終了するので，サーバ内では実行しない
+/// 返り値の真偽値は，同じソースをサーバ内で解析しても安全かどうか
+pub fn run_check(source_path: &str, target: &str) -> (bool, Vec<Diagnostic>) {
+    let output = std::env::current_exe().and_then(|exe| {
+        Command::new(exe)
+            .args(["check", source_path, "--target", target])
+            .env("NO_COLOR", "1")
+            .env("RUST_BACKTRACE", "0")
+            .output()
+    });
+
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            let message = format!("failed to run `peachili check`: {}", e);
+            return (false, vec![Diagnostic::unknown_position(message)]);
+        }
+    };
+
+    // ライブラリとして書かれたファイルも開かれるので，mainがないことは報告しない
+    let missing_main = TypeErrorKind::NotFoundMainFunction.to_string();
+    let all_diagnostics = parse_diagnostics(&String::from_utf8_lossy(&output.stderr));
+    let only_missing_main =
+        !all_diagnostics.is_empty() && all_diagnostics.iter().all(|d| d.message == missing_main);
+    let diagnostics: Vec<Diagnostic> = all_diagnostics
+        .into_iter()
+        .filter(|d| d.message != missing_main)
+        .collect();
+
+    let ok = output.status.success() || only_missing_main;
+    (ok, diagnostics)
+}
+
+/// `TypeError(48, 7) : message` の形式の行と，panicのメッセージを診断として取り出す
+pub fn parse_diagnostics(stderr: &str) -> Vec<Diagnostic> {
+    let stderr = strip_ansi_escapes(stderr);
+    let lines: Vec<&str> = stderr.lines().collect();
+    let mut diagnostics = Vec::new();
+
+    for (idx, line) in lines.iter().enumerate() {
+        if line.starts_with("thread '") && line.contains("panicked at") {
+            // 新しいRustではメッセージが次の行に出力される
+            let message = match line.split_once("panicked at '") {
+                Some((_, rest)) => rest.split("', ").next().unwrap_or(rest).to_string(),
+                None => lines.get(idx + 1).unwrap_or(&"").to_string(),
+            };
+            diagnostics.push(Diagnostic::unknown_position(message));
+            continue;
+        }
+
+        if let Some(diagnostic) = parse_error_line(line) {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    diagnostics
+}
+
+fn parse_error_line(line: &str) -> Option<Diagnostic> {
+    let (header, message) = line.split_once(" : ")?;
+    let (category, position) = header.split_once('(')?;
+    if category.is_empty() || !category.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let (row, column) = position.strip_suffix(')')?.split_once(", ")?;
+    Some(Diagnostic {
+        row: row.parse().ok()?,
+        column: column.parse().ok()?,
+        message: message.to_string(),
+    })
+}
+
+fn strip_ansi_escapes(s: &str) -> String {
+    let mut stripped = String::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // `ESC [ ... m` を読み飛ばす
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+            continue;
+        }
+        stripped.push(c);
+    }
+
+    stripped
+}
+
+impl Diagnostic {
+    fn unknown_position(message: String) -> Self {
+        Self {
+            row: 0,
+            column: 0,
+            message,
+        }
+    }
+}
+
+/// ソース上の宣言の位置
+#[derive(Debug, PartialEq)]
+pub struct DeclSite {
+    /// メソッドは `Rect.area` のように構造体名を付ける
+    pub name: String,
+    pub kind: ItemKind,
+    /// 宣言された名前の位置
+    pub position: Position,
+}
+
+/// トップレベルの宣言を，トークン列から探す
+/// 意味解析に失敗するソースでも使えるように，パーサは用いない
+pub fn scan_declarations(source: &str) -> Vec<DeclSite> {
+    let tokens = significant_tokens(source);
+    let mut sites = Vec::new();
+    let mut depth = 0;
+
+    for (idx, t) in tokens.iter().enumerate() {
+        match t.get_kind() {
+            TokenKind::LBRACE => depth += 1,
+            TokenKind::RBRACE => depth -= 1,
+            _ => {}
+        }
+        if depth != 0 {
+            continue;
+        }
+
+        let kind = match t.get_kind() {
+            TokenKind::FUNC => ItemKind::FUNCTION,
+            TokenKind::STRUCT => ItemKind::STRUCT,
+            TokenKind::PUBENUM => ItemKind::ENUM,
+            TokenKind::PUBINTERFACE => ItemKind::INTERFACE,
+            TokenKind::PUBTYPE => ItemKind::ALIAS,
+            TokenKind::PUBCONST => ItemKind::CONST,
+            _ => continue,
+        };
+
+        // `func (r *Rect) area()` の場合は，レシーバの型名を付ける
+        let mut name_idx = idx + 1;
+        let mut receiver = None;
+        if kind == ItemKind::FUNCTION && is_kind(&tokens, name_idx, &TokenKind::LPAREN) {
+            while name_idx < tokens.len() && tokens[name_idx].get_kind() != &TokenKind::RPAREN {
+                if let TokenKind::IDENTIFIER { name } = tokens[name_idx].get_kind() {
+                    receiver = Some(name.clone());
+                }
+                name_idx += 1;
+            }
+            name_idx += 1;
+        }
+
+        if let Some(TokenKind::IDENTIFIER { name }) = tokens.get(name_idx).map(|t| t.get_kind()) {
+            sites.push(DeclSite {
+                name: match &receiver {
+                    Some(struct_name) => ast::method_symbol(struct_name, name),
+                    None => name.clone(),
+                },
+                kind,
+                position: tokens[name_idx].get_position(),
+            });
+        }
+    }
+
+    sites
+}
+
+/// カーソル位置より前にある，ローカル変数・引数の宣言位置を探す
+pub fn local_declaration(source: &str, var_name: &str, cursor: Position) -> Option<Position> {
+    let tokens: Vec<Token> = significant_tokens(source)
+        .into_iter()
+        .take_while(|t| t.get_position() < cursor)
+        .collect();
+
+    // カーソルを囲む関数の先頭
+    let mut depth = 0;
+    let mut fn_head = None;
+    for (idx, t) in tokens.iter().enumerate() {
+        match t.get_kind() {
+            TokenKind::LBRACE => depth += 1,
+            TokenKind::RBRACE => depth -= 1,
+            TokenKind::FUNC if depth == 0 => fn_head = Some(idx),
+            _ => {}
+        }
+    }
+
+    let fn_head = fn_head?;
+    let mut found = None;
+    for idx in fn_head + 1..tokens.len() {
+        match tokens[idx].get_kind() {
+            TokenKind::IDENTIFIER { name } if name == var_name => {}
+            _ => continue,
+        }
+        let declares = match tokens[idx - 1].get_kind() {
+            TokenKind::DECLARE | TokenKind::VARINIT | TokenKind::CONST | TokenKind::COUNTUP => true,
+            // 引数とレシーバ
+            TokenKind::LPAREN | TokenKind::COMMA => {
+                !is_kind(&tokens, idx + 1, &TokenKind::RPAREN)
+                    && !is_kind(&tokens, idx + 1, &TokenKind::COMMA)
+                    && tokens[fn_head..idx]
+                        .iter()
+                        .all(|t| t.get_kind() != &TokenKind::LBRACE)
+            }
+            _ => false,
+        };
+
+        if declares {
+            found = Some(tokens[idx].get_position());
+        }
+    }
+
+    found
+}
+
+fn significant_tokens(source: &str) -> Vec<Token> {
+    match pass::tokenize_with_trivia(source.to_string()) {
+        Ok(tokens) => tokens
+            .into_iter()
+            .map(|(t, _)| t)
+            .filter(|t| !t.should_ignore() && !matches!(t.get_kind(), TokenKind::DOCCOMMENT { .. }))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn is_kind(tokens: &[Token], idx: usize, kind: &TokenKind) -> bool {
+    tokens.get(idx).is_some_and(|t| t.get_kind() == kind)
+}
+
+/// カーソル位置にある， `x64::exit_with` のような(修飾された)識別子
+#[derive(Debug, PartialEq)]
+pub struct Word {
+    pub text: String,
+    /// 行内のバイトオフセット
+    pub start: usize,
+    /// `.` の直後(メンバやメソッド)か
+    pub after_dot: bool,
+}
+
+/// 行内のバイトオフセットにある識別子を取り出す
+pub fn word_at(line: &str, offset: usize) -> Option<Word> {
+    let bytes = line.as_bytes();
+    let offset = offset.min(bytes.len());
+    let start = word_start(bytes, offset);
+
+    let mut end = offset;
+    loop {
+        if end < bytes.len() && is_ident_byte(bytes[end]) {
+            end += 1;
+        } else if bytes[end..].starts_with(b"::")
+            && bytes.get(end + 2).is_some_and(|b| is_ident_byte(*b))
+        {
+            end += 2;
+        } else {
+            break;
+        }
+    }
+
+    if start == end {
+        return None;
+    }
+
+    Some(Word {
+        text: line[start..end].to_string(),
+        start,
+        after_dot: start > 0 && bytes[start - 1] == b'.',
+    })
+}
+
+/// 補完のため，カーソルより前の識別子( `x64::` も含む)を取り出す
+pub fn prefix_at(line: &str, offset: usize) -> String {
+    let offset = offset.min(line.len());
+    let start = word_start(line.as_bytes(), offset);
+    line[start..offset].to_string()
+}
+
+fn word_start(bytes: &[u8], offset: usize) -> usize {
+    let mut start = offset;
+    loop {
+        if start > 0 && is_ident_byte(bytes[start - 1]) {
+            start -= 1;
+        } else if start >= 3 && &bytes[start - 2..start] == b"::" && is_ident_byte(bytes[start - 3])
+        {
+            start -= 2;
+        } else {
+            return start;
+        }
+    }
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// 補完候補
+pub struct Candidate {
+    pub label: String,
+    pub kind: CandidateKind,
+    pub detail: Option<String>,
+}
+
+pub enum CandidateKind {
+    MODULE,
+    VARIANT,
+    ITEM(ItemKind),
+}
+
+/// 意味解析が通ったソースから集めた情報
+pub struct Analysis {
+    fn_arena: ast::FnArena,
+    ast_root: ast::ASTRoot,
+    module_docs: Vec<ModuleDoc>,
+    /// 関数名 => (変数名 => 型)
+    type_env: BTreeMap<String, BTreeMap<String, Type>>,
+    /// (モジュール名, 宣言名) => (ファイルパス, 位置)
+    sites: BTreeMap<(String, String), (String, Position)>,
+}
+
+/// ソースを解析する
+/// `run_check` で安全と判定されたソースに対してのみ呼び出す
+pub fn analyze(source_path: &str) -> Analysis {
+    let module_arena = Arc::new(Mutex::new(Arena::new()));
+    let main_module = bundler::resolve_main(module_arena.clone(), source_path.to_string());
+
+    let mut sites = BTreeMap::new();
+    for (module_name, path) in pass::module_sources(module_arena.clone(), main_module) {
+        let source = std::fs::read_to_string(&path).unwrap_or_default();
+        for site in scan_declarations(&source) {
+            sites
+                .entry((module_name.clone(), site.name))
+                .or_insert((path.clone(), site.position));
+        }
+    }
+
+    let (fn_arena, ast_root, tld_env) = pass::collect_declarations(module_arena, main_module);
+    let module_docs = doc::collect_module_docs(fn_arena.clone(), &ast_root, &tld_env);
+
+    // 型環境はジェネリクスの実体化等を経て求まるので，フロントエンド全体を通す
+    let module_arena = Arc::new(Mutex::new(Arena::new()));
+    let main_module = bundler::resolve_main(module_arena.clone(), source_path.to_string());
+    let (_, _, type_env, _) = pass::frontend(module_arena, main_module, false);
+
+    Analysis {
+        fn_arena,
+        ast_root,
+        module_docs,
+        type_env,
+        sites,
+    }
+}
+
+impl Analysis {
+    /// ホバーに表示するMarkdown
+    pub fn hover(&self, word: &Word, cursor: Position) -> Option<String> {
+        if !word.after_dot && !word.text.contains("::") {
+            if let Some(var_type) = self.local_type(&word.text, cursor) {
+                return Some(format!(
+                    "```go\n{} {}\n```",
+                    word.text,
+                    display_value_type(&var_type)
+                ));
+            }
+        }
+
+        let (module_name, name) = self.resolve(word)?;
+        let item = self
+            .module_docs
+            .iter()
+            .find(|m| m.name == module_name)?
+            .items
+            .iter()
+            .find(|item| item.name == name)?;
+
+        let mut contents = format!("```go\n{}\n```", item.signature);
+        if let Some(doc) = &item.doc {
+            contents += &format!("\n\n{}", doc);
+        }
+        Some(contents)
+    }
+
+    /// 宣言の (ファイルパス, 位置)
+    /// ローカル変数は開いているソースの中から探すので，呼び出し側で扱う
+    pub fn definition(&self, word: &Word) -> Option<(String, Position)> {
+        self.sites.get(&self.resolve(word)?).cloned()
+    }
+
+    /// メインモジュールのトップレベル宣言
+    /// `(表示名, 種類, 位置)` を位置順に並べる
+    pub fn document_symbols(&self) -> Vec<(String, ItemKind, Position)> {
+        let mut names: Vec<(String, ItemKind)> = Vec::new();
+
+        let fn_arena = self.fn_arena.lock().unwrap();
+        for fn_id in self
+            .ast_root
+            .funcs
+            .iter()
+            .chain(self.ast_root.generic_funcs.iter())
+        {
+            let func = fn_arena.get(*fn_id).unwrap();
+            if !func.module_name.is_empty() {
+                continue;
+            }
+            let name = match &func.method_of {
+                Some(struct_name) => {
+                    ast::method_symbol(&doc::split_module(struct_name).1, &func.name)
+                }
+                None => func.name.clone(),
+            };
+            names.push((name, ItemKind::FUNCTION));
+        }
+
+        let declarations = [
+            (
+                ItemKind::STRUCT,
+                self.ast_root.typedefs.keys().collect::<Vec<_>>(),
+            ),
+            (ItemKind::ENUM, self.ast_root.enum_decls.keys().collect()),
+            (
+                ItemKind::INTERFACE,
+                self.ast_root.interfaces.keys().collect(),
+            ),
+            (ItemKind::CONST, self.ast_root.constants.keys().collect()),
+            (ItemKind::ALIAS, self.ast_root.alias.keys().collect()),
+        ];
+        for (kind, full_names) in declarations.iter() {
+            for full_name in full_names.iter() {
+                let (module_name, name) = doc::split_module(full_name);
+                if module_name.is_empty() {
+                    names.push((name, *kind));
+                }
+            }
+        }
+
+        let mut symbols: Vec<(String, ItemKind, Position)> = names
+            .into_iter()
+            .filter_map(|(name, kind)| {
+                let (_, position) = self.sites.get(&(String::new(), name.clone()))?;
+                Some((name, kind, *position))
+            })
+            .collect();
+        symbols.sort_by_key(|(_, _, position)| *position);
+        symbols
+    }
+
+    /// `x64::` のようなモジュール名の後ろや，列挙型名の後ろで補完する
+    pub fn completions(&self, prefix: &str) -> Vec<Candidate> {
+        let mut candidates = Vec::new();
+
+        if !prefix.contains("::") {
+            for module_doc in self.module_docs.iter() {
+                if module_doc.name.is_empty() {
+                    candidates.extend(self.item_candidates(module_doc));
+                } else if module_doc.name.starts_with(prefix) {
+                    candidates.push(Candidate {
+                        label: module_doc.name.clone(),
+                        kind: CandidateKind::MODULE,
+                        detail: None,
+                    });
+                }
+            }
+            return candidates;
+        }
+
+        let (module_name, _) = doc::split_module(prefix);
+        if let Some(module_doc) = self.module_docs.iter().find(|m| m.name == module_name) {
+            candidates.extend(self.item_candidates(module_doc));
+        }
+
+        // `Color::` のような列挙子
+        let enum_names = [module_name.clone(), format!("::{}", module_name)];
+        for enum_name in enum_names.iter() {
+            if let Some(enum_decl) = self.ast_root.enum_decls.get(enum_name) {
+                candidates.extend(enum_decl.variants.keys().map(|variant| Candidate {
+                    label: variant.clone(),
+                    kind: CandidateKind::VARIANT,
+                    detail: None,
+                }));
+            }
+        }
+
+        candidates
+    }
+
+    fn item_candidates(&self, module_doc: &ModuleDoc) -> Vec<Candidate> {
+        module_doc
+            .items
+            .iter()
+            .filter(|item| !item.name.contains('.'))
+            .map(|item| Candidate {
+                label: item.name.clone(),
+                kind: CandidateKind::ITEM(item.kind),
+                detail: Some(item.signature.clone()),
+            })
+            .collect()
+    }
+
+    /// 識別子を (モジュール名, 宣言名) に解決する
+    fn resolve(&self, word: &Word) -> Option<(String, String)> {
+        // メソッドは名前だけで探す
+        if word.after_dot {
+            let suffix = format!(".{}", word.text);
+            return self
+                .sites
+                .keys()
+                .find(|(_, name)| name.ends_with(&suffix))
+                .cloned();
+        }
+
+        let key = doc::split_module(&word.text);
+        if self.sites.contains_key(&key) {
+            return Some(key);
+        }
+
+        // `Color::RED` は列挙型の宣言に解決する
+        let enum_key = doc::split_module(&key.0);
+        if !key.0.is_empty() && self.sites.contains_key(&enum_key) {
+            return Some(enum_key);
+        }
+
+        None
+    }
+
+    /// カーソルを囲むメインモジュールの関数で，変数の型を探す
+    fn local_type(&self, var_name: &str, cursor: Position) -> Option<Type> {
+        let fn_arena = self.fn_arena.lock().unwrap();
+        let func = self
+            .ast_root
+            .funcs
+            .iter()
+            .map(|fn_id| fn_arena.get(*fn_id).unwrap())
+            .filter(|func| func.module_name.is_empty() && func.pos <= cursor)
+            .max_by_key(|func| func.pos)?;
+
+        self.type_env.get(&func.full_path())?.get(var_name).cloned()
+    }
+}
+
+/// 構造体は名前で表示する
+fn display_value_type(ty: &Type) -> String {
+    match &ty.kind {
+        TypeKind::STRUCT { name, members: _ } => doc::display_type(name),
+        TypeKind::POINTER { to } => format!("*{}", display_value_type(to)),
+        TypeKind::ARRAY { elem, length } => format!("[{}]{}", length, display_value_type(elem)),
+        TypeKind::SLICE { elem } => format!("[]{}", display_value_type(elem)),
+        TypeKind::CONST {
+            const_type,
+            value: _,
+        } => display_value_type(const_type),
+        _ => ty.dump(),
+    }
+}
+
+#[cfg(test)]
+mod analysis_tests {
+    use super::*;
+
+    #[test]
+    fn parse_diagnostics_test() {
+        let stderr = "\u{1b}[1;31mTypeError\u{1b}[0m(48, 7) : mismatched type in assignment\nthread 'main' (11106) panicked at src/common/pass/parser/parser_util.rs:173:9:\nexpected => BEGIN, got => INT64\nnote: run with `RUST_BACKTRACE=1`\n";
+
+        assert_eq!(
+            vec![
+                Diagnostic {
+                    row: 48,
+                    column: 7,
+                    message: "mismatched type in assignment".to_string(),
+                },
+                Diagnostic {
+                    row: 0,
+                    column: 0,
+                    message: "expected => BEGIN, got => INT64".to_string(),
+                },
+            ],
+            parse_diagnostics(stderr)
+        );
+    }
+
+    #[test]
+    fn scan_declarations_test() {
+        let source = "import x64;\n\nstruct Rect {\n    w Int64\n}\n\nfunc (r *Rect) area() Int64 {\n    return r.w;\n}\n\npubconst ONE : Int64 = 1;\nfunc main() Noreturn {}\n";
+        let names: Vec<(String, ItemKind, (usize, usize))> = scan_declarations(source)
+            .into_iter()
+            .map(|site| (site.name, site.kind, site.position.get_info()))
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("Rect".to_string(), ItemKind::STRUCT, (3, 8)),
+                ("Rect.area".to_string(), ItemKind::FUNCTION, (7, 16)),
+                ("ONE".to_string(), ItemKind::CONST, (11, 10)),
+                ("main".to_string(), ItemKind::FUNCTION, (12, 6)),
+            ],
+            names
+        );
+    }
+
+    #[test]
+    fn local_declaration_test() {
+        let source = "func f(a Int64, b Int64) Int64 {\n    varinit x = a;\n    declare y Int64;\n    return x + b;\n}\n";
+        let cursor = Position::new(4, 16);
+
+        assert_eq!(
+            Some(Position::new(2, 13)),
+            local_declaration(source, "x", cursor)
+        );
+        assert_eq!(
+            Some(Position::new(1, 17)),
+            local_declaration(source, "b", cursor)
+        );
+        assert_eq!(None, local_declaration(source, "z", cursor));
+
+        // 前の関数の引数は見えない
+        let source = format!("{}func g(c Int64) Int64 {{\n    return c;\n}}\n", source);
+        assert_eq!(
+            Some(Position::new(6, 8)),
+            local_declaration(&source, "c", Position::new(7, 12))
+        );
+        assert_eq!(None, local_declaration(&source, "a", Position::new(7, 12)));
+    }
+
+    #[test]
+    fn word_at_test() {
+        let line = "    x64::exit_with(p.norm);";
+        assert_eq!(
+            Some(Word {
+                text: "x64::exit_with".to_string(),
+                start: 4,
+                after_dot: false,
+            }),
+            word_at(line, 10)
+        );
+        assert_eq!(
+            Some(Word {
+                text: "norm".to_string(),
+                start: 21,
+                after_dot: true,
+            }),
+            word_at(line, 23)
+        );
+        assert_eq!(None, word_at(line, 2));
+
+        assert_eq!("x64::", prefix_at("    x64::", 9));
+        assert_eq!("x64::ex", prefix_at("    x64::ex", 11));
+    }
+}
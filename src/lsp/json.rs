@@ -0,0 +1,345 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Result as FR};
+
+/// LSPのメッセージを表すJSON値
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    NULL,
+    BOOLEAN(bool),
+    NUMBER(f64),
+    STRING(String),
+    ARRAY(Vec<Json>),
+    OBJECT(BTreeMap<String, Json>),
+}
+
+impl Json {
+    /// `(キー, 値)` の列からオブジェクトを作る
+    pub fn object(entries: Vec<(&str, Json)>) -> Self {
+        Json::OBJECT(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    pub fn string(s: &str) -> Self {
+        Json::STRING(s.to_string())
+    }
+
+    pub fn number(n: usize) -> Self {
+        Json::NUMBER(n as f64)
+    }
+
+    /// オブジェクトのメンバを取り出す
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::OBJECT(members) => members.get(key),
+            _ => None,
+        }
+    }
+
+    /// `textDocument.uri` のように，ドット区切りでメンバを辿る
+    pub fn path(&self, keys: &str) -> Option<&Json> {
+        keys.split('.').try_fold(self, |json, key| json.get(key))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::STRING(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Json::NUMBER(n) if *n >= 0.0 => Some(*n as usize),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Json>> {
+        match self {
+            Json::ARRAY(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// JSON文字列を解析する
+    pub fn parse(s: &str) -> Result<Json, String> {
+        let mut parser = JsonParser {
+            chars: s.chars().collect(),
+            cur: 0,
+        };
+
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.cur != parser.chars.len() {
+            return Err(format!("unexpected trailing characters at {}", parser.cur));
+        }
+        Ok(value)
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FR {
+        match self {
+            Json::NULL => write!(f, "null"),
+            Json::BOOLEAN(b) => write!(f, "{}", b),
+            Json::NUMBER(n) => {
+                if n.fract() == 0.0 && n.abs() < 1e15 {
+                    write!(f, "{}", *n as i64)
+                } else {
+                    write!(f, "{}", n)
+                }
+            }
+            Json::STRING(s) => write!(f, "{}", escape_string(s)),
+            Json::ARRAY(elements) => {
+                let elements: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                write!(f, "[{}]", elements.join(","))
+            }
+            Json::OBJECT(members) => {
+                let members: Vec<String> = members
+                    .iter()
+                    .map(|(key, value)| format!("{}:{}", escape_string(key), value))
+                    .collect();
+                write!(f, "{{{}}}", members.join(","))
+            }
+        }
+    }
+}
+
+fn escape_string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped += "\\\"",
+            '\\' => escaped += "\\\\",
+            '\n' => escaped += "\\n",
+            '\r' => escaped += "\\r",
+            '\t' => escaped += "\\t",
+            c if (c as u32) < 0x20 => escaped += &format!("\\u{:04x}", c as u32),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+struct JsonParser {
+    chars: Vec<char>,
+    cur: usize,
+}
+
+impl JsonParser {
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('n') => self.keyword("null", Json::NULL),
+            Some('t') => self.keyword("true", Json::BOOLEAN(true)),
+            Some('f') => self.keyword("false", Json::BOOLEAN(false)),
+            Some('"') => Ok(Json::STRING(self.string()?)),
+            Some('[') => self.array(),
+            Some('{') => self.object(),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => Err(format!("unexpected character `{}` at {}", c, self.cur)),
+            None => Err("unexpected end of input".to_string()),
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: Json) -> Result<Json, String> {
+        for expected in word.chars() {
+            if self.next() != Some(expected) {
+                return Err(format!("expected `{}` at {}", word, self.cur));
+            }
+        }
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.cur;
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_digit() || "+-.eE".contains(c)) {
+                break;
+            }
+            self.cur += 1;
+        }
+
+        let literal: String = self.chars[start..self.cur].iter().collect();
+        literal
+            .parse::<f64>()
+            .map(Json::NUMBER)
+            .map_err(|_| format!("invalid number `{}`", literal))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut s = String::new();
+
+        loop {
+            match self.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.next() {
+                    Some('"') => s.push('"'),
+                    Some('\\') => s.push('\\'),
+                    Some('/') => s.push('/'),
+                    Some('b') => s.push('\u{8}'),
+                    Some('f') => s.push('\u{c}'),
+                    Some('n') => s.push('\n'),
+                    Some('r') => s.push('\r'),
+                    Some('t') => s.push('\t'),
+                    Some('u') => s.push(self.unicode_escape()?),
+                    _ => return Err(format!("invalid escape sequence at {}", self.cur)),
+                },
+                Some(c) => s.push(c),
+                None => return Err("unterminated string".to_string()),
+            }
+        }
+    }
+
+    /// `\uXXXX` (サロゲートペアを含む)
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| "invalid unicode escape".to_string());
+        }
+
+        if self.next() != Some('\\') || self.next() != Some('u') {
+            return Err("unpaired surrogate".to_string());
+        }
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err("unpaired surrogate".to_string());
+        }
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+            .ok_or_else(|| "invalid unicode escape".to_string())
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let mut value = 0;
+        for _ in 0..4 {
+            let digit = self
+                .next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| format!("invalid unicode escape at {}", self.cur))?;
+            value = value * 16 + digit;
+        }
+        Ok(value)
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.expect('[')?;
+        let mut elements = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.cur += 1;
+            return Ok(Json::ARRAY(elements));
+        }
+
+        loop {
+            elements.push(self.value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Json::ARRAY(elements)),
+                _ => return Err(format!("expected `,` or `]` at {}", self.cur)),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.expect('{')?;
+        let mut members = BTreeMap::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.cur += 1;
+            return Ok(Json::OBJECT(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            members.insert(key, self.value()?);
+
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Json::OBJECT(members)),
+                _ => return Err(format!("expected `,` or `}}` at {}", self.cur)),
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            _ => Err(format!("expected `{}` at {}", expected, self.cur)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.cur += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.cur).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.cur += 1;
+        c
+    }
+}
+
+#[cfg(test)]
+mod json_tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        let json = Json::parse(
+            r#"{"id": 1, "params": {"textDocument": {"uri": "file:///a.go"}, "items": [true, null, -2.5]}, "s": "a\"\nあ\ud83c\udf51"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(Some(1), json.get("id").and_then(|id| id.as_usize()));
+        assert_eq!(
+            Some("file:///a.go"),
+            json.path("params.textDocument.uri")
+                .and_then(|uri| uri.as_str())
+        );
+        assert_eq!(
+            Some(&vec![Json::BOOLEAN(true), Json::NULL, Json::NUMBER(-2.5)]),
+            json.path("params.items").and_then(|items| items.as_array())
+        );
+        assert_eq!(Some("a\"\nあ🍑"), json.get("s").and_then(|s| s.as_str()));
+
+        assert!(Json::parse("{\"a\": }").is_err());
+        assert!(Json::parse("[1, 2] 3").is_err());
+    }
+
+    #[test]
+    fn display_test() {
+        let json = Json::object(vec![
+            ("id", Json::number(3)),
+            (
+                "result",
+                Json::ARRAY(vec![Json::NULL, Json::BOOLEAN(false)]),
+            ),
+            ("message", Json::string("say \"hi\"\n")),
+        ]);
+        assert_eq!(
+            r#"{"id":3,"message":"say \"hi\"\n","result":[null,false]}"#,
+            json.to_string()
+        );
+        assert_eq!(json, Json::parse(&json.to_string()).unwrap());
+    }
+}
@@ -0,0 +1,508 @@
+use crate::common::position::Position;
+use crate::doc::ItemKind;
+use crate::lsp::{analysis, Json};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// エディタで開かれているソース
+struct Document {
+    text: String,
+    /// 解析のためにソースを書き出した一時ファイル
+    temp_path: String,
+    /// 最後に意味解析が通ったときの結果
+    analysis: Option<analysis::Analysis>,
+}
+
+/// LSPのリクエスト/通知を処理する
+pub struct Server {
+    target: String,
+    documents: BTreeMap<String, Document>,
+    shutdown_requested: bool,
+    /// `exit` 通知を受け取ったら，終了コードがセットされる
+    exit_code: Option<i32>,
+    next_document_id: usize,
+}
+
+impl Server {
+    pub fn new(target: &str) -> Self {
+        Self {
+            target: target.to_string(),
+            documents: BTreeMap::new(),
+            shutdown_requested: false,
+            exit_code: None,
+            next_document_id: 0,
+        }
+    }
+
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    /// メッセージを1つ処理し，クライアントへ送るメッセージを返す
+    pub fn handle(&mut self, message: &Json) -> Vec<Json> {
+        let method = message.get("method").and_then(|m| m.as_str());
+        let params = message.get("params").unwrap_or(&Json::NULL);
+
+        // idを持たないものは通知
+        let id = match message.get("id") {
+            Some(id) => id.clone(),
+            None => {
+                return match method {
+                    Some(method) => self.handle_notification(method, params),
+                    None => Vec::new(),
+                };
+            }
+        };
+
+        let result = match method.unwrap_or_default() {
+            "initialize" => Ok(self.initialize()),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Json::NULL)
+            }
+            "textDocument/hover" => Ok(self.hover(params)),
+            "textDocument/definition" => Ok(self.definition(params)),
+            "textDocument/documentSymbol" => Ok(self.document_symbols(params)),
+            "textDocument/completion" => Ok(self.completion(params)),
+            method => Err(format!("method `{}` is not supported", method)),
+        };
+
+        vec![match result {
+            Ok(result) => Json::object(vec![
+                ("jsonrpc", Json::string("2.0")),
+                ("id", id),
+                ("result", result),
+            ]),
+            Err(message) => Json::object(vec![
+                ("jsonrpc", Json::string("2.0")),
+                ("id", id),
+                (
+                    "error",
+                    Json::object(vec![
+                        ("code", Json::NUMBER(-32601.0)),
+                        ("message", Json::STRING(message)),
+                    ]),
+                ),
+            ]),
+        }]
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Json) -> Vec<Json> {
+        let uri = params
+            .path("textDocument.uri")
+            .and_then(|uri| uri.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        match method {
+            "exit" => {
+                self.exit_code = Some(if self.shutdown_requested { 0 } else { 1 });
+                Vec::new()
+            }
+            "textDocument/didOpen" => {
+                let text = params.path("textDocument.text").and_then(|t| t.as_str());
+                self.update_document(&uri, text.unwrap_or_default())
+            }
+            "textDocument/didChange" => {
+                // 全文同期なので，最後の変更が現在の内容
+                let text = params
+                    .get("contentChanges")
+                    .and_then(|changes| changes.as_array())
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(|text| text.as_str());
+                match text {
+                    Some(text) => self.update_document(&uri, text),
+                    None => Vec::new(),
+                }
+            }
+            "textDocument/didSave" => {
+                let text = match self.documents.get(&uri) {
+                    Some(document) => document.text.clone(),
+                    None => return Vec::new(),
+                };
+                self.update_document(&uri, &text)
+            }
+            "textDocument/didClose" => {
+                if let Some(document) = self.documents.remove(&uri) {
+                    let _ = std::fs::remove_file(document.temp_path);
+                }
+                vec![publish_diagnostics(&uri, Vec::new())]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// 一時ファイルの削除
+    pub fn cleanup(&mut self) {
+        for (_, document) in std::mem::take(&mut self.documents) {
+            let _ = std::fs::remove_file(document.temp_path);
+        }
+    }
+
+    fn initialize(&self) -> Json {
+        Json::object(vec![
+            (
+                "capabilities",
+                Json::object(vec![
+                    ("textDocumentSync", Json::number(1)),
+                    ("hoverProvider", Json::BOOLEAN(true)),
+                    ("definitionProvider", Json::BOOLEAN(true)),
+                    ("documentSymbolProvider", Json::BOOLEAN(true)),
+                    (
+                        "completionProvider",
+                        Json::object(vec![(
+                            "triggerCharacters",
+                            Json::ARRAY(vec![Json::string(":")]),
+                        )]),
+                    ),
+                ]),
+            ),
+            (
+                "serverInfo",
+                Json::object(vec![("name", Json::string("peachili"))]),
+            ),
+        ])
+    }
+
+    /// ソースを一時ファイルに書き出して検査し，診断を送る
+    /// 検査が通れば，ホバー等のための解析結果を更新する
+    fn update_document(&mut self, uri: &str, text: &str) -> Vec<Json> {
+        if !self.documents.contains_key(uri) {
+            let temp_path = std::env::temp_dir()
+                .join(format!(
+                    "peachili-lsp-{}-{}.go",
+                    std::process::id(),
+                    self.next_document_id
+                ))
+                .to_string_lossy()
+                .to_string();
+            self.next_document_id += 1;
+
+            self.documents.insert(
+                uri.to_string(),
+                Document {
+                    text: String::new(),
+                    temp_path,
+                    analysis: None,
+                },
+            );
+        }
+        let document = self.documents.get_mut(uri).unwrap();
+        document.text = text.to_string();
+
+        if let Err(e) = std::fs::write(&document.temp_path, text) {
+            let diagnostic = analysis::Diagnostic {
+                row: 0,
+                column: 0,
+                message: format!("cannot write {}: {}", document.temp_path, e),
+            };
+            return vec![publish_diagnostics(
+                uri,
+                vec![diagnostic_json(text, &diagnostic)],
+            )];
+        }
+
+        let (ok, diagnostics) = analysis::run_check(&document.temp_path, &self.target);
+        // 失敗した場合は，以前のバッファの位置情報を使わないよう破棄する
+        document.analysis = if ok {
+            Some(analysis::analyze(&document.temp_path))
+        } else {
+            None
+        };
+
+        let diagnostics = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic_json(text, diagnostic))
+            .collect();
+        vec![publish_diagnostics(uri, diagnostics)]
+    }
+
+    /// `params` が指すドキュメントと，カーソル位置の識別子
+    fn word_at_cursor<'a>(
+        &'a self,
+        params: &'a Json,
+    ) -> Option<(&'a str, &'a Document, analysis::Word, Position)> {
+        let uri = params.path("textDocument.uri")?.as_str()?;
+        let document = self.documents.get(uri)?;
+        let line_number = params.path("position.line")?.as_usize()?;
+        let character = params.path("position.character")?.as_usize()?;
+
+        let line = document.text.lines().nth(line_number)?;
+        let offset = utf16_to_byte_offset(line, character);
+        let word = analysis::word_at(line, offset)?;
+        let cursor = Position::new(line_number + 1, offset + 1);
+        Some((uri, document, word, cursor))
+    }
+
+    fn hover(&self, params: &Json) -> Json {
+        let contents = self
+            .word_at_cursor(params)
+            .and_then(|(_, document, word, cursor)| {
+                document.analysis.as_ref()?.hover(&word, cursor)
+            });
+
+        match contents {
+            Some(contents) => Json::object(vec![(
+                "contents",
+                Json::object(vec![
+                    ("kind", Json::string("markdown")),
+                    ("value", Json::STRING(contents)),
+                ]),
+            )]),
+            None => Json::NULL,
+        }
+    }
+
+    fn definition(&self, params: &Json) -> Json {
+        let (uri, document, word, cursor) = match self.word_at_cursor(params) {
+            Some(found) => found,
+            None => return Json::NULL,
+        };
+
+        // ローカル変数は，解析に失敗していても探せる
+        if !word.after_dot && !word.text.contains("::") {
+            if let Some(position) = analysis::local_declaration(&document.text, &word.text, cursor)
+            {
+                return location_json(uri, &document.text, position, &word.text);
+            }
+        }
+
+        let (path, position) = match document
+            .analysis
+            .as_ref()
+            .and_then(|analysis| analysis.definition(&word))
+        {
+            Some(site) => site,
+            None => return Json::NULL,
+        };
+        let name = word.text.rsplit("::").next().unwrap_or_default();
+
+        if path == document.temp_path {
+            return location_json(uri, &document.text, position, name);
+        }
+        let source = std::fs::read_to_string(&path).unwrap_or_default();
+        location_json(&path_to_uri(&path), &source, position, name)
+    }
+
+    fn document_symbols(&self, params: &Json) -> Json {
+        let document = match params
+            .path("textDocument.uri")
+            .and_then(|uri| uri.as_str())
+            .and_then(|uri| self.documents.get(uri))
+        {
+            Some(document) => document,
+            None => return Json::NULL,
+        };
+
+        // 解析に失敗している場合は，トークン列から探す
+        let symbols: Vec<(String, ItemKind, Position)> = match &document.analysis {
+            Some(analysis) => analysis.document_symbols(),
+            None => analysis::scan_declarations(&document.text)
+                .into_iter()
+                .map(|site| (site.name, site.kind, site.position))
+                .collect(),
+        };
+
+        Json::ARRAY(
+            symbols
+                .into_iter()
+                .map(|(name, kind, position)| {
+                    let symbol_kind = match kind {
+                        ItemKind::FUNCTION if name.contains('.') => 6,
+                        ItemKind::FUNCTION => 12,
+                        ItemKind::STRUCT => 23,
+                        ItemKind::ENUM => 10,
+                        ItemKind::INTERFACE => 11,
+                        ItemKind::CONST => 14,
+                        ItemKind::ALIAS => 5,
+                    };
+                    let range = range_json(&document.text, position, &name);
+                    Json::object(vec![
+                        ("name", Json::STRING(name)),
+                        ("kind", Json::number(symbol_kind)),
+                        ("range", range.clone()),
+                        ("selectionRange", range),
+                    ])
+                })
+                .collect(),
+        )
+    }
+
+    fn completion(&self, params: &Json) -> Json {
+        let candidates = (|| {
+            let uri = params.path("textDocument.uri")?.as_str()?;
+            let document = self.documents.get(uri)?;
+            let line_number = params.path("position.line")?.as_usize()?;
+            let character = params.path("position.character")?.as_usize()?;
+
+            let line = document.text.lines().nth(line_number).unwrap_or_default();
+            let prefix = analysis::prefix_at(line, utf16_to_byte_offset(line, character));
+            Some(document.analysis.as_ref()?.completions(&prefix))
+        })()
+        .unwrap_or_default();
+
+        Json::ARRAY(
+            candidates
+                .into_iter()
+                .map(|candidate| {
+                    let kind = match candidate.kind {
+                        analysis::CandidateKind::MODULE => 9,
+                        analysis::CandidateKind::VARIANT => 20,
+                        analysis::CandidateKind::ITEM(ItemKind::FUNCTION) => 3,
+                        analysis::CandidateKind::ITEM(ItemKind::STRUCT) => 22,
+                        analysis::CandidateKind::ITEM(ItemKind::ENUM) => 13,
+                        analysis::CandidateKind::ITEM(ItemKind::INTERFACE) => 8,
+                        analysis::CandidateKind::ITEM(ItemKind::CONST) => 21,
+                        analysis::CandidateKind::ITEM(ItemKind::ALIAS) => 7,
+                    };
+                    let mut entries = vec![
+                        ("label", Json::STRING(candidate.label)),
+                        ("kind", Json::number(kind)),
+                    ];
+                    if let Some(detail) = candidate.detail {
+                        entries.push(("detail", Json::STRING(detail)));
+                    }
+                    Json::object(entries)
+                })
+                .collect(),
+        )
+    }
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Json>) -> Json {
+    Json::object(vec![
+        ("jsonrpc", Json::string("2.0")),
+        ("method", Json::string("textDocument/publishDiagnostics")),
+        (
+            "params",
+            Json::object(vec![
+                ("uri", Json::string(uri)),
+                ("diagnostics", Json::ARRAY(diagnostics)),
+            ]),
+        ),
+    ])
+}
+
+/// 診断の範囲は，位置にある識別子(なければ1文字)とする
+fn diagnostic_json(text: &str, diagnostic: &analysis::Diagnostic) -> Json {
+    let position = Position::new(diagnostic.row.max(1), diagnostic.column.max(1));
+    let word = text
+        .lines()
+        .nth(position.get_info().0 - 1)
+        .and_then(|line| analysis::word_at(line, position.get_info().1 - 1))
+        .map(|word| word.text)
+        .unwrap_or_else(|| " ".to_string());
+
+    Json::object(vec![
+        ("range", range_json(text, position, &word)),
+        ("severity", Json::number(1)),
+        ("source", Json::string("peachili")),
+        ("message", Json::string(&diagnostic.message)),
+    ])
+}
+
+fn location_json(uri: &str, text: &str, position: Position, name: &str) -> Json {
+    Json::object(vec![
+        ("uri", Json::string(uri)),
+        ("range", range_json(text, position, name)),
+    ])
+}
+
+/// コンパイラの位置(1始まりの行，バイト単位の列)から，
+/// `name` の長さ分のLSPの範囲(0始まりの行，UTF-16単位の列)を作る
+fn range_json(text: &str, position: Position, name: &str) -> Json {
+    let (row, column) = position.get_info();
+    let line = text.lines().nth(row.saturating_sub(1)).unwrap_or_default();
+    let start = column.saturating_sub(1);
+    let end = start + name.len();
+
+    let position_json = |offset: usize| {
+        Json::object(vec![
+            ("line", Json::number(row.saturating_sub(1))),
+            (
+                "character",
+                Json::number(byte_to_utf16_offset(line, offset)),
+            ),
+        ])
+    };
+    Json::object(vec![
+        ("start", position_json(start)),
+        ("end", position_json(end)),
+    ])
+}
+
+fn utf16_to_byte_offset(line: &str, character: usize) -> usize {
+    let mut utf16_offset = 0;
+    for (byte_offset, c) in line.char_indices() {
+        if utf16_offset >= character {
+            return byte_offset;
+        }
+        utf16_offset += c.len_utf16();
+    }
+    line.len()
+}
+
+fn byte_to_utf16_offset(line: &str, offset: usize) -> usize {
+    line.char_indices()
+        .take_while(|(byte_offset, _)| *byte_offset < offset)
+        .map(|(_, c)| c.len_utf16())
+        .sum::<usize>()
+        + offset.saturating_sub(line.len())
+}
+
+fn path_to_uri(path: &str) -> String {
+    let path = Path::new(path)
+        .canonicalize()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string());
+    format!("file://{}", path)
+}
+
+#[cfg(test)]
+mod server_tests {
+    use super::*;
+
+    fn request(id: usize, method: &str, params: Json) -> Json {
+        Json::object(vec![
+            ("jsonrpc", Json::string("2.0")),
+            ("id", Json::number(id)),
+            ("method", Json::string(method)),
+            ("params", params),
+        ])
+    }
+
+    #[test]
+    fn lifecycle_test() {
+        let mut server = Server::new("x86_64");
+
+        let responses = server.handle(&request(1, "initialize", Json::object(vec![])));
+        assert_eq!(1, responses.len());
+        assert_eq!(
+            Some(1),
+            responses[0]
+                .path("result.capabilities.textDocumentSync")
+                .and_then(|sync| sync.as_usize())
+        );
+
+        let responses = server.handle(&request(2, "workspace/symbol", Json::object(vec![])));
+        assert_eq!(
+            Some(&Json::NUMBER(-32601.0)),
+            responses[0].path("error.code")
+        );
+
+        server.handle(&request(3, "shutdown", Json::NULL));
+        assert_eq!(None, server.exit_code());
+        server.handle(&Json::object(vec![("method", Json::string("exit"))]));
+        assert_eq!(Some(0), server.exit_code());
+    }
+
+    #[test]
+    fn offset_conversion_test() {
+        let line = "// あ🍑 x";
+        assert_eq!(3, utf16_to_byte_offset(line, 3));
+        assert_eq!(10, utf16_to_byte_offset(line, 6));
+        assert_eq!(6, byte_to_utf16_offset(line, 10));
+        assert_eq!(line.len(), utf16_to_byte_offset(line, 100));
+    }
+}
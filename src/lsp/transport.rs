@@ -0,0 +1,79 @@
+use crate::lsp::Json;
+use std::io::{self, BufRead, Write};
+
+/// `Content-Length` ヘッダ付きのメッセージを1つ読み込む
+/// 入力が終了していればNone
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Json>> {
+    let mut content_length = None;
+
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+
+        let header = header.trim_end();
+        if header.is_empty() {
+            // ヘッダより前の空行は読み飛ばす
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length.unwrap()];
+    reader.read_exact(&mut body)?;
+
+    let body = String::from_utf8_lossy(&body);
+    Json::parse(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// メッセージに `Content-Length` ヘッダを付けて書き込む
+pub fn write_message<W: Write>(writer: &mut W, message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod transport_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn read_message_test() {
+        let body = r#"{"jsonrpc":"2.0","method":"initialized","params":{"s":"あ"}}"#;
+        let input = format!(
+            "Content-Length: {}\r\nContent-Type: application/vscode-jsonrpc\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let mut reader = Cursor::new(input.into_bytes());
+
+        let message = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(
+            Some("initialized"),
+            message.get("method").and_then(|m| m.as_str())
+        );
+        assert!(read_message(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn write_message_test() {
+        let mut output = Vec::new();
+        write_message(&mut output, &Json::object(vec![("s", Json::string("あ"))])).unwrap();
+        assert_eq!(
+            "Content-Length: 11\r\n\r\n{\"s\":\"あ\"}",
+            String::from_utf8(output).unwrap()
+        );
+    }
+}
@@ -13,6 +13,13 @@ mod arch;
 mod bundler;
 mod common;
 mod debug;
+mod difftest;
+mod doc;
+mod formatter;
+mod fuzz;
+mod interpreter;
+mod lsp;
+mod reducer;
 mod setup;
 
 #[macro_use]
@@ -22,6 +29,53 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     match setup::BUILD_OPTION.matches.subcommand() {
         ("build", Some(_build_m)) => {}
         ("compile", Some(_compile_m)) => {}
+        ("check", Some(_check_m)) => {}
+        ("run", Some(_run_m)) => {}
+        ("test", Some(_test_m)) => {}
+        ("doc", Some(_doc_m)) => {}
+        // 整形はモジュールの解決を行わない
+        ("fmt", Some(fmt_m)) => {
+            let files = fmt_m.values_of("files").unwrap().collect();
+            return formatter::main(files, fmt_m.is_present("check"));
+        }
+        // 各ファイルを子プロセスでビルド，実行する
+        ("difftest", Some(difftest_m)) => {
+            return difftest::main(difftest_m.values_of("paths").unwrap().collect());
+        }
+        // 生成したプログラムごとに，子プロセスでコンパイルする
+        ("fuzz", Some(fuzz_m)) => {
+            let seed = parse_number(fuzz_m, "seed");
+            let count = parse_number(fuzz_m, "count");
+            return fuzz::main(seed, count, fuzz_m.value_of("output").unwrap());
+        }
+        // 縮小の候補ごとに，子プロセスでコンパイルする
+        ("reduce", Some(reduce_m)) => {
+            let condition = reducer::Interestingness {
+                subcommand: reduce_m
+                    .value_of("command")
+                    .unwrap()
+                    .split_whitespace()
+                    .map(|s| s.to_string())
+                    .collect(),
+                panic_message: reduce_m.value_of("panic").map(|s| s.to_string()),
+                exit_code: reduce_m
+                    .value_of("exit-code")
+                    .map(|_| parse_number(reduce_m, "exit-code") as i32),
+            };
+            if condition.subcommand.is_empty()
+                || (condition.panic_message.is_none() && condition.exit_code.is_none())
+            {
+                eprintln!("please specify a subcommand and --panic and/or --exit-code");
+                std::process::exit(1);
+            }
+            return reducer::main(
+                reduce_m.value_of("source").unwrap(),
+                condition,
+                reduce_m.value_of("output").unwrap(),
+            );
+        }
+        // 開かれたソースごとに，サーバがモジュールを解決する
+        ("lsp", Some(lsp_m)) => return lsp::main(lsp_m.value_of("target").unwrap()),
         _ => {
             eprintln!("please specify a subcommand. see --help.");
             std::process::exit(1);
@@ -32,6 +86,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let source = setup::BUILD_OPTION.get_source();
     let main_module = bundler::resolve_main(module_arena.clone(), source);
 
+    // ドキュメント生成はコード生成を行わない
+    if let ("doc", Some(doc_m)) = setup::BUILD_OPTION.matches.subcommand() {
+        return doc::main(module_arena, main_module, doc_m.value_of("output").unwrap());
+    }
+
+    // 型検査まで行い，診断があれば出力して終了する
+    if let ("check", Some(_check_m)) = setup::BUILD_OPTION.matches.subcommand() {
+        common::pass::frontend(module_arena, main_module, true);
+        return Ok(());
+    }
+
+    // 機械語を生成せずに，三番地コードを解釈実行する
+    if let ("run", Some(run_m)) = setup::BUILD_OPTION.matches.subcommand() {
+        if run_m.is_present("interp") {
+            let exit_code = interpreter::main(
+                module_arena,
+                main_module,
+                run_m.is_present("debug"),
+                program_args(run_m),
+            );
+            std::process::exit(exit_code);
+        }
+    }
+
     // ******************
     // *    Compiler    *
     // ******************
@@ -47,3 +125,27 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// 実行するプログラムのargv(先頭はソースファイル名)
+fn program_args(run_m: &clap::ArgMatches) -> Vec<String> {
+    let mut args = vec![setup::BUILD_OPTION.get_source()];
+    if let Some(values) = run_m.values_of("args") {
+        args.extend(values.map(|arg| arg.to_string()));
+    }
+    args
+}
+
+/// 数値を取る引数を読む
+fn parse_number(matches: &clap::ArgMatches, name: &str) -> u64 {
+    let value = matches.value_of(name).unwrap();
+    match value.parse() {
+        Ok(number) => number,
+        Err(_) => {
+            eprintln!(
+                "--{} expects a non-negative integer, but got {}",
+                name, value
+            );
+            std::process::exit(1);
+        }
+    }
+}
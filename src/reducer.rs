@@ -0,0 +1,183 @@
+mod printer;
+mod program;
+mod transform;
+
+pub use printer::*;
+pub use program::*;
+pub use transform::*;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// 縮小後も保たれるべき，コンパイラの振る舞い
+#[derive(Debug, Clone)]
+pub struct Interestingness {
+    /// 再現に使うサブコマンドと，ファイル名以外の引数
+    pub subcommand: Vec<String>,
+    /// パニックメッセージに含まれるべき文字列
+    pub panic_message: Option<String>,
+    /// 終了ステータス
+    pub exit_code: Option<i32>,
+}
+
+impl Interestingness {
+    /// サブコマンドの実行結果が条件を全て満たすか
+    fn holds(&self, exit_code: Option<i32>, stderr: &str) -> bool {
+        if let Some(message) = &self.panic_message {
+            let panicked = stderr.lines().any(|l| l.contains("panicked at"));
+            if !panicked || !stderr.contains(message.as_str()) {
+                return false;
+            }
+        }
+
+        match self.exit_code {
+            Some(expected) => exit_code == Some(expected),
+            None => true,
+        }
+    }
+}
+
+/// `peachili reduce` のメインルーチン
+/// 条件を満たしたまま，文/腕/関数の削除や式の単純化をできなくなるまで繰り返す
+pub fn main(
+    source_path: &str,
+    condition: Interestingness,
+    output: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let source = std::fs::read_to_string(source_path)?;
+    let program = match std::panic::catch_unwind(|| Program::parse(source)) {
+        Ok(program) => program,
+        Err(_) => return Err(format!("failed to parse {}", source_path).into()),
+    };
+
+    let work_dir = std::env::temp_dir().join(format!("peachili-reduce-{}", std::process::id()));
+    std::fs::create_dir_all(&work_dir)?;
+    let mut tester = Tester {
+        condition,
+        work_dir,
+        uninteresting: HashSet::new(),
+        runs: 0,
+    };
+    let result = reduce(program, &mut tester);
+    let _ = std::fs::remove_dir_all(&tester.work_dir);
+
+    let reduced = result?;
+    std::fs::write(output, &reduced)?;
+    eprintln!(
+        "reduced to {} lines ({} runs), written to {}",
+        reduced.lines().count(),
+        tester.runs,
+        output
+    );
+    Ok(())
+}
+
+fn reduce(mut program: Program, tester: &mut Tester) -> Result<String, Box<dyn std::error::Error>> {
+    let mut current = print_program(&program);
+    if !tester.is_interesting(&current)? {
+        return Err("the input does not satisfy the condition (after pretty-printing)".into());
+    }
+
+    // 編集が1つも通らなくなれば終了
+    loop {
+        let mut reduced = false;
+        let mut n = 0;
+
+        loop {
+            let edits = enumerate_edits(&program);
+            let edit = match edits.get(n) {
+                Some(edit) => edit,
+                None => break,
+            };
+
+            let candidate = apply_edit(&program, edit);
+            let printed = print_program(&candidate);
+            if printed.len() < current.len() && tester.is_interesting(&printed)? {
+                eprintln!("{} bytes", printed.len());
+                program = candidate;
+                current = printed;
+                reduced = true;
+            } else {
+                // 通った場合は同じ位置に次の候補が来るので進めない
+                n += 1;
+            }
+        }
+
+        if !reduced {
+            return Ok(current);
+        }
+    }
+}
+
+struct Tester {
+    condition: Interestingness,
+    work_dir: PathBuf,
+    /// 条件を満たさなかったソースは再実行しない
+    uninteresting: HashSet<String>,
+    runs: usize,
+}
+
+impl Tester {
+    fn is_interesting(&mut self, source: &str) -> std::io::Result<bool> {
+        if self.uninteresting.contains(source) {
+            return Ok(false);
+        }
+
+        self.runs += 1;
+        let (exit_code, stderr) =
+            run_candidate(&self.condition.subcommand, source, &self.work_dir)?;
+        let interesting = self.condition.holds(exit_code, &stderr);
+        if !interesting {
+            self.uninteresting.insert(source.to_string());
+        }
+        Ok(interesting)
+    }
+}
+
+/// 候補を作業ディレクトリに書き出し，子プロセスのコンパイラに渡す
+fn run_candidate(
+    subcommand: &[String],
+    source: &str,
+    work_dir: &Path,
+) -> std::io::Result<(Option<i32>, String)> {
+    std::fs::write(work_dir.join("reduce.go"), source)?;
+
+    let output = Command::new(std::env::current_exe()?)
+        .arg(&subcommand[0])
+        .arg("reduce.go")
+        .args(&subcommand[1..])
+        .current_dir(work_dir)
+        .env("NO_COLOR", "1")
+        .env("RUST_BACKTRACE", "0")
+        .output()?;
+
+    Ok((
+        output.status.code(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod reducer_tests {
+    use super::*;
+
+    #[test]
+    fn holds_test() {
+        let condition = Interestingness {
+            subcommand: vec!["compile".to_string()],
+            panic_message: Some("codegen.rs".to_string()),
+            exit_code: Some(101),
+        };
+        let stderr = "thread 'main' panicked at src/arch/x64/pass/codegen.rs:524:18:\n";
+        assert!(condition.holds(Some(101), stderr));
+        assert!(!condition.holds(Some(1), stderr));
+        assert!(!condition.holds(Some(101), "src/arch/x64/pass/codegen.rs\n"));
+
+        let condition = Interestingness {
+            panic_message: None,
+            ..condition
+        };
+        assert!(condition.holds(Some(101), ""));
+    }
+}
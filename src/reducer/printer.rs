@@ -0,0 +1,447 @@
+use crate::common::ast::{
+    ExNodeId, ExpressionNode, ExpressionNodeKind, Function, StNodeId, StatementNode,
+    StatementNodeKind,
+};
+use crate::common::token::{byte_to_literal_char, is_raw_byte_char};
+use crate::doc::display_type;
+use crate::reducer::Program;
+use id_arena::Arena;
+
+const INDENT: &str = "    ";
+
+/// プログラムをパーサが読めるソースコードに戻す
+/// 演算子の優先順位を気にしなくて済むよう，二項演算の被演算子は常に括弧で囲む
+/// コメントや記述の順序は保存しない
+pub fn print_program(program: &Program) -> String {
+    let mut out = String::new();
+
+    for import in program.imports.iter() {
+        out += &format!("import {};\n", import);
+    }
+    if !program.imports.is_empty() {
+        out += "\n";
+    }
+
+    for (name, def) in program.root.typedefs.iter() {
+        out += &format!(
+            "struct {}{} {{\n",
+            display_type(name),
+            type_params(&def.type_params)
+        );
+        for (member, member_type) in def.members.iter() {
+            out += &format!("{}{} {}\n", INDENT, member, display_type(member_type));
+        }
+        out += "}\n\n";
+    }
+
+    for (name, def) in program.root.enum_decls.iter() {
+        let mut variants: Vec<(&String, usize)> = def
+            .variants
+            .iter()
+            .map(|(variant, def)| (variant, def.tag))
+            .collect();
+        variants.sort_by_key(|(_, tag)| *tag);
+
+        out += &format!("pubenum {} {{\n", display_type(name));
+        for (variant, _) in variants {
+            out += &format!("{}{},\n", INDENT, variant);
+        }
+        out += "}\n\n";
+    }
+
+    for (name, def) in program.root.interfaces.iter() {
+        out += &format!("pubinterface {} {{\n", display_type(name));
+        for (method, fn_type) in def.methods.iter() {
+            out += &format!(
+                "{}{}({}) {}\n",
+                INDENT,
+                method,
+                parameters(&fn_type.args),
+                display_type(&fn_type.return_type)
+            );
+        }
+        out += "}\n\n";
+    }
+
+    for (name, src_type) in program.root.alias.iter() {
+        out += &format!(
+            "pubtype {} = {};\n\n",
+            display_type(name),
+            display_type(src_type)
+        );
+    }
+
+    for (name, (const_type, expr)) in program.root.constants.iter() {
+        out += &format!(
+            "pubconst {} : {} = {};\n\n",
+            display_type(name),
+            display_type(const_type),
+            expr
+        );
+    }
+
+    for func in program.functions.iter() {
+        if program.root.tests.contains(&func.full_path()) {
+            out += "#[test]\n";
+        }
+        out += &print_function(func);
+        out += "\n";
+    }
+
+    out.trim_end().to_string() + "\n"
+}
+
+fn print_function(func: &Function) -> String {
+    let mut args = func.fn_type.args.as_slice();
+    let receiver = match func.method_of {
+        Some(_) => {
+            let (recv_name, recv_type) = &args[0];
+            args = &args[1..];
+            format!("({} {}) ", recv_name, display_type(recv_type))
+        }
+        None => String::new(),
+    };
+
+    let stmt_arena = func.stmt_arena.lock().unwrap();
+    let expr_arena = func.expr_arena.lock().unwrap();
+    let printer = Printer {
+        stmt_arena: &stmt_arena,
+        expr_arena: &expr_arena,
+    };
+
+    format!(
+        "func {}{}{}({}) {} {}\n",
+        receiver,
+        func.name,
+        type_params(&func.type_params),
+        parameters(args),
+        display_type(&func.fn_type.return_type),
+        printer.block(&func.stmts, 0)
+    )
+}
+
+fn type_params(type_params: &[String]) -> String {
+    if type_params.is_empty() {
+        return String::new();
+    }
+
+    let names: Vec<String> = type_params.iter().map(|p| display_type(p)).collect();
+    format!("[{}]", names.join(", "))
+}
+
+fn parameters(args: &[(String, String)]) -> String {
+    let args: Vec<String> = args
+        .iter()
+        .map(|(name, arg_type)| format!("{} {}", name, display_type(arg_type)))
+        .collect();
+    args.join(", ")
+}
+
+struct Printer<'a> {
+    stmt_arena: &'a Arena<StatementNode>,
+    expr_arena: &'a Arena<ExpressionNode>,
+}
+
+impl<'a> Printer<'a> {
+    /// `{` から `}` まで
+    /// 閉じ括弧はdepthの段下げで，文はその1段内側に置く
+    fn block(&self, stmts: &[StNodeId], depth: usize) -> String {
+        if stmts.is_empty() {
+            return "{}".to_string();
+        }
+
+        let mut out = "{\n".to_string();
+        for stmt_id in stmts.iter() {
+            out += &INDENT.repeat(depth + 1);
+            out += &self.statement(*stmt_id, depth + 1);
+            out += "\n";
+        }
+        out + &INDENT.repeat(depth) + "}"
+    }
+
+    fn statement(&self, stmt_id: StNodeId, depth: usize) -> String {
+        let stmt = self.stmt_arena.get(stmt_id).unwrap();
+
+        match stmt.get_kind() {
+            StatementNodeKind::RETURN { expr } => {
+                format!("return {};", self.expression(*expr, depth))
+            }
+            StatementNodeKind::EXPR { expr } => format!("{};", self.expression(*expr, depth)),
+            StatementNodeKind::IFRET { expr } => {
+                format!("ifret {};", self.expression(*expr, depth))
+            }
+            StatementNodeKind::DECLARE {
+                ident_name,
+                type_name,
+            } => format!("declare {} {};", ident_name, display_type(type_name)),
+            StatementNodeKind::COUNTUP {
+                ident_name,
+                begin_ex,
+                endpoint_ex,
+                body,
+            } => format!(
+                "countup {} begin {} exclude {} {};",
+                ident_name,
+                self.expression(*begin_ex, depth),
+                self.expression(*endpoint_ex, depth),
+                self.block(body, depth)
+            ),
+            StatementNodeKind::ASM { stmts } => format!("asm {};", self.block(stmts, depth)),
+            StatementNodeKind::VARINIT {
+                ident_name,
+                type_name,
+                expr,
+            } => format!(
+                "varinit {}{} = {};",
+                ident_name,
+                declared_type(type_name),
+                self.expression(*expr, depth)
+            ),
+            StatementNodeKind::CONST {
+                ident_name,
+                type_name,
+                expr,
+            } => format!(
+                "const {}{} = {};",
+                ident_name,
+                declared_type(type_name),
+                self.expression(*expr, depth)
+            ),
+            StatementNodeKind::MATCH { expr, arms } => {
+                let mut out = format!("match {} {{\n", self.expression(*expr, depth));
+                for (pattern, stmts) in arms.iter() {
+                    out += &format!(
+                        "{}{} -> {},\n",
+                        INDENT.repeat(depth + 1),
+                        pattern,
+                        self.block(stmts, depth + 1)
+                    );
+                }
+                out + &INDENT.repeat(depth) + "};"
+            }
+        }
+    }
+
+    fn expression(&self, expr_id: ExNodeId, depth: usize) -> String {
+        let expr = self.expr_arena.get(expr_id).unwrap();
+
+        match expr.get_kind() {
+            ExpressionNodeKind::IF {
+                cond_ex,
+                body,
+                alter,
+            } => {
+                let mut out = format!(
+                    "if ({}) {}",
+                    self.expression(*cond_ex, depth),
+                    self.block(body, depth)
+                );
+                if let Some(alter) = alter {
+                    out += &format!(" else {}", self.block(alter, depth));
+                }
+                out
+            }
+            ExpressionNodeKind::ADD { lhs, rhs } => self.binop(*lhs, "+", *rhs, depth),
+            ExpressionNodeKind::SUB { lhs, rhs } => self.binop(*lhs, "-", *rhs, depth),
+            ExpressionNodeKind::MUL { lhs, rhs } => self.binop(*lhs, "*", *rhs, depth),
+            ExpressionNodeKind::DIV { lhs, rhs } => self.binop(*lhs, "/", *rhs, depth),
+            ExpressionNodeKind::ASSIGN { lhs, rhs } => format!(
+                "{} = {}",
+                self.operand(*lhs, depth),
+                self.expression(*rhs, depth)
+            ),
+            ExpressionNodeKind::NEG { value } => format!("-{}", self.operand(*value, depth)),
+            ExpressionNodeKind::ADDRESSOF { value } => {
+                format!("&{}", self.operand(*value, depth))
+            }
+            ExpressionNodeKind::DEREFERENCE { value } => {
+                format!("*{}", self.operand(*value, depth))
+            }
+            ExpressionNodeKind::MEMBER { id, member } => {
+                format!("{}.{}", self.operand(*id, depth), member)
+            }
+            ExpressionNodeKind::INDEX { value, index } => format!(
+                "{}[{}]",
+                self.operand(*value, depth),
+                self.expression(*index, depth)
+            ),
+            ExpressionNodeKind::SLICE { value, low, high } => {
+                let bound = |b: &Option<ExNodeId>| match b {
+                    Some(b) => self.expression(*b, depth),
+                    None => String::new(),
+                };
+                format!(
+                    "{}[{}:{}]",
+                    self.operand(*value, depth),
+                    bound(low),
+                    bound(high)
+                )
+            }
+            ExpressionNodeKind::LEN { value } => format!("len({})", self.expression(*value, depth)),
+            ExpressionNodeKind::INTEGER { value } if *value < 0 => {
+                format!("(0 - {})", value.unsigned_abs())
+            }
+            ExpressionNodeKind::INTEGER { value } => value.to_string(),
+            ExpressionNodeKind::UINTEGER { value } => format!("{}u", value),
+            ExpressionNodeKind::CHARACTER { value } => {
+                format!(
+                    "'{}'",
+                    escape(&byte_to_literal_char(*value).to_string(), '\'')
+                )
+            }
+            ExpressionNodeKind::BOOLEAN { truth } => truth.to_string(),
+            ExpressionNodeKind::STRING { contents } => format!("\"{}\"", escape(contents, '"')),
+            ExpressionNodeKind::IDENTIFIER { names } => names.join("::"),
+            ExpressionNodeKind::CALL {
+                names,
+                type_args,
+                args,
+            } => {
+                let type_args = if type_args.is_empty() {
+                    String::new()
+                } else {
+                    let type_args: Vec<String> =
+                        type_args.iter().map(|t| display_type(t)).collect();
+                    format!("[{}]", type_args.join(", "))
+                };
+                format!(
+                    "{}{}({})",
+                    names.join("::"),
+                    type_args,
+                    self.arguments(args, depth)
+                )
+            }
+            ExpressionNodeKind::METHODCALL { recv, method, args } => format!(
+                "{}.{}({})",
+                self.operand(*recv, depth),
+                method,
+                self.arguments(args, depth)
+            ),
+            ExpressionNodeKind::STRUCTLITERAL {
+                type_name,
+                fields,
+                storage: _,
+            } => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(member, value)| {
+                        format!("{}: {}", member, self.expression(*value, depth))
+                    })
+                    .collect();
+                format!("{} {{ {} }}", display_type(type_name), fields.join(", "))
+            }
+        }
+    }
+
+    fn binop(&self, lhs: ExNodeId, operator: &str, rhs: ExNodeId, depth: usize) -> String {
+        format!(
+            "{} {} {}",
+            self.operand(lhs, depth),
+            operator,
+            self.operand(rhs, depth)
+        )
+    }
+
+    fn arguments(&self, args: &[ExNodeId], depth: usize) -> String {
+        let args: Vec<String> = args.iter().map(|a| self.expression(*a, depth)).collect();
+        args.join(", ")
+    }
+
+    /// 演算子の被演算子
+    /// 一次式と後置式以外は括弧で囲む
+    fn operand(&self, expr_id: ExNodeId, depth: usize) -> String {
+        let printed = self.expression(expr_id, depth);
+
+        match self.expr_arena.get(expr_id).unwrap().get_kind() {
+            ExpressionNodeKind::IF { .. }
+            | ExpressionNodeKind::ADD { .. }
+            | ExpressionNodeKind::SUB { .. }
+            | ExpressionNodeKind::MUL { .. }
+            | ExpressionNodeKind::DIV { .. }
+            | ExpressionNodeKind::ASSIGN { .. }
+            | ExpressionNodeKind::NEG { .. }
+            | ExpressionNodeKind::ADDRESSOF { .. }
+            | ExpressionNodeKind::DEREFERENCE { .. } => format!("({})", printed),
+            _ => printed,
+        }
+    }
+}
+
+/// 型名が省略されていれば，宣言にも書かない
+fn declared_type(type_name: &str) -> String {
+    if type_name.is_empty() {
+        return String::new();
+    }
+    format!(" {}", display_type(type_name))
+}
+
+/// トークナイザが解釈できるエスケープシーケンスだけを使う
+fn escape(contents: &str, quote: char) -> String {
+    let mut escaped = String::new();
+    for c in contents.chars() {
+        match c {
+            '\n' => escaped += "\\n",
+            '\t' => escaped += "\\t",
+            '\\' => escaped += "\\\\",
+            '\0' => escaped += "\\0",
+            c if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_ascii_control() => escaped += &format!("\\x{:02x}", c as u32),
+            // `\x80` 以上のバイトは，元の `\xNN` に戻す
+            c if is_raw_byte_char(c) => escaped += &format!("\\x{:02x}", c as u32 & 0xff),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod printer_tests {
+    use super::*;
+
+    #[test]
+    fn print_program_test() {
+        let source = "import x64;\n\nstruct Point { x Int64 y *Point }\n\nfunc (p *Point) norm() Int64 { return p.x * p.x; }\n\nfunc main() Noreturn {\n    varinit p = Point { x: 3, y: 0 };\n    varinit s ConstStr = \"a\\n\\\"b\\\"\";\n    countup i begin 0 exclude -(p.norm() + 1) { p.x = if (true) { ifret 1 + 2 * 3; } else { ifret '\\''; }; };\n    x64::exit_with(-p.x);\n}\n";
+
+        let printed = print_program(&Program::parse(source.to_string()));
+        assert_eq!(
+            "import x64;
+
+struct Point {
+    x Int64
+    y *Point
+}
+
+func (p *Point) norm() Int64 {
+    return p.x * p.x;
+}
+
+func main() Noreturn {
+    varinit p = Point { x: 3, y: 0 };
+    varinit s ConstStr = \"a\\n\\\"b\\\"\";
+    countup i begin 0 exclude -(p.norm() + 1) {
+        p.x = if (true) {
+            ifret 1 + (2 * 3);
+        } else {
+            ifret '\\'';
+        };
+    };
+    x64::exit_with(-p.x);
+}
+",
+            printed
+        );
+
+        // 出力をもう一度パースして印字しても変わらない
+        assert_eq!(printed, print_program(&Program::parse(printed.clone())));
+
+        let source = "#[test]\nfunc add_test() Int64 { return 0; }\n";
+        let printed = print_program(&Program::parse(source.to_string()));
+        assert_eq!(
+            "#[test]\nfunc add_test() Int64 {\n    return 0;\n}\n",
+            printed
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use crate::common::{ast, pass};
+use std::sync::{Arc, Mutex};
+
+/// 縮小の対象となる，1ファイル分のプログラム
+/// 関数は記述順に並べ，それぞれ独立したアロケータを持つ
+#[derive(Debug)]
+pub struct Program {
+    pub imports: Vec<String>,
+    /// 関数以外のトップレベル宣言
+    /// `funcs` / `generic_funcs` は使わない
+    pub root: ast::ASTRoot,
+    pub functions: Vec<ast::Function>,
+}
+
+impl Program {
+    pub fn parse(source: String) -> Self {
+        let (fn_arena, mut root, imports) = pass::parse_source(source);
+
+        let arena = fn_arena.lock().unwrap();
+        let mut functions: Vec<ast::Function> = root
+            .funcs
+            .drain(..)
+            .chain(root.generic_funcs.drain(..))
+            .map(|fn_id| arena.get(fn_id).unwrap().clone())
+            .collect();
+        functions.sort_by_key(|func| func.pos);
+
+        Self {
+            imports,
+            root,
+            functions,
+        }
+    }
+}
+
+/// 文/式のアロケータまで複製し，元のプログラムと共有しない
+impl Clone for Program {
+    fn clone(&self) -> Self {
+        let functions = self
+            .functions
+            .iter()
+            .map(|func| ast::Function {
+                stmt_arena: Arc::new(Mutex::new(func.stmt_arena.lock().unwrap().clone())),
+                expr_arena: Arc::new(Mutex::new(func.expr_arena.lock().unwrap().clone())),
+                ..func.clone()
+            })
+            .collect();
+
+        Self {
+            imports: self.imports.clone(),
+            root: self.root.clone(),
+            functions,
+        }
+    }
+}
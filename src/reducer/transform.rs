@@ -0,0 +1,496 @@
+use crate::common::ast::{
+    ExNodeId, ExpressionNode, ExpressionNodeKind, StNodeId, StatementNode, StatementNodeKind,
+};
+use crate::reducer::Program;
+use id_arena::Arena;
+
+/// プログラムを1箇所だけ小さくする編集
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Edit {
+    /// main以外の関数を削除する
+    REMOVEFUNCTION { func: usize },
+    /// 関数以外のトップレベル宣言を削除する
+    REMOVEDECLARATION { name: String },
+    /// インポート宣言を削除する
+    REMOVEIMPORT { idx: usize },
+    /// 文の列から1つ削除する
+    REMOVESTATEMENT {
+        func: usize,
+        list: StatementList,
+        idx: usize,
+    },
+    /// else節を削除する
+    REMOVEELSE { func: usize, if_expr: ExNodeId },
+    /// match文の腕を削除する
+    REMOVEARM {
+        func: usize,
+        match_stmt: StNodeId,
+        pattern: String,
+    },
+    /// 構造体のメンバを削除する
+    REMOVEMEMBER { struct_name: String, idx: usize },
+    /// 呼び出しの引数を削除する
+    REMOVEARGUMENT {
+        func: usize,
+        call: ExNodeId,
+        idx: usize,
+    },
+    /// 式を部分式で置き換える
+    REPLACEWITHCHILD {
+        func: usize,
+        expr: ExNodeId,
+        child: ExNodeId,
+    },
+    /// 式を整数リテラル `0` で置き換える
+    REPLACEWITHZERO { func: usize, expr: ExNodeId },
+}
+
+/// 文の列の持ち主
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum StatementList {
+    /// 関数本体
+    BODY,
+    /// countupの本体
+    COUNTUP(StNodeId),
+    /// asmブロック
+    ASM(StNodeId),
+    /// if式の本体
+    THEN(ExNodeId),
+    /// if式のelse節
+    ELSE(ExNodeId),
+    /// match文の腕(腕はパターン名の順で数える)
+    ARM(StNodeId, usize),
+}
+
+/// 適用できる編集を列挙する
+/// 大きく削れるものほど先に並べる
+pub fn enumerate_edits(program: &Program) -> Vec<Edit> {
+    let mut collector = Collector::default();
+
+    for (func, f) in program.functions.iter().enumerate() {
+        if f.name != "main" {
+            collector.removals.push(Edit::REMOVEFUNCTION { func });
+        }
+    }
+
+    let root = &program.root;
+    let declarations = root
+        .typedefs
+        .keys()
+        .chain(root.enum_decls.keys())
+        .chain(root.interfaces.keys())
+        .chain(root.alias.keys())
+        .chain(root.constants.keys());
+    for name in declarations {
+        collector
+            .removals
+            .push(Edit::REMOVEDECLARATION { name: name.clone() });
+    }
+    for idx in 0..program.imports.len() {
+        collector.removals.push(Edit::REMOVEIMPORT { idx });
+    }
+
+    for (func, f) in program.functions.iter().enumerate() {
+        let stmt_arena = f.stmt_arena.lock().unwrap();
+        let expr_arena = f.expr_arena.lock().unwrap();
+        collector.func = func;
+        collector.stmt_list(&stmt_arena, &expr_arena, StatementList::BODY, &f.stmts);
+    }
+
+    for (struct_name, def) in root.typedefs.iter() {
+        for idx in 0..def.members.len() {
+            collector.members.push(Edit::REMOVEMEMBER {
+                struct_name: struct_name.clone(),
+                idx,
+            });
+        }
+    }
+
+    let Collector {
+        mut removals,
+        statements,
+        arms,
+        members,
+        arguments,
+        expressions,
+        func: _,
+    } = collector;
+    removals.extend(statements);
+    removals.extend(arms);
+    removals.extend(members);
+    removals.extend(arguments);
+    removals.extend(expressions);
+    removals
+}
+
+#[derive(Default)]
+struct Collector {
+    func: usize,
+    removals: Vec<Edit>,
+    statements: Vec<Edit>,
+    arms: Vec<Edit>,
+    members: Vec<Edit>,
+    arguments: Vec<Edit>,
+    expressions: Vec<Edit>,
+}
+
+impl Collector {
+    fn stmt_list(
+        &mut self,
+        stmt_arena: &Arena<StatementNode>,
+        expr_arena: &Arena<ExpressionNode>,
+        list: StatementList,
+        stmts: &[StNodeId],
+    ) {
+        for (idx, stmt_id) in stmts.iter().enumerate() {
+            self.statements.push(Edit::REMOVESTATEMENT {
+                func: self.func,
+                list,
+                idx,
+            });
+            self.statement(stmt_arena, expr_arena, *stmt_id);
+        }
+    }
+
+    fn statement(
+        &mut self,
+        stmt_arena: &Arena<StatementNode>,
+        expr_arena: &Arena<ExpressionNode>,
+        stmt_id: StNodeId,
+    ) {
+        match stmt_arena.get(stmt_id).unwrap().get_kind() {
+            StatementNodeKind::RETURN { expr }
+            | StatementNodeKind::EXPR { expr }
+            | StatementNodeKind::IFRET { expr }
+            | StatementNodeKind::VARINIT { expr, .. }
+            | StatementNodeKind::CONST { expr, .. } => {
+                self.expression(stmt_arena, expr_arena, *expr)
+            }
+            StatementNodeKind::DECLARE { .. } => {}
+            StatementNodeKind::COUNTUP {
+                ident_name: _,
+                begin_ex,
+                endpoint_ex,
+                body,
+            } => {
+                self.expression(stmt_arena, expr_arena, *begin_ex);
+                self.expression(stmt_arena, expr_arena, *endpoint_ex);
+                self.stmt_list(
+                    stmt_arena,
+                    expr_arena,
+                    StatementList::COUNTUP(stmt_id),
+                    body,
+                );
+            }
+            StatementNodeKind::ASM { stmts } => {
+                self.stmt_list(stmt_arena, expr_arena, StatementList::ASM(stmt_id), stmts)
+            }
+            StatementNodeKind::MATCH { expr, arms } => {
+                self.expression(stmt_arena, expr_arena, *expr);
+                for (arm_idx, (pattern, stmts)) in arms.iter().enumerate() {
+                    self.arms.push(Edit::REMOVEARM {
+                        func: self.func,
+                        match_stmt: stmt_id,
+                        pattern: pattern.clone(),
+                    });
+                    self.stmt_list(
+                        stmt_arena,
+                        expr_arena,
+                        StatementList::ARM(stmt_id, arm_idx),
+                        stmts,
+                    );
+                }
+            }
+        }
+    }
+
+    fn expression(
+        &mut self,
+        stmt_arena: &Arena<StatementNode>,
+        expr_arena: &Arena<ExpressionNode>,
+        expr_id: ExNodeId,
+    ) {
+        let children = match expr_arena.get(expr_id).unwrap().get_kind() {
+            ExpressionNodeKind::IF {
+                cond_ex,
+                body,
+                alter,
+            } => {
+                self.stmt_list(stmt_arena, expr_arena, StatementList::THEN(expr_id), body);
+                if let Some(alter) = alter {
+                    self.arms.push(Edit::REMOVEELSE {
+                        func: self.func,
+                        if_expr: expr_id,
+                    });
+                    self.stmt_list(stmt_arena, expr_arena, StatementList::ELSE(expr_id), alter);
+                }
+                vec![*cond_ex]
+            }
+            ExpressionNodeKind::ADD { lhs, rhs }
+            | ExpressionNodeKind::SUB { lhs, rhs }
+            | ExpressionNodeKind::MUL { lhs, rhs }
+            | ExpressionNodeKind::DIV { lhs, rhs }
+            | ExpressionNodeKind::ASSIGN { lhs, rhs } => vec![*lhs, *rhs],
+            ExpressionNodeKind::NEG { value }
+            | ExpressionNodeKind::ADDRESSOF { value }
+            | ExpressionNodeKind::DEREFERENCE { value }
+            | ExpressionNodeKind::LEN { value }
+            | ExpressionNodeKind::MEMBER { id: value, .. } => vec![*value],
+            ExpressionNodeKind::INDEX { value, index } => vec![*value, *index],
+            ExpressionNodeKind::SLICE { value, low, high } => {
+                let mut children = vec![*value];
+                children.extend(low.iter().chain(high.iter()));
+                children
+            }
+            ExpressionNodeKind::CALL { args, .. } => {
+                self.push_arguments(expr_id, args.len());
+                args.clone()
+            }
+            ExpressionNodeKind::METHODCALL { recv, args, .. } => {
+                self.push_arguments(expr_id, args.len());
+                let mut children = vec![*recv];
+                children.extend(args.iter());
+                children
+            }
+            ExpressionNodeKind::STRUCTLITERAL { fields, .. } => {
+                fields.iter().map(|(_, value)| *value).collect()
+            }
+            ExpressionNodeKind::INTEGER { value: 0 } => return,
+            ExpressionNodeKind::INTEGER { .. }
+            | ExpressionNodeKind::UINTEGER { .. }
+            | ExpressionNodeKind::CHARACTER { .. }
+            | ExpressionNodeKind::BOOLEAN { .. }
+            | ExpressionNodeKind::STRING { .. }
+            | ExpressionNodeKind::IDENTIFIER { .. } => Vec::new(),
+        };
+
+        // 部分式への置き換えを先に試す
+        for child in children.iter() {
+            self.expressions.push(Edit::REPLACEWITHCHILD {
+                func: self.func,
+                expr: expr_id,
+                child: *child,
+            });
+        }
+        self.expressions.push(Edit::REPLACEWITHZERO {
+            func: self.func,
+            expr: expr_id,
+        });
+
+        for child in children {
+            self.expression(stmt_arena, expr_arena, child);
+        }
+    }
+
+    fn push_arguments(&mut self, call: ExNodeId, arg_number: usize) {
+        for idx in 0..arg_number {
+            self.arguments.push(Edit::REMOVEARGUMENT {
+                func: self.func,
+                call,
+                idx,
+            });
+        }
+    }
+}
+
+/// 編集を適用したプログラムを返す
+/// 元のプログラムは変更しない
+pub fn apply_edit(program: &Program, edit: &Edit) -> Program {
+    let mut program = program.clone();
+
+    match edit {
+        Edit::REMOVEFUNCTION { func } => {
+            program.functions.remove(*func);
+        }
+        Edit::REMOVEDECLARATION { name } => {
+            let root = &mut program.root;
+            root.typedefs.remove(name);
+            root.enum_decls.remove(name);
+            root.interfaces.remove(name);
+            root.alias.remove(name);
+            root.constants.remove(name);
+        }
+        Edit::REMOVEIMPORT { idx } => {
+            program.imports.remove(*idx);
+        }
+        Edit::REMOVESTATEMENT { func, list, idx } => {
+            let f = &mut program.functions[*func];
+            if *list == StatementList::BODY {
+                f.stmts.remove(*idx);
+            } else {
+                let mut stmt_arena = f.stmt_arena.lock().unwrap();
+                let mut expr_arena = f.expr_arena.lock().unwrap();
+                modify_stmt_list(&mut stmt_arena, &mut expr_arena, *list, |stmts| {
+                    stmts.remove(*idx);
+                });
+            }
+        }
+        Edit::REMOVEELSE { func, if_expr } => {
+            let f = &program.functions[*func];
+            let mut expr_arena = f.expr_arena.lock().unwrap();
+            let expr = expr_arena.get_mut(*if_expr).unwrap();
+            if let ExpressionNodeKind::IF {
+                cond_ex,
+                body,
+                alter: _,
+            } = expr.get_kind()
+            {
+                *expr = ExpressionNode::new_if(*cond_ex, body.clone(), None, expr.get_pos());
+            }
+        }
+        Edit::REMOVEARM {
+            func,
+            match_stmt,
+            pattern,
+        } => {
+            let f = &program.functions[*func];
+            let mut stmt_arena = f.stmt_arena.lock().unwrap();
+            let stmt = stmt_arena.get_mut(*match_stmt).unwrap();
+            if let StatementNodeKind::MATCH { expr, arms } = stmt.get_kind() {
+                let mut arms = arms.clone();
+                arms.remove(pattern);
+                *stmt = StatementNode::new(
+                    StatementNodeKind::MATCH { expr: *expr, arms },
+                    stmt.get_position(),
+                );
+            }
+        }
+        Edit::REMOVEMEMBER { struct_name, idx } => {
+            if let Some(def) = program.root.typedefs.get_mut(struct_name) {
+                def.members.remove(*idx);
+            }
+        }
+        Edit::REMOVEARGUMENT { func, call, idx } => {
+            let f = &program.functions[*func];
+            let mut expr_arena = f.expr_arena.lock().unwrap();
+            let expr = expr_arena.get_mut(*call).unwrap();
+            let pos = expr.get_pos();
+            match expr.get_kind() {
+                ExpressionNodeKind::CALL {
+                    names,
+                    type_args,
+                    args,
+                } => {
+                    let mut args = args.clone();
+                    args.remove(*idx);
+                    *expr = ExpressionNode::new_call(names.clone(), type_args.clone(), args, pos);
+                }
+                ExpressionNodeKind::METHODCALL { recv, method, args } => {
+                    let mut args = args.clone();
+                    args.remove(*idx);
+                    *expr = ExpressionNode::new_method_call(*recv, method.clone(), args, pos);
+                }
+                _ => {}
+            }
+        }
+        Edit::REPLACEWITHCHILD { func, expr, child } => {
+            let f = &program.functions[*func];
+            let mut expr_arena = f.expr_arena.lock().unwrap();
+            let child = expr_arena.get(*child).unwrap().clone();
+            *expr_arena.get_mut(*expr).unwrap() = child;
+        }
+        Edit::REPLACEWITHZERO { func, expr } => {
+            let f = &program.functions[*func];
+            let mut expr_arena = f.expr_arena.lock().unwrap();
+            let expr = expr_arena.get_mut(*expr).unwrap();
+            *expr = ExpressionNode::new_integer(0, expr.get_pos());
+        }
+    }
+
+    program
+}
+
+/// 関数本体以外の文の列を書き換える
+fn modify_stmt_list(
+    stmt_arena: &mut Arena<StatementNode>,
+    expr_arena: &mut Arena<ExpressionNode>,
+    list: StatementList,
+    modify: impl FnOnce(&mut Vec<StNodeId>),
+) {
+    match list {
+        StatementList::BODY => unreachable!(),
+        StatementList::COUNTUP(stmt_id)
+        | StatementList::ASM(stmt_id)
+        | StatementList::ARM(stmt_id, _) => {
+            let stmt = stmt_arena.get_mut(stmt_id).unwrap();
+            let mut kind = stmt.get_kind().clone();
+            match (&mut kind, list) {
+                (StatementNodeKind::COUNTUP { body, .. }, StatementList::COUNTUP(_)) => {
+                    modify(body)
+                }
+                (StatementNodeKind::ASM { stmts }, StatementList::ASM(_)) => modify(stmts),
+                (StatementNodeKind::MATCH { arms, .. }, StatementList::ARM(_, arm_idx)) => {
+                    if let Some(stmts) = arms.values_mut().nth(arm_idx) {
+                        modify(stmts);
+                    }
+                }
+                _ => {}
+            }
+            *stmt = StatementNode::new(kind, stmt.get_position());
+        }
+        StatementList::THEN(expr_id) | StatementList::ELSE(expr_id) => {
+            let expr = expr_arena.get_mut(expr_id).unwrap();
+            if let ExpressionNodeKind::IF {
+                cond_ex,
+                body,
+                alter,
+            } = expr.get_kind()
+            {
+                let mut body = body.clone();
+                let mut alter = alter.clone();
+                match (list, alter.as_mut()) {
+                    (StatementList::ELSE(_), Some(alter)) => modify(alter),
+                    (StatementList::ELSE(_), None) => {}
+                    _ => modify(&mut body),
+                }
+                *expr = ExpressionNode::new_if(*cond_ex, body, alter, expr.get_pos());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod transform_tests {
+    use super::*;
+    use crate::reducer::print_program;
+
+    #[test]
+    fn apply_edit_test() {
+        let source = "func f(a Int64) Int64 {\n    return a;\n}\n\nfunc main() Int64 {\n    countup i begin 0 exclude 3 {\n        f(i + 1);\n    };\n    return 2 * 3;\n}\n";
+        let program = Program::parse(source.to_string());
+        let edits = enumerate_edits(&program);
+
+        // 関数の削除を最初に試す
+        assert_eq!(Edit::REMOVEFUNCTION { func: 0 }, edits[0]);
+        assert_eq!(
+            "func main() Int64 {\n    countup i begin 0 exclude 3 {\n        f(i + 1);\n    };\n    return 2 * 3;\n}\n",
+            print_program(&apply_edit(&program, &edits[0]))
+        );
+
+        let printed: Vec<String> = edits
+            .iter()
+            .map(|edit| print_program(&apply_edit(&program, edit)))
+            .collect();
+        let main_of = |printed: &String| printed.split_once("\n\n").unwrap().1.to_string();
+        let candidates: Vec<String> = printed.iter().skip(1).map(main_of).collect();
+
+        // countupの本体の文を削除する
+        assert!(candidates.contains(
+            &"func main() Int64 {\n    countup i begin 0 exclude 3 {};\n    return 2 * 3;\n}\n"
+                .to_string()
+        ));
+        // 二項演算を被演算子で置き換える
+        assert!(candidates.contains(
+            &"func main() Int64 {\n    countup i begin 0 exclude 3 {\n        f(i + 1);\n    };\n    return 2;\n}\n"
+                .to_string()
+        ));
+        // 引数を削除する
+        assert!(candidates.contains(
+            &"func main() Int64 {\n    countup i begin 0 exclude 3 {\n        f();\n    };\n    return 2 * 3;\n}\n"
+                .to_string()
+        ));
+
+        // 元のプログラムは変わらない
+        assert_eq!(source, print_program(&program));
+    }
+}
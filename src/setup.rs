@@ -11,6 +11,11 @@ lazy_static! {
         let target = match matches.subcommand() {
             ("build", Some(build_m)) => Some(build_m.value_of("target").unwrap()),
             ("compile", Some(compile_m)) => Some(compile_m.value_of("target").unwrap()),
+            ("doc", Some(doc_m)) => Some(doc_m.value_of("target").unwrap()),
+            ("check", Some(check_m)) => Some(check_m.value_of("target").unwrap()),
+            ("run", Some(run_m)) => Some(run_m.value_of("target").unwrap()),
+            ("test", Some(test_m)) => Some(test_m.value_of("target").unwrap()),
+            ("lsp", Some(lsp_m)) => Some(lsp_m.value_of("target").unwrap()),
             _ => None,
         };
 
@@ -77,5 +82,211 @@ fn create_arg_matches() -> ArgMatches {
                     Arg::with_name("debug").long("debug").help("debug"),
                 ]),
         )
+        .subcommand(
+            App::new("run")
+                .version(PEACHILI_VERSION)
+                .author("Drumato <drumato43@gmail.com>")
+                .about("builds and runs the program")
+                .args(&[
+                    // 実行するファイル
+                    Arg::with_name("source")
+                        .required(true)
+                        .index(1)
+                        .help("Sets the input file to use"),
+                    // プログラムに渡す引数
+                    Arg::with_name("args")
+                        .multiple(true)
+                        .index(2)
+                        .help("arguments passed to the program"),
+                    // 生成するコードの対象
+                    Arg::with_name("target")
+                        .default_value("x86_64")
+                        .short('t')
+                        .long("target")
+                        .help("x86_64/aarch64"),
+                    // 機械語を生成せずに実行する
+                    Arg::with_name("interp")
+                        .long("interp")
+                        .help("interprets the three address code instead of native code"),
+                    // debugオプション
+                    Arg::with_name("debug").long("debug").help("debug"),
+                ]),
+        )
+        .subcommand(
+            App::new("test")
+                .version(PEACHILI_VERSION)
+                .author("Drumato <drumato43@gmail.com>")
+                .about("runs #[test] functions, each in a forked child process")
+                .args(&[
+                    // テストを含むファイル
+                    Arg::with_name("source")
+                        .required(true)
+                        .index(1)
+                        .help("Sets the input file to use"),
+                    // 生成するコードの対象
+                    Arg::with_name("target")
+                        .default_value("x86_64")
+                        .short('t')
+                        .long("target")
+                        .help("x86_64/aarch64"),
+                    // IRのダンプ
+                    Arg::with_name("verbose-hir")
+                        .long("verbose-hir")
+                        .help("dump IR-Module to hir.dot"),
+                ]),
+        )
+        .subcommand(
+            App::new("check")
+                .version(PEACHILI_VERSION)
+                .author("Drumato <drumato43@gmail.com>")
+                .about("checks the program without generating code")
+                .args(&[
+                    // 検査対象のファイル
+                    Arg::with_name("source")
+                        .required(true)
+                        .index(1)
+                        .help("Sets the input file to use"),
+                    // ライブラリの選択に用いる
+                    Arg::with_name("target")
+                        .default_value("x86_64")
+                        .short('t')
+                        .long("target")
+                        .help("x86_64/aarch64"),
+                ]),
+        )
+        .subcommand(
+            App::new("doc")
+                .version(PEACHILI_VERSION)
+                .author("Drumato <drumato43@gmail.com>")
+                .about("generates API documents of the modules")
+                .args(&[
+                    // ドキュメント化するモジュールを参照するファイル
+                    Arg::with_name("source")
+                        .required(true)
+                        .index(1)
+                        .help("Sets the input file to use"),
+                    // ライブラリの選択に用いる
+                    Arg::with_name("target")
+                        .default_value("x86_64")
+                        .short('t')
+                        .long("target")
+                        .help("x86_64/aarch64"),
+                    // 出力先ディレクトリ
+                    Arg::with_name("output")
+                        .default_value("doc")
+                        .short('o')
+                        .long("output")
+                        .help("output directory"),
+                ]),
+        )
+        .subcommand(
+            App::new("fmt")
+                .version(PEACHILI_VERSION)
+                .author("Drumato <drumato43@gmail.com>")
+                .about("formats source files in place")
+                .args(&[
+                    // 整形するファイル群
+                    Arg::with_name("files")
+                        .required(true)
+                        .multiple(true)
+                        .index(1)
+                        .help("Sets the files to format"),
+                    // 書き換えずに，整形済みかどうかだけを調べる
+                    Arg::with_name("check")
+                        .long("check")
+                        .help("exits with 1 if some files are not formatted"),
+                ]),
+        )
+        .subcommand(
+            App::new("difftest")
+                .version(PEACHILI_VERSION)
+                .author("Drumato <drumato43@gmail.com>")
+                .about("compares native x86_64 execution with the interpreter")
+                .args(&[
+                    // 比較するファイル群(ディレクトリは再帰的に辿る)
+                    Arg::with_name("paths")
+                        .required(true)
+                        .multiple(true)
+                        .index(1)
+                        .help("Sets the files or directories to test"),
+                ]),
+        )
+        .subcommand(
+            App::new("fuzz")
+                .version(PEACHILI_VERSION)
+                .author("Drumato <drumato43@gmail.com>")
+                .about("compiles randomly generated programs to find compiler bugs")
+                .args(&[
+                    // 最初のプログラムのシード(以降は1ずつ増やす)
+                    Arg::with_name("seed")
+                        .default_value("0")
+                        .short('s')
+                        .long("seed")
+                        .help("seed of the first program"),
+                    // 生成するプログラムの数
+                    Arg::with_name("count")
+                        .default_value("100")
+                        .short('n')
+                        .long("count")
+                        .help("number of programs to generate"),
+                    // 問題を起こしたプログラムの保存先
+                    Arg::with_name("output")
+                        .default_value("fuzz-failures")
+                        .short('o')
+                        .long("output")
+                        .help("output directory for failing programs"),
+                ]),
+        )
+        .subcommand(
+            App::new("reduce")
+                .version(PEACHILI_VERSION)
+                .author("Drumato <drumato43@gmail.com>")
+                .about("shrinks a program while the compiler keeps misbehaving on it")
+                .args(&[
+                    // 縮小するファイル
+                    Arg::with_name("source")
+                        .required(true)
+                        .index(1)
+                        .help("Sets the input file to reduce"),
+                    // パニックメッセージに含まれるべき文字列
+                    Arg::with_name("panic")
+                        .short('p')
+                        .long("panic")
+                        .takes_value(true)
+                        .help("keeps programs making the compiler panic with this message"),
+                    // 終了ステータス
+                    Arg::with_name("exit-code")
+                        .short('e')
+                        .long("exit-code")
+                        .takes_value(true)
+                        .help("keeps programs making the compiler exit with this status"),
+                    // 再現に使うサブコマンド(ファイル名は自動で渡す)
+                    Arg::with_name("command")
+                        .default_value("compile --target x86_64")
+                        .short('c')
+                        .long("command")
+                        .help("subcommand and options to run on each candidate"),
+                    // 縮小結果の出力先
+                    Arg::with_name("output")
+                        .default_value("reduced.go")
+                        .short('o')
+                        .long("output")
+                        .help("output file"),
+                ]),
+        )
+        .subcommand(
+            App::new("lsp")
+                .version(PEACHILI_VERSION)
+                .author("Drumato <drumato43@gmail.com>")
+                .about("runs the language server over stdio")
+                .args(&[
+                    // ライブラリの選択に用いる
+                    Arg::with_name("target")
+                        .default_value("x86_64")
+                        .short('t')
+                        .long("target")
+                        .help("x86_64/aarch64"),
+                ]),
+        )
         .get_matches()
 }
@@ -29,6 +29,22 @@ try() {
   fi
 }
 
+# `peachili test` の終了コードで，テストハーネスの成否を確かめる
+try_test() {
+    expected="$1"
+    input="$2"
+
+  ../../target/debug/peachili test "$input" > /dev/null
+  actual="$?"
+
+  if [ "$actual" = "$expected" ]; then
+      echo -e "$input (test) => \e[32m$actual\e[m"
+  else
+      echo -e "$input (test) => \e[32m$expected\e[m expected, but got \e[31m$actual\e[m"
+      exit 1
+  fi
+}
+
 echo -e "start to test normal program...\n\n"
 
 cd examples/x64
@@ -58,5 +74,24 @@ try 1 "if_expression.go"
 try 0 "hello_world.go" "-static"
 try 1 "if_expression.go"
 try 30 "global_const.go"
+try 43 "struct_layout.go"
+try 21 "method.go"
+try 34 "interface.go"
+try 21 "generics.go"
+try 21 "function_pointer.go"
+try 21 "argc.go"
+try 30 "main_return.go"
+try 21 "type_inference.go"
+try 21 "block_scope.go"
+try 21 "nested_member.go"
+try 30 "struct_value.go"
+try 10 "zero_init.go"
+try 156 "slices.go" "-static"
+try 2 "out_of_range.go" "-static"
+try 130 "escapes.go" "-static"
+try 177 "literals.go" "-static"
+try 21 "comments.go"
+try_test 0 "test_pass.go"
+try_test 1 "test_fail.go"
 
 echo -e "\n\nOK"